@@ -1,36 +1,149 @@
 // Minimal, clean App implementation with SearchResults and Help modal.
 // We'll expand features (confirm modal, input prompt, logs) once the repo is stable.
 
-use crate::brew::{Brew, FormulaInfo};
+use crate::api_cache::ApiEntry;
+use crate::backend::BrewBackend;
+use crate::brew::{
+    self as brew, Brew, BrewEnvironment, BrewError, CleanupPreview, DependencyPreview, DoctorItem,
+    FormulaCommit, FormulaInfo, InstalledCask, Livecheck, Service,
+};
+use crate::bus::{self, BusEvent, EventBus};
+use crate::cache::{self, MetadataCache};
+use crate::config::{Bell, Config, ConfigSource, MaintenanceStep};
+use crate::disk::{self, DiskUsage, DownloadCache};
+use crate::favorites::Favorites;
+use crate::github::{self, RepoStats};
+use crate::history::History;
+use crate::jobs::{self, CancelToken, JobKind, JobPool};
+use crate::keymap::{Action, Keymap};
+use crate::layout::PaneLayout;
+use crate::matcher::NameIndex;
+use crate::notes::Notes;
+use crate::output::{Level, LogEntry, LogLine};
+use crate::parse::parse_update;
+use crate::progress::PhaseTracker;
+use crate::queue::{OperationQueue, QueuedOperation};
+use crate::session::Session;
+use crate::snapshot::{SnapshotDiff, Snapshots};
+use crate::sort::{self, ListSort, SortContext};
+use crate::symbols::Symbols;
+use crate::terminal::Tui;
+use crate::theme::Theme;
 use crate::ui::draw_ui;
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode};
-use ratatui::backend::CrosstermBackend;
-use ratatui::Terminal;
-use std::process::Command as ProcessCommand;
-use std::sync::mpsc;
+use crossterm::event::{self, Event, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
+
+pub use crate::backend::{run_brew_operation, run_operation};
+pub use crate::brew::describe_error;
+pub use crate::event::{AppEvent, ConfirmAction, HOMEBREW_INSTALL_SCRIPT};
+
+mod cleanup;
+mod confirm;
+mod disk_usage;
+mod doctor;
+mod download_cache;
+mod environment;
+mod flags;
+mod formula_log;
+mod help;
+mod history;
+mod input;
+mod install_candidates;
+mod livecheck;
+mod logs;
+mod maintenance;
+mod marks;
+mod menu;
+mod normal;
+mod operation;
+mod outdated;
+mod resume;
+mod search_results;
+mod snapshot;
+mod tabs;
+mod versions;
+mod zoom;
 
 #[derive(Clone, Debug)]
 pub enum InputAction {
     Install,
     Search,
+    /// Text to find in the Logs tab.
+    LogSearch,
+    /// The version of the formula to `brew extract` and install.
+    ExtractVersion {
+        name: String,
+    },
+    /// The note on a package; clearing it removes the note.
+    Note {
+        name: String,
+    },
+    /// Environment variables for the operation of the confirm dialog below.
+    OperationEnv,
 }
 
-#[derive(Clone, Debug)]
-pub enum ConfirmAction {
-    Install,
-    Uninstall,
-    Upgrade,
-    BulkUpgrade(Vec<String>),
-    InstallBrew,
+/// An entry of the quick-actions menu.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MenuItem {
+    /// A list action, run as if its key had been pressed.
+    Run(Action),
+    /// Show the Details pane full screen.
+    Details,
+    /// Open the package's homepage in the browser.
+    Homepage(String),
+    /// The formulae the package depends on.
+    Dependencies,
+    /// The installed formulae that depend on the package.
+    Dependents,
 }
 
+/// Theme selected by the config: the named theme, or one matching the terminal background.
+/// Problems are appended to `logs`.
+fn theme_for(config: &Config, logs: &mut Vec<String>) -> Theme {
+    match config.theme.as_deref() {
+        Some(name) => Theme::by_name(name).unwrap_or_else(|| {
+            logs.push(format!("unknown theme '{}', using default", name));
+            Theme::default()
+        }),
+        None => Theme::for_background(config.background),
+    }
+}
+
+/// Rows moved by the page up/down actions.
+const PAGE_ROWS: isize = 10;
+
+/// Age in days the Download cache modal offers to purge from.
+const PURGE_DAYS: u32 = 30;
+
+/// Entries kept in the app's log; the oldest are dropped in batches once it is full.
+const MAX_LOG_ENTRIES: usize = 2000;
+
+/// How long the screen stays inverted for `[notify] bell = "flash"`.
+const FLASH: Duration = Duration::from_millis(150);
+
+/// How long a toast stays on screen.
+const TOAST_TTL: Duration = Duration::from_secs(4);
+
+/// How long the selection has to rest on a row before its details are fetched.
+pub const DETAILS_DEBOUNCE: Duration = Duration::from_millis(300);
+
 #[derive(Clone, Debug)]
 pub enum Mode {
     Normal,
-    Help,
+    /// The key reference, scrolled with the usual keys from line `scroll`. `/` types a
+    /// `query` (while `searching`) that narrows it to the matching keys and sections.
+    Help {
+        scroll: usize,
+        query: String,
+        searching: bool,
+    },
     Input {
         action: InputAction,
         buffer: String,
@@ -40,10 +153,23 @@ pub enum Mode {
         name: String,
         idx: Option<usize>,
     },
+    /// The flags offered for the action of the confirm dialog below, each checked or not.
+    Flags {
+        action: ConfirmAction,
+        name: String,
+        cursor: usize,
+        checked: Vec<bool>,
+    },
     SearchResults {
         results: Vec<String>,
         selected: usize,
     },
+    /// The packages in different taps a name typed in the Install prompt may mean.
+    InstallCandidates {
+        query: String,
+        names: Vec<String>,
+        selected: usize,
+    },
     Outdated {
         packages: Vec<String>,
         cursor: usize,
@@ -52,125 +178,449 @@ pub enum Mode {
     },
     Operation {
         title: String,
-        logs: Vec<String>,
+        logs: Vec<LogLine>,
+        scroll: usize,
+        /// True when showing the running operation (new output is appended), false when
+        /// viewing a finished operation's captured log.
+        live: bool,
+    },
+    History {
+        selected: usize,
+    },
+    /// Operations an earlier session left queued or running, offered again at startup; the
+    /// checked ones run when Enter resumes them.
+    Resume {
+        operations: Vec<QueuedOperation>,
+        cursor: usize,
+        checked: Vec<bool>,
+    },
+    /// What changed since the last snapshot (`I`); the checked removed packages are
+    /// reinstalled on Enter.
+    SnapshotDiff {
+        taken_at: u64,
+        diff: SnapshotDiff,
+        cursor: usize,
+        checked: Vec<bool>,
+    },
+    /// What `brew cleanup` would remove, before running it.
+    Cleanup {
+        preview: CleanupPreview,
+        /// Run with `--prune=all`.
+        prune_all: bool,
+        scroll: usize,
+    },
+    /// `brew doctor` findings as a checklist.
+    Doctor {
+        items: Vec<DoctorItem>,
+        selected: usize,
+        /// Findings the user has ticked off.
+        checked: Vec<bool>,
+    },
+    /// What `brew config` and `brew --env` report.
+    Environment {
+        env: BrewEnvironment,
+        scroll: usize,
+    },
+    /// The maintenance routine before it runs: its stages and the outdated packages the
+    /// upgrade stage may include.
+    Maintenance {
+        steps: Vec<MaintenanceStep>,
+        packages: Vec<String>,
+        /// Packages to upgrade.
+        checked: Vec<bool>,
+        cursor: usize,
+    },
+    /// The installed versions of a formula, to switch the linked one or remove stale ones.
+    Versions {
+        name: String,
+        versions: Vec<String>,
+        /// The version linked into the prefix.
+        linked: Option<String>,
+        selected: usize,
+    },
+    /// Older versions of a formula that can be installed: its versioned formulae, then a last
+    /// row (`selected == versioned.len()`) for extracting any other version.
+    OlderVersions {
+        name: String,
+        versioned: Vec<String>,
+        selected: usize,
+    },
+    /// `brew livecheck` results, those where brew lags upstream first.
+    Livecheck {
+        results: Vec<Livecheck>,
+        selected: usize,
+    },
+    /// Recent tap commits of a formula (`brew log`).
+    FormulaLog {
+        name: String,
+        commits: Vec<FormulaCommit>,
+        /// The installed version, to mark the commits that came after it.
+        installed: Option<String>,
+        selected: usize,
+    },
+    /// The actions that apply to the selected package (`m` or a right click).
+    Menu {
+        name: String,
+        items: Vec<MenuItem>,
+        selected: usize,
+    },
+    /// The dependencies of a package, or the installed formulae depending on it.
+    Related {
+        name: String,
+        dependents: bool,
+        packages: Vec<String>,
+        selected: usize,
+    },
+    /// Installed packages by the space they take in the Cellar.
+    DiskUsage {
+        usage: DiskUsage,
+        selected: usize,
+    },
+    /// The download cache (`App::download_cache`) and what a purge would reclaim.
+    DownloadCache {
+        /// Age the "older than" purge uses.
+        older_than_days: u32,
         scroll: usize,
     },
 }
 
-#[derive(Clone, PartialEq)]
+/// What a mode's key handler asks for once it has handled a key.
+pub enum Transition {
+    /// Stay in the current mode (whose state may have been edited in place).
+    Stay,
+    /// Leave the current mode for another one, forgetting the modes it was opened from.
+    To(Mode),
+    /// Open a mode on top of the current one, which `Back` returns to.
+    Push(Mode),
+    /// Close the current mode and return to the one it was opened from (Normal at the bottom).
+    Back,
+    Quit,
+}
+
+/// Views the TUI can start in (`--view`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum View {
+    Installed,
+    Available,
+    Outdated,
+    History,
+    Casks,
+    Services,
+    Taps,
+    Logs,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Focus {
+    #[default]
     Installed,
     Available,
 }
 
-pub enum AppEvent {
-    Status(String),
-    BrewList(Vec<FormulaInfo>),
-    BrewInfo(FormulaInfo, usize),
-    BrewInfoAvailable(FormulaInfo, usize),
-    Log(String),
-    OpStart(String),
-    OpLog(String),
-    OpEnd(String),
-    ShowConfirm(ConfirmAction, String, Option<usize>),
-    SearchResults(Vec<String>),
-    OutdatedList(Vec<String>),
-    AvailableList(Vec<String>),
+/// The top-level views in the tab bar, switched with the number keys or `[` / `]`. Formulae
+/// holds the Installed, Available and Details panes; the others are a single list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tab {
+    Formulae,
+    Casks,
+    Outdated,
+    Services,
+    Taps,
+    Logs,
+}
+
+/// A pane shown full screen (`e`): the focused list (Installed, Available or the tab's list),
+/// Details or Logs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Zoom {
+    List,
+    Details,
+    Logs,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 6] = [
+        Tab::Formulae,
+        Tab::Casks,
+        Tab::Outdated,
+        Tab::Services,
+        Tab::Taps,
+        Tab::Logs,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Formulae => "Formulae",
+            Tab::Casks => "Casks",
+            Tab::Outdated => "Outdated",
+            Tab::Services => "Services",
+            Tab::Taps => "Taps",
+            Tab::Logs => "Logs",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Tab::ALL.iter().position(|&t| t == self).unwrap_or(0)
+    }
 }
 
 pub struct App {
-    pub brew: Brew,
+    /// Answers queries and runs operations; the configured brew unless injected.
+    pub brew: Arc<dyn BrewBackend>,
     pub items: Vec<FormulaInfo>,
     pub available_items: Vec<String>,
+    /// Short descriptions of available packages, when the API cache provided them.
+    pub available_desc: HashMap<String, String>,
+    /// Lookup structure for filtering `available_items`, rebuilt when the list loads.
+    pub available_index: NameIndex,
     pub outdated_items: Vec<String>,
+    /// Pinned formulae (`brew list --pinned`), re-read whenever the Installed list loads.
+    pub pinned: Vec<String>,
+    /// Installed packages marked with Space for a batch action.
+    pub marked: HashSet<String>,
+    /// Starred packages, listed first in Available.
+    pub favorites: Favorites,
+    /// The user's notes on packages, shown in Details.
+    pub notes: Notes,
+    /// The last snapshot of the installed packages.
+    pub snapshots: Snapshots,
+    /// Bytes each installed package takes in the Cellar, measured whenever the Installed list
+    /// loads.
+    pub installed_sizes: HashMap<String, u64>,
+    /// The last scan of the download cache, shown in its modal and purge confirmation.
+    pub download_cache: Option<DownloadCache>,
     pub selected: usize,
     pub available_selected: usize,
+    /// First visible row of the Installed and Available panes (kept between frames).
+    pub installed_offset: usize,
+    pub available_offset: usize,
     pub last_selected: Option<(Focus, usize)>,
+    /// Selection waiting for `DETAILS_DEBOUNCE` to pass before its details are fetched.
+    pub pending_details: Option<((Focus, usize), Instant)>,
     pub available_details: Option<FormulaInfo>,
+    /// Stats of the GitHub repositories of packages shown in Details, by `owner/name`; `None`
+    /// while they load or when the lookup failed.
+    pub github_stats: HashMap<String, Option<RepoStats>>,
+    /// What installing a package would pull in, by name, for its confirm dialog; `None` while
+    /// it loads or when `brew deps` failed. Forgotten whenever the Installed list loads.
+    pub dependencies: HashMap<String, Option<DependencyPreview>>,
     pub available_filter: String,
+    /// The rows of the Available list: filter matches, with versioned formulae collapsed into
+    /// one row per formula unless expanded.
     pub available_filtered: Vec<usize>,
+    /// Collapsed versioned formulae: each head row's index and those of its other versions.
+    pub available_groups: HashMap<usize, Vec<usize>>,
+    /// Formulae (`python`) whose versions are listed under their head row.
+    pub available_expanded: HashSet<String>,
     pub last_refreshed: Option<SystemTime>,
+    /// When the Available list was last known to match the package index (unix seconds).
+    pub index_fresh_at: Option<u64>,
     pub operation_status: Option<String>,
     pub operation_percent: Option<u16>,
+    pub progress: PhaseTracker,
     pub spinner_idx: usize,
     pub loading_installed: bool,
     pub loading_available: bool,
+    /// Whether the versions and taps of the Installed table (`brew info --installed`) are
+    /// still loading after its names arrived.
+    pub loading_details: bool,
+    /// When the lists started loading, for the progress note their panes show meanwhile.
+    pub loading_since: Instant,
     pub operating: bool,
+    pub waiting_locks: Vec<String>,
+    /// Open the Outdated modal once the first outdated check arrives (`--view outdated`).
+    pub pending_outdated_view: bool,
+    /// Title of the running operation, kept while its log modal is closed.
+    pub current_op: Option<String>,
     pub status: String,
-    pub logs: Vec<String>,
+    /// Whether the terminal has the focus, as far as it reports focus changes.
+    pub focused: bool,
+    /// Whether the next `tick` redraws even when nothing else changed.
+    pub redraw: bool,
+    /// The terminal title last set (see `window_title`).
+    pub window_title_shown: String,
+    /// Ring the terminal bell after the next frame.
+    pub ring_bell: bool,
+    /// Until when the screen is drawn inverted.
+    pub flash_until: Option<Instant>,
+    /// A short notice shown over the top-right corner, and when it appeared.
+    pub toast: Option<(String, Instant)>,
+    pub logs: Vec<LogEntry>,
+    /// The least serious entries the log views show.
+    pub log_level: Level,
+    /// The operation whose lines alone the log views show.
+    pub log_op: Option<String>,
+    /// Text the Logs tab highlights and jumps between (empty when not searching).
+    pub log_search: String,
+    /// Whether the Logs tab wraps long entries instead of cutting them off.
+    pub log_wrap: bool,
+    /// Where the range of Logs tab lines to copy starts (a position in `visible_logs`); the
+    /// selection ends it.
+    pub log_anchor: Option<usize>,
+    pub history: History,
     pub rx: mpsc::Receiver<AppEvent>,
+    /// Events received but not handled yet (see `bus`).
+    pub bus: EventBus,
     pub tx: mpsc::Sender<AppEvent>,
     pub mode: Mode,
+    /// Modes the current one was opened from, innermost last; Esc unwinds them one by one.
+    pub nav_stack: Vec<Mode>,
+    pub tab: Tab,
+    /// Selected and first visible row of the list in the tabs other than Formulae.
+    pub tab_selected: usize,
+    pub tab_offset: usize,
+    /// The tab whose list is being loaded.
+    pub loading_tab: Option<Tab>,
+    pub casks: Vec<InstalledCask>,
+    pub services: Vec<Service>,
+    pub taps: Vec<String>,
     pub focus: Focus,
+    /// The pane filling the screen, if any; Details and Logs scroll by `zoom_scroll` lines.
+    pub zoom: Option<Zoom>,
+    pub zoom_scroll: usize,
+    /// The dependency of the zoomed Details pane that Enter jumps to.
+    pub details_dep: usize,
+    pub config: Config,
+    pub config_source: ConfigSource,
+    pub theme: Theme,
+    pub layout: PaneLayout,
+    /// Where the last session left the UI; the selections in it are put back once their lists
+    /// load, and the current state is written back on quit.
+    pub session: Session,
+    pub keymap: Keymap,
+    pub symbols: Symbols,
+    /// Seconds between background outdated checks (0 disables them); shared with the
+    /// refresher thread.
+    pub outdated_interval: Arc<AtomicU64>,
+    /// Available list and package details kept between runs.
+    pub cache: Arc<Mutex<MetadataCache>>,
+    /// Worker threads running brew queries and operations.
+    pub jobs: JobPool,
+    /// Cancels the most recently started operation.
+    pub op_cancel: Option<CancelToken>,
+    /// Its entry in `queue`.
+    op_queued: Option<u64>,
+    /// Confirmed operations that have not finished yet, kept on disk until they do.
+    pub queue: Arc<Mutex<OperationQueue>>,
 }
 
 impl App {
     pub fn new() -> Result<Self> {
-        let brew = Brew::new();
+        Self::with_config_source(ConfigSource::default())
+    }
+
+    /// Create the app with settings from `source` (an explicit config file and command-line
+    /// overrides). The same source is used when the config is reloaded.
+    pub fn with_config_source(config_source: ConfigSource) -> Result<Self> {
+        Self::build(config_source, None)
+    }
+
+    /// Create the app on top of `backend` instead of the configured brew (tests, alternative
+    /// backends). Settings still come from `config_source`, apart from `[brew]`.
+    pub fn with_backend(
+        config_source: ConfigSource,
+        backend: Arc<dyn BrewBackend>,
+    ) -> Result<Self> {
+        Self::build(config_source, Some(backend))
+    }
+
+    /// Create the app in demo mode: lists come from the bundled fixtures, operations are
+    /// simulated and nothing is written to the history file.
+    pub fn demo(config_source: ConfigSource) -> Result<Self> {
+        let mut app = Self::build(config_source, Some(Arc::new(Brew::demo())))?;
+        app.push_log("Demo mode: showing fixture data, operations are simulated".to_string());
+        Ok(app)
+    }
+
+    fn build(config_source: ConfigSource, brew: Option<Arc<dyn BrewBackend>>) -> Result<Self> {
+        let mut startup_logs = vec![];
+        let config = config_source.load().unwrap_or_else(|e| {
+            startup_logs.push(format!("config error (using defaults): {:#}", e));
+            Config::default()
+        });
+        let theme = theme_for(&config, &mut startup_logs);
+        let brew = brew.unwrap_or_else(|| Arc::new(Brew::from_config(&config.brew)));
         let (tx, rx) = mpsc::channel();
+        let jobs = JobPool::new(jobs::QUERY_WORKERS);
 
         // background loader for installed
         let tx_bg = tx.clone();
-        let brew_bg = brew.clone();
-        thread::spawn(move || {
+        let brew_bg = Arc::clone(&brew);
+        jobs.submit(JobKind::Refresh, move |_| {
             let _ = tx_bg.send(AppEvent::Status("loading installed".to_string()));
-            if let Ok(list) = brew_bg.list_installed() {
-                let _ = tx_bg.send(AppEvent::BrewList(list));
+            match brew_bg.list_installed() {
+                Ok(list) => {
+                    let _ = tx_bg.send(AppEvent::BrewList(list));
+                }
+                Err(e) => {
+                    let _ = tx_bg.send(AppEvent::Error(describe_error(
+                        "loading installed failed",
+                        &e,
+                    )));
+                }
             }
         });
 
-        // background loader for all available packages
+        let cache = Arc::new(Mutex::new(if brew.persistent() {
+            MetadataCache::load()
+        } else {
+            MetadataCache::in_memory()
+        }));
+
+        // background loader for all available packages: a cached list is shown right away and
+        // only refreshed once it has expired
         let tx_av = tx.clone();
-        let brew_av = brew.clone();
-        thread::spawn(move || {
-            let _ = tx_av.send(AppEvent::Status("loading available".to_string()));
-            if let Ok(list) = brew_av.all_available() {
-                let _ = tx_av.send(AppEvent::AvailableList(list));
-            }
+        let brew_av = Arc::clone(&brew);
+        let cache_av = Arc::clone(&cache);
+        jobs.submit(JobKind::Refresh, move |_| {
+            load_available(brew_av.as_ref(), &cache_av, &tx_av)
         });
 
         // background loader for outdated (upgradable) installed packages
-        let tx_out = tx.clone();
-        let brew_out = brew.clone();
-        thread::spawn(move || {
-            let _ = tx_out.send(AppEvent::Status("checking for updates".to_string()));
-            match brew_out.outdated() {
-                Ok(list) => {
-                    let _ = tx_out.send(AppEvent::OutdatedList(list));
-                }
-                Err(e) => {
-                    let _ = tx_out.send(AppEvent::Log(format!("outdated check failed: {}", e)));
-                }
-            }
-        });
+        let _ = tx.send(AppEvent::Status("checking for updates".to_string()));
+        submit_outdated_check(&jobs, &brew, &tx);
 
-        // periodic refresher: re-run outdated every 5 minutes
+        // periodic refresher: re-run outdated every `outdated_interval` seconds (read on every
+        // tick so a config reload takes effect without restarting the task); it ends once the
+        // app, the only other owner of the interval, is gone
+        let outdated_interval = Arc::new(AtomicU64::new(config.outdated_interval()));
         let tx_periodic = tx.clone();
-        let brew_periodic = brew.clone();
-        thread::spawn(move || loop {
-            thread::sleep(Duration::from_secs(300));
-            match brew_periodic.outdated() {
-                Ok(list) => {
-                    let _ = tx_periodic.send(AppEvent::OutdatedList(list));
-                }
-                Err(e) => {
-                    let _ =
-                        tx_periodic.send(AppEvent::Log(format!("outdated check failed: {}", e)));
+        let brew_periodic = Arc::clone(&brew);
+        let jobs_periodic = jobs.clone();
+        let interval = Arc::clone(&outdated_interval);
+        jobs.spawn(async move {
+            let mut waited = 0;
+            while Arc::strong_count(&interval) > 1 {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                waited += 1;
+                let secs = interval.load(Ordering::Relaxed);
+                if secs > 0 && waited >= secs {
+                    waited = 0;
+                    submit_outdated_check(&jobs_periodic, &brew_periodic, &tx_periodic);
                 }
             }
         });
 
-        // Start in Normal mode, but if brew is missing, send a delayed ShowConfirm event so
+        // Start in Normal mode (or with the review of what the last session left unfinished,
+        // see `offer_resume`), but if brew is missing, send a delayed ShowConfirm event so
         // the UI is drawn once before the prompt appears.
         let initial_mode = Mode::Normal;
-        // detect brew presence and post a ShowConfirm after a short delay if missing
+        let queue = if brew.persistent() {
+            OperationQueue::load()
+        } else {
+            OperationQueue::in_memory()
+        };
+        // check that brew answers, and post a ShowConfirm after a short delay if it is missing;
+        // a brew that hangs or misbehaves is reported instead of leaving the panes loading
         let tx_detect = tx.clone();
-        thread::spawn(move || {
-            if ProcessCommand::new("brew")
-                .arg("--version")
-                .output()
-                .is_err()
-            {
+        let brew_detect = Arc::clone(&brew);
+        jobs.submit(JobKind::Refresh, move |_| match brew_detect.health() {
+            Ok(health) if !health.version.is_empty() => {
+                tracing::info!("{} at {}", health.version, health.prefix.display());
+            }
+            Ok(_) => {}
+            Err(e) if matches!(e.downcast_ref(), Some(BrewError::NotInstalled { .. })) => {
                 // give the UI a chance to render once
                 thread::sleep(Duration::from_millis(250));
                 let _ = tx_detect.send(AppEvent::ShowConfirm(
@@ -179,33 +629,150 @@ impl App {
                     None,
                 ));
             }
+            Err(e) => {
+                let _ = tx_detect.send(AppEvent::Error(describe_error(
+                    "brew health check failed",
+                    &e,
+                )));
+            }
         });
 
-        Ok(Self {
+        let layout = if brew.persistent() {
+            PaneLayout::load(&config.layout)
+        } else {
+            PaneLayout::from_config(&config.layout)
+        };
+        let (history, session, favorites, notes, snapshots) = if brew.persistent() {
+            (
+                History::load(),
+                Session::load(),
+                Favorites::load(),
+                Notes::load(),
+                Snapshots::load(),
+            )
+        } else {
+            (
+                History::in_memory(),
+                Session::in_memory(),
+                Favorites::in_memory(),
+                Notes::in_memory(),
+                Snapshots::in_memory(),
+            )
+        };
+
+        let mut app = Self {
             brew,
             items: vec![],
             available_items: vec![],
+            available_desc: HashMap::new(),
+            available_index: NameIndex::default(),
             outdated_items: vec![],
+            pinned: vec![],
+            marked: HashSet::new(),
+            favorites,
+            notes,
+            snapshots,
+            installed_sizes: HashMap::new(),
             selected: 0,
             available_selected: 0,
+            installed_offset: 0,
+            available_offset: 0,
             last_selected: None,
+            pending_details: None,
             available_details: None,
+            github_stats: HashMap::new(),
+            dependencies: HashMap::new(),
             available_filter: String::new(),
             available_filtered: vec![],
+            available_groups: HashMap::new(),
+            available_expanded: HashSet::new(),
             last_refreshed: None,
+            index_fresh_at: None,
             operation_status: None,
             operation_percent: None,
+            progress: PhaseTracker::new(),
             spinner_idx: 0,
             loading_installed: true,
             loading_available: true,
+            loading_details: false,
+            loading_since: Instant::now(),
             operating: false,
+            waiting_locks: vec![],
+            pending_outdated_view: false,
+            download_cache: None,
+            current_op: None,
             status: "Starting...".into(),
-            logs: vec![],
+            focused: true,
+            redraw: true,
+            window_title_shown: String::new(),
+            ring_bell: false,
+            flash_until: None,
+            toast: None,
+            logs: startup_logs.into_iter().map(LogEntry::warning).collect(),
+            log_level: Level::Info,
+            log_op: None,
+            log_search: String::new(),
+            log_wrap: false,
+            log_anchor: None,
+            history,
             rx,
+            bus: EventBus::new(),
             tx,
             mode: initial_mode,
+            nav_stack: vec![],
+            tab: Tab::Formulae,
+            tab_selected: 0,
+            tab_offset: 0,
+            loading_tab: None,
+            casks: vec![],
+            services: vec![],
+            taps: vec![],
             focus: Focus::Installed,
-        })
+            zoom: None,
+            zoom_scroll: 0,
+            details_dep: 0,
+            layout,
+            session: Session::in_memory(),
+            keymap: Keymap::new(config.keymap),
+            symbols: Symbols::new(config.ascii),
+            outdated_interval,
+            cache,
+            jobs,
+            op_cancel: None,
+            op_queued: None,
+            queue: Arc::new(Mutex::new(queue)),
+            config,
+            config_source,
+            theme,
+        };
+        app.restore_session(session);
+        app.offer_resume();
+        Ok(app)
+    }
+
+    /// Open the Resume review when the persisted queue holds operations an earlier session
+    /// did not finish.
+    pub fn offer_resume(&mut self) {
+        let operations = self
+            .queue
+            .lock()
+            .map(|q| q.entries.clone())
+            .unwrap_or_default();
+        if !operations.is_empty() {
+            self.mode = Mode::Resume {
+                checked: vec![true; operations.len()],
+                operations,
+                cursor: 0,
+            };
+        }
+    }
+
+    /// Go back to where `session` left the UI: the focus and filter right away, the selected
+    /// packages and scroll positions once their lists are in.
+    pub fn restore_session(&mut self, session: Session) {
+        self.focus = session.focus;
+        self.available_filter = session.filter.clone();
+        self.session = session;
     }
 
     /// Handle a single `AppEvent`. This is extracted from the body of the main run loop so
@@ -214,68 +781,160 @@ impl App {
         match ev {
             AppEvent::BrewList(list) => {
                 self.items = list;
+                self.dependencies.clear();
+                let items = &self.items;
+                self.marked.retain(|n| items.iter().any(|f| &f.name == n));
                 self.status = format!("Loaded {} packages", self.items.len());
                 self.last_refreshed = Some(SystemTime::now());
                 self.loading_installed = false;
                 self.last_selected = None;
-                // refresh outdated list whenever installed list changes
-                let tx = self.tx.clone();
-                let brew = self.brew.clone();
-                thread::spawn(move || match brew.outdated() {
-                    Ok(list) => {
-                        let _ = tx.send(AppEvent::OutdatedList(list));
+                self.sort_installed();
+                if let Some(name) = self.session.installed.take() {
+                    if let Some(idx) = self.items.iter().position(|f| f.name == name) {
+                        self.selected = idx;
+                        self.installed_offset = self.session.installed_offset;
                     }
-                    Err(e) => {
-                        let _ = tx.send(AppEvent::Log(format!("outdated check failed: {}", e)));
+                }
+                // refresh outdated list, pins and the table columns whenever installed list
+                // changes
+                submit_outdated_check(&self.jobs, &self.brew, &self.tx);
+                submit_pinned_check(&self.jobs, &self.brew, &self.tx);
+                submit_installed_columns(&self.jobs, &self.brew, &self.tx, &self.cache);
+                self.loading_details = true;
+            }
+            AppEvent::InstalledDetails(list) => {
+                self.loading_details = false;
+                for info in list {
+                    if let Some(item) = self.items.iter_mut().find(|f| f.name == info.name) {
+                        *item = info;
                     }
-                });
+                }
+                self.sort_installed();
+            }
+            AppEvent::InstalledSizes(usage) => {
+                self.installed_sizes = usage.sizes();
+                self.sort_installed();
             }
-            AppEvent::BrewInfo(info, idx) => {
-                if idx < self.items.len() {
+            AppEvent::BrewInfo(mut info, idx) => {
+                // the list may have been reloaded while `brew info` ran
+                if self.items.get(idx).is_some_and(|f| f.name == info.name) {
+                    self.fetch_github(&info);
+                    // details from the formulae.brew.sh API know nothing of what is installed
+                    if info.installed.is_empty() {
+                        let listed = &self.items[idx];
+                        info.installed = listed.installed.clone();
+                        info.linked_keg = info.linked_keg.or_else(|| listed.linked_keg.clone());
+                    }
                     self.items[idx] = info;
                 }
-                self.last_selected = Some((Focus::Installed, idx));
             }
             AppEvent::BrewInfoAvailable(info, idx) => {
-                self.available_details = Some(info);
-                self.last_selected = Some((Focus::Available, idx));
+                // ignore details for a row the selection has already moved past
+                if idx == self.available_selected {
+                    self.fetch_github(&info);
+                    self.available_details = Some(info);
+                }
+            }
+            AppEvent::Dependencies(preview) => {
+                self.dependencies
+                    .insert(preview.name.clone(), Some(preview));
+            }
+            AppEvent::GithubStats(repo, stats) => {
+                self.github_stats.insert(repo, Some(stats));
             }
             AppEvent::Status(s) => self.status = s,
-            AppEvent::OutdatedList(list) => self.outdated_items = list,
+            AppEvent::OutdatedList(list) => {
+                self.outdated_items = list;
+                self.sort_installed();
+                if self.pending_outdated_view && matches!(self.mode, Mode::Normal) {
+                    self.pending_outdated_view = false;
+                    self.open_outdated();
+                }
+            }
+            AppEvent::PinnedList(list) => {
+                self.pinned = list;
+                self.sort_installed();
+            }
+            AppEvent::CaskList(list) => {
+                self.casks = list;
+                self.tab_loaded(Tab::Casks);
+            }
+            AppEvent::ServiceList(list) => {
+                self.services = list;
+                self.tab_loaded(Tab::Services);
+            }
+            AppEvent::TapList(list) => {
+                self.taps = list;
+                self.tab_loaded(Tab::Taps);
+            }
             AppEvent::Log(l) => self.push_log(l),
+            AppEvent::Error(e) => self.push_error(e),
+            AppEvent::OpLog(line) => self.push_op_line(LogLine::stdout(line)),
+            AppEvent::OpErr(line) => self.push_op_line(LogLine::stderr(line)),
             AppEvent::OpStart(title) => {
-                self.mode = Mode::Operation {
-                    title: title.clone(),
-                    logs: vec![],
-                    scroll: 0,
-                };
-                self.push_log(format!("Started: {}", title));
+                // open the log modal unless the user is busy in another modal; the operation
+                // keeps running in the background either way
+                if let Mode::Normal | Mode::Operation { .. } = self.mode {
+                    self.mode = Mode::Operation {
+                        title: title.clone(),
+                        logs: vec![],
+                        scroll: 0,
+                        live: true,
+                    };
+                }
+                self.current_op = Some(title.clone());
+                self.push_op_log(LogEntry::note(format!("Started: {}", title)));
+                self.history.start(&title);
                 self.operating = true;
                 self.operation_percent = None;
+                self.progress = PhaseTracker::new();
             }
-            AppEvent::OpLog(line) => {
-                if let Mode::Operation { logs, scroll, .. } = &mut self.mode {
-                    logs.push(line.clone());
-                    if *scroll > 0 {}
-                    if logs.len() > 2000 {
-                        logs.drain(0..500);
-                        if *scroll > logs.len() {
-                            *scroll = logs.len();
-                        }
-                    }
+            AppEvent::OpExit(code) => self.history.set_exit_code(code),
+            AppEvent::OpEnd(title) => {
+                self.push_op_log(LogEntry::note(format!("Finished: {}", title)));
+                // variables and flags set for the run surround the subcommand
+                if crate::history::subcommand(&title) == Some("update") {
+                    self.finish_update();
                 }
-                let line_clone = line.clone();
-                self.push_log(line_clone.clone());
-                if let Some(pct) = Self::parse_percent(&line_clone) {
-                    self.operation_status = Some(format!("{}%", pct));
-                    self.operation_percent = Some(pct);
+                if let Err(e) = self.history.finish() {
+                    self.push_error(format!("failed to save history: {}", e));
+                }
+                self.notify_finished(&title);
+                self.alert_finished();
+                self.run_hooks();
+                let failed = self
+                    .history
+                    .records
+                    .last()
+                    .map(|r| r.failed_packages())
+                    .unwrap_or_default();
+                if !failed.is_empty() {
+                    self.push_op_log(LogEntry::error(format!(
+                        "{} failed: in History (H), l shows its build logs and g uploads them \
+                         with brew gist-logs",
+                        title
+                    )));
                 }
-            }
-            AppEvent::OpEnd(title) => {
-                self.push_log(format!("Finished: {}", title));
                 self.operation_status = None;
                 self.operation_percent = None;
                 self.operating = false;
+                self.current_op = None;
+            }
+            AppEvent::LockWait(locks) => {
+                if locks.is_empty() {
+                    if !self.waiting_locks.is_empty() {
+                        self.push_log("brew lock released, starting queued operation".into());
+                    }
+                } else if self.waiting_locks.is_empty() {
+                    self.push_warning(format!(
+                        "waiting for other brew process to finish (locks: {})",
+                        locks.join(", ")
+                    ));
+                }
+                self.waiting_locks = locks;
+            }
+            AppEvent::IndexFresh(at) => {
+                self.index_fresh_at = Some(at);
             }
             AppEvent::SearchResults(results) => {
                 self.mode = Mode::SearchResults {
@@ -284,967 +943,1575 @@ impl App {
                 };
             }
             AppEvent::AvailableList(list) => {
+                let first_load = self.loading_available;
+                let previous = self.available_items.get(self.available_selected).cloned();
                 self.available_items = list;
+                self.available_index = NameIndex::new(&self.available_items);
                 self.status = format!("Loaded {} available packages", self.available_items.len());
                 self.last_refreshed = Some(SystemTime::now());
                 self.loading_available = false;
-                self.available_filtered = (0..self.available_items.len()).collect();
+                // a background refresh of a cached list keeps the selected package
+                if let Some(idx) =
+                    previous.and_then(|p| self.available_items.iter().position(|n| *n == p))
+                {
+                    self.available_selected = idx;
+                }
+                // keep a filter typed (or given with --search) while the list was loading
+                self.apply_available_filter();
+                if first_load && !self.available_filter.is_empty() {
+                    if let Some(&idx) = self.available_filtered.first() {
+                        self.available_selected = idx;
+                    }
+                }
+                // the package the last session had selected, while its filter is still the one
+                // in use (--search replaces it)
+                if let Some(name) = self.session.available.take() {
+                    let idx = self.available_items.iter().position(|n| *n == name);
+                    if let Some(idx) = idx.filter(|&i| {
+                        self.available_filter == self.session.filter
+                            && self.available_filtered.contains(&i)
+                    }) {
+                        self.available_selected = idx;
+                        self.available_offset = self.session.available_offset;
+                    }
+                }
+            }
+            AppEvent::AvailableDescriptions(descriptions) => {
+                self.available_desc = descriptions;
+            }
+            AppEvent::CleanupPreview(preview) => {
+                if let Mode::Normal = self.mode {
+                    self.mode = Mode::Cleanup {
+                        preview,
+                        prune_all: false,
+                        scroll: 0,
+                    };
+                }
+            }
+            AppEvent::Doctor(items) => {
+                if items.is_empty() {
+                    self.push_log("brew doctor: your system is ready to brew".into());
+                } else if let Mode::Normal = self.mode {
+                    let checked = vec![false; items.len()];
+                    self.mode = Mode::Doctor {
+                        items,
+                        selected: 0,
+                        checked,
+                    };
+                }
+            }
+            AppEvent::OpSummary(lines) => {
+                if let Mode::Operation { logs, .. } = &mut self.mode {
+                    logs.push(LogLine::stdout(String::new()));
+                    logs.extend(lines.iter().cloned().map(LogLine::stdout));
+                }
+                for line in lines {
+                    self.push_log(line);
+                }
+            }
+            AppEvent::BuildLogs(logs) => {
+                if let Mode::Normal | Mode::History { .. } = self.mode {
+                    self.push_mode(Mode::Operation {
+                        title: format!("Build logs: {} ({})", logs.name, logs.dir.display()),
+                        logs: logs.lines().into_iter().map(LogLine::stdout).collect(),
+                        scroll: 0,
+                        live: false,
+                    });
+                }
+            }
+            AppEvent::Livecheck(mut results) => {
+                if results.is_empty() {
+                    self.push_log("brew livecheck: nothing to check".into());
+                } else if let Mode::Normal = self.mode {
+                    let lagging = results.iter().filter(|r| r.lags()).count();
+                    self.push_log(format!(
+                        "brew livecheck: {} of {} checked formulae lag upstream",
+                        lagging,
+                        results.len()
+                    ));
+                    results.sort_by(|a, b| {
+                        b.lags()
+                            .cmp(&a.lags())
+                            .then_with(|| a.formula.cmp(&b.formula))
+                    });
+                    self.mode = Mode::Livecheck {
+                        results,
+                        selected: 0,
+                    };
+                }
+            }
+            AppEvent::FormulaLog(name, commits) => {
+                if commits.is_empty() {
+                    self.push_log(format!("brew log: no commits for {}", name));
+                } else if let Mode::Normal = self.mode {
+                    let installed = self
+                        .items
+                        .iter()
+                        .find(|f| f.name == name)
+                        .and_then(|f| f.installed.first())
+                        .map(|i| i.version.clone());
+                    self.mode = Mode::FormulaLog {
+                        name,
+                        commits,
+                        installed,
+                        selected: 0,
+                    };
+                }
+            }
+            AppEvent::DiskUsage(usage) => {
+                self.installed_sizes = usage.sizes();
+                if usage.packages.is_empty() {
+                    self.push_log(format!("No packages in {}", usage.cellar.display()));
+                } else if let Mode::Normal = self.mode {
+                    self.mode = Mode::DiskUsage { usage, selected: 0 };
+                }
+            }
+            AppEvent::DownloadCache(cache) => {
+                self.push_log(format!(
+                    "Download cache: {} in {} files ({})",
+                    disk::format_size(cache.total()),
+                    cache.entries.len(),
+                    cache.dir.display()
+                ));
+                self.download_cache = Some(cache);
+                if let Mode::Normal = self.mode {
+                    self.mode = Mode::DownloadCache {
+                        older_than_days: PURGE_DAYS,
+                        scroll: 0,
+                    };
+                }
+            }
+            AppEvent::Environment(env) => {
+                if let Mode::Normal = self.mode {
+                    self.mode = Mode::Environment { env, scroll: 0 };
+                }
+            }
+            AppEvent::InstallCandidates { query, mut names } => {
+                // the prompt was left for something else meanwhile
+                if !matches!(self.mode, Mode::Normal) {
+                    return;
+                }
+                if names.len() > 1 {
+                    self.status = format!("'{}' is in several taps", query);
+                    self.mode = Mode::InstallCandidates {
+                        query,
+                        names,
+                        selected: 0,
+                    };
+                    return;
+                }
+                // nothing found is left to brew, which taps what a qualified name names
+                let name = names.pop().unwrap_or(query);
+                self.push_mode(Mode::Confirm {
+                    action: ConfirmAction::Install,
+                    name,
+                    idx: None,
+                });
+                self.preview_dependencies();
             }
             AppEvent::ShowConfirm(action, name, idx) => {
-                self.mode = Mode::Confirm { action, name, idx };
+                self.push_mode(Mode::Confirm { action, name, idx });
             }
         }
     }
 
-    fn push_log(&mut self, s: String) {
-        self.logs.push(s);
-        if self.logs.len() > 300 {
-            self.logs.drain(0..100);
-        }
-    }
-
-    /// Try to extract a percentage value from a free-form log line.
-    /// Scans for patterns like "(\d+)%" or digits followed by % and returns 0..=100.
-    fn parse_percent(s: &str) -> Option<u16> {
-        // find the first '%' and then scan backwards for contiguous digits
-        if let Some(pos) = s.find('%') {
-            // scan left from pos-1
-            let mut i = pos as isize - 1;
-            let mut digits = String::new();
-            while i >= 0 {
-                let c = s.as_bytes()[i as usize] as char;
-                if c.is_ascii_digit() {
-                    digits.insert(0, c);
-                    i -= 1;
-                } else {
-                    break;
+    /// Apply a batch of operation output lines, as delivered by the event bus.
+    pub fn handle_lines(&mut self, lines: Vec<LogLine>) {
+        for line in lines {
+            self.push_op_line(line);
+        }
+    }
+
+    /// Report what a finished `brew update` changed and re-check the outdated list, which
+    /// the new formula definitions may have changed.
+    fn finish_update(&mut self) {
+        if let Some(rec) = self.history.records.last() {
+            if rec.exit_code == Some(0) {
+                let output: Vec<&str> = rec.logs.iter().map(|l| l.text.as_str()).collect();
+                let summary = parse_update(&output.join("\n"));
+                self.push_log(summary.message());
+            }
+        }
+        submit_outdated_check(&self.jobs, &self.brew, &self.tx);
+    }
+
+    /// Append a line of operation output to the live Operation modal, the running history
+    /// record and the bottom Logs pane, updating the progress estimate.
+    fn push_op_line(&mut self, line: LogLine) {
+        if let Mode::Operation {
+            logs,
+            scroll,
+            live: true,
+            ..
+        } = &mut self.mode
+        {
+            logs.push(line.clone());
+            if logs.len() > 2000 {
+                logs.drain(0..500);
+                if *scroll > logs.len() {
+                    *scroll = logs.len();
                 }
             }
-            if !digits.is_empty() {
-                if let Ok(mut v) = digits.parse::<u16>() {
-                    if v > 100 {
-                        v = 100;
-                    }
-                    return Some(v);
+        }
+        self.history.append_log(&line);
+        if let Some(pct) = self.progress.observe(&line) {
+            let label = self.progress.phase().label();
+            self.operation_status = Some(if label.is_empty() {
+                format!("{}%", pct)
+            } else {
+                format!("{} {}%", label, pct)
+            });
+            self.operation_percent = Some(pct);
+        }
+        self.push_log_entry(LogEntry::output(line).in_op(self.current_op.clone()));
+    }
+
+    /// An entry of the app's own about the running operation, filed with its output.
+    fn push_op_log(&mut self, entry: LogEntry) {
+        self.push_log_entry(entry.in_op(self.current_op.clone()));
+    }
+
+    /// Move the selection in the focused list by `delta` rows, clamped to the list or wrapping
+    /// around with `wrap`. The Available list only moves through the filtered entries.
+    fn step_selection(&mut self, delta: isize, wrap: bool) {
+        let (len, pos) = match self.focus {
+            Focus::Installed => (self.items.len(), self.selected),
+            Focus::Available => (
+                self.available_filtered.len(),
+                self.available_filtered
+                    .iter()
+                    .position(|&i| i == self.available_selected)
+                    .unwrap_or(0),
+            ),
+        };
+        if len == 0 {
+            return;
+        }
+        let (pos, len) = (pos as isize, len as isize);
+        let target = if wrap {
+            (pos + delta).rem_euclid(len)
+        } else {
+            pos.saturating_add(delta).clamp(0, len - 1)
+        } as usize;
+        match self.focus {
+            Focus::Installed => self.selected = target,
+            Focus::Available => self.available_selected = self.available_filtered[target],
+        }
+    }
+
+    /// Re-read the config file and apply theme, keymap, symbols, brew flags/environment and
+    /// the outdated-check interval. A config that fails to parse is reported in the Logs pane
+    /// and the current settings are kept.
+    pub fn reload_config(&mut self) {
+        let config = match self.config_source.load() {
+            Ok(c) => c,
+            Err(e) => {
+                self.push_error(format!(
+                    "config reload failed (keeping current settings): {:#}",
+                    e
+                ));
+                return;
+            }
+        };
+        let mut logs = vec![];
+        self.theme = theme_for(&config, &mut logs);
+        for l in logs {
+            self.push_warning(l);
+        }
+        self.keymap = Keymap::new(config.keymap);
+        self.symbols = Symbols::new(config.ascii);
+        if let Some(brew) = self.brew.reconfigure(&config.brew) {
+            self.brew = brew;
+        }
+        self.outdated_interval
+            .store(config.outdated_interval(), Ordering::Relaxed);
+        self.config = config;
+        self.push_log(format!(
+            "Config reloaded (theme: {}, keymap: {})",
+            self.theme.name,
+            self.keymap.preset.name()
+        ));
+    }
+
+    /// Whether `name` is pinned, so `brew upgrade` leaves it alone.
+    pub fn is_pinned(&self, name: &str) -> bool {
+        self.pinned.iter().any(|p| p == name)
+    }
+
+    fn open_outdated(&mut self) {
+        let packages = self.outdated_items.clone();
+        let checked = vec![false; packages.len()];
+        self.mode = Mode::Outdated {
+            packages,
+            cursor: 0,
+            checked,
+            scroll: 0,
+        };
+    }
+
+    /// Position the UI in `view`, e.g. for `--view` on the command line. The Outdated modal
+    /// opens as soon as the first outdated check has finished; Casks, Services, Taps and Logs
+    /// are tabs.
+    pub fn open_view(&mut self, view: View) {
+        match view {
+            View::Installed => self.focus = Focus::Installed,
+            View::Available => self.focus = Focus::Available,
+            View::Outdated => {
+                if self.outdated_items.is_empty() {
+                    self.pending_outdated_view = true;
+                } else {
+                    self.open_outdated();
                 }
             }
+            View::History => self.mode = Mode::History { selected: 0 },
+            View::Casks => self.show_tab(Tab::Casks),
+            View::Services => self.show_tab(Tab::Services),
+            View::Taps => self.show_tab(Tab::Taps),
+            View::Logs => self.show_tab(Tab::Logs),
         }
-        None
     }
 
-    pub fn run(
-        &mut self,
-        terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
-    ) -> Result<()> {
-        loop {
-            // drain events from background
-            while let Ok(ev) = self.rx.try_recv() {
-                match ev {
-                    AppEvent::BrewList(list) => {
-                        self.items = list;
-                        self.status = format!("Loaded {} packages", self.items.len());
-                        self.last_refreshed = Some(SystemTime::now());
-                        self.loading_installed = false;
-                        self.last_selected = None;
-                        // refresh outdated list whenever installed list changes
-                        let tx = self.tx.clone();
-                        let brew = self.brew.clone();
-                        thread::spawn(move || match brew.outdated() {
-                            Ok(list) => {
-                                let _ = tx.send(AppEvent::OutdatedList(list));
-                            }
-                            Err(e) => {
-                                let _ =
-                                    tx.send(AppEvent::Log(format!("outdated check failed: {}", e)));
-                            }
-                        });
-                    }
-                    AppEvent::BrewInfo(info, idx) => {
-                        if idx < self.items.len() {
-                            self.items[idx] = info;
-                        }
-                        self.last_selected = Some((Focus::Installed, idx));
-                    }
-                    AppEvent::BrewInfoAvailable(info, idx) => {
-                        self.available_details = Some(info);
-                        self.last_selected = Some((Focus::Available, idx));
-                    }
-                    AppEvent::Status(s) => self.status = s,
-                    AppEvent::OutdatedList(list) => self.outdated_items = list,
-                    AppEvent::Log(l) => self.push_log(l),
-                    AppEvent::OpStart(title) => {
-                        self.mode = Mode::Operation {
-                            title: title.clone(),
-                            logs: vec![],
-                            scroll: 0,
-                        };
-                        self.push_log(format!("Started: {}", title));
-                        self.operating = true;
-                        self.operation_percent = None;
-                    }
-                    AppEvent::OpLog(line) => {
-                        if let Mode::Operation { logs, scroll, .. } = &mut self.mode {
-                            logs.push(line.clone());
-                            if *scroll > 0 {
-                                // user scrolled up; do not auto-scroll
-                            }
-                            if logs.len() > 2000 {
-                                logs.drain(0..500);
-                                if *scroll > logs.len() {
-                                    *scroll = logs.len();
-                                }
-                            }
-                        }
-                        let line_clone = line.clone();
-                        self.push_log(line_clone.clone());
-                        if let Some(pct_pos) = line_clone.find('%') {
-                            let start = if pct_pos >= 4 { pct_pos - 4 } else { 0 };
-                            let candidate = &line_clone[start..pct_pos + 1];
-                            let filtered: String = candidate
-                                .chars()
-                                .filter(|c| c.is_digit(10) || *c == '%')
-                                .collect();
-                            if filtered.ends_with('%') {
-                                let digits: String =
-                                    filtered.chars().filter(|c| c.is_digit(10)).collect();
-                                if let Ok(pct) = digits.parse::<u16>() {
-                                    let p = if pct > 100 { 100 } else { pct };
-                                    self.operation_status = Some(format!("{}%", p));
-                                    self.operation_percent = Some(p);
-                                }
-                            }
+    /// Focus the Available list filtered by `query`; the filter also applies to the list once
+    /// it has finished loading.
+    pub fn start_search(&mut self, query: &str) {
+        self.focus = Focus::Available;
+        self.available_filter = query.to_string();
+        self.apply_available_filter();
+    }
+
+    /// The package selected in the focused list.
+    fn selected_name(&self) -> Option<String> {
+        match self.focus {
+            Focus::Installed => self.items.get(self.selected).map(|f| f.name.clone()),
+            Focus::Available => self.available_items.get(self.available_selected).cloned(),
+        }
+    }
+
+    /// Star or unstar the selected package of the focused list and save the favorites.
+    fn toggle_favorite(&mut self) {
+        let Some(name) = self.selected_name() else {
+            return;
+        };
+        let starred = self.favorites.toggle(&name);
+        if let Err(e) = self.favorites.save() {
+            self.push_error(format!("failed to save favorites: {}", e));
+        }
+        self.apply_available_filter();
+        self.push_log(if starred {
+            format!("Starred {}", name)
+        } else {
+            format!("Unstarred {}", name)
+        });
+    }
+
+    /// Replace the note on `name` (a blank one removes it) and save the notes.
+    fn save_note(&mut self, name: &str, note: &str) {
+        self.notes.set(name, note);
+        if let Err(e) = self.notes.save() {
+            self.push_error(format!("failed to save notes: {}", e));
+        }
+    }
+
+    fn apply_available_filter(&mut self) {
+        if self.available_index.len() != self.available_items.len() {
+            self.available_index = NameIndex::new(&self.available_items);
+        }
+        let matches = self.available_index.matches(&self.available_filter);
+        let (rows, groups) =
+            brew::group_versioned(&self.available_items, &matches, &self.available_expanded);
+        let members = groups.values().flatten().copied().collect();
+        let ctx = SortContext {
+            sizes: &self.installed_sizes,
+            pinned: &self.pinned,
+            outdated: &self.outdated_items,
+        };
+        let mut rows = rows;
+        self.layout.list_sort.sort_available(
+            &mut rows,
+            &members,
+            &self.available_items,
+            &self.items,
+            &ctx,
+        );
+        sort::favorites_first(&mut rows, &members, &self.available_items, |n| {
+            self.favorites.contains(n)
+        });
+        self.available_filtered = rows;
+        self.available_groups = groups;
+        // a version that is now collapsed hands the selection to its head row
+        if !self.available_filtered.contains(&self.available_selected) {
+            let head = self
+                .available_groups
+                .iter()
+                .find(|(_, members)| members.contains(&self.available_selected))
+                .map(|(h, _)| *h);
+            if let Some(head) = head {
+                self.available_selected = head;
+            }
+        }
+    }
+
+    /// Expand or collapse the versions of the selected Available row's formula. Collapsing
+    /// from one of the versions selects the head row again.
+    fn toggle_available_group(&mut self) {
+        let Some(name) = self.available_items.get(self.available_selected) else {
+            return;
+        };
+        let base = brew::formula_base(name).to_string();
+        let head = self
+            .available_groups
+            .iter()
+            .find(|(h, _)| brew::formula_base(&self.available_items[**h]) == base)
+            .map(|(h, _)| *h);
+        let Some(head) = head else {
+            return;
+        };
+        if !self.available_expanded.remove(&base) {
+            self.available_expanded.insert(base);
+        }
+        self.available_selected = head;
+        self.apply_available_filter();
+    }
+
+    /// Order the Installed list by the table's sort column, keeping the selected formula.
+    fn sort_installed(&mut self) {
+        let selected = self.items.get(self.selected).map(|f| f.name.clone());
+        let sort = self.layout.installed_sort;
+        let ctx = SortContext {
+            sizes: &self.installed_sizes,
+            pinned: &self.pinned,
+            outdated: &self.outdated_items,
+        };
+        self.items.sort_by(|a, b| sort.compare(a, b, &ctx));
+        // the Available list orders installed packages the same way
+        if self.layout.list_sort != ListSort::Name {
+            self.apply_available_filter();
+        }
+        if let Some(idx) = selected.and_then(|n| self.items.iter().position(|f| f.name == n)) {
+            if idx != self.selected {
+                self.selected = idx;
+                // details and lookups in flight refer to rows by index
+                self.last_selected = None;
+            }
+        }
+    }
+
+    /// Sort the Installed table by the next column, or flip the direction with `reverse`,
+    /// and remember the choice.
+    fn change_installed_sort(&mut self, reverse: bool) {
+        let sort = &mut self.layout.installed_sort;
+        if reverse {
+            sort.descending = !sort.descending;
+        } else {
+            sort.column = sort.column.next();
+            sort.descending = false;
+        }
+        self.status = format!(
+            "Installed sorted by {} ({})",
+            sort.column.title(),
+            if sort.descending {
+                "descending"
+            } else {
+                "ascending"
+            }
+        );
+        self.sort_installed();
+        self.save_layout();
+    }
+
+    /// Sort both lists by the next of the `ListSort` orders and remember the choice; the
+    /// Installed table takes the matching column.
+    fn cycle_list_sort(&mut self) {
+        let sort = self.layout.list_sort.next();
+        self.layout.list_sort = sort;
+        self.layout.installed_sort = sort.installed();
+        self.status = format!("Lists sorted by {}", sort.title());
+        self.sort_installed();
+        self.apply_available_filter();
+        self.save_layout();
+    }
+
+    fn save_layout(&mut self) {
+        if !self.brew.persistent() {
+            return;
+        }
+        if let Err(e) = self.layout.save() {
+            self.push_error(format!("failed to save layout: {}", e));
+        }
+    }
+
+    fn push_log(&mut self, s: String) {
+        self.push_log_entry(LogEntry::note(s));
+    }
+
+    fn push_warning(&mut self, s: String) {
+        self.push_log_entry(LogEntry::warning(s));
+    }
+
+    fn push_error(&mut self, s: String) {
+        self.push_log_entry(LogEntry::error(s));
+    }
+
+    /// Tell the desktop that operation `title` finished, when the terminal is in the background
+    /// and `[notify] desktop` is on.
+    fn notify_finished(&mut self, title: &str) {
+        if self.focused || !self.config.notify.desktop {
+            return;
+        }
+        let code = self.history.records.last().and_then(|r| r.exit_code);
+        let body = crate::notify::finished_message(title, code);
+        let tx = self.tx.clone();
+        self.jobs.submit(JobKind::Refresh, move |_| {
+            if let Err(e) = crate::notify::send("homebrew-tui", &body) {
+                let _ = tx.send(AppEvent::Error(format!(
+                    "desktop notification failed: {:#}",
+                    e
+                )));
+            }
+        });
+    }
+
+    /// Run the `[hooks]` due after the operation that just finished on a thread of their own,
+    /// so a slow hook holds up no query; a failing or timed out hook is reported in the Logs
+    /// pane.
+    fn run_hooks(&mut self) {
+        let Some(record) = self.history.records.last() else {
+            return;
+        };
+        let runs = crate::hooks::due(&self.config.hooks, record);
+        if runs.is_empty() {
+            return;
+        }
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            for run in runs {
+                tracing::info!("running the {} hook for {:?}", run.hook.name(), run.package);
+                let event = match run.run() {
+                    Ok(()) => AppEvent::Log(format!(
+                        "{} hook finished{}",
+                        run.hook.name(),
+                        if run.package.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" for {}", run.package)
                         }
+                    )),
+                    Err(e) => {
+                        tracing::warn!("{:#}", e);
+                        AppEvent::Error(format!("{:#}", e))
                     }
-                    AppEvent::OpEnd(title) => {
-                        self.push_log(format!("Finished: {}", title));
-                        self.operation_status = None;
-                        self.operation_percent = None;
-                        self.operating = false;
-                    }
-                    AppEvent::SearchResults(results) => {
-                        self.mode = Mode::SearchResults {
-                            results,
-                            selected: 0,
-                        };
+                };
+                let _ = tx.send(event);
+            }
+        });
+    }
+
+    /// Ring the bell or flash the screen (`[notify] bell`) when the operation that just
+    /// finished ran for at least `[notify] after` seconds.
+    fn alert_finished(&mut self) {
+        let notify = &self.config.notify;
+        let long = self
+            .history
+            .records
+            .last()
+            .and_then(|r| r.duration())
+            .is_some_and(|d| d >= Duration::from_secs(notify.after));
+        if !long {
+            return;
+        }
+        match notify.bell {
+            Bell::Off => {}
+            Bell::Bell => self.ring_bell = true,
+            Bell::Flash => self.flash_until = Some(Instant::now() + FLASH),
+        }
+    }
+
+    /// Show `s` in a toast for a few seconds; it is logged as well.
+    fn show_toast(&mut self, s: String) {
+        self.push_log(s.clone());
+        self.toast = Some((s, Instant::now()));
+    }
+
+    fn push_log_entry(&mut self, entry: LogEntry) {
+        self.logs.push(entry);
+        if self.logs.len() > MAX_LOG_ENTRIES {
+            self.logs.drain(0..MAX_LOG_ENTRIES / 4);
+        }
+    }
+
+    /// Handle one key press in the current mode. Returns true when the app should quit.
+    ///
+    /// Each mode has its own handler module. Handlers edit their modal's state in place and
+    /// only hand back a new mode when the modal closes or gives way to another one, so
+    /// scrolling a long operation log never moves or rebuilds it.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        // let the keymap preset's navigation keys drive the modals as well
+        let key = match self.mode {
+            Mode::Normal => key,
+            Mode::Input { .. }
+            | Mode::Help {
+                searching: true, ..
+            } => self.keymap.translate_input(key),
+            _ => self.keymap.translate_modal(key),
+        };
+        let transition = match self.mode {
+            Mode::Normal => normal::handle_key(self, key),
+            Mode::Help { .. } => help::handle_key(self, key),
+            Mode::Operation { .. } => operation::handle_key(self, key),
+            Mode::Confirm { .. } => confirm::handle_key(self, key),
+            Mode::Flags { .. } => flags::handle_key(self, key),
+            Mode::Input { .. } => input::handle_key(self, key),
+            Mode::SearchResults { .. } => search_results::handle_key(self, key),
+            Mode::InstallCandidates { .. } => install_candidates::handle_key(self, key),
+            Mode::Outdated { .. } => outdated::handle_key(self, key),
+            Mode::History { .. } => history::handle_key(self, key),
+            Mode::Resume { .. } => resume::handle_key(self, key),
+            Mode::SnapshotDiff { .. } => snapshot::handle_key(self, key),
+            Mode::Cleanup { .. } => cleanup::handle_key(self, key),
+            Mode::Doctor { .. } => doctor::handle_key(self, key),
+            Mode::Environment { .. } => environment::handle_key(self, key),
+            Mode::DownloadCache { .. } => download_cache::handle_key(self, key),
+            Mode::DiskUsage { .. } => disk_usage::handle_key(self, key),
+            Mode::Maintenance { .. } => maintenance::handle_key(self, key),
+            Mode::FormulaLog { .. } => formula_log::handle_key(self, key),
+            Mode::Livecheck { .. } => livecheck::handle_key(self, key),
+            Mode::Versions { .. } => versions::handle_key(self, key),
+            Mode::OlderVersions { .. } => versions::handle_older_key(self, key),
+            Mode::Menu { .. } => menu::handle_key(self, key),
+            Mode::Related { .. } => menu::handle_related_key(self, key),
+        };
+        let quit = match transition {
+            Transition::Stay => false,
+            Transition::To(mode) => {
+                self.nav_stack.clear();
+                self.mode = mode;
+                false
+            }
+            Transition::Push(mode) => {
+                self.push_mode(mode);
+                false
+            }
+            Transition::Back => {
+                self.mode = self.nav_stack.pop().unwrap_or(Mode::Normal);
+                false
+            }
+            Transition::Quit => {
+                self.save_cache();
+                self.save_session();
+                true
+            }
+        };
+        self.preview_dependencies();
+        quit
+    }
+
+    /// Once per package, look up what the install waiting in the confirm dialog would pull in.
+    fn preview_dependencies(&mut self) {
+        let Mode::Confirm { action, name, .. } = &self.mode else {
+            return;
+        };
+        if !matches!(action.base(), ConfirmAction::Install) || self.dependencies.contains_key(name)
+        {
+            return;
+        }
+        let name = name.clone();
+        self.dependencies.insert(name.clone(), None);
+        let installed = self.items.iter().map(|f| f.name.clone()).collect();
+        submit_dependency_preview(&self.jobs, &self.brew, &self.tx, name, installed);
+    }
+
+    /// A right click on the lists opens the quick-actions menu of the selected package; other
+    /// mouse events are ignored.
+    pub fn handle_mouse(&mut self, ev: MouseEvent) {
+        if ev.kind == MouseEventKind::Down(MouseButton::Right) && matches!(self.mode, Mode::Normal)
+        {
+            self.perform(Action::Menu);
+        }
+    }
+
+    /// Open `mode` on top of the current one, which Esc returns to; from Normal nothing needs
+    /// remembering and anything left over is dropped.
+    fn push_mode(&mut self, mode: Mode) {
+        let previous = std::mem::replace(&mut self.mode, mode);
+        if matches!(previous, Mode::Normal) {
+            self.nav_stack.clear();
+        } else {
+            self.nav_stack.push(previous);
+        }
+    }
+
+    /// Fetch details for the selected row once the selection has stayed on it for
+    /// `DETAILS_DEBOUNCE`, so scrolling through a list does not start one `brew info` per row.
+    /// Called every tick of the run loop with the current time.
+    pub fn load_details_if_settled(&mut self, now: Instant) {
+        let current = match self.focus {
+            Focus::Installed if !self.items.is_empty() => (Focus::Installed, self.selected),
+            Focus::Available if !self.available_items.is_empty() => {
+                (Focus::Available, self.available_selected)
+            }
+            _ => return,
+        };
+        if self.last_selected == Some(current) {
+            self.pending_details = None;
+            return;
+        }
+        match self.pending_details {
+            Some((sel, since)) if sel == current => {
+                if now.duration_since(since) < DETAILS_DEBOUNCE {
+                    return;
+                }
+            }
+            _ => {
+                self.pending_details = Some((current, now));
+                return;
+            }
+        }
+        self.pending_details = None;
+        self.fetch_details(current.0, current.1);
+    }
+
+    /// Run `brew info` for row `idx` of the `focus` list in the background.
+    fn fetch_details(&mut self, focus: Focus, idx: usize) {
+        let name = match focus {
+            Focus::Installed => self.items.get(idx).map(|f| f.name.clone()),
+            Focus::Available => self.available_items.get(idx).cloned(),
+        };
+        let Some(name) = name else {
+            return;
+        };
+        let event = move |info| match focus {
+            Focus::Installed => AppEvent::BrewInfo(info, idx),
+            Focus::Available => AppEvent::BrewInfoAvailable(info, idx),
+        };
+        self.last_selected = Some((focus, idx));
+        let cached = self
+            .cache
+            .lock()
+            .ok()
+            .and_then(|c| c.info.get(&name).cloned());
+        if let Some(cached) = cached {
+            let fresh = cached.is_fresh(cache::INFO_TTL, SystemTime::now());
+            let _ = self.tx.send(event(cached.value));
+            if fresh {
+                return;
+            }
+        }
+        let tx = self.tx.clone();
+        let brew = Arc::clone(&self.brew);
+        let cache = Arc::clone(&self.cache);
+        // only the newest selection matters; queued lookups for rows already left are dropped
+        self.jobs.cancel(JobKind::Info);
+        self.jobs
+            .submit(JobKind::Info, move |token| match brew.info(&name) {
+                Ok(info) => {
+                    if let Ok(mut c) = cache.lock() {
+                        c.set_info(info.clone(), SystemTime::now());
                     }
-                    AppEvent::AvailableList(list) => {
-                        self.available_items = list;
-                        self.status =
-                            format!("Loaded {} available packages", self.available_items.len());
-                        self.last_refreshed = Some(SystemTime::now());
-                        self.loading_available = false;
-                        self.available_filtered = (0..self.available_items.len()).collect();
+                    if !token.is_cancelled() {
+                        let _ = tx.send(event(info));
                     }
-                    AppEvent::ShowConfirm(action, name, idx) => {
-                        self.mode = Mode::Confirm { action, name, idx };
+                }
+                Err(e) => {
+                    let _ = tx.send(AppEvent::Error(describe_error("Info failed", &e)));
+                }
+            });
+    }
+
+    /// Look up the stats of the GitHub repository of `info` once per session, from the metadata
+    /// cache when they are current and through the API otherwise (`[github] stats`).
+    fn fetch_github(&mut self, info: &FormulaInfo) {
+        if !self.config.github.stats {
+            return;
+        }
+        let Some(repo) = github::repo_of(info) else {
+            return;
+        };
+        if self.github_stats.contains_key(&repo) {
+            return;
+        }
+        let cached = self
+            .cache
+            .lock()
+            .ok()
+            .and_then(|c| c.github.get(&repo).cloned());
+        if let Some(cached) = &cached {
+            self.github_stats
+                .insert(repo.clone(), Some(cached.value.clone()));
+            if cached.is_fresh(cache::GITHUB_TTL, SystemTime::now()) {
+                return;
+            }
+        } else {
+            self.github_stats.insert(repo.clone(), None);
+        }
+        let tx = self.tx.clone();
+        let brew = Arc::clone(&self.brew);
+        let cache = Arc::clone(&self.cache);
+        self.jobs
+            .submit(JobKind::Refresh, move |_| match brew.github_stats(&repo) {
+                Ok(stats) => {
+                    if let Ok(mut c) = cache.lock() {
+                        c.set_github(&repo, stats.clone(), SystemTime::now());
                     }
+                    let _ = tx.send(AppEvent::GithubStats(repo, stats));
+                }
+                // rate limits and offline machines are common; Details just goes without
+                Err(e) => tracing::warn!("GitHub stats of {} failed: {:#}", repo, e),
+            });
+    }
+
+    /// The terminal title for what the app is doing: `homebrew-tui: upgrading ripgrep (42%)`
+    /// while an operation runs, just `homebrew-tui` when idle.
+    pub fn window_title(&self) -> String {
+        let activity = if !self.waiting_locks.is_empty() {
+            Some("waiting for a brew lock".to_string())
+        } else if self.operating {
+            self.current_op.as_deref().map(|op| {
+                let mut s = activity(op);
+                if let Some(pct) = self.operation_percent {
+                    s.push_str(&format!(" ({}%)", pct));
+                }
+                s
+            })
+        } else {
+            None
+        };
+        match activity {
+            Some(a) => format!("homebrew-tui: {}", a),
+            None => "homebrew-tui".into(),
+        }
+    }
+
+    /// Whether a spinner is on screen (something is loading or an operation is running), so
+    /// the run loop keeps redrawing while idle frames are skipped. Never with reduced motion.
+    pub fn is_animating(&self) -> bool {
+        !self.config.reduced_motion
+            && (self.loading_installed
+                || self.loading_available
+                || self.operating
+                || !self.waiting_locks.is_empty())
+    }
+
+    /// The operations confirming `action` runs, in order: one, except for a bulk upgrade with
+    /// flags such as `--verbose` when some of its packages failed to upgrade last time, which
+    /// upgrades the others without the flags first and then retries those with them.
+    pub fn operation_stages(
+        &self,
+        action: &ConfirmAction,
+        name: &str,
+    ) -> Vec<(ConfirmAction, String)> {
+        let failed = action
+            .packages()
+            .map(|pkgs| self.history.last_failed(pkgs))
+            .unwrap_or_default();
+        let Some(stages) = action.split_retries(&failed) else {
+            return vec![(action.clone(), name.to_string())];
+        };
+        stages
+            .into_iter()
+            .map(|stage| {
+                let name = match stage.packages().unwrap_or_default() {
+                    [one] => one.clone(),
+                    all => format!("{} packages", all.len()),
+                };
+                (stage, name)
+            })
+            .collect()
+    }
+
+    /// Add a confirmed operation to the persisted queue; returns its id there.
+    fn enqueue(&mut self, action: &ConfirmAction, name: &str) -> u64 {
+        let command = action.command_line_with(name, self.brew.flags());
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        let id = queue.push(action.clone(), name, command);
+        let saved = queue.save();
+        drop(queue);
+        if let Err(e) = saved {
+            self.push_error(format!("failed to save the operation queue: {}", e));
+        }
+        id
+    }
+
+    /// Stop the running (or queued) operation: a brew process is asked to terminate and its
+    /// remaining output is still shown.
+    pub fn cancel_operation(&mut self) {
+        match self.op_cancel.take() {
+            Some(token) if self.operating || !self.waiting_locks.is_empty() => {
+                token.cancel();
+                // one cancelled before its turn never runs to take itself off
+                if let Some(id) = self.op_queued.take() {
+                    dequeue(&self.queue, id);
                 }
+                self.push_log("Cancelling operation...".into());
+            }
+            _ => self.push_log("No operation running".into()),
+        }
+    }
+
+    /// Adjust view state to a new terminal size: selections stay inside their lists and the
+    /// Operation log is scrolled no further back than its oldest line needs.
+    pub fn handle_resize(&mut self, size: Rect) {
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+        self.available_selected = self
+            .available_selected
+            .min(self.available_items.len().saturating_sub(1));
+        if let Mode::Operation { logs, scroll, .. } = &mut self.mode {
+            let rows = crate::ui::operation_rows(size);
+            *scroll = (*scroll).min(logs.len().saturating_sub(rows));
+        }
+    }
+
+    /// Write where the UI is to the session file. A list that never loaded keeps what the last
+    /// session had selected in it.
+    fn save_session(&mut self) {
+        self.session.focus = self.focus;
+        self.session.filter = self.available_filter.clone();
+        if !self.loading_installed {
+            self.session.installed = self.items.get(self.selected).map(|f| f.name.clone());
+            self.session.installed_offset = self.installed_offset;
+        }
+        if !self.loading_available {
+            self.session.available = self.available_items.get(self.available_selected).cloned();
+            self.session.available_offset = self.available_offset;
+        }
+        if let Err(e) = self.session.save() {
+            self.push_error(format!("failed to save session: {}", e));
+        }
+    }
+
+    /// Persist the metadata cache (details fetched this session).
+    fn save_cache(&mut self) {
+        let res = match self.cache.lock() {
+            Ok(c) => c.save(),
+            Err(_) => return,
+        };
+        if let Err(e) = res {
+            self.push_error(format!("cache save failed: {}", e));
+        }
+    }
+
+    /// One step of the app's own work between frames, without the terminal: config reload
+    /// requests, expiring toasts and flashes, queued events from background work (urgent ones
+    /// first, output lines in batches), the spinner, the status line and debounced detail
+    /// lookups. Returns whether the screen needs redrawing. `run` calls it once per frame;
+    /// tests and automation call it along with `inject_key` to drive the app without a
+    /// terminal.
+    pub fn tick(&mut self) -> bool {
+        let mut dirty = std::mem::take(&mut self.redraw);
+        if crate::signals::take_reload_request() {
+            self.reload_config();
+            dirty = true;
+        }
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= TOAST_TTL)
+        {
+            self.toast = None;
+            dirty = true;
+        }
+        if self
+            .flash_until
+            .is_some_and(|until| Instant::now() >= until)
+        {
+            self.flash_until = None;
+            dirty = true;
+        }
+
+        for ev in self.bus.collect(&self.rx, bus::LINES_PER_FRAME) {
+            match ev {
+                BusEvent::Event(ev) => self.handle_event(*ev),
+                BusEvent::Lines(lines) => self.handle_lines(lines),
             }
+            dirty = true;
+        }
+        self.load_details_if_settled(Instant::now());
 
-            // advance spinner frame each tick
+        // the screen is only redrawn when something changed or a spinner is visible
+        let animate = self.is_animating();
+        if animate {
             self.spinner_idx = (self.spinner_idx + 1) % 8;
+        }
+        if dirty || animate {
+            self.refresh_status();
+        }
+        dirty || animate
+    }
 
-            // build a richer status line for the bottom-right Status pane
-            let mode_str = match &self.mode {
-                Mode::Normal => "Normal".to_string(),
-                Mode::Help => "Help".to_string(),
-                Mode::Input { action, .. } => match action {
-                    InputAction::Install => "Input(Install)".to_string(),
-                    InputAction::Search => "Input(Search)".to_string(),
-                },
-                Mode::Confirm { action, name, .. } => match action {
-                    ConfirmAction::Install => format!("Confirm Install {}", name),
-                    ConfirmAction::Uninstall => format!("Confirm Uninstall {}", name),
-                    ConfirmAction::Upgrade => format!("Confirm Upgrade {}", name),
-                    ConfirmAction::BulkUpgrade(_) => format!("Confirm Bulk Upgrade {}", name),
-                    ConfirmAction::InstallBrew => format!("Confirm Install Homebrew"),
-                },
-                Mode::SearchResults { results, selected } => {
-                    format!("SearchResults {} results (sel {})", results.len(), selected)
-                }
-                Mode::Outdated {
-                    packages, cursor, ..
-                } => format!("Outdated {} packages (cursor {})", packages.len(), cursor),
-                Mode::Operation { title, logs, .. } => {
-                    format!("Operation: {} ({} lines)", title, logs.len())
-                }
-            };
+    /// Handle `key` as if it had been typed; the next `tick` redraws. Returns true when it
+    /// quits the app.
+    pub fn inject_key(&mut self, key: KeyEvent) -> bool {
+        self.redraw = true;
+        self.handle_key(key)
+    }
 
-            let focus_str = match &self.focus {
-                Focus::Installed => "Installed",
-                Focus::Available => "Available",
-            };
+    /// Build the status line of the bottom-right Status pane.
+    fn refresh_status(&mut self) {
+        let mode_str = match &self.mode {
+            Mode::Normal => "Normal".to_string(),
+            Mode::Help { .. } => "Help".to_string(),
+            Mode::Input { action, .. } => match action {
+                InputAction::Install => "Input(Install)".to_string(),
+                InputAction::Search => "Input(Search)".to_string(),
+                InputAction::LogSearch => "Input(LogSearch)".to_string(),
+                InputAction::ExtractVersion { .. } => "Input(ExtractVersion)".to_string(),
+                InputAction::Note { .. } => "Input(Note)".to_string(),
+                InputAction::OperationEnv => "Input(OperationEnv)".to_string(),
+            },
+            Mode::Confirm { action, name, .. } if action.acts_on_package() => {
+                format!("Confirm {} {}", action.title(), name)
+            }
+            Mode::Confirm { action, .. } => format!("Confirm {}", action.title()),
+            Mode::Flags { action, .. } => format!("Flags {}", action.title()),
+            Mode::SearchResults { results, selected } => {
+                format!("SearchResults {} results (sel {})", results.len(), selected)
+            }
+            Mode::InstallCandidates {
+                query,
+                names,
+                selected,
+            } => format!(
+                "InstallCandidates {} {} (sel {})",
+                query,
+                names.len(),
+                selected
+            ),
+            Mode::Outdated {
+                packages, cursor, ..
+            } => format!("Outdated {} packages (cursor {})", packages.len(), cursor),
+            Mode::Operation { title, logs, .. } => {
+                format!("Operation: {} ({} lines)", title, logs.len())
+            }
+            Mode::History { .. } => {
+                format!("History ({} operations)", self.history.records.len())
+            }
+            Mode::Resume { operations, .. } => format!("Resume ({} operations)", operations.len()),
+            Mode::SnapshotDiff { .. } => "Snapshot diff".into(),
+            Mode::Cleanup { preview, .. } => {
+                format!("Cleanup ({} files)", preview.files.len())
+            }
+            Mode::Doctor { items, .. } => format!("Doctor ({} findings)", items.len()),
+            Mode::Environment { .. } => "Environment".to_string(),
+            Mode::DownloadCache { .. } => "Download cache".to_string(),
+            Mode::Maintenance { steps, .. } => {
+                format!("Maintenance ({} steps)", steps.len())
+            }
+            Mode::DiskUsage { usage, .. } => {
+                format!("Disk usage ({} packages)", usage.packages.len())
+            }
+            Mode::FormulaLog { name, commits, .. } => {
+                format!("Formula history {} ({} commits)", name, commits.len())
+            }
+            Mode::Livecheck { results, .. } => format!("Livecheck ({})", results.len()),
+            Mode::Versions { name, versions, .. } => {
+                format!("Versions {} ({} installed)", name, versions.len())
+            }
+            Mode::OlderVersions {
+                name, versioned, ..
+            } => {
+                format!("OlderVersions {} ({} versioned)", name, versioned.len())
+            }
+            Mode::Menu { name, .. } => format!("Menu {}", name),
+            Mode::Related { name, packages, .. } => {
+                format!("Related {} ({} packages)", name, packages.len())
+            }
+        };
 
-            // determine selected name and whether it's installed
-            let selected_name = if self.focus == Focus::Installed {
-                self.items
-                    .get(self.selected)
-                    .map(|f| f.name.clone())
-                    .unwrap_or_default()
-            } else {
-                self.available_items
-                    .get(self.available_selected)
-                    .cloned()
-                    .unwrap_or_default()
-            };
+        let focus_str = match &self.focus {
+            Focus::Installed => "Installed",
+            Focus::Available => "Available",
+        };
+
+        // determine selected name and whether it's installed
+        let selected_name = if self.focus == Focus::Installed {
+            self.items
+                .get(self.selected)
+                .map(|f| f.name.clone())
+                .unwrap_or_default()
+        } else {
+            self.available_items
+                .get(self.available_selected)
+                .cloned()
+                .unwrap_or_default()
+        };
 
-            let selected_installed = if selected_name.is_empty() {
-                String::new()
+        let selected_installed = if selected_name.is_empty() {
+            String::new()
+        } else {
+            let installed = self.items.iter().any(|f| f.name == selected_name);
+            if installed {
+                "(installed)".to_string()
             } else {
-                let installed = self.items.iter().any(|f| f.name == selected_name);
-                if installed {
-                    "(installed)".to_string()
-                } else {
-                    "(not installed)".to_string()
-                }
-            };
+                "(not installed)".to_string()
+            }
+        };
 
-            let recent_logs = self.logs.len();
+        let recent_logs = self.logs.len();
 
-            self.status = format!(
-                "Installed: {}  Available: {}  Focus: {}  Selected: {} {}  Mode: {}  Logs: {}",
-                self.items.len(),
-                self.available_items.len(),
-                focus_str,
-                if selected_name.is_empty() {
-                    "-"
-                } else {
-                    &selected_name
-                },
-                selected_installed,
-                mode_str,
-                recent_logs
-            );
-
-            draw_ui(terminal, self)?;
-
-            if event::poll(std::time::Duration::from_millis(200))? {
-                if let Event::Key(key) = event::read()? {
-                    // Help modal
-                    if let Mode::Help = &self.mode {
-                        let mode_taken = std::mem::replace(&mut self.mode, Mode::Normal);
-                        if let Mode::Help = mode_taken {
-                            match key.code {
-                                KeyCode::Char('?') | KeyCode::Esc => {}
-                                _ => {}
-                            }
-                        }
-                    // Operation modal handling
-                    } else if let Mode::Operation { .. } = &self.mode {
-                        let mode_taken = std::mem::replace(&mut self.mode, Mode::Normal);
-                        if let Mode::Operation {
-                            title,
-                            logs,
-                            mut scroll,
-                        } = mode_taken
-                        {
-                            match key.code {
-                                KeyCode::Esc | KeyCode::Char('?') => {}
-                                KeyCode::Up | KeyCode::Char('k') => {
-                                    if scroll + 1 < logs.len() {
-                                        scroll = scroll.saturating_add(1);
-                                    } else {
-                                        scroll = logs.len();
-                                    }
-                                    self.mode = Mode::Operation {
-                                        title,
-                                        logs,
-                                        scroll,
-                                    };
-                                }
-                                KeyCode::Down | KeyCode::Char('j') => {
-                                    if scroll > 0 {
-                                        scroll = scroll.saturating_sub(1);
-                                    }
-                                    self.mode = Mode::Operation {
-                                        title,
-                                        logs,
-                                        scroll,
-                                    };
-                                }
-                                KeyCode::PageUp => {
-                                    let page = 10usize;
-                                    scroll = (scroll + page).min(logs.len());
-                                    self.mode = Mode::Operation {
-                                        title,
-                                        logs,
-                                        scroll,
-                                    };
-                                }
-                                KeyCode::PageDown => {
-                                    let page = 10usize;
-                                    scroll = scroll.saturating_sub(page);
-                                    self.mode = Mode::Operation {
-                                        title,
-                                        logs,
-                                        scroll,
-                                    };
-                                }
-                                KeyCode::Home => {
-                                    scroll = logs.len();
-                                    self.mode = Mode::Operation {
-                                        title,
-                                        logs,
-                                        scroll,
-                                    };
-                                }
-                                KeyCode::End => {
-                                    scroll = 0;
-                                    self.mode = Mode::Operation {
-                                        title,
-                                        logs,
-                                        scroll,
-                                    };
-                                }
-                                _ => {
-                                    self.mode = Mode::Operation {
-                                        title,
-                                        logs,
-                                        scroll,
-                                    };
-                                }
-                            }
-                        }
-                    // Confirm modal handling (with InstallBrew special-case)
-                    } else if let Mode::Confirm { .. } = &self.mode {
-                        let mode_taken = std::mem::replace(&mut self.mode, Mode::Normal);
-                        if let Mode::Confirm {
-                            action,
-                            name,
-                            idx: _,
-                        } = mode_taken
-                        {
-                            match key.code {
-                                KeyCode::Char('y') | KeyCode::Enter => {
-                                    let action = action.clone();
-                                    let name = name.clone();
-                                    let tx = self.tx.clone();
-                                    thread::spawn(move || {
-                                        use std::io::{BufRead, BufReader};
-                                        use std::process::{Command, Stdio};
-
-                                        // Special-case Homebrew installation
-                                        if let ConfirmAction::InstallBrew = action {
-                                            let title = "install-homebrew".to_string();
-                                            let _ = tx.send(AppEvent::OpStart(title.clone()));
-                                            match Command::new("/bin/bash")
-                                                .arg("-lc")
-                                                .arg("/bin/bash -c \"$(curl -fsSL https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh)\"")
-                                                .stdout(Stdio::piped())
-                                                .stderr(Stdio::piped())
-                                                .spawn()
-                                            {
-                                                Ok(mut child) => {
-                                                    if let Some(stdout) = child.stdout.take() {
-                                                        let txo = tx.clone();
-                                                        thread::spawn(move || {
-                                                            let reader = BufReader::new(stdout);
-                                                            for line in reader.lines() { if let Ok(l) = line { let _ = txo.send(AppEvent::OpLog(l)); } }
-                                                        });
-                                                    }
-                                                    if let Some(stderr) = child.stderr.take() {
-                                                        let txe = tx.clone();
-                                                        thread::spawn(move || {
-                                                            let reader = BufReader::new(stderr);
-                                                            for line in reader.lines() { if let Ok(l) = line { let _ = txe.send(AppEvent::OpLog(l)); } }
-                                                        });
-                                                    }
-                                                    match child.wait() {
-                                                        Ok(status) => {
-                                                            if status.success() {
-                                                                let _ = tx.send(AppEvent::Status(format!("{} completed", title)));
-                                                            } else {
-                                                                let _ = tx.send(AppEvent::Log(format!("{} failed: {}", title, status)));
-                                                            }
-                                                        }
-                                                        Err(e) => { let _ = tx.send(AppEvent::Log(format!("failed waiting for installer: {}", e))); }
-                                                    }
-                                                }
-                                                Err(e) => { let _ = tx.send(AppEvent::Log(format!("failed to spawn installer: {}", e))); }
-                                            }
-                                            let _ = tx.send(AppEvent::OpEnd(title));
-                                            return;
-                                        }
-
-                                        // Otherwise handle brew verbs normally
-                                        let (verb, args) = match action {
-                                            ConfirmAction::Uninstall => {
-                                                ("uninstall", vec![name.clone()])
-                                            }
-                                            ConfirmAction::Upgrade => {
-                                                ("upgrade", vec![name.clone()])
-                                            }
-                                            ConfirmAction::Install => {
-                                                ("install", vec![name.clone()])
-                                            }
-                                            ConfirmAction::BulkUpgrade(pkgs) => {
-                                                ("upgrade", pkgs.clone())
-                                            }
-                                            _ => ("", vec![]),
-                                        };
-
-                                        let title = if verb.is_empty() {
-                                            "brew op".to_string()
-                                        } else {
-                                            format!("brew {} {}", verb, args.join(" "))
-                                        };
-                                        let _ = tx.send(AppEvent::OpStart(title.clone()));
-
-                                        match Command::new("brew")
-                                            .arg(verb)
-                                            .args(&args)
-                                            .stdout(Stdio::piped())
-                                            .stderr(Stdio::piped())
-                                            .spawn()
-                                        {
-                                            Ok(mut child) => {
-                                                if let Some(stdout) = child.stdout.take() {
-                                                    let tx_out = tx.clone();
-                                                    thread::spawn(move || {
-                                                        let reader = BufReader::new(stdout);
-                                                        for line in reader.lines() {
-                                                            if let Ok(l) = line {
-                                                                let _ =
-                                                                    tx_out.send(AppEvent::OpLog(l));
-                                                            }
-                                                        }
-                                                    });
-                                                }
-                                                if let Some(stderr) = child.stderr.take() {
-                                                    let tx_err = tx.clone();
-                                                    thread::spawn(move || {
-                                                        let reader = BufReader::new(stderr);
-                                                        for line in reader.lines() {
-                                                            if let Ok(l) = line {
-                                                                let _ =
-                                                                    tx_err.send(AppEvent::OpLog(l));
-                                                            }
-                                                        }
-                                                    });
-                                                }
-                                                match child.wait() {
-                                                    Ok(status) => {
-                                                        if status.success() {
-                                                            let _ = tx.send(AppEvent::Status(
-                                                                format!("{} completed", title),
-                                                            ));
-                                                            if let Ok(list) =
-                                                                Brew::new().list_installed()
-                                                            {
-                                                                let _ = tx
-                                                                    .send(AppEvent::BrewList(list));
-                                                            }
-                                                        } else {
-                                                            let _ =
-                                                                tx.send(AppEvent::Log(format!(
-                                                                    "{} failed: {}",
-                                                                    title, status
-                                                                )));
-                                                        }
-                                                    }
-                                                    Err(e) => {
-                                                        let _ = tx.send(AppEvent::OpLog(format!(
-                                                            "failed waiting for brew: {}",
-                                                            e
-                                                        )));
-                                                    }
-                                                }
-                                            }
-                                            Err(e) => {
-                                                let _ = tx.send(AppEvent::OpLog(format!(
-                                                    "failed to spawn brew: {}",
-                                                    e
-                                                )));
-                                            }
-                                        }
-
-                                        let _ = tx.send(AppEvent::OpEnd(title));
-                                    });
-                                }
-                                KeyCode::Char('n') | KeyCode::Esc => {
-                                    self.status = "Cancelled".into();
-                                }
-                                _ => {}
-                            }
-                        }
-                    // Input modal handling (take ownership then reapply)
-                    } else if let Mode::Input { .. } = &self.mode {
-                        let mode_taken = std::mem::replace(&mut self.mode, Mode::Normal);
-                        if let Mode::Input { action, mut buffer } = mode_taken {
-                            match key.code {
-                                KeyCode::Esc => {
-                                    self.status = "Cancelled input".into();
-                                }
-                                KeyCode::Backspace => {
-                                    buffer.pop();
-                                    if let InputAction::Search = action {
-                                        self.available_filter = buffer.clone();
-                                        self.available_filtered = self
-                                            .available_items
-                                            .iter()
-                                            .enumerate()
-                                            .filter_map(|(i, name)| {
-                                                if name.contains(&self.available_filter) {
-                                                    Some(i)
-                                                } else {
-                                                    None
-                                                }
-                                            })
-                                            .collect();
-                                    }
-                                    self.mode = Mode::Input { action, buffer };
-                                }
-                                KeyCode::Enter => {
-                                    let value = buffer.trim().to_string();
-                                    if !value.is_empty() {
-                                        match action {
-                                            InputAction::Install => {
-                                                let name = value.clone();
-                                                self.mode = Mode::Confirm {
-                                                    action: ConfirmAction::Install,
-                                                    name,
-                                                    idx: None,
-                                                };
-                                            }
-                                            InputAction::Search => {
-                                                if self.focus == Focus::Available {
-                                                    self.available_filter = value.clone();
-                                                    self.available_filtered = self
-                                                        .available_items
-                                                        .iter()
-                                                        .enumerate()
-                                                        .filter_map(|(i, name)| {
-                                                            if name.contains(&self.available_filter)
-                                                            {
-                                                                Some(i)
-                                                            } else {
-                                                                None
-                                                            }
-                                                        })
-                                                        .collect();
-                                                    if let Some(&idx) =
-                                                        self.available_filtered.first()
-                                                    {
-                                                        self.available_selected = idx;
-                                                    }
-                                                } else {
-                                                    let query = value.clone();
-                                                    let tx = self.tx.clone();
-                                                    let brew = self.brew.clone();
-                                                    thread::spawn(move || {
-                                                        match brew.search(&query) {
-                                                            Ok(results) => {
-                                                                let _ = tx.send(
-                                                                    AppEvent::SearchResults(
-                                                                        results,
-                                                                    ),
-                                                                );
-                                                            }
-                                                            Err(e) => {
-                                                                let _ = tx.send(AppEvent::Log(
-                                                                    format!("Search failed: {}", e),
-                                                                ));
-                                                            }
-                                                        }
-                                                    });
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                KeyCode::Char(c) => {
-                                    buffer.push(c);
-                                    if let InputAction::Search = action {
-                                        self.available_filter = buffer.clone();
-                                        self.available_filtered = self
-                                            .available_items
-                                            .iter()
-                                            .enumerate()
-                                            .filter_map(|(i, name)| {
-                                                if name.contains(&self.available_filter) {
-                                                    Some(i)
-                                                } else {
-                                                    None
-                                                }
-                                            })
-                                            .collect();
-                                        if let Some(&idx) = self.available_filtered.first() {
-                                            self.available_selected = idx;
-                                        }
-                                    }
-                                    self.mode = Mode::Input { action, buffer };
-                                }
-                                _ => {
-                                    self.mode = Mode::Input { action, buffer };
-                                }
-                            }
-                        }
-                    // Search results modal handling
-                    } else if let Mode::SearchResults { .. } = &self.mode {
-                        let mode_taken = std::mem::replace(&mut self.mode, Mode::Normal);
-                        if let Mode::SearchResults {
-                            results,
-                            mut selected,
-                        } = mode_taken
-                        {
-                            match key.code {
-                                KeyCode::Up | KeyCode::Char('k') => {
-                                    if selected > 0 {
-                                        selected -= 1;
-                                    }
-                                    self.mode = Mode::SearchResults { results, selected };
-                                }
-                                KeyCode::Down | KeyCode::Char('j') => {
-                                    if selected + 1 < results.len() {
-                                        selected += 1;
-                                    }
-                                    self.mode = Mode::SearchResults { results, selected };
-                                }
-                                KeyCode::Enter => {
-                                    if let Some(name) = results.get(selected).cloned() {
-                                        self.mode = Mode::Confirm {
-                                            action: ConfirmAction::Install,
-                                            name,
-                                            idx: None,
-                                        };
-                                    } else {
-                                        self.mode = Mode::Normal;
-                                    }
-                                }
-                                KeyCode::Esc | KeyCode::Char('?') => {
-                                    self.mode = Mode::Normal;
-                                }
-                                _ => {
-                                    self.mode = Mode::SearchResults { results, selected };
-                                }
-                            }
-                        }
-                    } else if let Mode::Outdated { .. } = &self.mode {
-                        // Outdated modal handling
-                        let mode_taken = std::mem::replace(&mut self.mode, Mode::Normal);
-                        if let Mode::Outdated {
-                            packages,
-                            mut cursor,
-                            mut checked,
-                            scroll,
-                        } = mode_taken
-                        {
-                            match key.code {
-                                KeyCode::Esc | KeyCode::Char('?') => {}
-                                KeyCode::Up | KeyCode::Char('k') => {
-                                    if cursor > 0 {
-                                        cursor -= 1
-                                    }
-                                    self.mode = Mode::Outdated {
-                                        packages,
-                                        cursor,
-                                        checked,
-                                        scroll,
-                                    };
-                                }
-                                KeyCode::Down | KeyCode::Char('j') => {
-                                    if cursor + 1 < packages.len() {
-                                        cursor += 1
-                                    }
-                                    self.mode = Mode::Outdated {
-                                        packages,
-                                        cursor,
-                                        checked,
-                                        scroll,
-                                    };
-                                }
-                                KeyCode::Char(' ') => {
-                                    if cursor < checked.len() {
-                                        checked[cursor] = !checked[cursor];
-                                    }
-                                    self.mode = Mode::Outdated {
-                                        packages,
-                                        cursor,
-                                        checked,
-                                        scroll,
-                                    };
-                                }
-                                KeyCode::Enter => {
-                                    let to_upgrade: Vec<String> = packages
-                                        .iter()
-                                        .enumerate()
-                                        .filter_map(|(i, p)| {
-                                            if checked.get(i).copied().unwrap_or(false) {
-                                                Some(p.clone())
-                                            } else {
-                                                None
-                                            }
-                                        })
-                                        .collect();
-                                    if !to_upgrade.is_empty() {
-                                        let name = if to_upgrade.len() == 1 {
-                                            to_upgrade[0].clone()
-                                        } else {
-                                            format!("{} packages", to_upgrade.len())
-                                        };
-                                        self.mode = Mode::Confirm {
-                                            action: ConfirmAction::BulkUpgrade(to_upgrade),
-                                            name,
-                                            idx: None,
-                                        };
-                                    } else {
-                                        self.mode = Mode::Outdated {
-                                            packages,
-                                            cursor,
-                                            checked,
-                                            scroll,
-                                        };
-                                    }
-                                }
-                                _ => {
-                                    self.mode = Mode::Outdated {
-                                        packages,
-                                        cursor,
-                                        checked,
-                                        scroll,
-                                    };
-                                }
-                            }
-                        }
-                    } else {
-                        // Normal mode handling
-                        match key.code {
-                            KeyCode::Char('R') => {
-                                let tx = self.tx.clone();
-                                let brew = self.brew.clone();
-                                thread::spawn(move || match brew.outdated() {
-                                    Ok(list) => {
-                                        let _ = tx.send(AppEvent::OutdatedList(list));
-                                    }
-                                    Err(e) => {
-                                        let _ = tx.send(AppEvent::Log(format!(
-                                            "outdated check failed: {}",
-                                            e
-                                        )));
-                                    }
-                                });
-                            }
-                            KeyCode::Char('o') => {
-                                let packages = self.outdated_items.clone();
-                                let checked = vec![false; packages.len()];
-                                self.mode = Mode::Outdated {
-                                    packages,
-                                    cursor: 0,
-                                    checked,
-                                    scroll: 0,
-                                };
-                            }
-                            KeyCode::Char('q') => return Ok(()),
-                            KeyCode::Char('?') => {
-                                self.mode = Mode::Help;
-                            }
-                            KeyCode::Tab => {
-                                self.focus = match self.focus {
-                                    Focus::Installed => Focus::Available,
-                                    Focus::Available => Focus::Installed,
-                                };
-                            }
-                            KeyCode::Down | KeyCode::Char('j') => {
-                                if self.focus == Focus::Installed {
-                                    if self.selected + 1 < self.items.len() {
-                                        self.selected += 1;
-                                    }
-                                } else {
-                                    if !self.available_filtered.is_empty() {
-                                        if let Some(pos) = self
-                                            .available_filtered
-                                            .iter()
-                                            .position(|&idx| idx == self.available_selected)
-                                        {
-                                            let next_pos =
-                                                (pos + 1).min(self.available_filtered.len() - 1);
-                                            self.available_selected =
-                                                self.available_filtered[next_pos];
-                                        } else {
-                                            self.available_selected = self.available_filtered[0];
-                                        }
-                                    }
-                                }
-                            }
-                            KeyCode::Up | KeyCode::Char('k') => {
-                                if self.focus == Focus::Installed {
-                                    if self.selected > 0 {
-                                        self.selected -= 1;
-                                    }
-                                } else {
-                                    if !self.available_filtered.is_empty() {
-                                        if let Some(pos) = self
-                                            .available_filtered
-                                            .iter()
-                                            .position(|&idx| idx == self.available_selected)
-                                        {
-                                            let prev_pos = pos.saturating_sub(1);
-                                            self.available_selected =
-                                                self.available_filtered[prev_pos];
-                                        } else {
-                                            self.available_selected =
-                                                *self.available_filtered.last().unwrap();
-                                        }
-                                    }
-                                }
-                            }
-                            KeyCode::Char('r') => {
-                                if let Some(f) = self.items.get(self.selected) {
-                                    self.mode = Mode::Confirm {
-                                        action: ConfirmAction::Uninstall,
-                                        name: f.name.clone(),
-                                        idx: Some(self.selected),
-                                    };
-                                }
-                            }
-                            KeyCode::Char('u') => {
-                                if let Some(f) = self.items.get(self.selected) {
-                                    self.mode = Mode::Confirm {
-                                        action: ConfirmAction::Upgrade,
-                                        name: f.name.clone(),
-                                        idx: Some(self.selected),
-                                    };
-                                }
-                            }
-                            KeyCode::Char('i') => {
-                                self.mode = Mode::Input {
-                                    action: InputAction::Install,
-                                    buffer: String::new(),
-                                };
-                            }
-                            KeyCode::Char('s') => {
-                                self.mode = Mode::Input {
-                                    action: InputAction::Search,
-                                    buffer: String::new(),
-                                };
-                            }
-                            KeyCode::Char('f') => {
-                                self.mode = Mode::Input {
-                                    action: InputAction::Search,
-                                    buffer: self.available_filter.clone(),
-                                };
-                                self.focus = Focus::Available;
-                            }
-                            KeyCode::Char('F') => {
-                                self.available_filter.clear();
-                                self.available_filtered = (0..self.available_items.len()).collect();
-                            }
-                            KeyCode::Enter => {
-                                if self.focus == Focus::Installed {
-                                    if let Some(f) = self.items.get(self.selected) {
-                                        let name = f.name.clone();
-                                        self.mode = Mode::Confirm {
-                                            action: ConfirmAction::Uninstall,
-                                            name: name.clone(),
-                                            idx: Some(self.selected),
-                                        };
-                                        if self.last_selected
-                                            != Some((Focus::Installed, self.selected))
-                                        {
-                                            let tx = self.tx.clone();
-                                            let mut brew = self.brew.clone();
-                                            let idx = self.selected;
-                                            self.last_selected = Some((Focus::Installed, idx));
-                                            thread::spawn(move || match brew.info(&name) {
-                                                Ok(info) => {
-                                                    let _ = tx.send(AppEvent::BrewInfo(info, idx));
-                                                }
-                                                Err(e) => {
-                                                    let _ = tx.send(AppEvent::Log(format!(
-                                                        "Info failed: {}",
-                                                        e
-                                                    )));
-                                                }
-                                            });
-                                        }
-                                    }
-                                } else {
-                                    if let Some(name) =
-                                        self.available_items.get(self.available_selected)
-                                    {
-                                        let name = name.clone();
-                                        self.mode = Mode::Confirm {
-                                            action: ConfirmAction::Install,
-                                            name: name.clone(),
-                                            idx: Some(self.available_selected),
-                                        };
-                                        if self.last_selected
-                                            != Some((Focus::Available, self.available_selected))
-                                        {
-                                            let tx = self.tx.clone();
-                                            let mut brew = self.brew.clone();
-                                            let idx = self.available_selected;
-                                            self.last_selected = Some((Focus::Available, idx));
-                                            thread::spawn(move || match brew.info(&name) {
-                                                Ok(info) => {
-                                                    let _ = tx.send(AppEvent::BrewInfoAvailable(
-                                                        info, idx,
-                                                    ));
-                                                }
-                                                Err(e) => {
-                                                    let _ = tx.send(AppEvent::Log(format!(
-                                                        "Info failed: {}",
-                                                        e
-                                                    )));
-                                                }
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                            _ => {}
+        self.status = format!(
+            "Installed: {}  Available: {}  Focus: {}  Selected: {} {}  Mode: {}  Logs: {}",
+            self.items.len(),
+            self.available_items.len(),
+            focus_str,
+            if selected_name.is_empty() {
+                "-"
+            } else {
+                &selected_name
+            },
+            selected_installed,
+            mode_str,
+            recent_logs
+        );
+    }
+
+    pub fn run(&mut self, terminal: &mut Tui) -> Result<()> {
+        loop {
+            if crate::signals::termination_request().is_some() {
+                self.save_cache();
+                self.save_session();
+                return Ok(());
+            }
+            if self.tick() {
+                draw_ui(terminal, self)?;
+                if self.config.terminal.title {
+                    let title = self.window_title();
+                    if title != self.window_title_shown {
+                        if let Err(e) = crate::terminal::set_title(&title) {
+                            tracing::debug!("setting the terminal title failed: {:#}", e);
                         }
+                        self.window_title_shown = title;
                     }
                 }
+                if std::mem::take(&mut self.ring_bell) {
+                    use std::io::Write;
+                    let mut out = std::io::stdout();
+                    let _ = out.write_all(b"\x07").and_then(|_| out.flush());
+                }
             }
 
-            // automatic details loading
-            match self.focus {
-                Focus::Installed => {
-                    if !self.items.is_empty() {
-                        if self.last_selected != Some((Focus::Installed, self.selected)) {
-                            let idx = self.selected;
-                            if let Some(f) = self.items.get(idx) {
-                                let name = f.name.clone();
-                                let tx = self.tx.clone();
-                                let mut brew = self.brew.clone();
-                                self.last_selected = Some((Focus::Installed, idx));
-                                thread::spawn(move || match brew.info(&name) {
-                                    Ok(info) => {
-                                        let _ = tx.send(AppEvent::BrewInfo(info, idx));
-                                    }
-                                    Err(e) => {
-                                        let _ =
-                                            tx.send(AppEvent::Log(format!("Info failed: {}", e)));
-                                    }
-                                });
-                            }
-                        }
+            // with events still queued, only check for input before the next frame
+            let wait = if self.bus.has_backlog() {
+                Duration::ZERO
+            } else if let Some(until) = self.flash_until {
+                // end the flash on time
+                until.saturating_duration_since(Instant::now())
+            } else {
+                Duration::from_millis(200)
+            };
+            if event::poll(wait)? {
+                // any terminal event (keys, resize) may change what is on screen
+                self.redraw = true;
+                match event::read()? {
+                    Event::Key(key) if crate::terminal::is_suspend_key(&key) => {
+                        crate::terminal::suspend(terminal)?;
+                        // the shell had its own title meanwhile
+                        self.window_title_shown.clear();
                     }
-                }
-                Focus::Available => {
-                    if !self.available_items.is_empty() {
-                        if self.last_selected != Some((Focus::Available, self.available_selected)) {
-                            let idx = self.available_selected;
-                            if let Some(name) = self.available_items.get(idx) {
-                                let name = name.clone();
-                                let tx = self.tx.clone();
-                                let mut brew = self.brew.clone();
-                                self.last_selected = Some((Focus::Available, idx));
-                                thread::spawn(move || match brew.info(&name) {
-                                    Ok(info) => {
-                                        let _ = tx.send(AppEvent::BrewInfoAvailable(info, idx));
-                                    }
-                                    Err(e) => {
-                                        let _ =
-                                            tx.send(AppEvent::Log(format!("Info failed: {}", e)));
-                                    }
-                                });
-                            }
-                        }
+                    Event::Key(key) if self.inject_key(key) => return Ok(()),
+                    Event::Mouse(ev) => self.handle_mouse(ev),
+                    Event::FocusGained => self.focused = true,
+                    Event::FocusLost => self.focused = false,
+                    Event::Resize(width, height) => {
+                        // lay the next frame out for the new size right away
+                        let size = Rect::new(0, 0, width, height);
+                        terminal.resize(size)?;
+                        self.handle_resize(size);
                     }
+                    _ => {}
                 }
             }
         }
     }
 }
+
+/// Dry-run `brew cleanup` in the background; the result opens the Cleanup modal.
+fn submit_cleanup_preview(
+    jobs: &JobPool,
+    brew: &Arc<dyn BrewBackend>,
+    tx: &mpsc::Sender<AppEvent>,
+) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.cleanup_preview() {
+        Ok(preview) => {
+            let _ = tx.send(AppEvent::CleanupPreview(preview));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error(
+                "cleanup preview failed",
+                &e,
+            )));
+        }
+    });
+}
+
+/// Run `brew doctor` in the background; findings open the Doctor modal.
+fn submit_doctor(jobs: &JobPool, brew: &Arc<dyn BrewBackend>, tx: &mpsc::Sender<AppEvent>) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.doctor() {
+        Ok(items) => {
+            let _ = tx.send(AppEvent::Doctor(items));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error("brew doctor failed", &e)));
+        }
+    });
+}
+
+/// Find out in the background which dependencies of `name` are not in `installed` and how
+/// much their bottles weigh; a failed size lookup only leaves the sizes out.
+fn submit_dependency_preview(
+    jobs: &JobPool,
+    brew: &Arc<dyn BrewBackend>,
+    tx: &mpsc::Sender<AppEvent>,
+    name: String,
+    installed: HashSet<String>,
+) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.dependencies(&name) {
+        Ok(deps) => {
+            let new: Vec<String> = deps
+                .iter()
+                .filter(|d| !installed.contains(*d))
+                .cloned()
+                .collect();
+            let sizes = brew.bottle_sizes(&new).unwrap_or_default();
+            let preview = DependencyPreview::new(&name, deps, &installed, &sizes);
+            let _ = tx.send(AppEvent::Dependencies(preview));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error("brew deps failed", &e)));
+        }
+    });
+}
+
+/// Fetch the tap history of `name` in the background; the result opens the Formula history
+/// modal.
+fn submit_formula_log(
+    jobs: &JobPool,
+    brew: &Arc<dyn BrewBackend>,
+    tx: &mpsc::Sender<AppEvent>,
+    name: String,
+) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.formula_log(&name) {
+        Ok(commits) => {
+            let _ = tx.send(AppEvent::FormulaLog(name, commits));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error("brew log failed", &e)));
+        }
+    });
+}
+
+/// Run `brew livecheck` for `names` (every installed leaf when empty) in the background; the
+/// result opens the Livecheck modal.
+fn submit_livecheck(
+    jobs: &JobPool,
+    brew: &Arc<dyn BrewBackend>,
+    tx: &mpsc::Sender<AppEvent>,
+    names: Vec<String>,
+) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.livecheck(&names) {
+        Ok(results) => {
+            let _ = tx.send(AppEvent::Livecheck(results));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error("brew livecheck failed", &e)));
+        }
+    });
+}
+
+/// Load the list shown in `tab` in the background; the other tabs show data the app already
+/// has.
+fn submit_tab_list(
+    jobs: &JobPool,
+    brew: &Arc<dyn BrewBackend>,
+    tx: &mpsc::Sender<AppEvent>,
+    tab: Tab,
+) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| {
+        let (ev, what) = match tab {
+            Tab::Casks => (brew.casks().map(AppEvent::CaskList), "casks"),
+            Tab::Services => (brew.services().map(AppEvent::ServiceList), "services"),
+            Tab::Taps => (brew.taps().map(AppEvent::TapList), "taps"),
+            Tab::Formulae | Tab::Outdated | Tab::Logs => return,
+        };
+        let ev = ev.unwrap_or_else(|e| {
+            AppEvent::Error(describe_error(&format!("loading {} failed", what), &e))
+        });
+        let _ = tx.send(ev);
+    });
+}
+
+/// Find the build logs of the most recently built of `names` in the background. They open in
+/// the log viewer, or with `upload` in the confirmation for `brew gist-logs`.
+fn submit_build_logs(
+    jobs: &JobPool,
+    brew: &Arc<dyn BrewBackend>,
+    tx: &mpsc::Sender<AppEvent>,
+    names: Vec<String>,
+    upload: bool,
+) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.build_logs(&names) {
+        Ok(logs) if upload => {
+            let _ = tx.send(AppEvent::ShowConfirm(
+                ConfirmAction::GistLogs,
+                logs.name,
+                None,
+            ));
+        }
+        Ok(logs) => {
+            let _ = tx.send(AppEvent::BuildLogs(logs));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error(
+                "reading build logs failed",
+                &e,
+            )));
+        }
+    });
+}
+
+/// Measure the Cellar in the background; the result opens the Disk usage modal.
+fn submit_disk_usage(jobs: &JobPool, brew: &Arc<dyn BrewBackend>, tx: &mpsc::Sender<AppEvent>) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.disk_usage() {
+        Ok(usage) => {
+            let _ = tx.send(AppEvent::DiskUsage(usage));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error(
+                "disk usage scan failed",
+                &e,
+            )));
+        }
+    });
+}
+
+/// Measure the download cache in the background; the result opens the Download cache modal.
+fn submit_download_cache(jobs: &JobPool, brew: &Arc<dyn BrewBackend>, tx: &mpsc::Sender<AppEvent>) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.download_cache() {
+        Ok(cache) => {
+            let _ = tx.send(AppEvent::DownloadCache(cache));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error(
+                "download cache scan failed",
+                &e,
+            )));
+        }
+    });
+}
+
+/// Collect `brew config` and `brew --env` in the background for the Environment panel.
+fn submit_environment(jobs: &JobPool, brew: &Arc<dyn BrewBackend>, tx: &mpsc::Sender<AppEvent>) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.environment() {
+        Ok(env) => {
+            let _ = tx.send(AppEvent::Environment(env));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error("brew config failed", &e)));
+        }
+    });
+}
+
+/// Take the operation `id` off the persisted queue. Failing to save the queue only costs an
+/// offer to resume it again on the next launch.
+fn dequeue(queue: &Mutex<OperationQueue>, id: u64) {
+    let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+    queue.remove(id);
+    if let Err(e) = queue.save() {
+        tracing::warn!("failed to save the operation queue: {:#}", e);
+    }
+}
+
+/// Queue a `brew outdated` check reporting its result to the UI.
+fn submit_outdated_check(jobs: &JobPool, brew: &Arc<dyn BrewBackend>, tx: &mpsc::Sender<AppEvent>) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.outdated() {
+        Ok(list) => {
+            let _ = tx.send(AppEvent::OutdatedList(list));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error("outdated check failed", &e)));
+        }
+    });
+}
+
+/// Load what the Installed table shows beyond names: every formula's details in one `brew
+/// info` call (also stored in the metadata cache) and the packages' sizes in the Cellar.
+fn submit_installed_columns(
+    jobs: &JobPool,
+    brew: &Arc<dyn BrewBackend>,
+    tx: &mpsc::Sender<AppEvent>,
+    cache: &Arc<Mutex<MetadataCache>>,
+) {
+    let (brew_sizes, tx_sizes) = (Arc::clone(brew), tx.clone());
+    jobs.submit(JobKind::Refresh, move |_| {
+        if let Ok(usage) = brew_sizes.disk_usage() {
+            let _ = tx_sizes.send(AppEvent::InstalledSizes(usage));
+        }
+    });
+    let (brew, tx, cache) = (Arc::clone(brew), tx.clone(), Arc::clone(cache));
+    jobs.submit(JobKind::Refresh, move |_| match brew.installed_details() {
+        Ok(list) => {
+            if let Ok(mut c) = cache.lock() {
+                let now = SystemTime::now();
+                for info in &list {
+                    c.set_info(info.clone(), now);
+                }
+            }
+            let _ = tx.send(AppEvent::InstalledDetails(list));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error(
+                "loading installed details failed",
+                &e,
+            )));
+            // for the table to stop waiting for them
+            let _ = tx.send(AppEvent::InstalledDetails(vec![]));
+        }
+    });
+}
+
+/// Queue a `brew list --pinned` check reporting its result to the UI.
+fn submit_pinned_check(jobs: &JobPool, brew: &Arc<dyn BrewBackend>, tx: &mpsc::Sender<AppEvent>) {
+    let brew = Arc::clone(brew);
+    let tx = tx.clone();
+    jobs.submit(JobKind::Refresh, move |_| match brew.pinned() {
+        Ok(list) => {
+            let _ = tx.send(AppEvent::PinnedList(list));
+        }
+        Err(e) => {
+            let _ = tx.send(AppEvent::Error(describe_error("pinned check failed", &e)));
+        }
+    });
+}
+
+/// Send the Available list to the UI, from the cache while it is fresh. Once it expires the index
+/// is refreshed, and read again only when it changed since the list was cached; either way the
+/// list is then known to be current, which `AppEvent::IndexFresh` reports.
+pub fn load_available(
+    brew: &dyn BrewBackend,
+    cache: &Mutex<MetadataCache>,
+    tx: &mpsc::Sender<AppEvent>,
+) {
+    let cached = cache.lock().ok().and_then(|c| c.available.clone());
+    let since = cached.as_ref().map(|c| c.fetched_at);
+    if let Some(cached) = cached {
+        let fresh = cached.is_fresh(cache::AVAILABLE_TTL, SystemTime::now());
+        let _ = tx.send(AppEvent::IndexFresh(cached.fetched_at));
+        send_available(tx, cached.value.clone());
+        if fresh {
+            return;
+        }
+        let _ = tx.send(AppEvent::Status("checking the package index".to_string()));
+        match brew.refresh_index(since) {
+            Ok(true) => {}
+            Ok(false) => {
+                // unchanged: the cached list is current again
+                let now = SystemTime::now();
+                save_available(cache, cached.value, now, tx);
+                let _ = tx.send(AppEvent::IndexFresh(cache::unix_secs(now)));
+                return;
+            }
+            Err(e) => {
+                let _ = tx.send(AppEvent::Error(describe_error("index refresh failed", &e)));
+            }
+        }
+    } else if let Err(e) = brew.refresh_index(None) {
+        let _ = tx.send(AppEvent::Error(describe_error("index refresh failed", &e)));
+    }
+    let _ = tx.send(AppEvent::Status("loading available".to_string()));
+    if let Ok(entries) = brew.available_entries() {
+        let now = SystemTime::now();
+        save_available(cache, entries.clone(), now, tx);
+        let _ = tx.send(AppEvent::IndexFresh(cache::unix_secs(now)));
+        send_available(tx, entries);
+    }
+}
+
+fn save_available(
+    cache: &Mutex<MetadataCache>,
+    entries: Vec<ApiEntry>,
+    now: SystemTime,
+    tx: &mpsc::Sender<AppEvent>,
+) {
+    if let Ok(mut c) = cache.lock() {
+        c.set_available(entries, now);
+        if let Err(e) = c.save() {
+            let _ = tx.send(AppEvent::Error(format!("cache save failed: {}", e)));
+        }
+    }
+}
+
+/// Send an Available list (and the descriptions it carries) to the UI.
+fn send_available(tx: &mpsc::Sender<AppEvent>, entries: Vec<ApiEntry>) {
+    let mut descriptions = HashMap::new();
+    let names = entries
+        .into_iter()
+        .map(|e| {
+            if let Some(desc) = e.desc {
+                descriptions.insert(e.name.clone(), desc);
+            }
+            e.name
+        })
+        .collect();
+    if !descriptions.is_empty() {
+        let _ = tx.send(AppEvent::AvailableDescriptions(descriptions));
+    }
+    let _ = tx.send(AppEvent::AvailableList(names));
+}
+
+/// What operation `command` does, for the terminal title: `upgrading ripgrep`, `installing 3
+/// packages`; commands other than brew's are shown as they are, cut short.
+fn activity(command: &str) -> String {
+    if command == "install-homebrew" {
+        return "installing Homebrew".into();
+    }
+    let Some(sub) = crate::history::subcommand(command) else {
+        let mut s: String = command.chars().take(40).collect();
+        if s.len() < command.len() {
+            s.push_str("...");
+        }
+        return format!("running {}", s);
+    };
+    let packages = crate::history::packages(command);
+    let verb = match sub {
+        "install" => "installing",
+        "uninstall" | "remove" | "rm" => "uninstalling",
+        "reinstall" => "reinstalling",
+        "upgrade" if packages.is_empty() => return "upgrading all packages".into(),
+        "upgrade" => "upgrading",
+        "update" => return "updating Homebrew".into(),
+        "cleanup" => "cleaning up",
+        "autoremove" => return "removing unused dependencies".into(),
+        "postinstall" => "running the post-install of",
+        "link" => "linking",
+        "unlink" => "unlinking",
+        "pin" => "pinning",
+        "unpin" => "unpinning",
+        "gist-logs" => "uploading the logs of",
+        _ => return format!("running brew {}", sub),
+    };
+    match packages.len() {
+        0 => verb.into(),
+        1 | 2 => format!("{} {}", verb, packages.join(", ")),
+        n => format!("{} {} packages", verb, n),
+    }
+}
@@ -0,0 +1,38 @@
+// Cleanup modal: lists what `brew cleanup` would remove; `a` toggles `--prune=all` and
+// `y`/Enter runs the cleanup as an operation.
+use super::confirm::start_operation;
+use super::{App, ConfirmAction, Mode, Transition};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Cleanup {
+        preview,
+        prune_all,
+        scroll,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let last = preview.files.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('n') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *scroll = (*scroll + 1).min(last),
+        KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+        KeyCode::PageDown => *scroll = (*scroll + 10).min(last),
+        KeyCode::Home => *scroll = 0,
+        KeyCode::End => *scroll = last,
+        KeyCode::Char('a') => *prune_all = !*prune_all,
+        KeyCode::Char('y') | KeyCode::Enter => {
+            let action = if *prune_all {
+                ConfirmAction::CleanupPruneAll
+            } else {
+                ConfirmAction::Cleanup
+            };
+            start_operation(app, action, "cleanup".into());
+            return Transition::To(Mode::Normal);
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
@@ -0,0 +1,129 @@
+// Confirm modal: `y`/Enter runs the action as an operation job, `c` copies its command line,
+// `o` picks flags (`--verbose`, `--debug`, and more for installs and uninstalls), `e` sets
+// environment variables for this run alone, `n`/Esc cancels.
+use super::{dequeue, run_operation, App, AppEvent, ConfirmAction, InputAction, Mode, Transition};
+use crate::jobs::JobKind;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::sync::Arc;
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Confirm { action, name, .. } = &app.mode else {
+        return Transition::Stay;
+    };
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => {
+            let (action, name) = (action.clone(), name.clone());
+            if action.packages().is_some() {
+                app.marked.clear();
+            }
+            start_operation(app, action, name);
+            Transition::To(Mode::Normal)
+        }
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            let stages = app.operation_stages(action, name);
+            let line = |full: bool| {
+                stages
+                    .iter()
+                    .map(|(stage, name)| {
+                        if full {
+                            stage.full_command_line_with(name, app.brew.flags())
+                        } else {
+                            stage.command_line_with(name, app.brew.flags())
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            };
+            // the log keeps the variables' values out, like the operation title
+            match crate::clipboard::copy(&line(true)) {
+                Ok(()) => app.push_log(format!("Copied: {}", line(false))),
+                Err(e) => app.push_error(format!("Copy failed: {}", e)),
+            }
+            Transition::Stay
+        }
+        KeyCode::Char('o') | KeyCode::Char('O') if !action.flag_choices().is_empty() => {
+            let checked = action
+                .flag_choices()
+                .iter()
+                .map(|(flag, _)| action.flags().iter().any(|f| f == flag))
+                .collect();
+            Transition::Push(Mode::Flags {
+                action: action.clone(),
+                name: name.clone(),
+                cursor: 0,
+                checked,
+            })
+        }
+        KeyCode::Char('e') | KeyCode::Char('E') => {
+            let buffer = action
+                .env()
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Transition::Push(Mode::Input {
+                action: InputAction::OperationEnv,
+                buffer,
+            })
+        }
+        KeyCode::Char('n') | KeyCode::Esc => {
+            app.status = "Cancelled".into();
+            Transition::Back
+        }
+        _ => Transition::Stay,
+    }
+}
+
+/// Queue `action` on the operation lane, as the stages of `App::operation_stages` one after
+/// the other until one is cancelled; once one succeeded the Installed list is reloaded (after
+/// `brew update` only the outdated list is re-checked, when the app sees `OpEnd`, and a
+/// download cache purge changes no package; `brew gist-logs` changes nothing). Until it has
+/// ended it is kept in the persisted queue.
+pub(super) fn start_operation(app: &mut App, action: ConfirmAction, name: String) {
+    let id = app.enqueue(&action, &name);
+    let stages = app.operation_stages(&action, &name);
+    let queue = Arc::clone(&app.queue);
+    let tx = app.tx.clone();
+    let brew = Arc::clone(&app.brew);
+    let cache = Arc::clone(&app.cache);
+    let token = app
+        .jobs
+        .submit_async(JobKind::Operation, move |token| async move {
+            let mut succeeded = false;
+            for (stage, stage_name) in &stages {
+                let code = run_operation(brew.as_ref(), stage, stage_name, &tx, &token).await;
+                succeeded |= code == Some(0);
+                if code.is_none() {
+                    break;
+                }
+            }
+            dequeue(&queue, id);
+            if let ConfirmAction::InstallBrew | ConfirmAction::GistLogs = action.base() {
+                return;
+            }
+
+            // the changed packages' details are stale either way
+            let changed = match action.packages() {
+                Some(pkgs) => pkgs.to_vec(),
+                None if action.acts_on_package() => vec![name.clone()],
+                None => vec![],
+            };
+            if let Ok(mut c) = cache.lock() {
+                c.forget_info(&changed);
+            }
+            let reload = !matches!(
+                action.base(),
+                ConfirmAction::Update | ConfirmAction::PurgeCache { .. }
+            );
+            if succeeded && reload {
+                let _ = tokio::task::spawn_blocking(move || {
+                    if let Ok(list) = brew.list_installed() {
+                        let _ = tx.send(AppEvent::BrewList(list));
+                    }
+                })
+                .await;
+            }
+        });
+    app.op_cancel = Some(token);
+    app.op_queued = Some(id);
+}
@@ -0,0 +1,33 @@
+// Disk usage modal: installed packages by size, largest first. `r` asks to uninstall the
+// selected package.
+use super::{App, ConfirmAction, Mode, Transition, PAGE_ROWS};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::DiskUsage { usage, selected } = &mut app.mode else {
+        return Transition::Stay;
+    };
+    let last = usage.packages.len().saturating_sub(1);
+    let page = PAGE_ROWS as usize;
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('S') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last),
+        KeyCode::PageUp => *selected = selected.saturating_sub(page),
+        KeyCode::PageDown => *selected = (*selected + page).min(last),
+        KeyCode::Home => *selected = 0,
+        KeyCode::End => *selected = last,
+        KeyCode::Char('r') => {
+            if let Some(pkg) = usage.packages.get(*selected) {
+                let idx = app.items.iter().position(|f| f.name == pkg.name);
+                return Transition::Push(Mode::Confirm {
+                    action: ConfirmAction::Uninstall,
+                    name: pkg.name.clone(),
+                    idx,
+                });
+            }
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
@@ -0,0 +1,39 @@
+// Doctor modal: the findings of `brew doctor` as a checklist. Space ticks the selected finding
+// off, `c` copies it to the clipboard and Esc closes the modal.
+use super::{App, Mode, Transition};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Doctor {
+        items,
+        selected,
+        checked,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let last = items.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last),
+        KeyCode::Home => *selected = 0,
+        KeyCode::End => *selected = last,
+        KeyCode::Char(' ') => {
+            if let Some(c) = checked.get_mut(*selected) {
+                *c = !*c;
+            }
+        }
+        KeyCode::Char('c') => {
+            if let Some(item) = items.get(*selected) {
+                let (text, title) = (item.text(), item.title.clone());
+                match crate::clipboard::copy(&text) {
+                    Ok(()) => app.push_log(format!("Copied: {}", title)),
+                    Err(e) => app.push_error(format!("Copy failed: {}", e)),
+                }
+            }
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
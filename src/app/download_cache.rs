@@ -0,0 +1,65 @@
+// Download cache modal: what `brew --cache` holds; `a` purges all of it, `o` the files older
+// than the chosen age (`+`/`-` change it). Both go through the Confirm dialog.
+use super::{App, ConfirmAction, Mode, Transition};
+use crate::disk;
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::DownloadCache {
+        older_than_days,
+        scroll,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let Some(cache) = &app.download_cache else {
+        return Transition::Back;
+    };
+    let last = cache.entries.len().saturating_sub(1);
+    let purge = match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('K') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => {
+            *scroll = scroll.saturating_sub(1);
+            None
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            *scroll = (*scroll + 1).min(last);
+            None
+        }
+        KeyCode::PageUp => {
+            *scroll = scroll.saturating_sub(10);
+            None
+        }
+        KeyCode::PageDown => {
+            *scroll = (*scroll + 10).min(last);
+            None
+        }
+        KeyCode::Char('+') => {
+            *older_than_days += 1;
+            None
+        }
+        KeyCode::Char('-') => {
+            *older_than_days = older_than_days.saturating_sub(1).max(1);
+            None
+        }
+        KeyCode::Char('a') => Some(None),
+        KeyCode::Char('o') => Some(Some(*older_than_days)),
+        _ => None,
+    };
+    let Some(older_than_days) = purge else {
+        return Transition::Stay;
+    };
+    if cache.reclaimable(older_than_days, disk::now_secs()).1 == 0 {
+        let msg = match older_than_days {
+            Some(days) => format!("Nothing in the download cache is older than {} days", days),
+            None => "The download cache is empty".to_string(),
+        };
+        app.push_log(msg);
+        return Transition::Stay;
+    }
+    Transition::Push(Mode::Confirm {
+        action: ConfirmAction::PurgeCache { older_than_days },
+        name: cache.dir.display().to_string(),
+        idx: None,
+    })
+}
@@ -0,0 +1,34 @@
+// Environment panel: `brew config` and `brew --env`, scrolled with the usual keys. `a` asks to
+// turn Homebrew's analytics on or off.
+use super::{App, ConfirmAction, Mode, Transition};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Environment { env, scroll } = &mut app.mode else {
+        return Transition::Stay;
+    };
+    let last = env.config.len() + env.env.len();
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('E') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *scroll = (*scroll + 1).min(last),
+        KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+        KeyCode::PageDown => *scroll = (*scroll + 10).min(last),
+        KeyCode::Home => *scroll = 0,
+        KeyCode::End => *scroll = last,
+        KeyCode::Char('a') => {
+            let action = if env.analytics == Some(true) {
+                ConfirmAction::AnalyticsOff
+            } else {
+                ConfirmAction::AnalyticsOn
+            };
+            return Transition::Push(Mode::Confirm {
+                action,
+                name: "analytics".into(),
+                idx: None,
+            });
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
@@ -0,0 +1,36 @@
+// Options overlay, opened with `o` from the confirm dialog of an install or uninstall: Space
+// toggles a flag, Enter hands the picked ones back to the confirm dialog (whose command line
+// then shows them) and Esc leaves its flags as they were.
+use super::{App, Mode, Transition};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Flags {
+        action,
+        cursor,
+        checked,
+        ..
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => *cursor = cursor.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') if *cursor + 1 < checked.len() => *cursor += 1,
+        KeyCode::Char(' ') => {
+            if let Some(c) = checked.get_mut(*cursor) {
+                *c = !*c;
+            }
+        }
+        KeyCode::Enter => {
+            let action = action.with_flags(action.checked_flags(checked));
+            if let Some(Mode::Confirm { action: a, .. }) = app.nav_stack.last_mut() {
+                *a = action;
+            }
+            return Transition::Back;
+        }
+        KeyCode::Esc | KeyCode::Char('q') => return Transition::Back,
+        _ => {}
+    }
+    Transition::Stay
+}
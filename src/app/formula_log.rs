@@ -0,0 +1,35 @@
+// Formula history modal: the latest commits to a formula in its tap, newest first, with those
+// made since the installed version marked. `c` copies the selected commit.
+use super::{App, Mode, Transition, PAGE_ROWS};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::FormulaLog {
+        commits, selected, ..
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let last = commits.len().saturating_sub(1);
+    let page = PAGE_ROWS as usize;
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last),
+        KeyCode::PageUp => *selected = selected.saturating_sub(page),
+        KeyCode::PageDown => *selected = (*selected + page).min(last),
+        KeyCode::Home => *selected = 0,
+        KeyCode::End => *selected = last,
+        KeyCode::Char('c') => {
+            if let Some(commit) = commits.get(*selected) {
+                let text = format!("{} {}", commit.hash, commit.subject);
+                match crate::clipboard::copy(&text) {
+                    Ok(()) => app.push_log(format!("Copied: {}", text)),
+                    Err(e) => app.push_error(format!("Copy failed: {}", e)),
+                }
+            }
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
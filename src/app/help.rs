@@ -0,0 +1,54 @@
+// Help modal: scrolled with the usual keys, `/` types a search that narrows it to the matching
+// keys. Esc clears a search before it closes the modal.
+use super::{App, Mode, Transition, PAGE_ROWS};
+use crate::help;
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let sections = help::sections(&app.keymap, app.symbols);
+    let Mode::Help {
+        scroll,
+        query,
+        searching,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    if *searching {
+        match key.code {
+            KeyCode::Enter => *searching = false,
+            KeyCode::Esc => {
+                query.clear();
+                *searching = false;
+            }
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) => query.push(c),
+            _ => {}
+        }
+        *scroll = 0;
+        return Transition::Stay;
+    }
+    let last = help::lines(&sections, query).len().saturating_sub(1);
+    let page = PAGE_ROWS as usize;
+    match key.code {
+        KeyCode::Esc if !query.is_empty() => {
+            query.clear();
+            *scroll = 0;
+        }
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => return Transition::Back,
+        KeyCode::Char('/') => {
+            query.clear();
+            *searching = true;
+        }
+        KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *scroll = (*scroll + 1).min(last),
+        KeyCode::PageUp => *scroll = scroll.saturating_sub(page),
+        KeyCode::PageDown => *scroll = (*scroll + page).min(last),
+        KeyCode::Home => *scroll = 0,
+        KeyCode::End => *scroll = last,
+        _ => {}
+    }
+    Transition::Stay
+}
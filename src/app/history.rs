@@ -0,0 +1,60 @@
+// History modal: browse past operations (newest first) and open one's captured log. For a
+// failed install or upgrade, `l` opens the build logs brew kept and `g` uploads them with
+// `brew gist-logs`. `s` saves the selected operation's log to a file.
+use super::{submit_build_logs, App, Mode, Transition};
+use crate::history::export_log;
+use crate::output::LogLine;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::path::PathBuf;
+
+impl App {
+    /// Write the log of `command` to a timestamped file in the working directory and show
+    /// where it went.
+    pub(super) fn export_operation_log(&mut self, command: &str, logs: &[LogLine], stamp: u64) {
+        let dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        match export_log(command, logs, &dir, stamp) {
+            Ok(path) => self.show_toast(format!("Log saved to {}", path.display())),
+            Err(e) => self.push_error(format!("Could not save the log: {:#}", e)),
+        }
+    }
+}
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::History { selected } = &mut app.mode else {
+        return Transition::Stay;
+    };
+    let total = app.history.records.len();
+    let record = total
+        .checked_sub(*selected + 1)
+        .and_then(|i| app.history.records.get(i));
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('H') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') if *selected + 1 < total => *selected += 1,
+        KeyCode::Enter => {
+            if let Some(rec) = record {
+                return Transition::Push(Mode::Operation {
+                    title: format!("History: {}", rec.command),
+                    logs: rec.logs.clone(),
+                    scroll: 0,
+                    live: false,
+                });
+            }
+        }
+        KeyCode::Char(c @ ('l' | 'g')) => {
+            let packages = record.map(|r| r.failed_packages()).unwrap_or_default();
+            if packages.is_empty() {
+                app.status = "Build logs are kept for failed installs and upgrades".into();
+            } else {
+                submit_build_logs(&app.jobs, &app.brew, &app.tx, packages, c == 'g');
+            }
+        }
+        KeyCode::Char('s') => {
+            if let Some(rec) = record.cloned() {
+                app.export_operation_log(&rec.command, &rec.logs, rec.started_at);
+            }
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
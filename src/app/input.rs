@@ -0,0 +1,143 @@
+// Input prompt: edits the buffer in place. A Search prompt filters the Available list while
+// typing; Enter installs the typed name (looking up which tap it is in first when it is not a
+// known package), runs the search, finds the text in the log, saves the note or hands the
+// variables back to the confirm dialog.
+use super::{describe_error, App, AppEvent, ConfirmAction, Focus, InputAction, Mode, Transition};
+use crate::brew::install_candidates;
+use crate::event::parse_env;
+use crate::jobs::JobKind;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::sync::Arc;
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Input { action, buffer } = &mut app.mode else {
+        return Transition::Stay;
+    };
+    let search = matches!(action, InputAction::Search);
+    match key.code {
+        KeyCode::Esc => {
+            app.status = "Cancelled input".into();
+            return Transition::Back;
+        }
+        KeyCode::Backspace => {
+            buffer.pop();
+            if search {
+                app.available_filter = buffer.clone();
+                app.apply_available_filter();
+            }
+        }
+        KeyCode::Char(c) => {
+            buffer.push(c);
+            if search {
+                app.available_filter = buffer.clone();
+                app.apply_available_filter();
+                if let Some(&idx) = app.available_filtered.first() {
+                    app.available_selected = idx;
+                }
+            }
+        }
+        KeyCode::Enter => {
+            let value = buffer.trim().to_string();
+            if let InputAction::ExtractVersion { name } = action {
+                if value.is_empty() {
+                    return Transition::Back;
+                }
+                return Transition::Push(Mode::Confirm {
+                    action: ConfirmAction::ExtractInstall { version: value },
+                    name: name.clone(),
+                    idx: None,
+                });
+            }
+            if let InputAction::Note { name } = action {
+                let name = name.clone();
+                app.save_note(&name, &value);
+                return Transition::Back;
+            }
+            if let InputAction::OperationEnv = action {
+                let env = match parse_env(&value) {
+                    Ok(env) => env,
+                    Err(e) => {
+                        app.status = e.to_string();
+                        return Transition::Stay;
+                    }
+                };
+                if let Some(Mode::Confirm { action, .. }) = app.nav_stack.last_mut() {
+                    *action = action.with_env(env);
+                }
+                return Transition::Back;
+            }
+            if let InputAction::LogSearch = action {
+                app.search_logs(value);
+                return Transition::To(Mode::Normal);
+            }
+            return submit(app, value, search);
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
+
+fn submit(app: &mut App, value: String, search: bool) -> Transition {
+    if value.is_empty() {
+        return Transition::Back;
+    }
+    if !search {
+        return resolve_install(app, value);
+    }
+    if app.focus == Focus::Available {
+        app.available_filter = value;
+        app.apply_available_filter();
+        if let Some(&idx) = app.available_filtered.first() {
+            app.available_selected = idx;
+        }
+    } else {
+        let tx = app.tx.clone();
+        let brew = Arc::clone(&app.brew);
+        app.jobs
+            .submit(JobKind::Refresh, move |_| match brew.search(&value) {
+                Ok(results) => {
+                    let _ = tx.send(AppEvent::SearchResults(results));
+                }
+                Err(e) => {
+                    let _ = tx.send(AppEvent::Error(describe_error("Search failed", &e)));
+                }
+            });
+    }
+    Transition::To(Mode::Normal)
+}
+
+/// A name of the Available list is installed as typed; anything else is searched for by its
+/// short name, and the results say whether it is in one tap or several
+/// (`AppEvent::InstallCandidates`).
+fn resolve_install(app: &mut App, value: String) -> Transition {
+    if app
+        .available_items
+        .iter()
+        .any(|n| n.eq_ignore_ascii_case(&value))
+    {
+        return Transition::Push(Mode::Confirm {
+            action: ConfirmAction::Install,
+            name: value,
+            idx: None,
+        });
+    }
+    app.status = format!("Looking up '{}'...", value);
+    let tx = app.tx.clone();
+    let brew = Arc::clone(&app.brew);
+    app.jobs.submit(JobKind::Refresh, move |_| {
+        let short = value.rsplit('/').next().unwrap_or(&value);
+        match brew.search(short) {
+            Ok(results) => {
+                let names = install_candidates(&value, &results);
+                let _ = tx.send(AppEvent::InstallCandidates {
+                    query: value,
+                    names,
+                });
+            }
+            Err(e) => {
+                let _ = tx.send(AppEvent::Error(describe_error("Search failed", &e)));
+            }
+        }
+    });
+    Transition::To(Mode::Normal)
+}
@@ -0,0 +1,30 @@
+// Install candidates modal: the name typed in the Install prompt is in several taps; pick the
+// one meant and confirm installing it under its full name.
+use super::{App, ConfirmAction, Mode, Transition};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::InstallCandidates {
+        names, selected, ..
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') if *selected + 1 < names.len() => *selected += 1,
+        KeyCode::Enter => {
+            return match names.get(*selected).cloned() {
+                Some(name) => Transition::Push(Mode::Confirm {
+                    action: ConfirmAction::Install,
+                    name,
+                    idx: None,
+                }),
+                None => Transition::Back,
+            };
+        }
+        KeyCode::Esc | KeyCode::Char('q') => return Transition::Back,
+        _ => {}
+    }
+    Transition::Stay
+}
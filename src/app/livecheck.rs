@@ -0,0 +1,37 @@
+// Livecheck modal: the upstream version `brew livecheck` found next to the one brew packages,
+// formulae where brew lags behind first. `c` copies the selected result.
+use super::{App, Mode, Transition, PAGE_ROWS};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Livecheck { results, selected } = &mut app.mode else {
+        return Transition::Stay;
+    };
+    let last = results.len().saturating_sub(1);
+    let page = PAGE_ROWS as usize;
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last),
+        KeyCode::PageUp => *selected = selected.saturating_sub(page),
+        KeyCode::PageDown => *selected = (*selected + page).min(last),
+        KeyCode::Home => *selected = 0,
+        KeyCode::End => *selected = last,
+        KeyCode::Char('c') => {
+            if let Some(r) = results.get(*selected) {
+                let text = format!(
+                    "{}: brew {}, upstream {}",
+                    r.formula,
+                    r.current.as_deref().unwrap_or("?"),
+                    r.latest.as_deref().unwrap_or("?")
+                );
+                match crate::clipboard::copy(&text) {
+                    Ok(()) => app.push_log(format!("Copied: {}", text)),
+                    Err(e) => app.push_error(format!("Copy failed: {}", e)),
+                }
+            }
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
@@ -0,0 +1,183 @@
+// Logs filter: the Logs pane, tab and zoom show the lines at or above a level, optionally only
+// those of one operation. `v` cycles the level, `t` the operations that have lines, `X` clears
+// the log. In the Logs tab the search keys find text in the lines shown, Enter moves on to the
+// older match (the presets' match keys go either way) and Ctrl-w wraps long lines. Space starts
+// a range of lines at the selection and `c` copies the range, or the selected line, to the
+// clipboard.
+use super::{App, InputAction, Mode, Tab};
+use crate::keymap::Action;
+use crate::output::{match_ranges, Level, LogEntry};
+use std::ops::RangeInclusive;
+
+impl App {
+    /// The log entries the filter lets through, oldest first.
+    pub fn visible_logs(&self) -> Vec<&LogEntry> {
+        self.logs
+            .iter()
+            .filter(|l| l.level >= self.log_level)
+            .filter(|l| self.log_op.is_none() || l.op == self.log_op)
+            .collect()
+    }
+
+    /// Show everything, then only warnings and errors, then only errors.
+    pub(super) fn cycle_log_level(&mut self) {
+        self.log_level = match self.log_level {
+            Level::Info => Level::Warning,
+            Level::Warning => Level::Error,
+            Level::Error => Level::Info,
+        };
+        self.logs_filtered();
+    }
+
+    /// Show every operation's lines, then only those of the newest operation, the one before
+    /// and so on.
+    pub(super) fn cycle_log_op(&mut self) {
+        let mut ops: Vec<&String> = vec![];
+        for op in self.logs.iter().rev().filter_map(|l| l.op.as_ref()) {
+            if !ops.contains(&op) {
+                ops.push(op);
+            }
+        }
+        let next = match &self.log_op {
+            None => ops.first(),
+            Some(current) => ops
+                .iter()
+                .position(|op| *op == current)
+                .and_then(|i| ops.get(i + 1)),
+        };
+        self.log_op = next.map(|op| op.to_string());
+        self.logs_filtered();
+    }
+
+    pub(super) fn clear_logs(&mut self) {
+        self.logs.clear();
+        self.log_op = None;
+        self.logs_filtered();
+    }
+
+    /// Positions in `visible_logs` of the entries containing the search text.
+    pub fn log_matches(&self) -> Vec<usize> {
+        if self.log_search.is_empty() {
+            return vec![];
+        }
+        self.visible_logs()
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| !match_ranges(&l.message, &self.log_search).is_empty())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The search keys of the Logs tab. Returns false for other actions.
+    pub(super) fn perform_in_logs(&mut self, action: Action) -> bool {
+        match action {
+            Action::Search | Action::Filter => {
+                self.mode = Mode::Input {
+                    action: InputAction::LogSearch,
+                    buffer: self.log_search.clone(),
+                };
+            }
+            Action::SearchNext => self.step_log_match(true),
+            Action::SearchPrev => self.step_log_match(false),
+            Action::Open if !self.log_search.is_empty() => self.step_log_match(false),
+            Action::ClearFilter => self.log_search.clear(),
+            Action::Toggle => {
+                self.log_anchor = match self.log_anchor {
+                    Some(_) => None,
+                    None => Some(self.tab_selected),
+                };
+            }
+            Action::FormulaLog => self.copy_log_selection(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Search the log for `query`, starting at the newest match; an empty query ends the
+    /// search.
+    pub(super) fn search_logs(&mut self, query: String) {
+        self.log_search = query;
+        if self.log_search.is_empty() {
+            return;
+        }
+        match self.log_matches().last() {
+            Some(&idx) => self.tab_selected = idx,
+            None => self.push_log(format!("No log line contains \"{}\"", self.log_search)),
+        }
+    }
+
+    /// Positions in `visible_logs` of the lines to copy: from the range start to the selection,
+    /// or the selected line alone.
+    pub fn log_range(&self) -> RangeInclusive<usize> {
+        let last = self.visible_logs().len().saturating_sub(1);
+        let selected = self.tab_selected.min(last);
+        let anchor = self.log_anchor.unwrap_or(selected).min(last);
+        anchor.min(selected)..=anchor.max(selected)
+    }
+
+    /// The lines `log_range` covers, one per line.
+    pub fn log_selection_text(&self) -> String {
+        let logs = self.visible_logs();
+        let range = self.log_range();
+        logs.get(range)
+            .unwrap_or_default()
+            .iter()
+            .map(|l| l.message.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn copy_log_selection(&mut self) {
+        let text = self.log_selection_text();
+        if text.is_empty() {
+            return;
+        }
+        let lines = self.log_range().count();
+        match crate::clipboard::copy(&text) {
+            Ok(()) if lines == 1 => self.push_log(format!("Copied: {}", text)),
+            Ok(()) => self.push_log(format!("Copied {} log lines", lines)),
+            Err(e) => self.push_error(format!("Copy failed: {}", e)),
+        }
+        self.log_anchor = None;
+    }
+
+    /// Select the next match below the selection (`forward`) or above it, wrapping around.
+    fn step_log_match(&mut self, forward: bool) {
+        let matches = self.log_matches();
+        let current = self.tab_selected;
+        let next = if forward {
+            matches.iter().find(|&&i| i > current).or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|&&i| i < current)
+                .or(matches.last())
+        };
+        if let Some(&idx) = next {
+            self.tab_selected = idx;
+        }
+    }
+
+    /// The filter changed: start the Logs tab at the newest line and the zoom at the top.
+    fn logs_filtered(&mut self) {
+        self.log_anchor = None;
+        if self.tab == Tab::Logs {
+            self.tab_selected = self.visible_logs().len().saturating_sub(1);
+        }
+        self.zoom_scroll = 0;
+    }
+
+    /// What the log title says about the filter, like `errors, brew install wget`; empty while
+    /// everything is shown.
+    pub fn log_filter_label(&self) -> String {
+        let mut parts = vec![];
+        if self.log_level != Level::Info {
+            parts.push(self.log_level.name().to_string());
+        }
+        if let Some(op) = &self.log_op {
+            parts.push(op.clone());
+        }
+        parts.join(", ")
+    }
+}
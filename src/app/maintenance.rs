@@ -0,0 +1,150 @@
+// Maintenance modal and routine: the configured stages (`[maintenance] steps`) run one after
+// another as a single queued job, each streamed like any operation. Space picks the outdated
+// packages the upgrade stage includes; the routine stops at the first stage that fails and
+// ends with a summary of every stage.
+use super::{run_operation, App, AppEvent, ConfirmAction, MaintenanceStep, Mode, Transition};
+use crate::history::format_duration;
+use crate::jobs::JobKind;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::sync::Arc;
+use std::time::Instant;
+
+impl App {
+    /// Open the Maintenance modal with every outdated package that is not pinned selected for
+    /// the upgrade stage.
+    pub(super) fn open_maintenance(&mut self) {
+        let steps = self.config.maintenance.steps.clone();
+        if steps.is_empty() {
+            self.push_log("No maintenance steps configured ([maintenance] steps)".into());
+            return;
+        }
+        let packages = if steps.contains(&MaintenanceStep::Upgrade) {
+            self.outdated_items.clone()
+        } else {
+            vec![]
+        };
+        let checked = packages.iter().map(|p| !self.is_pinned(p)).collect();
+        self.mode = Mode::Maintenance {
+            steps,
+            packages,
+            checked,
+            cursor: 0,
+        };
+    }
+}
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Maintenance {
+        steps,
+        packages,
+        checked,
+        cursor,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let last = packages.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => {
+            app.status = "Cancelled".into();
+            return Transition::Back;
+        }
+        KeyCode::Up | KeyCode::Char('k') => *cursor = cursor.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *cursor = (*cursor + 1).min(last),
+        KeyCode::Char(' ') => {
+            let pinned = packages
+                .get(*cursor)
+                .filter(|p| app.pinned.contains(p))
+                .cloned();
+            if let Some(name) = pinned {
+                app.status = format!("{} is pinned; p in the Installed list unpins it", name);
+            } else if let Some(c) = checked.get_mut(*cursor) {
+                *c = !*c;
+            }
+        }
+        KeyCode::Char('y') | KeyCode::Enter => {
+            let selected = packages
+                .iter()
+                .zip(checked.iter())
+                .filter(|(_, &c)| c)
+                .map(|(p, _)| p.clone())
+                .collect();
+            let steps = steps.clone();
+            start_maintenance(app, &steps, selected);
+            return Transition::To(Mode::Normal);
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
+
+/// Queue the routine on the operation lane. Once a stage changed packages the Installed list
+/// is reloaded at the end.
+fn start_maintenance(app: &mut App, steps: &[MaintenanceStep], upgrade: Vec<String>) {
+    let stages: Vec<_> = steps.iter().filter_map(|s| s.operation(&upgrade)).collect();
+    if stages.is_empty() {
+        app.push_log("Maintenance: nothing to do without packages to upgrade".into());
+        return;
+    }
+    let tx = app.tx.clone();
+    let brew = Arc::clone(&app.brew);
+    let cache = Arc::clone(&app.cache);
+    let token = app
+        .jobs
+        .submit_async(JobKind::Operation, move |token| async move {
+            let started = Instant::now();
+            let mut results = vec![];
+            let mut ok = 0;
+            let mut failed = false;
+            let mut changed = false;
+            for (action, name) in &stages {
+                let command = action.command_line_with(name, brew.flags());
+                if failed {
+                    results.push(format!("  skipped  {}", command));
+                    continue;
+                }
+                let stage_started = Instant::now();
+                let code = run_operation(brew.as_ref(), action, name, &tx, &token).await;
+                let took = format_duration(stage_started.elapsed());
+                match code {
+                    Some(0) => {
+                        ok += 1;
+                        changed |= matches!(
+                            action,
+                            ConfirmAction::BulkUpgrade(_) | ConfirmAction::Autoremove
+                        );
+                        results.push(format!("  ok       {} ({})", command, took));
+                    }
+                    Some(c) => {
+                        failed = true;
+                        results.push(format!("  failed   {} (exit code {})", command, c));
+                    }
+                    None => {
+                        failed = true;
+                        results.push(format!("  stopped  {}", command));
+                    }
+                }
+            }
+            let mut summary = vec![format!(
+                "Maintenance finished in {}: {} of {} steps succeeded",
+                format_duration(started.elapsed()),
+                ok,
+                stages.len()
+            )];
+            summary.extend(results);
+            let _ = tx.send(AppEvent::OpSummary(summary));
+
+            if let Ok(mut c) = cache.lock() {
+                c.forget_info(&upgrade);
+            }
+            if changed {
+                let _ = tokio::task::spawn_blocking(move || {
+                    if let Ok(list) = brew.list_installed() {
+                        let _ = tx.send(AppEvent::BrewList(list));
+                    }
+                })
+                .await;
+            }
+        });
+    app.op_cancel = Some(token);
+}
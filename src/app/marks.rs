@@ -0,0 +1,80 @@
+// Marks in the Installed list: Space marks packages, and while any are marked uninstall,
+// upgrade and pin act on all of them at once, as the Outdated modal does for upgrades. `B`
+// exports the marked packages (all installed ones when none are) as a Brewfile.
+use super::{App, ConfirmAction, Mode};
+use crate::brewfile;
+use std::path::Path;
+
+impl App {
+    pub(super) fn toggle_mark(&mut self) {
+        if let Some(f) = self.items.get(self.selected) {
+            if !self.marked.remove(&f.name) {
+                self.marked.insert(f.name.clone());
+            }
+        }
+    }
+
+    /// The marked packages in Installed list order.
+    pub fn marked_names(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|f| self.marked.contains(&f.name))
+            .map(|f| f.name.clone())
+            .collect()
+    }
+
+    /// Ask to run `action` (uninstall, upgrade or pin) on the marked packages. Pinned
+    /// packages are left out of upgrades, and pinning a set that is pinned already unpins it.
+    pub(super) fn confirm_marked(&mut self, action: &ConfirmAction) {
+        let names = self.marked_names();
+        let (pinned, unpinned): (Vec<String>, Vec<String>) =
+            names.iter().cloned().partition(|n| self.is_pinned(n));
+        let (action, targets) = match action {
+            ConfirmAction::Uninstall => (ConfirmAction::BulkUninstall(names.clone()), names),
+            ConfirmAction::Upgrade if unpinned.is_empty() => {
+                self.status = "Every marked package is pinned; p unpins them".into();
+                return;
+            }
+            ConfirmAction::Upgrade => (ConfirmAction::BulkUpgrade(unpinned.clone()), unpinned),
+            _ if unpinned.is_empty() => (ConfirmAction::BulkUnpin(pinned.clone()), pinned),
+            _ => (ConfirmAction::BulkPin(unpinned.clone()), unpinned),
+        };
+        let name = match targets.as_slice() {
+            [one] => one.clone(),
+            all => format!("{} packages", all.len()),
+        };
+        self.mode = Mode::Confirm {
+            action,
+            name,
+            idx: None,
+        };
+    }
+
+    /// Write the marked packages, or every installed one when none are marked, to a new
+    /// `Brewfile` in the working directory.
+    pub(super) fn export_brewfile(&mut self) {
+        let formulae: Vec<_> = if self.marked.is_empty() {
+            self.items.iter().collect()
+        } else {
+            self.items
+                .iter()
+                .filter(|f| self.marked.contains(&f.name))
+                .collect()
+        };
+        if formulae.is_empty() {
+            self.status = "Nothing to export".into();
+            return;
+        }
+        let path = Path::new("Brewfile");
+        match brewfile::write_new(path, &brewfile::render(&formulae)) {
+            Ok(()) => {
+                self.push_log(format!(
+                    "Exported {} packages to {}",
+                    formulae.len(),
+                    path.display()
+                ));
+            }
+            Err(e) => self.push_error(format!("Export failed: {:#}", e)),
+        }
+    }
+}
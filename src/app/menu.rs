@@ -0,0 +1,292 @@
+// Quick-actions menu (`m`, or a right click on the lists): the actions that apply to the
+// selected package with the keys that run them directly, so none of the letters has to be
+// remembered. Enter runs the highlighted entry as if its key had been pressed, and Esc in the
+// dialog it opens comes back to the menu. Dependencies and Used by list the related formulae;
+// Enter there jumps to one in the lists.
+use super::{App, Focus, MenuItem, Mode, Tab, Transition, Zoom, PAGE_ROWS};
+use crate::keymap::Action;
+use crossterm::event::{KeyCode, KeyEvent};
+
+impl App {
+    /// Open the menu for the selected package of the Formulae tab.
+    pub(super) fn open_menu(&mut self) {
+        let Some((name, items)) = self.menu_items() else {
+            return;
+        };
+        self.mode = Mode::Menu {
+            name,
+            items,
+            selected: 0,
+        };
+    }
+
+    /// The selected package and the entries of its menu: an installed formula gets its
+    /// maintenance actions (Upgrade only while it is outdated), an available one Install.
+    pub fn menu_items(&self) -> Option<(String, Vec<MenuItem>)> {
+        use MenuItem::*;
+        let (name, info, installed) = match self.focus {
+            Focus::Installed => {
+                let f = self.items.get(self.selected)?;
+                (f.name.clone(), Some(f), true)
+            }
+            Focus::Available => {
+                let name = self.available_items.get(self.available_selected)?.clone();
+                let info = self.available_details.as_ref().filter(|d| d.name == name);
+                (name, info, false)
+            }
+        };
+        let mut items = vec![];
+        if installed {
+            if self.outdated_items.contains(&name) {
+                items.push(Run(Action::Upgrade));
+            }
+            items.extend([
+                Run(Action::Uninstall),
+                Run(Action::TogglePin),
+                Run(Action::Toggle),
+                Run(Action::Versions),
+                Run(Action::Link),
+                Run(Action::Unlink),
+                Run(Action::Postinstall),
+            ]);
+        } else {
+            items.push(Run(Action::Open));
+        }
+        items.extend([Run(Action::ToggleFavorite), Run(Action::EditNote)]);
+        items.push(Details);
+        if let Some(url) = info.and_then(|f| f.homepage.clone()) {
+            items.push(Homepage(url));
+        }
+        if self.brew_page(&name).is_some() {
+            items.push(Run(Action::BrewPage));
+        }
+        if info.is_some_and(|f| !f.dependencies.is_empty()) {
+            items.push(Dependencies);
+        }
+        if !self.dependents(&name).is_empty() {
+            items.push(Dependents);
+        }
+        items.extend([Run(Action::FormulaLog), Run(Action::Livecheck)]);
+        Some((name, items))
+    }
+
+    /// What the menu shows for `item` of `name`.
+    pub fn menu_label(&self, item: &MenuItem, name: &str) -> String {
+        match item {
+            MenuItem::Run(Action::Upgrade) => "Upgrade".into(),
+            MenuItem::Run(Action::Uninstall) => "Uninstall".into(),
+            MenuItem::Run(Action::TogglePin) if self.is_pinned(name) => "Unpin".into(),
+            MenuItem::Run(Action::TogglePin) => "Pin".into(),
+            MenuItem::Run(Action::ToggleFavorite) if self.favorites.contains(name) => {
+                "Unstar".into()
+            }
+            MenuItem::Run(Action::ToggleFavorite) => "Star (list first in Available)".into(),
+            MenuItem::Run(Action::EditNote) if self.notes.get(name).is_some() => {
+                "Edit the note".into()
+            }
+            MenuItem::Run(Action::EditNote) => "Add a note".into(),
+            MenuItem::Run(Action::Toggle) if self.marked.contains(name) => "Unmark".into(),
+            MenuItem::Run(Action::Toggle) => "Mark for a batch action".into(),
+            MenuItem::Run(Action::Versions) => "Installed versions".into(),
+            MenuItem::Run(Action::Link) => "Link".into(),
+            MenuItem::Run(Action::Unlink) => "Unlink".into(),
+            MenuItem::Run(Action::Postinstall) => "Re-run post-install".into(),
+            MenuItem::Run(Action::Open) => "Install".into(),
+            MenuItem::Run(Action::FormulaLog) => "Tap history".into(),
+            MenuItem::Run(Action::Livecheck) => "Check the upstream version".into(),
+            MenuItem::Run(Action::BrewPage) => "Open on formulae.brew.sh".into(),
+            MenuItem::Run(action) => format!("{:?}", action),
+            MenuItem::Details => "Info (Details full screen)".into(),
+            MenuItem::Homepage(_) => "Open the homepage".into(),
+            MenuItem::Dependencies => format!("Dependencies ({})", self.dependencies(name).len()),
+            MenuItem::Dependents => format!("Used by ({})", self.dependents(name).len()),
+        }
+    }
+
+    /// The formulae.brew.sh page of formula `name`, when it comes from homebrew/core as far
+    /// as its details tell.
+    fn brew_page(&self, name: &str) -> Option<String> {
+        let tap = self
+            .items
+            .iter()
+            .chain(self.available_details.as_ref())
+            .find(|f| f.name == name)
+            .and_then(|f| f.tap.as_deref());
+        crate::browser::brew_page(name, tap, false)
+    }
+
+    /// Open the formulae.brew.sh page of the selected formula, or of the selected cask on the
+    /// Casks tab.
+    pub(super) fn open_brew_page(&mut self) {
+        let (name, url) = if self.tab == Tab::Casks {
+            let Some(cask) = self.casks.get(self.tab_selected) else {
+                return;
+            };
+            (
+                cask.name.clone(),
+                crate::browser::brew_page(&cask.name, None, true),
+            )
+        } else {
+            let name = match self.focus {
+                Focus::Installed => self.items.get(self.selected).map(|f| f.name.clone()),
+                Focus::Available => self.available_items.get(self.available_selected).cloned(),
+            };
+            let Some(name) = name else {
+                return;
+            };
+            let url = self.brew_page(&name);
+            (name, url)
+        };
+        match url {
+            Some(url) => self.open_url(&url),
+            None => self.push_warning(format!(
+                "{} is not from an official tap, so formulae.brew.sh has no page for it",
+                name
+            )),
+        }
+    }
+
+    fn open_url(&mut self, url: &str) {
+        match crate::browser::open(url) {
+            Ok(()) => self.push_log(format!("Opened {}", url)),
+            Err(e) => self.push_error(format!("Could not open {}: {:#}", url, e)),
+        }
+    }
+
+    /// The direct dependencies of `name`, as far as its details are known.
+    fn dependencies(&self, name: &str) -> Vec<String> {
+        self.items
+            .iter()
+            .chain(self.available_details.as_ref())
+            .find(|f| f.name == name)
+            .map(|f| f.dependencies.clone())
+            .unwrap_or_default()
+    }
+
+    /// The installed formulae depending on `name` directly (`brew uses --installed`).
+    pub fn dependents(&self, name: &str) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|f| f.dependencies.iter().any(|d| d == name))
+            .map(|f| f.name.clone())
+            .collect()
+    }
+
+    /// Select `name` in the Formulae tab: in Installed when it is installed, otherwise in
+    /// Available (clearing a filter that hides it). Returns false when neither list has it.
+    pub fn select_package(&mut self, name: &str) -> bool {
+        let installed = self.items.iter().position(|f| f.name == name);
+        let available = self.available_items.iter().position(|n| n == name);
+        if installed.is_none() && available.is_none() {
+            self.push_warning(format!("{} is neither installed nor available", name));
+            return false;
+        }
+        self.show_tab(Tab::Formulae);
+        self.zoom = None;
+        if let Some(idx) = installed {
+            self.focus = Focus::Installed;
+            self.selected = idx;
+            return true;
+        }
+        let Some(idx) = available else {
+            return false;
+        };
+        self.focus = Focus::Available;
+        if !self.available_filtered.contains(&idx) {
+            self.available_filter.clear();
+        }
+        self.available_selected = idx;
+        self.apply_available_filter();
+        true
+    }
+
+    /// Run `item` of the menu on `name` from Normal mode.
+    fn run_menu_item(&mut self, item: MenuItem, name: String) {
+        match item {
+            MenuItem::Run(action) => self.perform(action),
+            MenuItem::Details => {
+                self.zoom = Some(Zoom::Details);
+                self.zoom_scroll = 0;
+                self.details_dep = 0;
+            }
+            MenuItem::Homepage(url) => self.open_url(&url),
+            MenuItem::Dependencies | MenuItem::Dependents => {
+                let dependents = item == MenuItem::Dependents;
+                let packages = if dependents {
+                    self.dependents(&name)
+                } else {
+                    self.dependencies(&name)
+                };
+                self.mode = Mode::Related {
+                    name,
+                    dependents,
+                    packages,
+                    selected: 0,
+                };
+            }
+        }
+    }
+}
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Menu {
+        name,
+        items,
+        selected,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let last = items.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('m') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last),
+        KeyCode::Home => *selected = 0,
+        KeyCode::End => *selected = last,
+        KeyCode::Enter => {
+            let Some(item) = items.get(*selected).cloned() else {
+                return Transition::Stay;
+            };
+            let name = name.clone();
+            // the entry runs from the lists; a dialog it opens goes on top of the menu
+            let menu = std::mem::replace(&mut app.mode, Mode::Normal);
+            app.nav_stack.clear();
+            app.run_menu_item(item, name);
+            if !matches!(app.mode, Mode::Normal) {
+                app.nav_stack.push(menu);
+            }
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
+
+pub(super) fn handle_related_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Related {
+        packages, selected, ..
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let last = packages.len().saturating_sub(1);
+    let page = PAGE_ROWS as usize;
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last),
+        KeyCode::PageUp => *selected = selected.saturating_sub(page),
+        KeyCode::PageDown => *selected = (*selected + page).min(last),
+        KeyCode::Home => *selected = 0,
+        KeyCode::End => *selected = last,
+        KeyCode::Enter => {
+            if let Some(name) = packages.get(*selected).cloned() {
+                if app.select_package(&name) {
+                    return Transition::To(Mode::Normal);
+                }
+            }
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
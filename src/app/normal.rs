@@ -0,0 +1,342 @@
+// Normal mode: keys resolve through the keymap to actions on the lists.
+use super::{
+    submit_cleanup_preview, submit_disk_usage, submit_doctor, submit_download_cache,
+    submit_environment, submit_formula_log, submit_livecheck, submit_outdated_check, App,
+    ConfirmAction, Focus, InputAction, Mode, Tab, Transition, Zoom, PAGE_ROWS,
+};
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    match app.keymap.resolve(key) {
+        Some(Action::Quit) => Transition::Quit,
+        Some(action) => {
+            app.perform(action);
+            Transition::Stay
+        }
+        None => Transition::Stay,
+    }
+}
+
+impl App {
+    /// Run a Normal-mode action resolved from the keymap. `Action::Quit` is handled by
+    /// `handle_key`.
+    pub fn perform(&mut self, action: Action) {
+        if self.zoom.is_some() && self.perform_zoomed(action) {
+            return;
+        }
+        if self.tab != Tab::Formulae && self.perform_in_tab(action) {
+            return;
+        }
+        match action {
+            Action::ShowTab(i) => {
+                if let Some(&tab) = Tab::ALL.get(i) {
+                    self.show_tab(tab);
+                }
+            }
+            Action::Zoom => self.zoom = Some(Zoom::List),
+            Action::NextTab => self.step_tab(1),
+            Action::PrevTab => self.step_tab(-1),
+            Action::RefreshOutdated => submit_outdated_check(&self.jobs, &self.brew, &self.tx),
+            Action::Outdated => self.open_outdated(),
+            Action::UpdateBrew => {
+                self.mode = Mode::Confirm {
+                    action: ConfirmAction::Update,
+                    name: "Homebrew".into(),
+                    idx: None,
+                };
+            }
+            Action::Environment => submit_environment(&self.jobs, &self.brew, &self.tx),
+            Action::Maintenance => self.open_maintenance(),
+            Action::DiskUsage => {
+                self.push_log("Measuring installed packages...".into());
+                submit_disk_usage(&self.jobs, &self.brew, &self.tx);
+            }
+            Action::DownloadCache => {
+                self.push_log("Measuring the download cache...".into());
+                submit_download_cache(&self.jobs, &self.brew, &self.tx);
+            }
+            Action::BrewPage => self.open_brew_page(),
+            Action::FormulaLog => {
+                if let Some(name) = self.selected_name() {
+                    self.push_log(format!("Reading the tap history of {}...", name));
+                    submit_formula_log(&self.jobs, &self.brew, &self.tx, name);
+                }
+            }
+            Action::Livecheck => {
+                if let Some(name) = self.selected_name() {
+                    self.push_log(format!("Checking the upstream version of {}...", name));
+                    submit_livecheck(&self.jobs, &self.brew, &self.tx, vec![name]);
+                }
+            }
+            Action::LivecheckLeaves => {
+                self.push_log(
+                    "Checking upstream versions of the installed leaves (this can take a while)..."
+                        .into(),
+                );
+                submit_livecheck(&self.jobs, &self.brew, &self.tx, vec![]);
+            }
+            Action::Doctor => {
+                self.push_log("Running brew doctor...".into());
+                submit_doctor(&self.jobs, &self.brew, &self.tx);
+            }
+            Action::Cleanup => {
+                self.push_log("Checking what brew cleanup would remove...".into());
+                submit_cleanup_preview(&self.jobs, &self.brew, &self.tx);
+            }
+            Action::History => {
+                self.mode = Mode::History { selected: 0 };
+            }
+            Action::Snapshot => self.take_snapshot(),
+            Action::SnapshotDiff => self.open_snapshot_diff(),
+            Action::ReopenOperation => {
+                // re-open the log of the running (or most recent) operation
+                if let Some(rec) = self.history.records.last() {
+                    let live = self.current_op.is_some();
+                    self.mode = Mode::Operation {
+                        title: rec.command.clone(),
+                        logs: rec.logs.clone(),
+                        scroll: 0,
+                        live,
+                    };
+                } else {
+                    self.status = "No operations yet".into();
+                }
+            }
+            Action::GrowPane | Action::ShrinkPane => {
+                let idx = match self.focus {
+                    Focus::Installed => 0,
+                    Focus::Available => 1,
+                };
+                if action == Action::GrowPane {
+                    self.layout.grow(idx);
+                } else {
+                    self.layout.shrink(idx);
+                }
+                self.save_layout();
+            }
+            Action::GrowBottom | Action::ShrinkBottom => {
+                let delta = if action == Action::GrowBottom { 1 } else { -1 };
+                self.layout.resize_bottom(delta);
+                self.save_layout();
+            }
+            Action::ReloadConfig => self.reload_config(),
+            Action::LogLevel => self.cycle_log_level(),
+            Action::LogOperation => self.cycle_log_op(),
+            Action::ClearLogs => self.clear_logs(),
+            Action::WrapLogs => {
+                self.log_wrap = !self.log_wrap;
+                self.push_log(if self.log_wrap {
+                    "Logs tab: wrapping long entries".into()
+                } else {
+                    "Logs tab: cutting long entries off".into()
+                });
+            }
+            Action::CycleTheme => {
+                self.theme = self.theme.next();
+                self.push_log(format!("Theme: {}", self.theme.name));
+            }
+            Action::Cancel => self.marked.clear(),
+            Action::Quit => {}
+            Action::Help => {
+                self.mode = Mode::Help {
+                    scroll: 0,
+                    query: String::new(),
+                    searching: false,
+                };
+            }
+            Action::SwitchFocus => {
+                self.focus = match self.focus {
+                    Focus::Installed => Focus::Available,
+                    Focus::Available => Focus::Installed,
+                };
+            }
+            Action::MoveDown => {
+                if self.focus == Focus::Installed {
+                    if self.selected + 1 < self.items.len() {
+                        self.selected += 1;
+                    }
+                } else {
+                    if !self.available_filtered.is_empty() {
+                        if let Some(pos) = self
+                            .available_filtered
+                            .iter()
+                            .position(|&idx| idx == self.available_selected)
+                        {
+                            let next_pos = (pos + 1).min(self.available_filtered.len() - 1);
+                            self.available_selected = self.available_filtered[next_pos];
+                        } else {
+                            self.available_selected = self.available_filtered[0];
+                        }
+                    }
+                }
+            }
+            Action::MoveUp => {
+                if self.focus == Focus::Installed {
+                    if self.selected > 0 {
+                        self.selected -= 1;
+                    }
+                } else {
+                    if !self.available_filtered.is_empty() {
+                        if let Some(pos) = self
+                            .available_filtered
+                            .iter()
+                            .position(|&idx| idx == self.available_selected)
+                        {
+                            let prev_pos = pos.saturating_sub(1);
+                            self.available_selected = self.available_filtered[prev_pos];
+                        } else {
+                            self.available_selected = *self.available_filtered.last().unwrap();
+                        }
+                    }
+                }
+            }
+            Action::PageDown => self.step_selection(PAGE_ROWS, false),
+            Action::PageUp => self.step_selection(-PAGE_ROWS, false),
+            Action::Top => self.step_selection(isize::MIN, false),
+            Action::Bottom => self.step_selection(isize::MAX, false),
+            Action::SearchNext | Action::SearchPrev => {
+                // the Available list only holds filter matches, so jumping between matches
+                // walks it with wrap-around
+                self.focus = Focus::Available;
+                let delta = if action == Action::SearchNext { 1 } else { -1 };
+                self.step_selection(delta, true);
+            }
+            Action::Uninstall | Action::Upgrade | Action::TogglePin if !self.marked.is_empty() => {
+                let action = match action {
+                    Action::Uninstall => ConfirmAction::Uninstall,
+                    Action::Upgrade => ConfirmAction::Upgrade,
+                    _ => ConfirmAction::Pin,
+                };
+                self.confirm_marked(&action);
+            }
+            Action::Uninstall => {
+                if let Some(f) = self.items.get(self.selected) {
+                    self.mode = Mode::Confirm {
+                        action: ConfirmAction::Uninstall,
+                        name: f.name.clone(),
+                        idx: Some(self.selected),
+                    };
+                }
+            }
+            Action::Upgrade => {
+                if let Some(f) = self.items.get(self.selected) {
+                    self.mode = Mode::Confirm {
+                        action: ConfirmAction::Upgrade,
+                        name: f.name.clone(),
+                        idx: Some(self.selected),
+                    };
+                }
+            }
+            Action::Postinstall => {
+                if let Some(f) = self.items.get(self.selected) {
+                    self.mode = Mode::Confirm {
+                        action: ConfirmAction::Postinstall,
+                        name: f.name.clone(),
+                        idx: Some(self.selected),
+                    };
+                }
+            }
+            Action::Link | Action::LinkOverwrite | Action::Unlink => {
+                if let Some(f) = self.items.get(self.selected) {
+                    let action = match action {
+                        Action::Link => ConfirmAction::Link,
+                        Action::LinkOverwrite => ConfirmAction::LinkOverwrite,
+                        _ => ConfirmAction::Unlink,
+                    };
+                    self.mode = Mode::Confirm {
+                        action,
+                        name: f.name.clone(),
+                        idx: Some(self.selected),
+                    };
+                }
+            }
+            Action::TogglePin => {
+                if let Some(f) = self.items.get(self.selected) {
+                    let action = if self.is_pinned(&f.name) {
+                        ConfirmAction::Unpin
+                    } else {
+                        ConfirmAction::Pin
+                    };
+                    self.mode = Mode::Confirm {
+                        action,
+                        name: f.name.clone(),
+                        idx: Some(self.selected),
+                    };
+                }
+            }
+            Action::ToggleFavorite => self.toggle_favorite(),
+            Action::EditNote => {
+                if let Some(name) = self.selected_name() {
+                    self.mode = Mode::Input {
+                        buffer: self.notes.get(&name).unwrap_or_default().to_string(),
+                        action: InputAction::Note { name },
+                    };
+                }
+            }
+            Action::Versions => self.open_versions(),
+            Action::InstallPrompt => {
+                self.mode = Mode::Input {
+                    action: InputAction::Install,
+                    buffer: String::new(),
+                };
+            }
+            Action::Search => {
+                self.mode = Mode::Input {
+                    action: InputAction::Search,
+                    buffer: String::new(),
+                };
+            }
+            Action::Filter => {
+                self.mode = Mode::Input {
+                    action: InputAction::Search,
+                    buffer: self.available_filter.clone(),
+                };
+                self.focus = Focus::Available;
+            }
+            Action::ClearFilter => {
+                self.available_filter.clear();
+                self.apply_available_filter();
+            }
+            Action::SortInstalled => self.change_installed_sort(false),
+            Action::ReverseSort => self.change_installed_sort(true),
+            Action::CycleSort => self.cycle_list_sort(),
+            Action::Toggle => {
+                if self.focus == Focus::Available {
+                    self.toggle_available_group();
+                } else {
+                    self.toggle_mark();
+                }
+            }
+            Action::ExportBrewfile => self.export_brewfile(),
+            Action::Menu => self.open_menu(),
+            Action::Open => {
+                if self.focus == Focus::Installed {
+                    if let Some(f) = self.items.get(self.selected) {
+                        let name = f.name.clone();
+                        self.mode = Mode::Confirm {
+                            action: ConfirmAction::Uninstall,
+                            name: name.clone(),
+                            idx: Some(self.selected),
+                        };
+                        if self.last_selected != Some((Focus::Installed, self.selected)) {
+                            self.fetch_details(Focus::Installed, self.selected);
+                        }
+                    }
+                } else {
+                    if let Some(name) = self.available_items.get(self.available_selected) {
+                        let name = name.clone();
+                        self.mode = Mode::Confirm {
+                            action: ConfirmAction::Install,
+                            name: name.clone(),
+                            idx: Some(self.available_selected),
+                        };
+                        if self.last_selected != Some((Focus::Available, self.available_selected)) {
+                            self.fetch_details(Focus::Available, self.available_selected);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
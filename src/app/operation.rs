@@ -0,0 +1,46 @@
+// Operation modal: the live (or a recorded) operation log. Up/Down and the page keys scroll,
+// `x` cancels the running operation, `s` saves the log to a file.
+use super::{App, Mode, Transition};
+use crate::disk::now_secs;
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Lines moved by PageUp/PageDown.
+const PAGE: usize = 10;
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Operation {
+        title,
+        logs,
+        scroll,
+        live,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let live = *live;
+    let len = logs.len();
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('?') => {
+            if live && app.operating {
+                app.push_log("Operation continues in background (O to reopen)".into());
+            }
+            return Transition::Back;
+        }
+        KeyCode::Char('x') if live => app.cancel_operation(),
+        KeyCode::Char('s') => {
+            let command = title.strip_prefix("History: ").unwrap_or(title).to_string();
+            let logs = logs.clone();
+            app.export_operation_log(&command, &logs, now_secs());
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            *scroll = if *scroll + 1 < len { *scroll + 1 } else { len };
+        }
+        KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_sub(1),
+        KeyCode::PageUp => *scroll = (*scroll + PAGE).min(len),
+        KeyCode::PageDown => *scroll = scroll.saturating_sub(PAGE),
+        KeyCode::Home => *scroll = len,
+        KeyCode::End => *scroll = 0,
+        _ => {}
+    }
+    Transition::Stay
+}
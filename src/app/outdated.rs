@@ -0,0 +1,54 @@
+// Outdated modal: Space checks packages, Enter asks to upgrade the checked ones. Pinned
+// packages cannot be checked; `brew upgrade` would refuse them.
+use super::{App, ConfirmAction, Mode, Transition};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Outdated {
+        packages,
+        cursor,
+        checked,
+        ..
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('?') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *cursor = cursor.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') if *cursor + 1 < packages.len() => *cursor += 1,
+        KeyCode::Char(' ') => {
+            let pinned = packages
+                .get(*cursor)
+                .filter(|p| app.pinned.contains(p))
+                .cloned();
+            if let Some(name) = pinned {
+                app.status = format!("{} is pinned; p in the Installed list unpins it", name);
+            } else if let Some(c) = checked.get_mut(*cursor) {
+                *c = !*c;
+            }
+        }
+        KeyCode::Enter => {
+            let to_upgrade: Vec<String> = packages
+                .iter()
+                .zip(checked.iter())
+                .filter(|(_, &c)| c)
+                .map(|(p, _)| p.clone())
+                .collect();
+            if !to_upgrade.is_empty() {
+                let name = if to_upgrade.len() == 1 {
+                    to_upgrade[0].clone()
+                } else {
+                    format!("{} packages", to_upgrade.len())
+                };
+                return Transition::Push(Mode::Confirm {
+                    action: ConfirmAction::BulkUpgrade(to_upgrade),
+                    name,
+                    idx: None,
+                });
+            }
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
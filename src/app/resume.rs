@@ -0,0 +1,60 @@
+// Resume review: operations the last session confirmed but did not finish, shown at startup.
+// Space picks them (all start picked), Enter runs the picked ones in their old order and Esc
+// drops them all; either way the old queue is forgotten.
+use super::confirm::start_operation;
+use super::{App, Mode, Transition};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Resume {
+        operations,
+        cursor,
+        checked,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => *cursor = cursor.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') if *cursor + 1 < operations.len() => *cursor += 1,
+        KeyCode::Char(' ') => {
+            if let Some(c) = checked.get_mut(*cursor) {
+                *c = !*c;
+            }
+        }
+        KeyCode::Enter | KeyCode::Char('y') => {
+            let picked: Vec<_> = operations
+                .iter()
+                .zip(checked.iter())
+                .filter(|(_, &c)| c)
+                .map(|(op, _)| (op.action.clone(), op.name.clone()))
+                .collect();
+            app.forget_queue();
+            app.push_log(format!("Resuming {} interrupted operations", picked.len()));
+            for (action, name) in picked {
+                start_operation(app, action, name);
+            }
+            return Transition::To(Mode::Normal);
+        }
+        KeyCode::Esc | KeyCode::Char('n') => {
+            app.forget_queue();
+            app.push_log("Dropped the interrupted operations".into());
+            return Transition::To(Mode::Normal);
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
+
+impl App {
+    /// Empty the persisted queue of the last session.
+    fn forget_queue(&mut self) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.entries.clear();
+        let saved = queue.save();
+        drop(queue);
+        if let Err(e) = saved {
+            self.push_error(format!("failed to save the operation queue: {}", e));
+        }
+    }
+}
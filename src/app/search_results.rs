@@ -0,0 +1,26 @@
+// Search results modal: pick a result to install.
+use super::{App, ConfirmAction, Mode, Transition};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::SearchResults { results, selected } = &mut app.mode else {
+        return Transition::Stay;
+    };
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') if *selected + 1 < results.len() => *selected += 1,
+        KeyCode::Enter => {
+            return match results.get(*selected).cloned() {
+                Some(name) => Transition::Push(Mode::Confirm {
+                    action: ConfirmAction::Install,
+                    name,
+                    idx: None,
+                }),
+                None => Transition::Back,
+            };
+        }
+        KeyCode::Esc | KeyCode::Char('?') => return Transition::Back,
+        _ => {}
+    }
+    Transition::Stay
+}
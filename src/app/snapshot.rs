@@ -0,0 +1,84 @@
+// Snapshot diff: what was removed, added or changed version since the last snapshot. The cursor
+// walks the removed packages; Space checks them (all start checked) and Enter asks to reinstall
+// the checked ones.
+use super::{App, ConfirmAction, Mode, Transition};
+use crate::disk;
+use crate::snapshot::Snapshot;
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::SnapshotDiff {
+        diff,
+        cursor,
+        checked,
+        ..
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => *cursor = cursor.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') if *cursor + 1 < diff.removed.len() => *cursor += 1,
+        KeyCode::Char(' ') => {
+            if let Some(c) = checked.get_mut(*cursor) {
+                *c = !*c;
+            }
+        }
+        KeyCode::Enter => {
+            let names: Vec<String> = diff
+                .removed
+                .iter()
+                .zip(checked.iter())
+                .filter(|(_, &c)| c)
+                .map(|((name, _), _)| name.clone())
+                .collect();
+            let name = match names.as_slice() {
+                [] => {
+                    app.status = "Nothing to reinstall".into();
+                    return Transition::Stay;
+                }
+                [one] => one.clone(),
+                all => format!("{} packages", all.len()),
+            };
+            return Transition::Push(Mode::Confirm {
+                action: ConfirmAction::BulkInstall(names),
+                name,
+                idx: None,
+            });
+        }
+        KeyCode::Esc | KeyCode::Char('q') => return Transition::Back,
+        _ => {}
+    }
+    Transition::Stay
+}
+
+impl App {
+    /// Replace the snapshot with the installed packages as they are now.
+    pub(super) fn take_snapshot(&mut self) {
+        if self.loading_installed {
+            self.status = "Wait for the Installed list before taking a snapshot".into();
+            return;
+        }
+        let snapshot = Snapshot::of(&self.items, disk::now_secs());
+        let count = snapshot.packages.len();
+        match self.snapshots.take(snapshot) {
+            Ok(()) => self.show_toast(format!("Snapshot of {} packages saved", count)),
+            Err(e) => self.push_error(format!("failed to save the snapshot: {}", e)),
+        }
+    }
+
+    /// Compare the Installed list with the last snapshot.
+    pub(super) fn open_snapshot_diff(&mut self) {
+        let Some(snapshot) = &self.snapshots.latest else {
+            self.status = "No snapshot yet; Y takes one".into();
+            return;
+        };
+        let diff = snapshot.diff(&self.items);
+        self.mode = Mode::SnapshotDiff {
+            taken_at: snapshot.taken_at,
+            checked: vec![true; diff.removed.len()],
+            diff,
+            cursor: 0,
+        };
+    }
+}
@@ -0,0 +1,115 @@
+// Tab bar: Formulae keeps the Installed, Available and Details panes; Casks, Outdated,
+// Services, Taps and Logs are one list each, moved through with the usual navigation keys.
+// Casks, services and taps are (re)loaded whenever their tab is shown.
+use super::{submit_tab_list, App, Focus, Mode, Tab, PAGE_ROWS};
+use crate::keymap::Action;
+
+impl App {
+    /// Switch to `tab`, starting its list at the top (the Logs tab at the newest line).
+    pub fn show_tab(&mut self, tab: Tab) {
+        if tab == self.tab {
+            return;
+        }
+        self.tab = tab;
+        self.zoom = None;
+        self.tab_offset = 0;
+        self.log_anchor = None;
+        self.tab_selected = match tab {
+            Tab::Logs => self.visible_logs().len().saturating_sub(1),
+            _ => 0,
+        };
+        if matches!(tab, Tab::Casks | Tab::Services | Tab::Taps) {
+            self.loading_tab = Some(tab);
+            submit_tab_list(&self.jobs, &self.brew, &self.tx, tab);
+        }
+    }
+
+    /// Move `delta` tabs along the tab bar, wrapping around at either end.
+    pub(super) fn step_tab(&mut self, delta: isize) {
+        let count = Tab::ALL.len() as isize;
+        let index = (self.tab.index() as isize + delta).rem_euclid(count);
+        self.show_tab(Tab::ALL[index as usize]);
+    }
+
+    pub(super) fn tab_loaded(&mut self, tab: Tab) {
+        if self.loading_tab == Some(tab) {
+            self.loading_tab = None;
+        }
+        self.tab_selected = self.tab_selected.min(self.tab_len().saturating_sub(1));
+    }
+
+    /// Number of rows in the current tab's list.
+    pub fn tab_len(&self) -> usize {
+        match self.tab {
+            Tab::Formulae => 0,
+            Tab::Casks => self.casks.len(),
+            Tab::Outdated => self.outdated_items.len(),
+            Tab::Services => self.services.len(),
+            Tab::Taps => self.taps.len(),
+            Tab::Logs => self.visible_logs().len(),
+        }
+    }
+
+    /// Handle `action` on a tab other than Formulae. Returns false for actions that are not
+    /// about the tab's list, which then run as usual; those that work on the selected
+    /// formula are swallowed, as no formula is shown.
+    pub(super) fn perform_in_tab(&mut self, action: Action) -> bool {
+        if self.tab == Tab::Logs && self.perform_in_logs(action) {
+            return true;
+        }
+        let last = self.tab_len().saturating_sub(1);
+        let page = PAGE_ROWS as usize;
+        let selected = self.tab_selected.min(last);
+        self.tab_selected = match action {
+            Action::MoveDown => (selected + 1).min(last),
+            Action::MoveUp => selected.saturating_sub(1),
+            Action::PageDown => (selected + page).min(last),
+            Action::PageUp => selected.saturating_sub(page),
+            Action::Top => 0,
+            Action::Bottom => last,
+            Action::BrewPage if self.tab == Tab::Casks => {
+                self.open_brew_page();
+                return true;
+            }
+            Action::Open => {
+                if self.tab == Tab::Outdated && !self.outdated_items.is_empty() {
+                    self.open_outdated();
+                    if let Mode::Outdated { cursor, .. } = &mut self.mode {
+                        *cursor = selected;
+                    }
+                }
+                return true;
+            }
+            // the filter and match jumps are about the Available pane
+            Action::Filter | Action::SearchNext | Action::SearchPrev => {
+                self.show_tab(Tab::Formulae);
+                self.focus = Focus::Available;
+                return false;
+            }
+            Action::SwitchFocus
+            | Action::Uninstall
+            | Action::Upgrade
+            | Action::Postinstall
+            | Action::Link
+            | Action::LinkOverwrite
+            | Action::Unlink
+            | Action::TogglePin
+            | Action::ToggleFavorite
+            | Action::EditNote
+            | Action::Versions
+            | Action::SortInstalled
+            | Action::ReverseSort
+            | Action::CycleSort
+            | Action::Toggle
+            | Action::ExportBrewfile
+            | Action::Menu
+            | Action::BrewPage
+            | Action::FormulaLog
+            | Action::Livecheck
+            | Action::GrowPane
+            | Action::ShrinkPane => return true,
+            _ => return false,
+        };
+        true
+    }
+}
@@ -0,0 +1,130 @@
+// Versions modal: every installed version of a formula in the Cellar, the linked one marked.
+// Enter (or `s`) makes the selected version the linked one, `d` deletes a version that is not
+// linked; both go through the Confirm dialog. `o` offers older versions to install: the
+// formula's versioned formulae (`name@x.y`) or, for any other version, `brew extract` into a
+// local tap.
+use super::{App, ConfirmAction, InputAction, Mode, Transition};
+use crate::brew;
+use crossterm::event::{KeyCode, KeyEvent};
+
+impl App {
+    /// Open the Versions modal for the selected installed formula, once its details (which
+    /// list the versions) have loaded.
+    pub(super) fn open_versions(&mut self) {
+        let Some(f) = self.items.get(self.selected) else {
+            return;
+        };
+        if f.installed.is_empty() {
+            self.status = format!("Versions of {} are still loading", f.name);
+            return;
+        }
+        self.mode = Mode::Versions {
+            name: f.name.clone(),
+            versions: f.installed.iter().map(|i| i.version.clone()).collect(),
+            linked: f.linked_keg.clone(),
+            selected: 0,
+        };
+    }
+
+    /// Offer older versions of `name`: the Older versions modal when versioned formulae exist,
+    /// otherwise straight the prompt for a version to extract.
+    fn open_older_versions(&mut self, name: String) -> Transition {
+        if self.available_items.is_empty() {
+            self.status = "The list of available formulae is still loading".into();
+            return Transition::Stay;
+        }
+        let versioned = brew::versioned_formulae(&self.available_items, &name);
+        if versioned.is_empty() {
+            return Transition::Push(Mode::Input {
+                action: InputAction::ExtractVersion { name },
+                buffer: String::new(),
+            });
+        }
+        Transition::Push(Mode::OlderVersions {
+            name,
+            versioned,
+            selected: 0,
+        })
+    }
+}
+
+pub(super) fn handle_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::Versions {
+        name,
+        versions,
+        linked,
+        selected,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    let last = versions.len().saturating_sub(1);
+    let version = versions.get(*selected).cloned();
+    let is_linked = version.is_some() && version == *linked;
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('V') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last),
+        KeyCode::Char('o') => {
+            let name = name.clone();
+            return app.open_older_versions(name);
+        }
+        KeyCode::Enter | KeyCode::Char('s') | KeyCode::Char('d') => {
+            let Some(version) = version else {
+                return Transition::Stay;
+            };
+            if is_linked {
+                app.status = format!("{} {} is already the linked version", name, version);
+                return Transition::Stay;
+            }
+            let action = if key.code == KeyCode::Char('d') {
+                ConfirmAction::RemoveVersion { version }
+            } else {
+                ConfirmAction::SwitchVersion { version }
+            };
+            let idx = app.items.iter().position(|f| &f.name == name);
+            return Transition::Push(Mode::Confirm {
+                action,
+                name: name.clone(),
+                idx,
+            });
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
+
+pub(super) fn handle_older_key(app: &mut App, key: KeyEvent) -> Transition {
+    let Mode::OlderVersions {
+        name,
+        versioned,
+        selected,
+    } = &mut app.mode
+    else {
+        return Transition::Stay;
+    };
+    // the row after the versioned formulae extracts another version
+    let last = versioned.len();
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Transition::Back,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(last),
+        KeyCode::Enter => {
+            let Some(formula) = versioned.get(*selected) else {
+                return Transition::Push(Mode::Input {
+                    action: InputAction::ExtractVersion { name: name.clone() },
+                    buffer: String::new(),
+                });
+            };
+            let formula = formula.clone();
+            let idx = app.items.iter().position(|f| f.name == formula);
+            return Transition::Push(Mode::Confirm {
+                action: ConfirmAction::Install,
+                name: formula,
+                idx,
+            });
+        }
+        _ => {}
+    }
+    Transition::Stay
+}
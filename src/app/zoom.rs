@@ -0,0 +1,99 @@
+// Zoom: `e` shows the focused list full screen, Tab moves the zoom on to Details and Logs
+// (which the navigation keys then scroll) and back, `e` or Esc returns to the normal layout.
+// In Details up / down walk the dependencies instead, when there are any, and Enter jumps to
+// the selected one.
+use super::{App, Focus, Tab, Zoom, PAGE_ROWS};
+use crate::brew::FormulaInfo;
+use crate::keymap::Action;
+
+impl App {
+    /// Handle `action` while a pane is zoomed. Returns false for actions that run as usual,
+    /// like moving through a zoomed list.
+    pub(super) fn perform_zoomed(&mut self, action: Action) -> bool {
+        let Some(zoom) = self.zoom else {
+            return false;
+        };
+        match action {
+            Action::Zoom | Action::Cancel => self.zoom = None,
+            Action::SwitchFocus => self.next_zoom(zoom),
+            _ if zoom == Zoom::List => return false,
+            Action::MoveDown | Action::MoveUp | Action::Open
+                if zoom == Zoom::Details && self.details_dependencies() > 0 =>
+            {
+                self.step_dependency(action)
+            }
+            Action::MoveDown => self.scroll_zoom(1),
+            Action::MoveUp => self.scroll_zoom(-1),
+            Action::PageDown => self.scroll_zoom(PAGE_ROWS),
+            Action::PageUp => self.scroll_zoom(-PAGE_ROWS),
+            Action::Top => self.zoom_scroll = 0,
+            Action::Bottom => self.scroll_zoom(isize::MAX),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Installed, Available, Details, Logs and around again; the other tabs have no Details.
+    fn next_zoom(&mut self, zoom: Zoom) {
+        self.zoom_scroll = 0;
+        self.details_dep = 0;
+        self.zoom = Some(match zoom {
+            Zoom::List if self.tab == Tab::Formulae && self.focus == Focus::Installed => {
+                self.focus = Focus::Available;
+                Zoom::List
+            }
+            Zoom::List if self.tab == Tab::Formulae => Zoom::Details,
+            Zoom::List | Zoom::Details => Zoom::Logs,
+            Zoom::Logs => {
+                self.focus = Focus::Installed;
+                Zoom::List
+            }
+        });
+    }
+
+    /// The package the Details pane shows: the selected installed one, or the selected
+    /// available one once its details have loaded.
+    pub fn details_formula(&self) -> Option<&FormulaInfo> {
+        match self.focus {
+            Focus::Installed => self.items.get(self.selected),
+            Focus::Available => {
+                let name = self.available_items.get(self.available_selected)?;
+                self.available_details.as_ref().filter(|d| &d.name == name)
+            }
+        }
+    }
+
+    fn details_dependencies(&self) -> usize {
+        self.details_formula().map_or(0, |f| f.dependencies.len())
+    }
+
+    /// Move the dependency cursor of the zoomed Details, keeping a few lines above it in view,
+    /// or jump to the dependency it is on.
+    fn step_dependency(&mut self, action: Action) {
+        let Some(f) = self.details_formula() else {
+            return;
+        };
+        let last = f.dependencies.len().saturating_sub(1);
+        let dep = self.details_dep.min(last);
+        if action == Action::Open {
+            let name = f.dependencies[dep].clone();
+            self.select_package(&name);
+            return;
+        }
+        let dep = match action {
+            Action::MoveDown => (dep + 1).min(last),
+            _ => dep.saturating_sub(1),
+        };
+        self.zoom_scroll = crate::ui::dependency_row(self, f, dep).saturating_sub(3);
+        self.details_dep = dep;
+    }
+
+    fn scroll_zoom(&mut self, delta: isize) {
+        // Details wraps, so only the log has a known last line
+        let last = match self.zoom {
+            Some(Zoom::Logs) => self.visible_logs().len().saturating_sub(1),
+            _ => u16::MAX as usize,
+        };
+        self.zoom_scroll = self.zoom_scroll.saturating_add_signed(delta).min(last);
+    }
+}
@@ -0,0 +1,830 @@
+// The interface the app uses to query and change packages. `Brew` is the real implementation
+// (shelling out to brew, or answering from the demo fixtures); `MockBackend` answers from fixed
+// data and records operations instead of running them, so the app can be driven
+// deterministically in tests; `MockBackend::from_fixtures` fills it from files in brew's own
+// output formats, with scripted output for the operations, so whole flows run on machines
+// without Homebrew. A `Brew` with a transcript records every command it runs, or answers from a
+// recorded transcript instead of running brew (see `transcript`).
+use crate::api_cache::{self, ApiEntry};
+use crate::brew::{
+    self, Brew, BrewEnvironment, BrewError, CleanupPreview, DoctorItem, FormulaCommit, FormulaInfo,
+    Health, InstalledCask, Livecheck, Service,
+};
+use crate::build_logs::BuildLogs;
+use crate::config::BrewConfig;
+use crate::disk::{self, DiskUsage, DownloadCache};
+use crate::event::{AppEvent, ConfirmAction, HOMEBREW_INSTALL_SCRIPT};
+use crate::executor;
+use crate::github::{self, RepoStats};
+use crate::jobs::{self, CancelToken};
+use crate::lock;
+use crate::output::{LogLine, Stream};
+use crate::parse;
+use crate::transcript::Exchange;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+/// A running operation; resolves to its exit code.
+pub type Execution<'a> = Pin<Box<dyn Future<Output = Result<Option<i32>>> + Send + 'a>>;
+
+pub trait BrewBackend: Send + Sync {
+    fn list_installed(&self) -> Result<Vec<FormulaInfo>>;
+
+    fn info(&self, name: &str) -> Result<FormulaInfo>;
+
+    fn search(&self, query: &str) -> Result<Vec<String>>;
+
+    /// Names of installed packages with a newer version available.
+    fn outdated(&self) -> Result<Vec<String>>;
+
+    /// Names of the pinned formulae.
+    fn pinned(&self) -> Result<Vec<String>>;
+
+    /// Every package that can be installed, with its description when known.
+    fn available_entries(&self) -> Result<Vec<ApiEntry>>;
+
+    /// Whether the index `available_entries` reads changed since `since` (unix seconds), see
+    /// `Brew::refresh_index`.
+    fn refresh_index(&self, since: Option<u64>) -> Result<bool>;
+
+    /// What `brew cleanup` would remove.
+    fn cleanup_preview(&self) -> Result<CleanupPreview>;
+
+    /// Findings of `brew doctor`.
+    fn doctor(&self) -> Result<Vec<DoctorItem>>;
+
+    /// The files in the download cache.
+    fn download_cache(&self) -> Result<DownloadCache>;
+
+    /// Installed packages by the space they take in the Cellar.
+    fn disk_usage(&self) -> Result<DiskUsage>;
+
+    /// What `brew config` and `brew --env` report.
+    fn environment(&self) -> Result<BrewEnvironment>;
+
+    /// The build logs of whichever of `names` was built last.
+    fn build_logs(&self, names: &[String]) -> Result<BuildLogs>;
+
+    /// Recent commits to the formula in its tap, newest first.
+    fn formula_log(&self, name: &str) -> Result<Vec<FormulaCommit>>;
+
+    /// Full details of every installed formula.
+    fn installed_details(&self) -> Result<Vec<FormulaInfo>>;
+
+    /// Upstream versions of `names` (every installed leaf when empty) from `brew livecheck`.
+    fn livecheck(&self, names: &[String]) -> Result<Vec<Livecheck>>;
+
+    /// Installed casks.
+    fn casks(&self) -> Result<Vec<InstalledCask>>;
+
+    /// Background services of the installed formulae.
+    fn services(&self) -> Result<Vec<Service>>;
+
+    /// Tapped repositories.
+    fn taps(&self) -> Result<Vec<String>>;
+
+    /// Stars and last push of the GitHub repository `repo` (`owner/name`).
+    fn github_stats(&self, repo: &str) -> Result<RepoStats>;
+
+    /// Every formula `name` needs, build dependencies included.
+    fn dependencies(&self, name: &str) -> Result<Vec<String>>;
+
+    /// Download sizes in bytes of the bottles of `names`, for those where it is known.
+    fn bottle_sizes(&self, names: &[String]) -> Result<HashMap<String, u64>>;
+
+    /// Run a confirmed action, streaming its output as `OpLog`/`OpErr` events. Resolves to the
+    /// exit code (`None` when it was cancelled before finishing or killed by a signal); an
+    /// error means it could not be started at all.
+    fn execute<'a>(
+        &'a self,
+        action: &'a ConfirmAction,
+        name: &'a str,
+        tx: &'a mpsc::Sender<AppEvent>,
+        cancel: &'a CancelToken,
+    ) -> Execution<'a>;
+
+    /// Flags appended to operations, shown in their command lines.
+    fn flags(&self) -> &[String] {
+        &[]
+    }
+
+    /// False when the backend cannot be used at all; the app then offers to install Homebrew.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    /// The startup check that the backend answers (see `Brew::health_check`).
+    fn health(&self) -> Result<Health> {
+        Ok(Health::default())
+    }
+
+    /// Whether results are worth keeping in the on-disk metadata cache, and what the app does
+    /// in its state files (history, queue, session, favorites, notes, snapshots).
+    fn persistent(&self) -> bool {
+        false
+    }
+
+    /// The backend to use after the `[brew]` configuration changed, or `None` to keep this one.
+    fn reconfigure(&self, _cfg: &BrewConfig) -> Option<Arc<dyn BrewBackend>> {
+        None
+    }
+}
+
+impl BrewBackend for Brew {
+    fn list_installed(&self) -> Result<Vec<FormulaInfo>> {
+        Brew::list_installed(self)
+    }
+
+    fn info(&self, name: &str) -> Result<FormulaInfo> {
+        self.shared_info(name)
+    }
+
+    fn search(&self, query: &str) -> Result<Vec<String>> {
+        Brew::search(self, query)
+    }
+
+    fn outdated(&self) -> Result<Vec<String>> {
+        Brew::outdated(self)
+    }
+
+    fn pinned(&self) -> Result<Vec<String>> {
+        Brew::pinned(self)
+    }
+
+    fn available_entries(&self) -> Result<Vec<ApiEntry>> {
+        Brew::available_entries(self)
+    }
+
+    fn refresh_index(&self, since: Option<u64>) -> Result<bool> {
+        Brew::refresh_index(self, since)
+    }
+
+    fn cleanup_preview(&self) -> Result<CleanupPreview> {
+        Brew::cleanup_preview(self)
+    }
+
+    fn doctor(&self) -> Result<Vec<DoctorItem>> {
+        Brew::doctor(self)
+    }
+
+    fn download_cache(&self) -> Result<DownloadCache> {
+        Brew::download_cache(self)
+    }
+
+    fn disk_usage(&self) -> Result<DiskUsage> {
+        Brew::disk_usage(self)
+    }
+
+    fn environment(&self) -> Result<BrewEnvironment> {
+        Brew::environment(self)
+    }
+
+    fn build_logs(&self, names: &[String]) -> Result<BuildLogs> {
+        Brew::build_logs(self, names)
+    }
+
+    fn formula_log(&self, name: &str) -> Result<Vec<FormulaCommit>> {
+        Brew::formula_log(self, name)
+    }
+
+    fn installed_details(&self) -> Result<Vec<FormulaInfo>> {
+        Brew::installed_details(self)
+    }
+
+    fn livecheck(&self, names: &[String]) -> Result<Vec<Livecheck>> {
+        Brew::livecheck(self, names)
+    }
+
+    fn casks(&self) -> Result<Vec<InstalledCask>> {
+        Brew::casks(self)
+    }
+
+    fn services(&self) -> Result<Vec<Service>> {
+        Brew::services(self)
+    }
+
+    fn taps(&self) -> Result<Vec<String>> {
+        Brew::taps(self)
+    }
+
+    /// Uses brew's `HOMEBREW_GITHUB_API_TOKEN` when set; the demo stays offline.
+    fn github_stats(&self, repo: &str) -> Result<RepoStats> {
+        if self.is_demo() {
+            anyhow::bail!("no GitHub lookups in demo mode");
+        }
+        if self.is_replay() {
+            anyhow::bail!("no GitHub lookups when replaying a transcript");
+        }
+        let token = self
+            .env
+            .iter()
+            .find(|(k, _)| k == "HOMEBREW_GITHUB_API_TOKEN")
+            .map(|(_, v)| v.clone())
+            .or_else(|| std::env::var("HOMEBREW_GITHUB_API_TOKEN").ok());
+        github::fetch(repo, token.as_deref())
+    }
+
+    fn dependencies(&self, name: &str) -> Result<Vec<String>> {
+        Brew::dependencies(self, name)
+    }
+
+    fn bottle_sizes(&self, names: &[String]) -> Result<HashMap<String, u64>> {
+        Brew::bottle_sizes(self, names)
+    }
+
+    /// `InstallBrew` runs the Homebrew installer, demo mode simulates the action, a replayed
+    /// transcript prints what the command printed when it was recorded, and anything else (brew,
+    /// `find` for `PurgeCache` or the version scripts) runs once locks held by other brew
+    /// processes are released. The variables set for the operation alone are added to the
+    /// configured environment of whatever runs.
+    fn execute<'a>(
+        &'a self,
+        action: &'a ConfirmAction,
+        name: &'a str,
+        tx: &'a mpsc::Sender<AppEvent>,
+        cancel: &'a CancelToken,
+    ) -> Execution<'a> {
+        Box::pin(async move {
+            let title = operation_title(action, name, &self.flags);
+            if let Some(transcript) = self.transcript.as_ref().filter(|t| t.is_replay()) {
+                let exchange = transcript
+                    .next(true, &title)
+                    .with_context(|| format!("`{}` is not in the transcript", title))?;
+                for line in &exchange.output {
+                    let _ = tx.send(line.event());
+                }
+                return Ok(exchange.exit_code);
+            }
+
+            let env = action.env().iter().map(|(k, v)| (k, v));
+            if let ConfirmAction::InstallBrew = action.base() {
+                let mut cmd = tokio::process::Command::new("/bin/bash");
+                cmd.arg("-lc")
+                    .arg(HOMEBREW_INSTALL_SCRIPT)
+                    .envs(self.env.iter().map(|(k, v)| (k, v)))
+                    .envs(env);
+                let code = run_command(self, &title, cmd, tx, cancel).await;
+                self.inflight.operation_ended();
+                return code;
+            }
+
+            if let Some(state) = &self.demo {
+                let (state, action, name, tx) = (
+                    Arc::clone(state),
+                    action.clone(),
+                    name.to_string(),
+                    tx.clone(),
+                );
+                let code = tokio::task::spawn_blocking(move || {
+                    crate::demo::simulate_operation(&state, &action, &name, &tx)
+                })
+                .await?;
+                return Ok(code);
+            }
+
+            if !wait_for_brew_locks(self, tx, cancel).await {
+                return Ok(None);
+            }
+            let mut cmd = match action.base() {
+                ConfirmAction::PurgeCache { older_than_days } => {
+                    let mut cmd = tokio::process::Command::new("find");
+                    cmd.args(disk::purge_args(name, *older_than_days));
+                    cmd
+                }
+                ConfirmAction::SwitchVersion { .. }
+                | ConfirmAction::RemoveVersion { .. }
+                | ConfirmAction::ExtractInstall { .. } => {
+                    let brew = self.program.to_string_lossy();
+                    let mut cmd = tokio::process::Command::new("sh");
+                    cmd.arg("-c")
+                        .arg(action.shell_script(name, &brew).unwrap_or_default())
+                        .envs(self.env.iter().map(|(k, v)| (k, v)));
+                    cmd
+                }
+                _ => {
+                    let mut cmd = tokio::process::Command::from(self.command());
+                    cmd.args(action.brew_args_with(name, &self.flags));
+                    cmd
+                }
+            };
+            cmd.envs(env);
+            let code = run_command(self, &title, cmd, tx, cancel).await;
+            // queries already running may have seen the packages before it changed them
+            self.inflight.operation_ended();
+            code
+        })
+    }
+
+    fn flags(&self) -> &[String] {
+        &self.flags
+    }
+
+    fn is_available(&self) -> bool {
+        self.is_demo()
+            || self.is_replay()
+            || !matches!(
+                self.health_check()
+                    .as_ref()
+                    .map_err(|e| e.downcast_ref::<BrewError>()),
+                Err(Some(BrewError::NotInstalled { .. }))
+            )
+    }
+
+    fn health(&self) -> Result<Health> {
+        self.health_check()
+    }
+
+    fn persistent(&self) -> bool {
+        !self.is_demo() && !self.is_replay()
+    }
+
+    fn reconfigure(&self, cfg: &BrewConfig) -> Option<Arc<dyn BrewBackend>> {
+        if self.is_demo() || self.is_replay() {
+            return None;
+        }
+        // a recording goes on with the new settings
+        Some(Arc::new(Brew {
+            transcript: self.transcript.clone(),
+            ..Brew::from_config(cfg)
+        }))
+    }
+}
+
+/// How an operation is announced: its command line, or `install-homebrew` for the installer.
+fn operation_title(action: &ConfirmAction, name: &str, flags: &[String]) -> String {
+    if let ConfirmAction::InstallBrew = action.base() {
+        "install-homebrew".to_string()
+    } else {
+        action.command_line_with(name, flags)
+    }
+}
+
+/// Run an operation's command, killing it after `brew`'s operation timeout and adding it to the
+/// transcript when `brew` records one. Resolves to the exit code.
+async fn run_command(
+    brew: &Brew,
+    title: &str,
+    cmd: tokio::process::Command,
+    tx: &mpsc::Sender<AppEvent>,
+    cancel: &CancelToken,
+) -> Result<Option<i32>> {
+    let run = record_command(brew, title, cmd, tx, cancel);
+    let Some(limit) = brew.operation_timeout else {
+        return run.await;
+    };
+    // dropping the unfinished run kills the child
+    match tokio::time::timeout(limit, run).await {
+        Ok(result) => result,
+        Err(_) => {
+            tracing::warn!("{} timed out after {:?}", title, limit);
+            Err(BrewError::Timeout {
+                command: title.to_string(),
+                after: limit,
+            }
+            .into())
+        }
+    }
+}
+
+async fn record_command(
+    brew: &Brew,
+    title: &str,
+    cmd: tokio::process::Command,
+    tx: &mpsc::Sender<AppEvent>,
+    cancel: &CancelToken,
+) -> Result<Option<i32>> {
+    let Some(transcript) = &brew.transcript else {
+        return Ok(executor::run(cmd, tx, cancel).await?.code());
+    };
+    let mut output = vec![];
+    let status = executor::run_with(cmd, tx, cancel, |line| output.push(line.into())).await?;
+    transcript.save(&Exchange {
+        command: title.to_string(),
+        operation: true,
+        output,
+        exit_code: status.code(),
+    });
+    Ok(status.code())
+}
+
+/// Wait until no brew process outside the TUI holds a Homebrew lock, reporting the held
+/// locks to the UI while waiting so the queued operation shows a "waiting" state. Returns
+/// `false` when `cancel` fired first.
+async fn wait_for_brew_locks(
+    brew: &Brew,
+    tx: &mpsc::Sender<AppEvent>,
+    cancel: &CancelToken,
+) -> bool {
+    let Some(dir) = lock::lock_dir(brew) else {
+        return true;
+    };
+    let mut last: Vec<String> = vec![];
+    let mut free = true;
+    loop {
+        let held = lock::held_locks(&dir);
+        if held.is_empty() {
+            break;
+        }
+        if held != last {
+            let _ = tx.send(AppEvent::LockWait(held.clone()));
+            last = held;
+        }
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                free = false;
+                break;
+            }
+            _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+        }
+    }
+    if !last.is_empty() {
+        let _ = tx.send(AppEvent::LockWait(vec![]));
+    }
+    free
+}
+
+/// Run an operation to completion through `brew`: the command (or the Homebrew installer for
+/// `InstallBrew`) is announced with `OpStart`, its output streamed as `OpLog`/`OpErr` events and
+/// its result reported before `OpEnd`. Cancelling stops a wait for brew locks or terminates the
+/// running process. Returns the exit code (`None` when the command could not be spawned, was
+/// killed or never started).
+pub async fn run_operation(
+    brew: &dyn BrewBackend,
+    action: &ConfirmAction,
+    name: &str,
+    tx: &mpsc::Sender<AppEvent>,
+    cancel: &CancelToken,
+) -> Option<i32> {
+    let title = operation_title(action, name, brew.flags());
+    let _ = tx.send(AppEvent::OpStart(title.clone()));
+    tracing::info!("operation started: {}", title);
+
+    let code = match brew.execute(action, name, tx, cancel).await {
+        Ok(code) => {
+            let _ = tx.send(AppEvent::OpExit(code));
+            if cancel.is_cancelled() {
+                tracing::info!("operation cancelled: {}", title);
+                let _ = tx.send(AppEvent::Log(format!("{} cancelled", title)));
+            } else if code == Some(0) {
+                tracing::info!("operation completed: {}", title);
+                let _ = tx.send(AppEvent::Status(format!("{} completed", title)));
+            } else {
+                let reason = match code {
+                    Some(c) => format!("exit code {}", c),
+                    None => "terminated".to_string(),
+                };
+                tracing::error!("operation failed: {}: {}", title, reason);
+                let _ = tx.send(AppEvent::Error(format!("{} failed: {}", title, reason)));
+            }
+            code
+        }
+        Err(e) => {
+            tracing::error!("failed to run {}: {:#}", title, e);
+            let hint = e
+                .downcast_ref::<BrewError>()
+                .and_then(BrewError::hint)
+                .map(|h| format!(" ({})", h))
+                .unwrap_or_default();
+            let _ = tx.send(AppEvent::OpExit(None));
+            let _ = tx.send(AppEvent::OpErr(format!(
+                "failed to run {}: {:#}{}",
+                title, e, hint
+            )));
+            None
+        }
+    };
+    let _ = tx.send(AppEvent::OpEnd(title));
+    code
+}
+
+/// Blocking form of `run_operation` for callers outside the runtime (`homebrew-tui upgrade`,
+/// tests). Must not be called from async code.
+pub fn run_brew_operation(
+    brew: &dyn BrewBackend,
+    action: &ConfirmAction,
+    name: &str,
+    tx: &mpsc::Sender<AppEvent>,
+) -> Option<i32> {
+    jobs::runtime().block_on(run_operation(brew, action, name, tx, &CancelToken::new()))
+}
+
+/// What a scripted operation prints, line by line.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+impl ScriptLine {
+    /// The event the line is shown with, `OpLog` or `OpErr`.
+    pub fn event(&self) -> AppEvent {
+        match self {
+            ScriptLine::Stdout(l) => AppEvent::OpLog(l.clone()),
+            ScriptLine::Stderr(l) => AppEvent::OpErr(l.clone()),
+        }
+    }
+}
+
+impl From<&LogLine> for ScriptLine {
+    fn from(line: &LogLine) -> Self {
+        match line.stream {
+            Stream::Stdout => ScriptLine::Stdout(line.text.clone()),
+            Stream::Stderr => ScriptLine::Stderr(line.text.clone()),
+        }
+    }
+}
+
+/// The output and exit code of the operation with the command line `command` (without the
+/// configured flags, e.g. `brew upgrade jq`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptedOperation {
+    pub command: String,
+    pub output: Vec<ScriptLine>,
+    pub exit_code: i32,
+}
+
+/// A backend answering from the data in its fields. Operations with a script in `scripts` run
+/// it; the others print `output` and exit with `exit_code`. All are recorded (as command lines)
+/// in `executed`; nothing is run.
+#[derive(Default)]
+pub struct MockBackend {
+    pub installed: Vec<FormulaInfo>,
+    pub available: Vec<ApiEntry>,
+    pub outdated: Vec<String>,
+    pub pinned: Vec<String>,
+    pub cleanup: CleanupPreview,
+    pub doctor: Vec<DoctorItem>,
+    pub download_cache: DownloadCache,
+    pub disk_usage: DiskUsage,
+    pub environment: BrewEnvironment,
+    /// Build logs, found by package name.
+    pub build_logs: Vec<BuildLogs>,
+    /// Tap history returned for every formula.
+    pub formula_log: Vec<FormulaCommit>,
+    /// livecheck results; those of the requested formulae are returned, all of them for a
+    /// bulk check.
+    pub livecheck: Vec<Livecheck>,
+    pub casks: Vec<InstalledCask>,
+    pub services: Vec<Service>,
+    pub taps: Vec<String>,
+    /// Stats of GitHub repositories by `owner/name`; other repositories fail.
+    pub github: Vec<(String, RepoStats)>,
+    /// What formulae depend on, by name; others have no dependencies.
+    pub dependencies: Vec<(String, Vec<String>)>,
+    /// Bottle download sizes by formula name.
+    pub bottle_sizes: Vec<(String, u64)>,
+    /// The index has not changed since it was last read, so `available` is not asked for.
+    pub index_unchanged: bool,
+    /// Output of the operations, by command line; the first matching script is used.
+    pub scripts: Vec<ScriptedOperation>,
+    /// Lines every operation without a script prints to stdout.
+    pub output: Vec<String>,
+    pub exit_code: i32,
+    pub executed: Mutex<Vec<String>>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A backend serving the fixtures in `dir`, each file optional and in the format of the
+    /// brew command it stands in for:
+    ///
+    /// - `info.json`: `brew info --json=v2 --installed`, the installed formulae
+    /// - `api/formula.jws.json`, `api/cask.jws.json`: Homebrew's API cache, the Available list
+    /// - `outdated.txt`: `brew outdated --formula`
+    /// - `pinned.txt`: `brew list --pinned`
+    /// - `taps.txt`: `brew tap`
+    /// - `casks.txt`: `brew list --cask --versions`
+    /// - `services.json`: `brew services list --json`
+    /// - `doctor.txt`: `brew doctor`
+    /// - `cleanup.txt`: `brew cleanup --dry-run`
+    /// - `livecheck.json`: `brew livecheck --json`
+    /// - `log.txt`: `brew log`, the tap history of every formula
+    /// - `operations.json`: a list of `ScriptedOperation`s
+    pub fn from_fixtures(dir: &Path) -> Result<Self> {
+        let read = |file: &str| -> Result<Option<String>> {
+            let path = dir.join(file);
+            if !path.exists() {
+                return Ok(None);
+            }
+            fs::read_to_string(&path)
+                .map(Some)
+                .with_context(|| format!("reading {}", path.display()))
+        };
+        let json = |file: &str, e: serde_json::Error| {
+            anyhow::anyhow!("parsing {}: {}", dir.join(file).display(), e)
+        };
+        let mut mock = Self::new();
+        if let Some(s) = read("info.json")? {
+            mock.installed = brew::parse_formulae(&s).map_err(|e| json("info.json", e))?;
+        }
+        if dir.join("api").join("formula.jws.json").exists() {
+            mock.available = api_cache::load(dir)?;
+        }
+        if let Some(s) = read("outdated.txt")? {
+            mock.outdated = parse::parse_outdated(&s);
+        }
+        if let Some(s) = read("pinned.txt")? {
+            mock.pinned = parse::parse_names(&s);
+        }
+        if let Some(s) = read("taps.txt")? {
+            mock.taps = parse::parse_names(&s);
+        }
+        if let Some(s) = read("casks.txt")? {
+            mock.casks = parse::parse_cask_versions(&s);
+        }
+        if let Some(s) = read("services.json")? {
+            mock.services = parse::parse_services(&s).map_err(|e| json("services.json", e))?;
+        }
+        if let Some(s) = read("doctor.txt")? {
+            mock.doctor = parse::parse_doctor(&s);
+        }
+        if let Some(s) = read("cleanup.txt")? {
+            mock.cleanup = parse::parse_cleanup_dry_run(&s);
+        }
+        if let Some(s) = read("livecheck.json")? {
+            mock.livecheck = parse::parse_livecheck(&s).map_err(|e| json("livecheck.json", e))?;
+        }
+        if let Some(s) = read("log.txt")? {
+            mock.formula_log = parse::parse_log(&s);
+        }
+        if let Some(s) = read("operations.json")? {
+            mock.scripts = serde_json::from_str(&s).map_err(|e| json("operations.json", e))?;
+        }
+        Ok(mock)
+    }
+
+    /// Command lines of the operations executed so far.
+    pub fn executed(&self) -> Vec<String> {
+        self.executed.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+}
+
+impl BrewBackend for MockBackend {
+    fn list_installed(&self) -> Result<Vec<FormulaInfo>> {
+        Ok(self.installed.clone())
+    }
+
+    fn info(&self, name: &str) -> Result<FormulaInfo> {
+        if let Some(f) = self.installed.iter().find(|f| f.name == name) {
+            return Ok(f.clone());
+        }
+        self.available
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| FormulaInfo {
+                name: e.name.clone(),
+                desc: e.desc.clone(),
+                ..Default::default()
+            })
+            .ok_or_else(|| anyhow::anyhow!("no info"))
+    }
+
+    fn search(&self, query: &str) -> Result<Vec<String>> {
+        let query = query.to_lowercase();
+        Ok(self
+            .available
+            .iter()
+            .filter(|e| e.name.to_lowercase().contains(&query))
+            .map(|e| e.name.clone())
+            .collect())
+    }
+
+    fn outdated(&self) -> Result<Vec<String>> {
+        Ok(self.outdated.clone())
+    }
+
+    fn pinned(&self) -> Result<Vec<String>> {
+        Ok(self.pinned.clone())
+    }
+
+    fn available_entries(&self) -> Result<Vec<ApiEntry>> {
+        Ok(self.available.clone())
+    }
+
+    fn refresh_index(&self, since: Option<u64>) -> Result<bool> {
+        Ok(since.is_none() || !self.index_unchanged)
+    }
+
+    fn cleanup_preview(&self) -> Result<CleanupPreview> {
+        Ok(self.cleanup.clone())
+    }
+
+    fn doctor(&self) -> Result<Vec<DoctorItem>> {
+        Ok(self.doctor.clone())
+    }
+
+    fn download_cache(&self) -> Result<DownloadCache> {
+        Ok(self.download_cache.clone())
+    }
+
+    fn disk_usage(&self) -> Result<DiskUsage> {
+        Ok(self.disk_usage.clone())
+    }
+
+    fn environment(&self) -> Result<BrewEnvironment> {
+        Ok(self.environment.clone())
+    }
+
+    fn build_logs(&self, names: &[String]) -> Result<BuildLogs> {
+        self.build_logs
+            .iter()
+            .find(|l| names.contains(&l.name))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("no build logs"))
+    }
+
+    fn formula_log(&self, _name: &str) -> Result<Vec<FormulaCommit>> {
+        Ok(self.formula_log.clone())
+    }
+
+    fn installed_details(&self) -> Result<Vec<FormulaInfo>> {
+        Ok(self.installed.clone())
+    }
+
+    fn livecheck(&self, names: &[String]) -> Result<Vec<Livecheck>> {
+        Ok(self
+            .livecheck
+            .iter()
+            .filter(|l| names.is_empty() || names.contains(&l.formula))
+            .cloned()
+            .collect())
+    }
+
+    fn casks(&self) -> Result<Vec<InstalledCask>> {
+        Ok(self.casks.clone())
+    }
+
+    fn services(&self) -> Result<Vec<Service>> {
+        Ok(self.services.clone())
+    }
+
+    fn taps(&self) -> Result<Vec<String>> {
+        Ok(self.taps.clone())
+    }
+
+    fn github_stats(&self, repo: &str) -> Result<RepoStats> {
+        self.github
+            .iter()
+            .find(|(r, _)| r == repo)
+            .map(|(_, s)| s.clone())
+            .ok_or_else(|| anyhow::anyhow!("no such repository"))
+    }
+
+    fn dependencies(&self, name: &str) -> Result<Vec<String>> {
+        Ok(self
+            .dependencies
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, deps)| deps.clone())
+            .unwrap_or_default())
+    }
+
+    fn bottle_sizes(&self, names: &[String]) -> Result<HashMap<String, u64>> {
+        Ok(self
+            .bottle_sizes
+            .iter()
+            .filter(|(n, _)| names.contains(n))
+            .cloned()
+            .collect())
+    }
+
+    fn execute<'a>(
+        &'a self,
+        action: &'a ConfirmAction,
+        name: &'a str,
+        tx: &'a mpsc::Sender<AppEvent>,
+        cancel: &'a CancelToken,
+    ) -> Execution<'a> {
+        Box::pin(async move {
+            let command = action.command_line_with(name, &[]);
+            if let Ok(mut e) = self.executed.lock() {
+                e.push(command.clone());
+            }
+            if cancel.is_cancelled() {
+                return Ok(None);
+            }
+            if let Some(script) = self.scripts.iter().find(|s| s.command == command) {
+                for line in &script.output {
+                    let _ = tx.send(line.event());
+                }
+                return Ok(Some(script.exit_code));
+            }
+            for line in &self.output {
+                let _ = tx.send(AppEvent::OpLog(line.clone()));
+            }
+            Ok(Some(self.exit_code))
+        })
+    }
+}
@@ -0,0 +1,105 @@
+// Download sizes of bottles, for the dependency preview of an install. Homebrew publishes its
+// bottles as OCI images on ghcr.io, and the image index annotates every platform's bottle with
+// its size (`sh.brew.bottle.size`); it is read anonymously through `curl`, like the GitHub
+// stats.
+use crate::brew::FormulaInfo;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::process::Command;
+
+/// The image of a homebrew/core formula: `python@3.12` is `homebrew/core/python/3.12`.
+pub fn image_name(name: &str) -> String {
+    format!("homebrew/core/{}", name.replace('@', "/").replace('+', "x"))
+}
+
+/// The tag of the formula's current bottle: its version, `_<revision>` and `-<rebuild>` when
+/// non-zero. None for formulae outside homebrew/core or without a stable version.
+pub fn image_tag(info: &FormulaInfo) -> Option<String> {
+    if info.tap.as_deref().is_some_and(|t| t != "homebrew/core") {
+        return None;
+    }
+    let mut tag = info.stable_version()?.to_string();
+    if info.revision > 0 {
+        tag.push_str(&format!("_{}", info.revision));
+    }
+    let rebuild = info
+        .bottle
+        .as_ref()
+        .and_then(|b| b.get("stable")?.get("rebuild")?.as_u64())
+        .unwrap_or(0);
+    if rebuild > 0 {
+        tag.push_str(&format!("-{}", rebuild));
+    }
+    Some(tag)
+}
+
+/// The bottle size for `os` and `arch` (as in `std::env::consts`) in an image index; the
+/// largest of the matching entries, since there is one per macOS version, and of all entries
+/// when none matches (an `all` bottle).
+pub fn parse_size(index: &str, os: &str, arch: &str) -> Result<Option<u64>> {
+    #[derive(Deserialize)]
+    struct Index {
+        #[serde(default)]
+        manifests: Vec<Manifest>,
+    }
+    #[derive(Deserialize)]
+    struct Manifest {
+        #[serde(default)]
+        platform: HashMap<String, String>,
+        #[serde(default)]
+        annotations: HashMap<String, String>,
+    }
+    let index: Index = serde_json::from_str(index).context("unexpected bottle manifest")?;
+    let os = if os == "macos" { "darwin" } else { os };
+    let arch = match arch {
+        "aarch64" => "arm64",
+        "x86_64" => "amd64",
+        a => a,
+    };
+    let sized: Vec<(&Manifest, u64)> = index
+        .manifests
+        .iter()
+        .filter_map(|m| {
+            let size = m.annotations.get("sh.brew.bottle.size")?.parse().ok()?;
+            Some((m, size))
+        })
+        .collect();
+    let matching = sized.iter().filter(|(m, _)| {
+        m.platform.get("os").is_some_and(|o| o == os)
+            && m.platform.get("architecture").is_some_and(|a| a == arch)
+    });
+    Ok(matching
+        .map(|(_, s)| *s)
+        .max()
+        .or_else(|| sized.iter().map(|(_, s)| *s).max()))
+}
+
+/// Ask ghcr.io for the size of `info`'s bottle on this machine; None when it has none there.
+pub fn fetch_size(info: &FormulaInfo) -> Result<Option<u64>> {
+    let Some(tag) = image_tag(info) else {
+        return Ok(None);
+    };
+    let url = format!(
+        "https://ghcr.io/v2/{}/manifests/{}",
+        image_name(&info.name),
+        tag
+    );
+    let out = Command::new("curl")
+        .args(["-fsSL", "--max-time", "10"])
+        .args(["-H", "Accept: application/vnd.oci.image.index.v1+json"])
+        // ghcr's anonymous token
+        .args(["-H", "Authorization: Bearer QQ=="])
+        .arg(&url)
+        .output()
+        .map_err(|e| anyhow!("could not run curl: {}", e))?;
+    if !out.status.success() {
+        let err = String::from_utf8_lossy(&out.stderr);
+        return Err(anyhow!("{} failed: {}", url, err.trim()));
+    }
+    parse_size(
+        &String::from_utf8_lossy(&out.stdout),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    )
+}
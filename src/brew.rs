@@ -1,17 +1,143 @@
+use crate::api_cache::{self, ApiEntry};
+use crate::bottles;
+use crate::build_logs::{self, BuildLogs};
+use crate::coalesce::Coalescer;
+use crate::config::{BrewConfig, Metadata};
+use crate::demo::DemoState;
+use crate::disk::{self, DiskUsage, DownloadCache};
+use crate::formulae_api;
+use crate::parse::{
+    parse_analytics_state, parse_cask_versions, parse_cleanup_dry_run, parse_doctor,
+    parse_key_values, parse_livecheck, parse_log, parse_names, parse_outdated, parse_search,
+    parse_services,
+};
+use crate::paths;
+use crate::transcript::{Exchange, Transcript};
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::process::Command;
-use std::str;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-#[derive(Clone, Deserialize, Debug, Default)]
+/// How long a query (list, info, search, outdated) may run before it is killed, unless
+/// `query_timeout` under `[brew]` says otherwise.
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The longest the startup health check waits for `brew --version` and `brew --prefix`.
+pub const HEALTH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many commits `formula_log` asks `brew log` for.
+pub const LOG_COMMITS: usize = 20;
+
+/// Why a brew call failed. Functions here return it inside `anyhow::Error`; callers that want
+/// to react to the cause use `downcast_ref::<BrewError>()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BrewError {
+    /// The brew executable could not be started.
+    NotInstalled { program: PathBuf },
+    /// brew ran but exited unsuccessfully.
+    CommandFailed {
+        command: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+    /// brew's output was not what we expected.
+    ParseError { command: String, message: String },
+    /// brew did not finish within the query timeout and was killed.
+    Timeout { command: String, after: Duration },
+}
+
+impl BrewError {
+    /// What the user can do about it, when there is something.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            BrewError::NotInstalled { .. } => {
+                Some("install Homebrew or set `path` under [brew] in the config file")
+            }
+            BrewError::CommandFailed { stderr, .. }
+                if stderr.contains("Another active Homebrew process") =>
+            {
+                Some("another brew process is running; try again once it has finished")
+            }
+            BrewError::CommandFailed { .. } => None,
+            BrewError::ParseError { .. } => Some("this brew version may be unsupported"),
+            BrewError::Timeout { .. } => Some(
+                "brew may be hanging or busy updating itself; try again in a moment, or raise \
+                 `query_timeout`/`operation_timeout` under [brew]",
+            ),
+        }
+    }
+}
+
+/// A log line for a failed brew call: `what` and the error, plus a suggestion when the cause is
+/// a known `BrewError`. The failure is also recorded in the log file.
+pub fn describe_error(what: &str, e: &anyhow::Error) -> String {
+    let hint = e.downcast_ref::<BrewError>().and_then(BrewError::hint);
+    let line = match hint {
+        Some(hint) => format!("{}: {:#} ({})", what, e, hint),
+        None => format!("{}: {:#}", what, e),
+    };
+    tracing::error!("{}", line);
+    line
+}
+
+impl fmt::Display for BrewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BrewError::NotInstalled { program } => {
+                write!(f, "brew could not be run ({})", program.display())
+            }
+            BrewError::CommandFailed {
+                command,
+                code,
+                stderr,
+            } => {
+                match code {
+                    Some(c) => write!(f, "{} failed with exit code {}", command, c)?,
+                    None => write!(f, "{} was killed", command)?,
+                }
+                let stderr = stderr.trim();
+                if !stderr.is_empty() {
+                    write!(f, ": {}", stderr)?;
+                }
+                Ok(())
+            }
+            BrewError::ParseError { command, message } => {
+                write!(f, "unexpected output from {}: {}", command, message)
+            }
+            BrewError::Timeout { command, after } => {
+                write!(f, "{} did not finish within {}s", command, after.as_secs())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BrewError {}
+
+type BrewResult<T> = std::result::Result<T, BrewError>;
+
+/// One installed version of a formula.
+#[derive(Clone, Deserialize, Serialize, Debug, Default)]
 pub struct InstalledInfo {
     #[serde(default)]
     pub version: String,
+    /// When it was installed, in unix seconds.
+    #[serde(default)]
+    pub time: Option<u64>,
     // other fields omitted
 }
 
-#[derive(Clone, Deserialize, Debug, Default)]
+/// A formula as described by brew's JSON output; list queries fill in only `name`.
+#[derive(Clone, Deserialize, Serialize, Debug, Default)]
 pub struct FormulaInfo {
     pub name: String,
     pub full_name: Option<String>,
@@ -26,181 +152,1313 @@ pub struct FormulaInfo {
     pub installed: Vec<InstalledInfo>,
     #[serde(default)]
     pub versions: Option<JsonValue>,
+    /// Where the sources come from (`urls.stable.url`, `urls.head.url`).
+    #[serde(default)]
+    pub urls: Option<JsonValue>,
     #[serde(default)]
     pub caveats: Option<String>,
+    /// Version currently symlinked into the prefix, if any.
+    #[serde(default)]
+    pub linked_keg: Option<String>,
+    #[serde(default)]
+    pub keg_only: bool,
+    /// The tap the formula comes from, e.g. `homebrew/core`.
+    #[serde(default)]
+    pub tap: Option<String>,
+    /// Bumped when the formula is rebuilt without a new version; part of the bottle's tag.
+    #[serde(default)]
+    pub revision: u32,
+    /// The bottles brew pours (`bottle.stable.rebuild`, `bottle.stable.files`).
+    #[serde(default)]
+    pub bottle: Option<JsonValue>,
+    /// Install counts, which only the formulae.brew.sh API has (`analytics.install.30d`, ...).
+    #[serde(default)]
+    pub analytics: Option<JsonValue>,
 }
 
+impl FormulaInfo {
+    /// The current stable version brew would install (`versions.stable`).
+    pub fn stable_version(&self) -> Option<&str> {
+        self.versions.as_ref()?.get("stable")?.as_str()
+    }
+
+    /// The URL of the stable source (`urls.stable.url`).
+    pub fn source_url(&self) -> Option<&str> {
+        self.urls.as_ref()?.get("stable")?.get("url")?.as_str()
+    }
+
+    /// The version in use: the linked keg, otherwise the first installed version.
+    pub fn installed_version(&self) -> Option<&str> {
+        self.linked_keg
+            .as_deref()
+            .or_else(|| self.installed.first().map(|i| i.version.as_str()))
+    }
+
+    /// When the newest of the installed versions was installed, in unix seconds.
+    pub fn installed_time(&self) -> Option<u64> {
+        self.installed.iter().filter_map(|i| i.time).max()
+    }
+
+    /// Installs over the last 30, 90 and 365 days, when the API reported them; each period
+    /// counts its installs by option (`jq`, `jq --HEAD`), which are added up.
+    pub fn install_counts(&self) -> Option<[u64; 3]> {
+        let install = self.analytics.as_ref()?.get("install")?;
+        let count = |period: &str| -> Option<u64> {
+            let by_option = install.get(period)?.as_object()?;
+            Some(by_option.values().filter_map(JsonValue::as_u64).sum())
+        };
+        Some([count("30d")?, count("90d")?, count("365d")?])
+    }
+
+    /// The tap, from `tap` or the prefix of a qualified `full_name` (`user/repo/name`).
+    pub fn tap_name(&self) -> Option<&str> {
+        self.tap.as_deref().or_else(|| {
+            let full = self.full_name.as_deref()?;
+            full.rsplit_once('/').map(|(tap, _)| tap)
+        })
+    }
+}
+
+/// Compare version strings part by part, numeric parts as numbers: `1.10` is newer than `1.9`
+/// and `1.7.1_1` (a revision) newer than `1.7.1`.
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parts = |v: &str| -> Vec<(u64, String)> {
+        v.split(['.', '_', '-'])
+            .map(|p| (p.parse().unwrap_or(0), p.to_string()))
+            .collect()
+    };
+    parts(a).cmp(&parts(b))
+}
+
+/// What `brew cleanup --dry-run` would remove.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CleanupPreview {
+    /// Paths with the size brew reports for them, e.g. `("/opt/homebrew/Cellar/jq/1.6",
+    /// Some("18 files, 1.1MB"))`.
+    pub files: Vec<(String, Option<String>)>,
+    /// Total reclaimable space as brew prints it ("2.6MB"), when it printed one.
+    pub total: Option<String>,
+}
+
+/// The tap of a tap-qualified name (`someone/tools` for `someone/tools/tool`); None for the
+/// short names of the official formulae and casks.
+pub fn tap_of(name: &str) -> Option<&str> {
+    name.rsplit_once('/')
+        .map(|(tap, _)| tap)
+        .filter(|t| t.contains('/'))
+}
+
+/// The `brew search` results `query` from the Install prompt could mean: a tap-qualified query
+/// only itself, a short one every tap's package of that name. When nothing has that exact name
+/// all the results are offered.
+pub fn install_candidates(query: &str, results: &[String]) -> Vec<String> {
+    let query = query.to_lowercase();
+    let exact: Vec<String> = results
+        .iter()
+        .filter(|r| {
+            let r = r.to_lowercase();
+            if query.contains('/') {
+                r == query
+            } else {
+                r.rsplit('/').next() == Some(query.as_str())
+            }
+        })
+        .cloned()
+        .collect();
+    if exact.is_empty() {
+        results.to_vec()
+    } else {
+        exact
+    }
+}
+
+/// What installing a formula would pull in, for its confirm dialog.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DependencyPreview {
+    pub name: String,
+    /// Dependencies that are not installed yet, in `brew deps` order.
+    pub new: Vec<String>,
+    /// How many of its dependencies are installed already.
+    pub installed: usize,
+    /// Download size in bytes of the new dependencies' bottles, of those where it is known.
+    pub download: u64,
+    /// How many of the new dependencies have no known size.
+    pub unknown_size: usize,
+}
+
+impl DependencyPreview {
+    /// The preview for `name` from its dependencies, the installed formulae and the bottle
+    /// sizes found.
+    pub fn new(
+        name: &str,
+        deps: Vec<String>,
+        installed: &HashSet<String>,
+        sizes: &HashMap<String, u64>,
+    ) -> Self {
+        let (present, new): (Vec<String>, Vec<String>) =
+            deps.into_iter().partition(|d| installed.contains(d));
+        Self {
+            name: name.to_string(),
+            download: new.iter().filter_map(|d| sizes.get(d)).sum(),
+            unknown_size: new.iter().filter(|d| !sizes.contains_key(*d)).count(),
+            installed: present.len(),
+            new,
+        }
+    }
+
+    /// `Pulls in 2 new dependencies, about 1.2MB to download: oniguruma, pcre2`, or that it
+    /// needs nothing new.
+    pub fn summary(&self) -> String {
+        if self.new.is_empty() {
+            return match self.installed {
+                0 => "No dependencies.".to_string(),
+                n => format!("No new dependencies ({} installed already).", n),
+            };
+        }
+        let size = if self.unknown_size == self.new.len() {
+            "download size unknown".to_string()
+        } else if self.unknown_size > 0 {
+            format!("at least {} to download", disk::format_size(self.download))
+        } else {
+            format!("about {} to download", disk::format_size(self.download))
+        };
+        let count = match self.new.len() {
+            1 => "1 new dependency".to_string(),
+            n => format!("{} new dependencies", n),
+        };
+        format!("Pulls in {}, {}: {}", count, size, self.new.join(", "))
+    }
+
+    /// Whether the download is big enough (100 MB) to point out.
+    pub fn is_large(&self) -> bool {
+        self.download >= 100 << 20
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// One finding of `brew doctor`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DoctorItem {
+    pub severity: Severity,
+    /// The `Warning:`/`Error:` line without its prefix.
+    pub title: String,
+    /// The explanation brew prints below it.
+    pub details: Vec<String>,
+}
+
+impl DoctorItem {
+    /// The finding as brew printed it, for copying.
+    pub fn text(&self) -> String {
+        let prefix = match self.severity {
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        };
+        let mut lines = vec![format!("{}: {}", prefix, self.title)];
+        lines.extend(self.details.iter().cloned());
+        lines.join("\n")
+    }
+}
+
+/// What `brew config` and `brew --env` report about the installation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BrewEnvironment {
+    /// `brew config` lines as key/value pairs, in brew's order.
+    pub config: Vec<(String, String)>,
+    /// The build environment from `brew --env`.
+    pub env: Vec<(String, String)>,
+    /// `brew --cellar`.
+    pub cellar: Option<String>,
+    /// Whether `brew analytics` reports analytics as enabled.
+    pub analytics: Option<bool>,
+}
+
+impl BrewEnvironment {
+    /// The value of a `brew config` key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.config
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Whether formulae come from the JSON API rather than a local homebrew/core clone.
+    pub fn uses_api(&self) -> bool {
+        matches!(self.get("HOMEBREW_NO_INSTALL_FROM_API"), None | Some(""))
+    }
+
+    /// The facts that usually explain odd behaviour, with "unknown" for anything brew did not
+    /// report.
+    pub fn summary(&self) -> Vec<(&'static str, String)> {
+        let value = |key: &str| self.get(key).unwrap_or("unknown").to_string();
+        let tap_clone = self.get("Core tap HEAD").or(self.get("Core tap origin"));
+        let core_tap = match tap_clone {
+            Some(_) => "installed",
+            None => self.get("Core tap").unwrap_or("not installed"),
+        };
+        vec![
+            ("Homebrew", value("HOMEBREW_VERSION")),
+            ("Prefix", value("HOMEBREW_PREFIX")),
+            (
+                "Cellar",
+                self.cellar.clone().unwrap_or_else(|| "unknown".into()),
+            ),
+            (
+                "Formulae from",
+                if self.uses_api() { "JSON API" } else { "taps" }.into(),
+            ),
+            ("Core tap", core_tap.to_string()),
+            (
+                "Analytics",
+                match self.analytics {
+                    Some(true) => "enabled",
+                    Some(false) => "disabled",
+                    None => "unknown",
+                }
+                .into(),
+            ),
+            ("macOS", value("macOS")),
+            ("CLT", value("CLT")),
+            ("Xcode", value("Xcode")),
+            ("Git", value("Git")),
+            ("Curl", value("Curl")),
+        ]
+    }
+}
+
+/// What a `brew update` changed, counted from its output.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct UpdateSummary {
+    pub new_formulae: usize,
+    pub updated_formulae: usize,
+    pub renamed_formulae: usize,
+    pub deleted_formulae: usize,
+    /// New, updated, renamed and deleted casks together.
+    pub casks: usize,
+}
+
+impl UpdateSummary {
+    /// Formulae added, updated, renamed or deleted.
+    pub fn formulae(&self) -> usize {
+        self.new_formulae + self.updated_formulae + self.renamed_formulae + self.deleted_formulae
+    }
+
+    /// One line for the log: "brew update: 14 formulae changed (2 new, 12 updated)".
+    pub fn message(&self) -> String {
+        if self.formulae() == 0 && self.casks == 0 {
+            return "brew update: already up to date".into();
+        }
+        let mut text = match self.formulae() {
+            0 => "brew update: no formulae changed".to_string(),
+            n => {
+                let parts: Vec<String> = [
+                    (self.new_formulae, "new"),
+                    (self.updated_formulae, "updated"),
+                    (self.renamed_formulae, "renamed"),
+                    (self.deleted_formulae, "deleted"),
+                ]
+                .iter()
+                .filter(|(count, _)| *count > 0)
+                .map(|(count, what)| format!("{} {}", count, what))
+                .collect();
+                let noun = if n == 1 { "formula" } else { "formulae" };
+                format!("brew update: {} {} changed ({})", n, noun, parts.join(", "))
+            }
+        };
+        if self.casks > 0 {
+            let noun = if self.casks == 1 { "cask" } else { "casks" };
+            text.push_str(&format!(", {} {} changed", self.casks, noun));
+        }
+        text
+    }
+}
+
+/// One commit of a formula's tap history, from `brew log`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FormulaCommit {
+    pub hash: String,
+    pub author: String,
+    /// The date as git prints it, e.g. "Tue Mar 5 10:12:44 2024 +0000".
+    pub date: String,
+    /// The first line of the commit message.
+    pub subject: String,
+}
+
+impl FormulaCommit {
+    /// The day of the commit, e.g. "Mar 5 2024".
+    pub fn day(&self) -> String {
+        let parts: Vec<&str> = self.date.split_whitespace().collect();
+        match parts.as_slice() {
+            [_, month, day, _, year, ..] => format!("{} {} {}", month, day, year),
+            _ => self.date.clone(),
+        }
+    }
+
+    /// Whether this commit brought `name` to `version`: homebrew-core titles version bumps
+    /// "jq 1.7.1" and the bottle commits that follow them "jq: update 1.7.1 bottle.".
+    pub fn bumps_to(&self, name: &str, version: &str) -> bool {
+        let bump = format!("{} {}", name, version);
+        let bottle = format!("{}: update {} bottle", name, version);
+        self.subject == bump
+            || self.subject.starts_with(&format!("{} ", bump))
+            || self.subject.starts_with(&bottle)
+    }
+}
+
+/// The versioned formulae (`python@3.11`) of the formula `name` among `available`, newest
+/// version first. `name` may itself be versioned; it is left out.
+pub fn versioned_formulae(available: &[String], name: &str) -> Vec<String> {
+    let prefix = format!("{}@", formula_base(name));
+    let mut found: Vec<String> = available
+        .iter()
+        .filter(|n| n.starts_with(&prefix) && n.as_str() != name)
+        .cloned()
+        .collect();
+    found.sort_by(|a, b| version_key(b).cmp(&version_key(a)).then_with(|| a.cmp(b)));
+    found
+}
+
+/// The formula a versioned formula is a version of: `python` for `python@3.12`.
+pub fn formula_base(name: &str) -> &str {
+    name.split('@').next().unwrap_or(name)
+}
+
+/// Orders formula names of one base: the unversioned formula highest, then by version with
+/// numeric parts compared as numbers, so python@3.9 sorts below python@3.12.
+fn version_key(name: &str) -> (bool, Vec<u64>) {
+    match name.split_once('@') {
+        Some((_, version)) => (
+            false,
+            version.split('.').map(|p| p.parse().unwrap_or(0)).collect(),
+        ),
+        None => (true, vec![]),
+    }
+}
+
+/// Collapse the versioned formulae among `matches` (indices into `names`, in list order) into
+/// one row per formula: the unversioned formula when it matched, otherwise its newest version.
+/// Returns the rows to list, with the other members right after their head for bases in
+/// `expanded`, and each head's other members, newest first.
+pub fn group_versioned(
+    names: &[String],
+    matches: &[usize],
+    expanded: &HashSet<String>,
+) -> (Vec<usize>, HashMap<usize, Vec<usize>>) {
+    let base = |i: usize| formula_base(&names[i]);
+    let mut members: HashMap<&str, Vec<usize>> = HashMap::new();
+    for &i in matches {
+        members.entry(base(i)).or_default().push(i);
+    }
+    let mut heads: HashMap<&str, usize> = HashMap::new();
+    let mut groups = HashMap::new();
+    for (b, mut m) in members {
+        if m.len() < 2 {
+            continue;
+        }
+        m.sort_by(|&x, &y| version_key(&names[y]).cmp(&version_key(&names[x])));
+        let head = m.remove(0);
+        heads.insert(b, head);
+        groups.insert(head, m);
+    }
+    let mut rows = Vec::with_capacity(matches.len());
+    for &i in matches {
+        match heads.get(base(i)) {
+            None => rows.push(i),
+            Some(&head) if head == i => {
+                rows.push(i);
+                if expanded.contains(base(i)) {
+                    rows.extend(&groups[&head]);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    (rows, groups)
+}
+
+/// What `brew livecheck` found for one formula: the version brew packages against the newest
+/// one upstream.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Livecheck {
+    pub formula: String,
+    /// The formula's version in brew.
+    pub current: Option<String>,
+    /// The newest version livecheck found upstream.
+    pub latest: Option<String>,
+    /// Upstream has released a newer version than brew packages.
+    pub outdated: bool,
+    pub newer_than_upstream: bool,
+    /// `error` or `skipped` when there is no comparison, with livecheck's reasons.
+    pub status: Option<String>,
+    pub messages: Vec<String>,
+}
+
+impl Livecheck {
+    /// Whether brew lags upstream.
+    pub fn lags(&self) -> bool {
+        self.outdated && self.status.is_none()
+    }
+}
+
+/// An installed cask and its version, as `brew list --cask --versions` prints it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InstalledCask {
+    pub name: String,
+    pub version: String,
+}
+
+/// A formula's background service as `brew services list --json` reports it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Service {
+    pub name: String,
+    /// `started`, `stopped`, `scheduled`, `error`, `none`, ...
+    pub status: String,
+    /// Who runs it; empty when it is not loaded.
+    #[serde(default)]
+    pub user: Option<String>,
+    /// The launchd plist or systemd unit.
+    #[serde(default, alias = "plist")]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+}
+
+/// Runs the `brew` CLI (or answers from the demo fixtures). Clones share running queries.
 #[derive(Clone)]
-pub struct Brew {}
+pub struct Brew {
+    /// The brew executable.
+    pub program: PathBuf,
+    /// Flags appended to operations that change packages (install, upgrade, link, ...). Queries
+    /// keep brew's default output so it can still be parsed.
+    pub flags: Vec<String>,
+    /// Environment variables set on every spawned brew process.
+    pub env: Vec<(String, String)>,
+    /// Fixture state answering every call instead of brew (`--demo`).
+    pub demo: Option<Arc<Mutex<DemoState>>>,
+    /// Queries currently running, shared by every clone.
+    pub inflight: Arc<Inflight>,
+    /// Limit for each query.
+    pub query_timeout: Duration,
+    /// Limit for each operation; `None` lets them run as long as they take.
+    pub operation_timeout: Option<Duration>,
+    /// Transcript every command is recorded to, or answered from when replaying one.
+    pub transcript: Option<Arc<Transcript>>,
+    /// Where `info` gets package details.
+    pub metadata: Metadata,
+}
+
+/// What a finished query printed, and its exit code (`None` when killed by a signal).
+struct QueryOutput {
+    code: Option<i32>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+impl QueryOutput {
+    fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// What the startup health check found out about brew.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Health {
+    /// The first line of `brew --version`, e.g. `Homebrew 4.3.0`.
+    pub version: String,
+    /// `brew --prefix`.
+    pub prefix: PathBuf,
+}
+
+/// Identical `info` and `outdated` calls made while one is already running wait for its result
+/// instead of starting another brew process. Only calls made since the same operation last
+/// ended are identical: one that started before an operation changed the Cellar may answer
+/// with the packages as they were.
+#[derive(Default)]
+pub struct Inflight {
+    info: Coalescer<BrewResult<FormulaInfo>>,
+    outdated: Coalescer<BrewResult<Vec<String>>>,
+    /// How many operations have ended, part of every key.
+    generation: AtomicU64,
+}
+
+impl Inflight {
+    /// Note that an operation ended: later calls no longer join those already running.
+    pub fn operation_ended(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}:{}", self.generation.load(Ordering::SeqCst), name)
+    }
+}
+
+/// Standard install locations checked when `brew` is not on `PATH`: Apple Silicon, Intel macOS
+/// and the shared Linux prefix. `~/.linuxbrew` is added at runtime.
+const KNOWN_LOCATIONS: [&str; 3] = [
+    "/opt/homebrew/bin/brew",
+    "/usr/local/bin/brew",
+    "/home/linuxbrew/.linuxbrew/bin/brew",
+];
+
+/// The brew executable to use when none is configured: `brew` from `PATH`, otherwise the first
+/// standard prefix that has one, otherwise plain `brew` (so errors mention the usual name).
+pub fn detect_program() -> PathBuf {
+    let mut fallbacks: Vec<PathBuf> = KNOWN_LOCATIONS.iter().map(PathBuf::from).collect();
+    if let Some(home) = env::var_os("HOME").filter(|h| !h.is_empty()) {
+        fallbacks.push(Path::new(&home).join(".linuxbrew").join("bin").join("brew"));
+    }
+    find_brew(env::var_os("PATH").as_deref(), &fallbacks).unwrap_or_else(|| PathBuf::from("brew"))
+}
+
+/// Look for an executable `brew` in the directories of `path_var`, then in `fallbacks`.
+pub fn find_brew(path_var: Option<&OsStr>, fallbacks: &[PathBuf]) -> Option<PathBuf> {
+    let on_path = path_var
+        .into_iter()
+        .flat_map(env::split_paths)
+        .map(|dir| dir.join("brew"));
+    on_path
+        .chain(fallbacks.iter().cloned())
+        .find(|p| is_executable(p))
+}
+
+#[cfg(unix)]
+fn is_executable(p: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    p.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(p: &Path) -> bool {
+    p.is_file()
+}
+
+impl Default for Brew {
+    fn default() -> Self {
+        Self {
+            program: detect_program(),
+            flags: vec![],
+            env: vec![],
+            demo: None,
+            inflight: Arc::default(),
+            query_timeout: QUERY_TIMEOUT,
+            operation_timeout: None,
+            transcript: None,
+            metadata: Metadata::Auto,
+        }
+    }
+}
 
 impl Brew {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
     }
 
+    pub fn from_config(cfg: &BrewConfig) -> Self {
+        Self {
+            program: cfg.path.clone().unwrap_or_else(detect_program),
+            flags: cfg.flags.clone(),
+            env: cfg
+                .env
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            demo: None,
+            inflight: Arc::default(),
+            query_timeout: cfg.query_timeout.map_or(QUERY_TIMEOUT, Duration::from_secs),
+            operation_timeout: cfg.operation_timeout.map(Duration::from_secs),
+            transcript: None,
+            metadata: cfg.metadata,
+        }
+    }
+
+    /// A brew backed by the bundled demo fixtures.
+    pub fn demo() -> Self {
+        Self {
+            demo: Some(Arc::new(Mutex::new(DemoState::default()))),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_demo(&self) -> bool {
+        self.demo.is_some()
+    }
+
+    /// Whether commands are answered from a recorded transcript instead of brew.
+    pub fn is_replay(&self) -> bool {
+        self.transcript.as_ref().is_some_and(|t| t.is_replay())
+    }
+
+    /// Run `f` against the demo state when in demo mode.
+    fn with_demo<T>(&self, f: impl FnOnce(&DemoState) -> T) -> Option<T> {
+        let state = self.demo.as_ref()?;
+        let guard = state.lock().unwrap_or_else(|e| e.into_inner());
+        Some(f(&guard))
+    }
+
+    /// Check that brew answers at all before the lists are loaded: `brew --version` has to
+    /// name Homebrew and `brew --prefix` an existing directory, each within `HEALTH_TIMEOUT`
+    /// (or the shorter `query_timeout`). Demo mode and replayed transcripts have nothing to
+    /// check.
+    pub fn health_check(&self) -> Result<Health> {
+        if self.is_demo() || self.is_replay() {
+            return Ok(Health::default());
+        }
+        let probe = Brew {
+            query_timeout: self.query_timeout.min(HEALTH_TIMEOUT),
+            ..self.clone()
+        };
+        let version = probe.query(&["--version"])?;
+        let version = version
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if !version.starts_with("Homebrew") {
+            return Err(BrewError::ParseError {
+                command: "brew --version".into(),
+                message: format!("{:?} does not look like Homebrew", version),
+            }
+            .into());
+        }
+        let prefix = PathBuf::from(probe.query(&["--prefix"])?.trim());
+        if prefix.as_os_str().is_empty() {
+            return Err(BrewError::ParseError {
+                command: "brew --prefix".into(),
+                message: "no prefix in the output".into(),
+            }
+            .into());
+        }
+        if !prefix.is_dir() {
+            anyhow::bail!(
+                "brew --prefix reports {}, which is not a directory",
+                prefix.display()
+            );
+        }
+        Ok(Health { version, prefix })
+    }
+
+    /// Installation prefix reported by `brew --prefix`.
+    pub fn prefix(&self) -> Option<PathBuf> {
+        if self.is_demo() {
+            return None;
+        }
+        let prefix = self.query(&["--prefix"]).ok()?.trim().to_string();
+        if prefix.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(prefix))
+        }
+    }
+
+    /// A `brew` command with the configured environment applied.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.envs(self.env.iter().map(|(k, v)| (k, v)));
+        cmd
+    }
+
+    /// Run `brew <args>` and return its stdout. Fails with a `BrewError` when brew cannot be
+    /// started, exits unsuccessfully, prints something other than UTF-8 or runs longer than
+    /// `query_timeout`.
+    fn query(&self, args: &[&str]) -> BrewResult<String> {
+        let command = format!("brew {}", args.join(" "));
+        let output = self.run_query(args)?;
+        if !output.success() {
+            tracing::warn!(
+                "{} exited with {:?}: {}",
+                command,
+                output.code,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Err(BrewError::CommandFailed {
+                command,
+                code: output.code,
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            });
+        }
+        String::from_utf8(output.stdout).map_err(|e| BrewError::ParseError {
+            command,
+            message: e.to_string(),
+        })
+    }
+
+    /// Run `brew <args>` to completion (or `query_timeout`) and collect its output, whatever
+    /// its exit status. With a transcript the finished query is recorded, or answered from the
+    /// transcript without running brew.
+    fn run_query(&self, args: &[&str]) -> BrewResult<QueryOutput> {
+        let command = format!("brew {}", args.join(" "));
+        if let Some(transcript) = self.transcript.as_ref().filter(|t| t.is_replay()) {
+            let exchange =
+                transcript
+                    .next(false, &command)
+                    .ok_or_else(|| BrewError::CommandFailed {
+                        command: command.clone(),
+                        code: None,
+                        stderr: "not in the transcript".into(),
+                    })?;
+            return Ok(QueryOutput {
+                code: exchange.exit_code,
+                stdout: exchange.stdout().into_bytes(),
+                stderr: exchange.stderr().into_bytes(),
+            });
+        }
+        tracing::debug!("running {}", command);
+        let started = Instant::now();
+        let mut child = self
+            .command()
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|_| BrewError::NotInstalled {
+                program: self.program.clone(),
+            })?;
+        // both pipes are drained while waiting so a chatty brew cannot block on a full pipe
+        let stdout = child.stdout.take().map(drain);
+        let stderr = child.stderr.take().map(drain);
+        let deadline = Instant::now() + self.query_timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    tracing::warn!("{} timed out after {:?}", command, self.query_timeout);
+                    return Err(BrewError::Timeout {
+                        command,
+                        after: self.query_timeout,
+                    });
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(10)),
+                Err(e) => {
+                    return Err(BrewError::CommandFailed {
+                        command,
+                        code: None,
+                        stderr: e.to_string(),
+                    })
+                }
+            }
+        };
+        tracing::debug!(
+            "{} exited with {:?} after {:?}",
+            command,
+            status.code(),
+            started.elapsed()
+        );
+        let output = QueryOutput {
+            code: status.code(),
+            stdout: stdout.and_then(|h| h.join().ok()).unwrap_or_default(),
+            stderr: stderr.and_then(|h| h.join().ok()).unwrap_or_default(),
+        };
+        if let Some(transcript) = &self.transcript {
+            transcript.save(&Exchange::query(
+                &command,
+                &output.stdout,
+                &output.stderr,
+                output.code,
+            ));
+        }
+        Ok(output)
+    }
+
+    /// Installed formulae, by name.
     pub fn list_installed(&self) -> Result<Vec<FormulaInfo>> {
-        // Preferred: call `brew list --formula` to get names (more portable).
-        let out = Command::new("brew")
-            .arg("list")
-            .arg("--formula")
-            .output()
-            .context("failed to run brew list --formula")?;
-
-        // If the command succeeded and returned names, parse them line-by-line.
-        if out.status.success() {
-            let s = str::from_utf8(&out.stdout)?;
-            let formulas: Vec<FormulaInfo> = s
-                .lines()
-                .filter(|l| !l.trim().is_empty())
-                .map(|name| {
-                    let mut fi = FormulaInfo::default();
-                    fi.name = name.to_string();
-                    fi
+        if let Some(list) = self.with_demo(|d| {
+            d.installed
+                .iter()
+                .map(|f| FormulaInfo {
+                    name: f.name.clone(),
+                    ..Default::default()
                 })
-                .collect();
-            if !formulas.is_empty() {
-                return Ok(formulas);
+                .collect()
+        }) {
+            return Ok(list);
+        }
+        // Preferred: call `brew list --formula` to get names (more portable).
+        match self.query(&["list", "--formula"]) {
+            Ok(s) => {
+                // If the command succeeded and returned names, parse them line-by-line.
+                let formulas: Vec<FormulaInfo> = s
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|name| FormulaInfo {
+                        name: name.to_string(),
+                        ..Default::default()
+                    })
+                    .collect();
+                if !formulas.is_empty() {
+                    return Ok(formulas);
+                }
+                // If empty, fall through to JSON attempt below
             }
-            // If empty, fall through to JSON attempt below
+            Err(e @ BrewError::NotInstalled { .. }) => return Err(e.into()),
+            Err(_) => {}
         }
 
         // Fallback: try JSON output (older/newer brews may support this on 'info' but not 'list')
-        let out = Command::new("brew")
-            .arg("list")
-            .arg("--formula")
-            .arg("--json=v2")
-            .output()
-            .context("failed to run brew list --json")?;
-        if !out.status.success() {
-            return Err(anyhow::anyhow!(
-                "brew list failed: {}",
-                String::from_utf8_lossy(&out.stderr)
-            ));
-        }
-        let s = str::from_utf8(&out.stdout)?;
-        // Brew JSON v2 has {"formulae": [...]}
-        #[derive(Deserialize)]
-        struct List {
-            formulae: Vec<FormulaInfo>,
+        let args = ["list", "--formula", "--json=v2"];
+        let s = self.query(&args)?;
+        Ok(parse_json(&args, &s)?)
+    }
+
+    /// Full details of every installed formula in one call (`brew info --json=v2 --installed`),
+    /// for the columns of the Installed table.
+    pub fn installed_details(&self) -> Result<Vec<FormulaInfo>> {
+        if let Some(list) = self.with_demo(|d| d.installed.clone()) {
+            return Ok(list);
         }
-        let list: List = serde_json::from_str(s)?;
-        Ok(list.formulae)
+        let args = ["info", "--json=v2", "--installed"];
+        let s = self.query(&args)?;
+        Ok(parse_json(&args, &s)?)
     }
 
+    /// Full details of one formula (`brew info --json=v2`).
     pub fn info(&mut self, name: &str) -> Result<FormulaInfo> {
-        let out = Command::new("brew")
-            .arg("info")
-            .arg("--json=v2")
-            .arg(name)
-            .output()?;
-        if !out.status.success() {
-            return Err(anyhow::anyhow!(
-                "brew info failed: {}",
-                String::from_utf8_lossy(&out.stderr)
-            ));
+        self.shared_info(name)
+    }
+
+    /// `info` for callers that share this `Brew` (the background jobs).
+    pub(crate) fn shared_info(&self, name: &str) -> Result<FormulaInfo> {
+        if let Some(info) = self.with_demo(|d| d.info(name)) {
+            return info.ok_or_else(|| {
+                BrewError::CommandFailed {
+                    command: format!("brew info {}", name),
+                    code: Some(1),
+                    stderr: format!("Error: No available formula with the name \"{}\".", name),
+                }
+                .into()
+            });
         }
-        let s = str::from_utf8(&out.stdout)?;
-        #[derive(Deserialize)]
-        struct Info {
-            formulae: Vec<FormulaInfo>,
+        let from_brew = || -> Result<FormulaInfo> {
+            let key = self.inflight.key(name);
+            Ok(self.inflight.info.run(&key, || self.fetch_info(name))?)
+        };
+        // a replayed transcript holds brew's answers only
+        if self.is_replay() {
+            return from_brew();
+        }
+        match self.metadata {
+            Metadata::Brew => from_brew(),
+            Metadata::Api => formulae_api::fetch(name).or_else(|_| from_brew()),
+            Metadata::Auto => from_brew().or_else(|e| match e.downcast_ref::<BrewError>() {
+                Some(BrewError::NotInstalled { .. } | BrewError::Timeout { .. }) => {
+                    formulae_api::fetch(name).map_err(|_| e)
+                }
+                _ => Err(e),
+            }),
         }
-        let info: Info = serde_json::from_str(s)?;
-        info.formulae
+    }
+
+    fn fetch_info(&self, name: &str) -> BrewResult<FormulaInfo> {
+        let args = ["info", "--json=v2", name];
+        let s = self.query(&args)?;
+        parse_json(&args, &s)?
             .into_iter()
             .next()
-            .ok_or_else(|| anyhow::anyhow!("no info"))
+            .ok_or_else(|| BrewError::ParseError {
+                command: format!("brew {}", args.join(" ")),
+                message: "no formula in the output".into(),
+            })
     }
 
+    /// Formulae and casks matching `query` (`brew search`).
     pub fn search(&self, query: &str) -> Result<Vec<String>> {
-        let out = Command::new("brew").arg("search").arg(query).output()?;
-        if !out.status.success() {
-            return Err(anyhow::anyhow!(
-                "brew search failed: {}",
-                String::from_utf8_lossy(&out.stderr)
-            ));
+        if let Some(results) = self.with_demo(|d| d.search(query)) {
+            return Ok(results);
         }
-        let s = str::from_utf8(&out.stdout)?;
-        Ok(s.lines().map(|l| l.to_string()).collect())
+        let s = self.query(&["search", query])?;
+        Ok(parse_search(&s))
     }
 
     pub fn all_available(&self) -> Result<Vec<String>> {
+        Ok(self
+            .available_entries()?
+            .into_iter()
+            .map(|e| e.name)
+            .collect())
+    }
+
+    /// Every available formula (and, from the API cache, cask) with its description when
+    /// known. Reads Homebrew's local API cache and falls back to `brew search` without it.
+    pub fn available_entries(&self) -> Result<Vec<ApiEntry>> {
+        if let Some(list) = self.with_demo(|d| d.available.clone()) {
+            return Ok(list
+                .into_iter()
+                .map(|name| ApiEntry {
+                    name,
+                    desc: None,
+                    cask: false,
+                })
+                .collect());
+        }
+        for dir in self.index_dirs() {
+            if let Ok(entries) = api_cache::load(&dir) {
+                return Ok(entries);
+            }
+        }
+        Ok(self
+            .search_all()?
+            .into_iter()
+            .map(|name| ApiEntry {
+                name,
+                desc: None,
+                cask: false,
+            })
+            .collect())
+    }
+
+    /// Where the API index is read from: brew's cache, after the copy homebrew-tui downloads
+    /// itself into its cache directory with `metadata = "api"`.
+    fn index_dirs(&self) -> Vec<PathBuf> {
+        let own = paths::cache_dir().filter(|_| self.metadata == Metadata::Api);
+        own.into_iter()
+            .chain(api_cache::cache_dir(&self.env))
+            .collect()
+    }
+
+    /// Whether the API index changed since `since` (unix seconds; None when it was never
+    /// read). The copy homebrew-tui keeps is brought up to date first with a conditional
+    /// request; brew's own is only compared by modification time, brew updates it.
+    pub fn refresh_index(&self, since: Option<u64>) -> Result<bool> {
+        if self.is_demo() || self.is_replay() {
+            return Ok(true);
+        }
+        let Some(dir) = self.index_dirs().into_iter().next() else {
+            return Ok(true);
+        };
+        let changed = if paths::cache_dir().as_ref() == Some(&dir) {
+            formulae_api::download_index(&dir)?
+        } else {
+            false
+        };
+        Ok(
+            changed
+                || since.is_none_or(|since| api_cache::modified(&dir).is_none_or(|m| m > since)),
+        )
+    }
+
+    fn search_all(&self) -> BrewResult<Vec<String>> {
         // Homebrew `brew search` requires an argument; use a regex that matches everything
         // and restrict to formulae for a stable list.
-        let out = Command::new("brew")
-            .arg("search")
-            .arg("/.*/")
-            .arg("--formula")
-            .output()?;
-        if !out.status.success() {
-            return Err(anyhow::anyhow!(
-                "brew search (all) failed: {}",
-                String::from_utf8_lossy(&out.stderr)
-            ));
-        }
-        let s = str::from_utf8(&out.stdout)?;
-        let mut v: Vec<String> = s
-            .lines()
-            .map(|l| l.trim().to_string())
-            .filter(|l| !l.is_empty())
-            .collect();
+        let s = self.query(&["search", "/.*/", "--formula"])?;
+        let mut v = parse_search(&s);
         // dedupe and sort for stable display
         v.sort();
         v.dedup();
         Ok(v)
     }
 
+    /// Names of installed formulae with a newer version available.
     pub fn outdated(&self) -> Result<Vec<String>> {
+        if let Some(list) = self.with_demo(|d| d.outdated.clone()) {
+            return Ok(list);
+        }
+        let key = self.inflight.key("");
+        Ok(self.inflight.outdated.run(&key, || self.fetch_outdated())?)
+    }
+
+    fn fetch_outdated(&self) -> BrewResult<Vec<String>> {
         // `brew outdated --formula` lists installed formulae that are outdated
-        let out = Command::new("brew")
-            .arg("outdated")
-            .arg("--formula")
-            .output()?;
-        if !out.status.success() {
-            return Err(anyhow::anyhow!(
-                "brew outdated failed: {}",
-                String::from_utf8_lossy(&out.stderr)
-            ));
+        let s = self.query(&["outdated", "--formula"])?;
+        Ok(parse_outdated(&s))
+    }
+
+    /// Files `brew cleanup` would remove, from a dry run.
+    pub fn cleanup_preview(&self) -> Result<CleanupPreview> {
+        if let Some(preview) = self.with_demo(|d| d.cleanup_preview()) {
+            return Ok(preview);
         }
-        let s = str::from_utf8(&out.stdout)?;
-        let v: Vec<String> = s
-            .lines()
-            .filter_map(|l| {
-                let t = l.trim();
-                if t.is_empty() {
-                    return None;
+        let s = self.query(&["cleanup", "--dry-run"])?;
+        Ok(parse_cleanup_dry_run(&s))
+    }
+
+    /// Installed packages by the space they take in the Cellar (`brew --cellar`, or `Cellar`
+    /// under `brew --prefix`).
+    pub fn disk_usage(&self) -> Result<DiskUsage> {
+        if let Some(usage) = self.with_demo(|d| d.disk_usage()) {
+            return Ok(usage);
+        }
+        let cellar = self
+            .query(&["--cellar"])
+            .ok()
+            .map(|s| PathBuf::from(s.trim()))
+            .filter(|p| !p.as_os_str().is_empty())
+            .or_else(|| self.prefix().map(|p| p.join("Cellar")))
+            .context("could not determine the Cellar")?;
+        disk::cellar_usage(&cellar).with_context(|| format!("failed to read {}", cellar.display()))
+    }
+
+    /// The latest `LOG_COMMITS` commits touching `name` in its tap (`brew log`), newest first.
+    /// Needs the tap as a git checkout; with the API alone brew refuses.
+    pub fn formula_log(&self, name: &str) -> Result<Vec<FormulaCommit>> {
+        if let Some(commits) = self.with_demo(|d| d.formula_log(name)) {
+            return Ok(commits);
+        }
+        let max_count = format!("--max-count={}", LOG_COMMITS);
+        let output = self.query(&["log", &max_count, name])?;
+        Ok(parse_log(&output))
+    }
+
+    /// Everything `name` needs, build dependencies included (`brew deps --include-build`),
+    /// whether installed or not.
+    pub fn dependencies(&self, name: &str) -> Result<Vec<String>> {
+        if let Some(deps) = self.with_demo(|d| d.dependencies(name)) {
+            return Ok(deps);
+        }
+        let output = self.query(&["deps", "--include-build", name])?;
+        Ok(parse_names(&output))
+    }
+
+    /// Download sizes of the bottles of `names` on this machine, from their image manifests on
+    /// ghcr.io; formulae whose size could not be found are left out. The demo and replayed
+    /// transcripts stay offline and know none.
+    pub fn bottle_sizes(&self, names: &[String]) -> Result<HashMap<String, u64>> {
+        if names.is_empty() || self.is_demo() || self.is_replay() {
+            return Ok(HashMap::new());
+        }
+        let mut args = vec!["info", "--json=v2", "--formula"];
+        args.extend(names.iter().map(String::as_str));
+        let s = self.query(&args)?;
+        let mut sizes = HashMap::new();
+        for info in parse_json(&args, &s)? {
+            match bottles::fetch_size(&info) {
+                Ok(Some(size)) => {
+                    sizes.insert(info.name, size);
                 }
-                // Take the first token before whitespace (e.g., "awscli (2.30.7) < 2.31.2" -> "awscli")
-                let name = t.split_whitespace().next().unwrap_or(t).to_string();
-                Some(name)
-            })
-            .collect();
-        Ok(v)
+                Ok(None) => {}
+                Err(e) => tracing::debug!("no bottle size for {}: {:#}", info.name, e),
+            }
+        }
+        Ok(sizes)
     }
 
-    pub fn install(&mut self, name: &str) -> Result<()> {
-        let status = Command::new("brew").arg("install").arg(name).status()?;
-        if status.success() {
-            Ok(())
+    /// Upstream versions of `names` from `brew livecheck`, or of every installed leaf (`brew
+    /// leaves`) when `names` is empty. livecheck exits with an error when any formula could not
+    /// be checked, so only output that does not parse counts as failure.
+    pub fn livecheck(&self, names: &[String]) -> Result<Vec<Livecheck>> {
+        if let Some(results) = self.with_demo(|d| d.livecheck(names)) {
+            return Ok(results);
+        }
+        let names: Vec<String> = if names.is_empty() {
+            self.query(&["leaves"])?
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(String::from)
+                .collect()
         } else {
-            Err(anyhow::anyhow!("install failed"))
+            names.to_vec()
+        };
+        if names.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut args = vec!["livecheck", "--json", "--formula"];
+        args.extend(names.iter().map(String::as_str));
+        let output = self.run_query(&args)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        match parse_livecheck(&stdout) {
+            Ok(results) => Ok(results),
+            Err(_) if !output.success() => Err(BrewError::CommandFailed {
+                command: "brew livecheck".into(),
+                code: output.code,
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            }
+            .into()),
+            Err(e) => Err(BrewError::ParseError {
+                command: "brew livecheck --json".into(),
+                message: e.to_string(),
+            }
+            .into()),
         }
     }
 
-    pub fn upgrade(&mut self, name: &str) -> Result<()> {
-        let status = Command::new("brew").arg("upgrade").arg(name).status()?;
-        if status.success() {
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("upgrade failed"))
+    /// The build logs of whichever of `names` was built last, from `HOMEBREW_LOGS` (or the
+    /// standard location).
+    pub fn build_logs(&self, names: &[String]) -> Result<BuildLogs> {
+        if let Some(logs) = self.with_demo(|d| d.build_logs(names)) {
+            return logs;
+        }
+        let dir = build_logs::logs_dir(&self.env)
+            .context("could not determine the Homebrew logs directory")?;
+        let name = build_logs::most_recent(&dir, names).context("no package to read logs of")?;
+        build_logs::read(&dir, name)
+    }
+
+    /// The files in the download cache, from `brew --cache` (or `HOMEBREW_CACHE` and the
+    /// standard location when brew does not answer). A cache that does not exist yet is empty.
+    pub fn download_cache(&self) -> Result<DownloadCache> {
+        if let Some(cache) = self.with_demo(|d| d.download_cache()) {
+            return Ok(cache);
+        }
+        let dir = self
+            .query(&["--cache"])
+            .ok()
+            .map(|s| PathBuf::from(s.trim()))
+            .filter(|p| !p.as_os_str().is_empty())
+            .or_else(|| api_cache::cache_dir(&self.env))
+            .context("could not determine the Homebrew cache directory")?;
+        match disk::scan_cache(&dir) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DownloadCache {
+                dir,
+                entries: vec![],
+            }),
+            other => other.with_context(|| format!("failed to read {}", dir.display())),
+        }
+    }
+
+    /// `brew config`, `brew --env` and `brew --cellar`.
+    pub fn environment(&self) -> Result<BrewEnvironment> {
+        if let Some(env) = self.with_demo(|d| d.environment()) {
+            return Ok(env);
+        }
+        let config = parse_key_values(&self.query(&["config"])?);
+        // the build environment is extra detail; a brew without `--env` still gets the panel
+        let env = self
+            .query(&["--env", "--plain"])
+            .map(|s| parse_key_values(&s))
+            .unwrap_or_default();
+        let cellar = self
+            .query(&["--cellar"])
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let analytics = self
+            .query(&["analytics", "state"])
+            .ok()
+            .and_then(|s| parse_analytics_state(&s));
+        Ok(BrewEnvironment {
+            config,
+            env,
+            cellar,
+            analytics,
+        })
+    }
+
+    /// Findings of `brew doctor`; empty when the system is ready to brew. Doctor exits with
+    /// status 1 when it found something, so only output without any finding counts as failure.
+    pub fn doctor(&self) -> Result<Vec<DoctorItem>> {
+        if let Some(items) = self.with_demo(|_| crate::demo::doctor()) {
+            return Ok(items);
+        }
+        let output = self.run_query(&["doctor"])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let items = parse_doctor(&format!("{}\n{}", stdout, stderr));
+        if items.is_empty() && !output.success() {
+            return Err(BrewError::CommandFailed {
+                command: "brew doctor".into(),
+                code: output.code,
+                stderr: stderr.into_owned(),
+            }
+            .into());
+        }
+        Ok(items)
+    }
+
+    /// Installed casks with their versions (`brew list --cask --versions`).
+    pub fn casks(&self) -> Result<Vec<InstalledCask>> {
+        if let Some(casks) = self.with_demo(|d| d.casks.clone()) {
+            return Ok(casks);
+        }
+        let s = self.query(&["list", "--cask", "--versions"])?;
+        Ok(parse_cask_versions(&s))
+    }
+
+    /// Services of the installed formulae (`brew services list --json`).
+    pub fn services(&self) -> Result<Vec<Service>> {
+        if let Some(services) = self.with_demo(|d| d.services.clone()) {
+            return Ok(services);
+        }
+        let s = self.query(&["services", "list", "--json"])?;
+        parse_services(&s).context("could not parse `brew services list --json`")
+    }
+
+    /// Tapped repositories (`brew tap`).
+    pub fn taps(&self) -> Result<Vec<String>> {
+        if let Some(taps) = self.with_demo(DemoState::taps) {
+            return Ok(taps);
         }
+        let s = self.query(&["tap"])?;
+        Ok(parse_names(&s))
+    }
+
+    /// Names of pinned formulae (`brew list --pinned`); `brew upgrade` refuses to touch them.
+    pub fn pinned(&self) -> Result<Vec<String>> {
+        if let Some(pinned) = self.with_demo(|d| d.pinned.clone()) {
+            return Ok(pinned);
+        }
+        let s = self.query(&["list", "--pinned"])?;
+        Ok(parse_names(&s))
+    }
+
+    pub fn install(&mut self, name: &str) -> Result<()> {
+        self.run_interactive("install", name)
+    }
+
+    pub fn upgrade(&mut self, name: &str) -> Result<()> {
+        self.run_interactive("upgrade", name)
     }
 
     pub fn uninstall(&mut self, name: &str) -> Result<()> {
-        let status = Command::new("brew").arg("uninstall").arg(name).status()?;
+        self.run_interactive("uninstall", name)
+    }
+
+    /// Run `brew <verb> <name>` with the configured flags on the inherited terminal.
+    fn run_interactive(&self, verb: &str, name: &str) -> Result<()> {
+        let status = self
+            .command()
+            .arg(verb)
+            .arg(name)
+            .args(&self.flags)
+            .status()
+            .map_err(|_| BrewError::NotInstalled {
+                program: self.program.clone(),
+            })?;
         if status.success() {
             Ok(())
         } else {
-            Err(anyhow::anyhow!("uninstall failed"))
+            Err(BrewError::CommandFailed {
+                command: format!("brew {} {}", verb, name),
+                code: status.code(),
+                stderr: String::new(),
+            }
+            .into())
         }
     }
 }
+
+/// Read a child's pipe to the end on its own thread.
+fn drain<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+/// Formulae from brew's JSON v2 output (`brew info --json=v2`, `brew list --json=v2`), which
+/// wraps them in `{"formulae": [...]}`.
+pub fn parse_formulae(json: &str) -> serde_json::Result<Vec<FormulaInfo>> {
+    #[derive(Deserialize)]
+    struct Formulae {
+        formulae: Vec<FormulaInfo>,
+    }
+    serde_json::from_str::<Formulae>(json).map(|f| f.formulae)
+}
+
+/// `parse_formulae` on the output of `brew <args>`.
+fn parse_json(args: &[&str], s: &str) -> BrewResult<Vec<FormulaInfo>> {
+    parse_formulae(s).map_err(|e| BrewError::ParseError {
+        command: format!("brew {}", args.join(" ")),
+        message: e.to_string(),
+    })
+}
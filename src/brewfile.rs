@@ -0,0 +1,44 @@
+// Brewfiles as `brew bundle` reads them: a `tap` line for every third-party tap the formulae
+// come from, then one `brew` line per formula. `brew bundle install` in the directory of one
+// installs the same set on another machine.
+use crate::brew::FormulaInfo;
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// The Brewfile listing `formulae`, taps sorted and formulae in the given order. Formulae
+/// from a third-party tap are named with it (`user/repo/name`), as `brew bundle dump` does.
+pub fn render(formulae: &[&FormulaInfo]) -> String {
+    let mut taps: Vec<&str> = formulae
+        .iter()
+        .filter_map(|f| f.tap_name())
+        .filter(|t| *t != "homebrew/core")
+        .collect();
+    taps.sort_unstable();
+    taps.dedup();
+    let mut out = String::new();
+    for tap in taps {
+        out.push_str(&format!("tap \"{}\"\n", tap));
+    }
+    for f in formulae {
+        let name = match f.tap_name() {
+            Some(tap) if tap != "homebrew/core" => format!("{}/{}", tap, f.name),
+            _ => f.name.clone(),
+        };
+        out.push_str(&format!("brew \"{}\"\n", name));
+    }
+    out
+}
+
+/// Write `contents` to `path`, refusing to replace a file that is already there (like
+/// `brew bundle dump` without `--force`).
+pub fn write_new(path: &Path, contents: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .with_context(|| format!("cannot create {}", path.display()))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("cannot write {}", path.display()))
+}
@@ -0,0 +1,43 @@
+// Opening URLs in the default browser through the platform's opener.
+use anyhow::{anyhow, Result};
+use std::process::{Command, Stdio};
+
+/// `open` on macOS, `xdg-open` elsewhere.
+fn opener() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    }
+}
+
+pub fn open(url: &str) -> Result<()> {
+    let tool = opener();
+    let status = Command::new(tool)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| anyhow!("could not run {}: {}", tool, e))?;
+    if !status.success() {
+        return Err(anyhow!("{} failed ({})", tool, status));
+    }
+    Ok(())
+}
+
+/// The package's page on formulae.brew.sh, which only lists the official taps (homebrew/core
+/// and, for casks, homebrew/cask): `None` for a formula from another tap or a tap-qualified
+/// name.
+pub fn brew_page(name: &str, tap: Option<&str>, cask: bool) -> Option<String> {
+    let official = if cask {
+        "homebrew/cask"
+    } else {
+        "homebrew/core"
+    };
+    if name.contains('/') || tap.is_some_and(|t| t != official) {
+        return None;
+    }
+    let kind = if cask { "cask" } else { "formula" };
+    Some(format!("https://formulae.brew.sh/{}/{}", kind, name))
+}
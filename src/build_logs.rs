@@ -0,0 +1,109 @@
+// Build logs brew keeps after building a formula from source: one directory per formula under
+// `HOMEBREW_LOGS` holding numbered files (`01.configure`, `02.make`, ...). They are what
+// `brew gist-logs` uploads, and reading them locally is the quickest way to see why a build
+// failed.
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One file of a formula's build logs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BuildLogFile {
+    pub name: String,
+    pub lines: Vec<String>,
+}
+
+/// The build logs of one formula.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BuildLogs {
+    pub name: String,
+    pub dir: PathBuf,
+    /// Sorted by file name, which is the order brew wrote them in.
+    pub files: Vec<BuildLogFile>,
+}
+
+impl BuildLogs {
+    /// Every file's lines after a `==> <file>` header, for the log viewer.
+    pub fn lines(&self) -> Vec<String> {
+        let mut out = vec![];
+        for file in &self.files {
+            out.push(format!("==> {}", file.name));
+            out.extend(file.lines.iter().cloned());
+        }
+        out
+    }
+}
+
+/// Homebrew's log directory: `HOMEBREW_LOGS` (from `env`, then the process environment),
+/// otherwise `~/Library/Logs/Homebrew` on macOS and `$XDG_CACHE_HOME/Homebrew/Logs` or
+/// `~/.cache/Homebrew/Logs` elsewhere.
+pub fn logs_dir(env: &[(String, String)]) -> Option<PathBuf> {
+    let configured = env
+        .iter()
+        .find(|(k, _)| k == "HOMEBREW_LOGS")
+        .map(|(_, v)| v.into())
+        .or_else(|| env::var_os("HOMEBREW_LOGS"))
+        .filter(|d| !d.is_empty());
+    if let Some(dir) = configured {
+        return Some(PathBuf::from(dir));
+    }
+    let home = env::var_os("HOME")
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from);
+    if cfg!(target_os = "macos") {
+        return home.map(|h| h.join("Library").join("Logs").join("Homebrew"));
+    }
+    if let Some(dir) = env::var_os("XDG_CACHE_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("Homebrew").join("Logs"));
+    }
+    home.map(|h| h.join(".cache").join("Homebrew").join("Logs"))
+}
+
+/// Read the logs of `name` below `logs_dir`. A formula without logs (a bottle was poured, or
+/// it never got as far as building) is an error.
+pub fn read(logs_dir: &Path, name: &str) -> Result<BuildLogs> {
+    let dir = logs_dir.join(name);
+    let mut files: Vec<BuildLogFile> = fs::read_dir(&dir)
+        .with_context(|| format!("no build logs for {} in {}", name, logs_dir.display()))?
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .map(|e| BuildLogFile {
+            name: e.file_name().to_string_lossy().into_owned(),
+            lines: fs::read(e.path())
+                .map(|b| {
+                    String::from_utf8_lossy(&b)
+                        .lines()
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .collect();
+    if files.is_empty() {
+        anyhow::bail!("no build logs for {} in {}", name, dir.display());
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(BuildLogs {
+        name: name.to_string(),
+        dir,
+        files,
+    })
+}
+
+/// Of `names`, the one whose logs were written last; the first name when none has logs. After
+/// a bulk upgrade failed this is the formula whose build broke it.
+pub fn most_recent<'a>(logs_dir: &Path, names: &'a [String]) -> Option<&'a String> {
+    let modified = |name: &String| {
+        fs::metadata(logs_dir.join(name))
+            .and_then(|m| m.modified())
+            .ok()
+    };
+    names
+        .iter()
+        .filter_map(|n| modified(n).map(|t| (n, t)))
+        .max_by_key(|&(_, t)| t)
+        .map(|(n, _)| n)
+        .or_else(|| names.first())
+}
@@ -0,0 +1,86 @@
+// Delivery of background events to the UI loop. Everything sent on the app's channel is
+// collected once per frame: events the user is waiting to see (status changes, confirmation
+// prompts) are handed over first, runs of operation output lines are merged into a single
+// batch, and at most `LINES_PER_FRAME` lines are applied per frame so a very chatty install
+// cannot keep the loop from drawing and reading keys. Anything beyond the budget stays queued,
+// in order, for the next frame.
+use crate::event::AppEvent;
+use crate::output::LogLine;
+use std::collections::VecDeque;
+use std::sync::mpsc;
+
+/// Operation output lines applied per frame.
+pub const LINES_PER_FRAME: usize = 500;
+
+pub enum BusEvent {
+    Event(Box<AppEvent>),
+    /// Consecutive `OpLog`/`OpErr` lines, oldest first.
+    Lines(Vec<LogLine>),
+}
+
+#[derive(Default)]
+pub struct EventBus {
+    backlog: VecDeque<AppEvent>,
+}
+
+/// Shown to the user as soon as it arrives; the relative order of everything else is kept.
+fn is_urgent(ev: &AppEvent) -> bool {
+    matches!(ev, AppEvent::Status(_) | AppEvent::ShowConfirm(..))
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True while events are left over from an earlier frame.
+    pub fn has_backlog(&self) -> bool {
+        !self.backlog.is_empty()
+    }
+
+    /// The events to handle this frame: everything urgent that has arrived, then the queue in
+    /// order with output lines batched, up to `budget` lines.
+    pub fn collect(&mut self, rx: &mpsc::Receiver<AppEvent>, budget: usize) -> Vec<BusEvent> {
+        self.backlog.extend(rx.try_iter());
+
+        let mut out = vec![];
+        let mut rest = VecDeque::with_capacity(self.backlog.len());
+        for ev in self.backlog.drain(..) {
+            if is_urgent(&ev) {
+                out.push(BusEvent::Event(Box::new(ev)));
+            } else {
+                rest.push_back(ev);
+            }
+        }
+
+        let mut lines_left = budget;
+        let mut batch: Vec<LogLine> = vec![];
+        while let Some(ev) = rest.pop_front() {
+            match ev {
+                AppEvent::OpLog(_) | AppEvent::OpErr(_) if lines_left == 0 => {
+                    rest.push_front(ev);
+                    break;
+                }
+                AppEvent::OpLog(l) => {
+                    batch.push(LogLine::stdout(l));
+                    lines_left -= 1;
+                }
+                AppEvent::OpErr(l) => {
+                    batch.push(LogLine::stderr(l));
+                    lines_left -= 1;
+                }
+                ev => {
+                    if !batch.is_empty() {
+                        out.push(BusEvent::Lines(std::mem::take(&mut batch)));
+                    }
+                    out.push(BusEvent::Event(Box::new(ev)));
+                }
+            }
+        }
+        if !batch.is_empty() {
+            out.push(BusEvent::Lines(batch));
+        }
+        self.backlog = rest;
+        out
+    }
+}
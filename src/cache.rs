@@ -0,0 +1,140 @@
+// Package metadata cache: the Available list (with descriptions) and `brew info` results are
+// kept in the cache directory so later launches show them immediately. Expired entries are
+// still shown, but refreshed in the background. GitHub repository stats shown in Details are
+// kept too, so browsing packages does not use up the API's rate limit.
+use crate::api_cache::ApiEntry;
+use crate::brew::FormulaInfo;
+use crate::github::RepoStats;
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Bumped whenever the file layout changes; files with another version are ignored.
+pub const CACHE_VERSION: u32 = 1;
+/// How long the Available list is considered current.
+pub const AVAILABLE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How long a package's `brew info` is considered current.
+pub const INFO_TTL: Duration = Duration::from_secs(60 * 60);
+/// How long a repository's GitHub stats are considered current.
+pub const GITHUB_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Cached<T> {
+    /// Seconds since the unix epoch.
+    pub fetched_at: u64,
+    pub value: T,
+}
+
+impl<T> Cached<T> {
+    pub fn new(value: T, now: SystemTime) -> Self {
+        Self {
+            fetched_at: unix_secs(now),
+            value,
+        }
+    }
+
+    pub fn is_fresh(&self, ttl: Duration, now: SystemTime) -> bool {
+        unix_secs(now).saturating_sub(self.fetched_at) < ttl.as_secs()
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    #[serde(default)]
+    available: Option<Cached<Vec<ApiEntry>>>,
+    #[serde(default)]
+    info: BTreeMap<String, Cached<FormulaInfo>>,
+    #[serde(default)]
+    github: BTreeMap<String, Cached<RepoStats>>,
+}
+
+pub struct MetadataCache {
+    pub available: Option<Cached<Vec<ApiEntry>>>,
+    pub info: BTreeMap<String, Cached<FormulaInfo>>,
+    /// By repository (`owner/name`).
+    pub github: BTreeMap<String, Cached<RepoStats>>,
+    path: Option<PathBuf>,
+}
+
+impl MetadataCache {
+    /// Cache that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            available: None,
+            info: BTreeMap::new(),
+            github: BTreeMap::new(),
+            path: None,
+        }
+    }
+
+    /// Load `metadata.json` from the cache directory. A missing, unreadable or outdated file
+    /// yields an empty cache that will still be saved to the same location.
+    pub fn load() -> Self {
+        Self::load_from(paths::cache_dir().map(|d| d.join("metadata.json")))
+    }
+
+    pub fn load_from(path: Option<PathBuf>) -> Self {
+        let file: CacheFile = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .filter(|f: &CacheFile| f.version == CACHE_VERSION)
+            .unwrap_or_default();
+        Self {
+            available: file.available,
+            info: file.info,
+            github: file.github,
+            path,
+        }
+    }
+
+    pub fn set_available(&mut self, entries: Vec<ApiEntry>, now: SystemTime) {
+        self.available = Some(Cached::new(entries, now));
+    }
+
+    pub fn set_info(&mut self, info: FormulaInfo, now: SystemTime) {
+        self.info.insert(info.name.clone(), Cached::new(info, now));
+    }
+
+    pub fn set_github(&mut self, repo: &str, stats: RepoStats, now: SystemTime) {
+        self.github
+            .insert(repo.to_string(), Cached::new(stats, now));
+    }
+
+    /// Drop the cached info of packages an operation just changed.
+    pub fn forget_info(&mut self, names: &[String]) {
+        for name in names {
+            self.info.remove(name);
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let file = CacheFile {
+            version: CACHE_VERSION,
+            available: self.available.clone(),
+            info: self.info.clone(),
+            github: self.github.clone(),
+        };
+        let s = serde_json::to_string(&file)?;
+        fs::write(path, s).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+pub fn unix_secs(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,89 @@
+// Deduplication of identical in-flight requests: while one caller runs the work for a key,
+// other callers asking for the same key wait for that result instead of starting their own
+// brew process.
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+
+struct Slot<V> {
+    state: Mutex<(bool, Option<V>)>,
+    ready: Condvar,
+}
+
+pub struct Coalescer<V> {
+    inflight: Mutex<HashMap<String, Arc<Slot<V>>>>,
+}
+
+impl<V> Default for Coalescer<V> {
+    fn default() -> Self {
+        Self {
+            inflight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Publishes the leader's result (or its absence, if the work panicked) and retires the key.
+struct Finish<'a, V> {
+    owner: &'a Coalescer<V>,
+    key: &'a str,
+    slot: Arc<Slot<V>>,
+}
+
+impl<V> Drop for Finish<'_, V> {
+    fn drop(&mut self) {
+        if let Ok(mut map) = self.owner.inflight.lock() {
+            map.remove(self.key);
+        }
+        if let Ok(mut state) = self.slot.state.lock() {
+            state.0 = true;
+        }
+        self.slot.ready.notify_all();
+    }
+}
+
+impl<V: Clone> Coalescer<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `work` for `key`, or wait for the identical call already running and share its
+    /// result.
+    pub fn run(&self, key: &str, work: impl FnOnce() -> V) -> V {
+        let (slot, leader) = {
+            let mut map = self.inflight.lock().unwrap_or_else(|e| e.into_inner());
+            match map.get(key) {
+                Some(slot) => (Arc::clone(slot), false),
+                None => {
+                    let slot = Arc::new(Slot {
+                        state: Mutex::new((false, None)),
+                        ready: Condvar::new(),
+                    });
+                    map.insert(key.to_string(), Arc::clone(&slot));
+                    (slot, true)
+                }
+            }
+        };
+
+        if leader {
+            let finish = Finish {
+                owner: self,
+                key,
+                slot,
+            };
+            let value = work();
+            if let Ok(mut state) = finish.slot.state.lock() {
+                state.1 = Some(value.clone());
+            }
+            return value;
+        }
+
+        let shared = {
+            let mut state = slot.state.lock().unwrap_or_else(|e| e.into_inner());
+            while !state.0 {
+                state = slot.ready.wait(state).unwrap_or_else(|e| e.into_inner());
+            }
+            state.1.clone()
+        };
+        // the leader panicked before producing a result: do the work ourselves
+        shared.unwrap_or_else(work)
+    }
+}
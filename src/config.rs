@@ -0,0 +1,325 @@
+// User configuration read from `config.toml` in the config directory.
+//
+// Every field is optional so an empty or partial file is valid; unknown keys are rejected so
+// typos are reported instead of silently ignored.
+use crate::event::ConfirmAction;
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_OUTDATED_INTERVAL: u64 = 300;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Name of the built-in theme to start with (see `theme::THEMES`).
+    pub theme: Option<String>,
+    /// Terminal background hint ("auto", "light" or "dark") used to pick the default theme
+    /// when `theme` is not set.
+    pub background: Background,
+    /// Key binding preset: "default", "vim" or "emacs".
+    pub keymap: Preset,
+    /// Use plain ASCII instead of Unicode spinner frames, check marks and arrows.
+    pub ascii: bool,
+    /// Disable the spinner animation and redraw only when something changed.
+    pub reduced_motion: bool,
+    /// Seconds between background `brew outdated` checks; 0 disables them. Defaults to 300.
+    pub outdated_interval: Option<u64>,
+    pub layout: LayoutConfig,
+    pub brew: BrewConfig,
+    pub maintenance: MaintenanceConfig,
+    pub log: LogConfig,
+    pub notify: NotifyConfig,
+    pub hooks: HooksConfig,
+    pub github: GithubConfig,
+    pub terminal: TerminalConfig,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+    /// Column widths in percent for Installed, Available and Details.
+    pub columns: Option<[u16; 3]>,
+    /// Height of the bottom Logs/Status panel in rows.
+    pub bottom_height: Option<u16>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrewConfig {
+    /// Path of the brew executable; `brew` from `PATH` when unset.
+    pub path: Option<PathBuf>,
+    /// Flags appended to every install/upgrade/uninstall/link/unlink operation (and to no other
+    /// command), e.g. `["--verbose"]`.
+    pub flags: Vec<String>,
+    /// Environment variables for every spawned brew command, e.g. `HOMEBREW_NO_AUTO_UPDATE`.
+    pub env: BTreeMap<String, String>,
+    /// Seconds a query (list, info, search, outdated, ...) may run before it is killed; 300
+    /// when unset.
+    pub query_timeout: Option<u64>,
+    /// Seconds an operation may run before it is killed; no limit when unset.
+    pub operation_timeout: Option<u64>,
+    /// Where package details come from: "auto" (the default), "brew" or "api".
+    pub metadata: Metadata,
+}
+
+/// The source of package details: `brew info`, the formulae.brew.sh API, or brew with the API
+/// when brew is missing or times out (`auto`, the default). With `api` brew is only asked about
+/// what the API does not know, like the formulae of other taps; the API says nothing about
+/// what is installed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metadata {
+    #[default]
+    Auto,
+    Brew,
+    Api,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaintenanceConfig {
+    /// Stages of the maintenance routine, run in this order. Defaults to all of them.
+    pub steps: Vec<MaintenanceStep>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            steps: vec![
+                MaintenanceStep::Update,
+                MaintenanceStep::Upgrade,
+                MaintenanceStep::Autoremove,
+                MaintenanceStep::Cleanup,
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    /// How much goes into the log file in the state directory; read at startup.
+    pub level: LogLevel,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Send a desktop notification when an operation finishes while the terminal is not
+    /// focused. Defaults to true.
+    pub desktop: bool,
+    /// Ring the terminal bell or flash the screen when a long operation finishes.
+    pub bell: Bell,
+    /// Seconds an operation has to run before `bell` goes off. Defaults to 10.
+    pub after: u64,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            desktop: true,
+            bell: Bell::Off,
+            after: 10,
+        }
+    }
+}
+
+/// Shell commands (`sh -c`) run after operations, with the package and the outcome in
+/// `HOMEBREW_TUI_*` environment variables (see `hooks`).
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    /// After each package a successful `brew install` installed.
+    pub post_install: Option<String>,
+    /// After each package a successful `brew upgrade` upgraded.
+    pub post_upgrade: Option<String>,
+    /// After any operation that failed.
+    pub on_failure: Option<String>,
+    /// Seconds a hook may run before it is killed; 300 when unset.
+    pub timeout: Option<u64>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerminalConfig {
+    /// Show what the app is doing in the terminal's window title (and the tmux pane title),
+    /// restoring the previous title on exit. Defaults to true.
+    pub title: bool,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self { title: true }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GithubConfig {
+    /// Show the stars and last push of a formula's GitHub repository in Details, looked up
+    /// through the GitHub API. Defaults to true.
+    pub stats: bool,
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        Self { stats: true }
+    }
+}
+
+/// How a long operation announces it finished: not at all (the default), with the terminal
+/// bell or with a short flash of the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bell {
+    #[default]
+    Off,
+    Bell,
+    Flash,
+}
+
+/// Verbosity of the log file: `info` (the default) keeps operations and errors, `debug` adds
+/// every brew invocation, `off` writes nothing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn filter(self) -> tracing::level_filters::LevelFilter {
+        use tracing::level_filters::LevelFilter;
+        match self {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// One stage of the maintenance routine: `brew update`, `brew upgrade` of the packages picked
+/// in the Maintenance modal, `brew autoremove` or `brew cleanup`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaintenanceStep {
+    Update,
+    Upgrade,
+    Autoremove,
+    Cleanup,
+}
+
+impl MaintenanceStep {
+    /// The operation this stage runs, with the name used for its title; the upgrade stage
+    /// upgrades `upgrade` and has nothing to do when it is empty.
+    pub fn operation(self, upgrade: &[String]) -> Option<(ConfirmAction, String)> {
+        let op = match self {
+            MaintenanceStep::Update => (ConfirmAction::Update, "Homebrew".into()),
+            MaintenanceStep::Upgrade if upgrade.is_empty() => return None,
+            MaintenanceStep::Upgrade => (
+                ConfirmAction::BulkUpgrade(upgrade.to_vec()),
+                format!("{} packages", upgrade.len()),
+            ),
+            MaintenanceStep::Autoremove => (ConfirmAction::Autoremove, "autoremove".into()),
+            MaintenanceStep::Cleanup => (ConfirmAction::Cleanup, "cleanup".into()),
+        };
+        Some(op)
+    }
+}
+
+/// Terminal background brightness. `Auto` (the default) detects it at startup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Background {
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
+
+/// Key binding preset selected with `keymap = "..."` in the config file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    #[default]
+    Default,
+    Vim,
+    Emacs,
+}
+
+impl Preset {
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Default => "default",
+            Preset::Vim => "vim",
+            Preset::Emacs => "emacs",
+        }
+    }
+}
+
+impl Config {
+    /// Default location of the config file.
+    pub fn default_path() -> Option<PathBuf> {
+        paths::config_dir().map(|d| d.join("config.toml"))
+    }
+
+    /// Load the config from the default location; a missing file is not an error.
+    pub fn load() -> Result<Self> {
+        match Self::default_path() {
+            Some(p) if p.exists() => Self::load_from(&p),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let s = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&s).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    pub fn outdated_interval(&self) -> u64 {
+        self.outdated_interval.unwrap_or(DEFAULT_OUTDATED_INTERVAL)
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+/// Where the config comes from plus settings given on the command line, which take precedence
+/// over the file (also when it is reloaded).
+#[derive(Clone, Debug, Default)]
+pub struct ConfigSource {
+    /// Explicit config file; unlike the default location it must exist.
+    pub path: Option<PathBuf>,
+    pub theme: Option<String>,
+    pub brew_path: Option<PathBuf>,
+}
+
+impl ConfigSource {
+    pub fn load(&self) -> Result<Config> {
+        let mut config = match &self.path {
+            Some(p) => Config::load_from(p)?,
+            None => Config::load()?,
+        };
+        if let Some(theme) = &self.theme {
+            config.theme = Some(theme.clone());
+        }
+        if let Some(path) = &self.brew_path {
+            config.brew.path = Some(path.clone());
+        }
+        Ok(config)
+    }
+}
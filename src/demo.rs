@@ -0,0 +1,557 @@
+// `--demo`: fixture data standing in for a Homebrew installation, so the UI can be explored,
+// screenshotted and developed on machines without brew. Operations are simulated with
+// brew-like output and update the fixture state (installing adds a package, upgrading clears
+// its outdated flag, ...).
+use crate::brew::{
+    BrewEnvironment, CleanupPreview, DoctorItem, FormulaCommit, FormulaInfo, InstalledCask,
+    InstalledInfo, Livecheck, Service, Severity,
+};
+use crate::build_logs::{BuildLogFile, BuildLogs};
+use crate::disk::{self, CacheEntry, DiskUsage, DownloadCache, PackageUsage};
+use crate::event::{AppEvent, ConfirmAction, EXTRACT_TAP};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const FIXTURE: &str = include_str!("../fixtures/demo.json");
+
+/// Delay between simulated output lines.
+const LINE_DELAY: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DemoState {
+    pub installed: Vec<FormulaInfo>,
+    pub available: Vec<String>,
+    pub outdated: Vec<String>,
+    /// `brew analytics` state, toggled by the simulated `brew analytics on/off`.
+    #[serde(default)]
+    pub analytics: bool,
+    /// Formulae held back by a simulated `brew pin`.
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    /// Upstream releases that brew has not packaged yet, as `brew livecheck` reports them.
+    #[serde(default)]
+    pub upstream: HashMap<String, String>,
+    #[serde(default)]
+    pub casks: Vec<InstalledCask>,
+    #[serde(default)]
+    pub services: Vec<Service>,
+    /// Tapped repositories besides those the installed formulae come from.
+    #[serde(default)]
+    taps: Vec<String>,
+    /// Download cache files removed by a simulated purge.
+    #[serde(skip)]
+    pub purged_downloads: Vec<PathBuf>,
+}
+
+impl Default for DemoState {
+    fn default() -> Self {
+        serde_json::from_str(FIXTURE).expect("bundled demo fixture is valid")
+    }
+}
+
+impl DemoState {
+    pub fn info(&self, name: &str) -> Option<FormulaInfo> {
+        if let Some(f) = self.installed.iter().find(|f| f.name == name) {
+            return Some(f.clone());
+        }
+        self.available
+            .iter()
+            .any(|n| n == name)
+            .then(|| FormulaInfo {
+                name: name.to_string(),
+                full_name: Some(name.to_string()),
+                desc: Some(format!("{} (demo package)", name)),
+                homepage: Some(format!("https://formulae.brew.sh/formula/{}", name)),
+                ..Default::default()
+            })
+    }
+
+    /// What a known package depends on; the demo's packages list their own.
+    pub fn dependencies(&self, name: &str) -> Vec<String> {
+        self.info(name).map(|f| f.dependencies).unwrap_or_default()
+    }
+
+    /// The fixture's taps and those of installed formulae (an extracted version adds its tap).
+    pub fn taps(&self) -> Vec<String> {
+        let mut taps = self.taps.clone();
+        for tap in self.installed.iter().filter_map(FormulaInfo::tap_name) {
+            if !taps.iter().any(|t| t == tap) {
+                taps.push(tap.to_string());
+            }
+        }
+        taps.sort();
+        taps
+    }
+
+    /// Old downloads of the installed packages, as a dry run would list them.
+    pub fn cleanup_preview(&self) -> CleanupPreview {
+        let files = self
+            .installed
+            .iter()
+            .map(|f| {
+                (
+                    format!(
+                        "/Users/demo/Library/Caches/Homebrew/downloads/{}--0.9.0.bottle.tar.gz",
+                        f.name
+                    ),
+                    Some("1.2MB".to_string()),
+                )
+            })
+            .collect::<Vec<_>>();
+        let total = format!("{:.1}MB", files.len() as f64 * 1.2);
+        CleanupPreview {
+            files,
+            total: Some(total),
+        }
+    }
+
+    /// Made-up Cellar sizes for the installed packages.
+    pub fn disk_usage(&self) -> DiskUsage {
+        let mut packages: Vec<PackageUsage> = self
+            .installed
+            .iter()
+            .map(|f| PackageUsage {
+                name: f.name.clone(),
+                // vary by name so the order is not just the list order
+                size: f.name.bytes().map(u64::from).sum::<u64>() * 37_000,
+            })
+            .collect();
+        packages.sort_by_key(|p| std::cmp::Reverse(p.size));
+        DiskUsage {
+            cellar: PathBuf::from("/opt/homebrew/Cellar"),
+            packages,
+        }
+    }
+
+    /// One bottle download per installed package, a few days to a few months old.
+    pub fn download_cache(&self) -> DownloadCache {
+        let dir = PathBuf::from("/Users/demo/Library/Caches/Homebrew");
+        let now = disk::now_secs();
+        let mut entries: Vec<CacheEntry> = self
+            .installed
+            .iter()
+            .enumerate()
+            .map(|(i, f)| CacheEntry {
+                path: dir
+                    .join("downloads")
+                    .join(format!("{}--1.0.0.arm64_sonoma.bottle.tar.gz", f.name)),
+                size: (i as u64 + 1) * 1_300_000,
+                modified: now.saturating_sub((3 + 11 * i as u64) * 24 * 60 * 60),
+            })
+            .filter(|e| !self.purged_downloads.contains(&e.path))
+            .collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.size));
+        DownloadCache { dir, entries }
+    }
+
+    /// livecheck of known packages (every installed one when `names` is empty): upstream is at
+    /// the stable version unless `upstream` has a newer release.
+    pub fn livecheck(&self, names: &[String]) -> Vec<Livecheck> {
+        let names: Vec<String> = if names.is_empty() {
+            self.installed.iter().map(|f| f.name.clone()).collect()
+        } else {
+            names.to_vec()
+        };
+        names
+            .into_iter()
+            .map(|name| match self.info(&name) {
+                Some(info) => {
+                    let current = info.stable_version().map(String::from);
+                    let latest = self
+                        .upstream
+                        .get(&name)
+                        .cloned()
+                        .or_else(|| current.clone());
+                    Livecheck {
+                        outdated: latest != current,
+                        formula: name,
+                        current,
+                        latest,
+                        ..Default::default()
+                    }
+                }
+                None => Livecheck {
+                    messages: vec![format!("No formula with the name \"{}\"", name)],
+                    formula: name,
+                    status: Some("error".into()),
+                    ..Default::default()
+                },
+            })
+            .collect()
+    }
+
+    /// Version bumps and bottle updates of a known package, with its newest version on top.
+    pub fn formula_log(&self, name: &str) -> Vec<FormulaCommit> {
+        let Some(info) = self.info(name) else {
+            return vec![];
+        };
+        let installed = info.installed.first().map(|i| i.version.clone());
+        let stable = info
+            .stable_version()
+            .map(String::from)
+            .or_else(|| installed.clone())
+            .unwrap_or_else(|| "1.0.0".into());
+        let mut subjects = vec![
+            format!("{}: update {} bottle.", name, stable),
+            format!("{} {}", name, stable),
+        ];
+        if let Some(v) = installed.filter(|v| *v != stable) {
+            subjects.push(format!("{}: update {} bottle.", name, v));
+            subjects.push(format!("{} {}", name, v));
+        }
+        subjects.push(format!("{}: use `std_cmake_args`", name));
+        subjects.push(format!("{}: add livecheck", name));
+        subjects
+            .into_iter()
+            .enumerate()
+            .map(|(i, subject)| FormulaCommit {
+                hash: format!("{:07x}{:033x}", 0xa1b2c3d + i * 0x1111, i),
+                author: "BrewTestBot <1589480+BrewTestBot@users.noreply.github.com>".into(),
+                date: format!("Mon Jun {} 09:{:02}:00 2024 +0000", 24 - 3 * i, 10 + i),
+                subject,
+            })
+            .collect()
+    }
+
+    /// A source build that broke in `make`, for the first of `names` that is a known package.
+    pub fn build_logs(&self, names: &[String]) -> anyhow::Result<BuildLogs> {
+        let name = names
+            .iter()
+            .find(|n| self.info(n).is_some())
+            .ok_or_else(|| anyhow::anyhow!("no build logs for {}", names.join(", ")))?;
+        let file = |file: &str, lines: &[&str]| BuildLogFile {
+            name: file.to_string(),
+            lines: lines.iter().map(|l| l.replace("{}", name)).collect(),
+        };
+        Ok(BuildLogs {
+            name: name.clone(),
+            dir: PathBuf::from("/Users/demo/Library/Logs/Homebrew").join(name),
+            files: vec![
+                file(
+                    "01.configure",
+                    &[
+                        "./configure --prefix=/opt/homebrew/Cellar/{}/1.0.0",
+                        "checking for gcc... clang",
+                        "checking whether the C compiler works... yes",
+                        "config.status: creating Makefile",
+                    ],
+                ),
+                file(
+                    "02.make",
+                    &[
+                        "make",
+                        "clang -O2 -c src/{}.c -o src/{}.o",
+                        "src/{}.c:42:10: fatal error: 'demo.h' file not found",
+                        "make: *** [src/{}.o] Error 1",
+                    ],
+                ),
+            ],
+        })
+    }
+
+    /// A plausible Apple Silicon installation for the environment panel.
+    pub fn environment(&self) -> BrewEnvironment {
+        let pairs = |list: &[(&str, &str)]| {
+            list.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        BrewEnvironment {
+            config: pairs(&[
+                ("HOMEBREW_VERSION", "4.3.0"),
+                ("ORIGIN", "https://github.com/Homebrew/brew"),
+                ("Core tap JSON", "12 May 09:30 UTC"),
+                ("HOMEBREW_PREFIX", "/opt/homebrew"),
+                ("HOMEBREW_NO_AUTO_UPDATE", "set"),
+                ("CPU", "octa-core 64-bit arm_firestorm_icestorm"),
+                ("Clang", "15.0.0 build 1500"),
+                (
+                    "Git",
+                    "2.39.3 => /Library/Developer/CommandLineTools/usr/bin/git",
+                ),
+                ("Curl", "8.4.0 => /usr/bin/curl"),
+                ("macOS", "14.4-arm64"),
+                ("CLT", "15.3.0.0.1.1708646388"),
+                ("Xcode", "N/A"),
+                ("Rosetta 2", "false"),
+            ]),
+            env: pairs(&[
+                ("HOMEBREW_CC", "clang"),
+                ("HOMEBREW_CXX", "clang++"),
+                ("MAKEFLAGS", "-j8"),
+                ("CMAKE_PREFIX_PATH", "/opt/homebrew"),
+                ("PKG_CONFIG_LIBDIR", "/usr/lib/pkgconfig"),
+            ]),
+            cellar: Some("/opt/homebrew/Cellar".into()),
+            analytics: Some(self.analytics),
+        }
+    }
+
+    pub fn search(&self, query: &str) -> Vec<String> {
+        self.available
+            .iter()
+            .filter(|n| n.contains(query))
+            .cloned()
+            .collect()
+    }
+
+    fn apply(&mut self, action: &ConfirmAction, pkg: &str) {
+        match action {
+            ConfirmAction::Install | ConfirmAction::BulkInstall(_) => {
+                if !self.installed.iter().any(|f| f.name == pkg) {
+                    if let Some(mut info) = self.info(pkg) {
+                        info.installed = vec![InstalledInfo {
+                            version: "1.0.0".into(),
+                            time: Some(disk::now_secs()),
+                        }];
+                        info.linked_keg = Some("1.0.0".into());
+                        self.installed.push(info);
+                        self.installed.sort_by(|a, b| a.name.cmp(&b.name));
+                    }
+                }
+            }
+            ConfirmAction::Uninstall | ConfirmAction::BulkUninstall(_) => {
+                self.installed.retain(|f| f.name != pkg);
+                self.outdated.retain(|n| n != pkg);
+            }
+            ConfirmAction::Upgrade | ConfirmAction::BulkUpgrade(_) => {
+                self.outdated.retain(|n| n != pkg);
+            }
+            ConfirmAction::Link | ConfirmAction::LinkOverwrite | ConfirmAction::Unlink => {
+                let linked = !matches!(action, ConfirmAction::Unlink);
+                if let Some(f) = self.installed.iter_mut().find(|f| f.name == pkg) {
+                    f.linked_keg = if linked {
+                        f.installed.first().map(|i| i.version.clone())
+                    } else {
+                        None
+                    };
+                }
+            }
+            ConfirmAction::Postinstall
+            | ConfirmAction::InstallBrew
+            | ConfirmAction::Cleanup
+            | ConfirmAction::CleanupPruneAll
+            | ConfirmAction::Update
+            | ConfirmAction::Autoremove
+            | ConfirmAction::GistLogs => {}
+            ConfirmAction::PurgeCache { .. } => self.purged_downloads.push(PathBuf::from(pkg)),
+            ConfirmAction::WithOptions { action, .. } => self.apply(action, pkg),
+            ConfirmAction::SwitchVersion { version } => {
+                if let Some(f) = self.installed.iter_mut().find(|f| f.name == pkg) {
+                    f.linked_keg = Some(version.clone());
+                }
+            }
+            ConfirmAction::RemoveVersion { version } => {
+                if let Some(f) = self.installed.iter_mut().find(|f| f.name == pkg) {
+                    f.installed.retain(|i| &i.version != version);
+                }
+            }
+            ConfirmAction::ExtractInstall { version } => {
+                let extracted = format!("{}@{}", pkg, version);
+                let exists = self.installed.iter().any(|f| f.name == extracted);
+                if let Some(mut info) = self.info(pkg).filter(|_| !exists) {
+                    info.full_name = Some(format!("{}/{}", EXTRACT_TAP, extracted));
+                    info.name = extracted;
+                    info.installed = vec![InstalledInfo {
+                        version: version.clone(),
+                        time: Some(disk::now_secs()),
+                    }];
+                    info.linked_keg = None;
+                    self.installed.push(info);
+                    self.installed.sort_by(|a, b| a.name.cmp(&b.name));
+                }
+            }
+            ConfirmAction::Pin | ConfirmAction::BulkPin(_) => {
+                if !self.pinned.iter().any(|p| p == pkg) {
+                    self.pinned.push(pkg.to_string());
+                }
+            }
+            ConfirmAction::Unpin | ConfirmAction::BulkUnpin(_) => self.pinned.retain(|p| p != pkg),
+            ConfirmAction::AnalyticsOn => self.analytics = true,
+            ConfirmAction::AnalyticsOff => self.analytics = false,
+        }
+    }
+}
+
+/// Sample `brew doctor` findings.
+pub fn doctor() -> Vec<DoctorItem> {
+    vec![
+        DoctorItem {
+            severity: Severity::Warning,
+            title: "Some installed formulae are deprecated or disabled.".into(),
+            details: vec![
+                "You should find replacements for the following formulae:".into(),
+                "  python@3.8".into(),
+            ],
+        },
+        DoctorItem {
+            severity: Severity::Warning,
+            title: "Unbrewed dylibs were found in /usr/local/lib.".into(),
+            details: vec![
+                "If you didn't put them there on purpose they could cause problems when".into(),
+                "building Homebrew formulae and may need to be deleted.".into(),
+            ],
+        },
+    ]
+}
+
+/// Output lines resembling what brew prints for the action.
+fn script(action: &ConfirmAction, pkg: &str) -> Vec<String> {
+    let cellar = format!("/opt/homebrew/Cellar/{}/1.0.0", pkg);
+    let fetch = vec![
+        format!("==> Fetching {}", pkg),
+        format!(
+            "==> Downloading https://ghcr.io/v2/homebrew/core/{}/blobs/sha256:demo",
+            pkg
+        ),
+        "######################################################################## 100.0%"
+            .to_string(),
+    ];
+    match action {
+        ConfirmAction::Install
+        | ConfirmAction::BulkInstall(_)
+        | ConfirmAction::Upgrade
+        | ConfirmAction::BulkUpgrade(_) => {
+            let verb = if let ConfirmAction::Install | ConfirmAction::BulkInstall(_) = action {
+                "Installing"
+            } else {
+                "Upgrading"
+            };
+            let mut lines = fetch;
+            lines.push(format!("==> {} {}", verb, pkg));
+            lines.push(format!(
+                "==> Pouring {}--1.0.0.arm64_sonoma.bottle.tar.gz",
+                pkg
+            ));
+            lines.push(format!("🍺  {}: 42 files, 3.1MB", cellar));
+            lines.push("==> Running `brew cleanup`...".to_string());
+            lines
+        }
+        ConfirmAction::Uninstall | ConfirmAction::BulkUninstall(_) => {
+            vec![format!("Uninstalling {}... (42 files, 3.1MB)", cellar)]
+        }
+        ConfirmAction::Postinstall => vec![format!("==> Postinstalling {}", pkg)],
+        ConfirmAction::Link | ConfirmAction::LinkOverwrite => {
+            vec![format!("Linking {}... 7 symlinks created.", cellar)]
+        }
+        ConfirmAction::Unlink => vec![format!("Unlinking {}... 7 symlinks removed.", cellar)],
+        ConfirmAction::SwitchVersion { version } => vec![
+            format!("Unlinking {}... 7 symlinks removed.", cellar),
+            format!(
+                "Linking /opt/homebrew/Cellar/{}/{}... 7 symlinks created.",
+                pkg, version
+            ),
+        ],
+        ConfirmAction::ExtractInstall { version } => {
+            let tap = format!("/opt/homebrew/Library/Taps/{}", EXTRACT_TAP.replace('/', "/homebrew-"));
+            let mut lines = vec![
+                format!("==> Created {}", EXTRACT_TAP),
+                "==> Searching repository history".to_string(),
+                format!(
+                    "==> Writing formula for {} at {} from revision 1a2b3c4 to:",
+                    pkg, version
+                ),
+                format!("{}/Formula/{}@{}.rb", tap, pkg, version),
+            ];
+            lines.extend(fetch);
+            lines.push(format!("==> Installing {}/{}@{}", EXTRACT_TAP, pkg, version));
+            lines.push(format!(
+                "🍺  /opt/homebrew/Cellar/{}@{}/{}: 42 files, 3.1MB, built in 12 seconds",
+                pkg, version, version
+            ));
+            lines
+        }
+        ConfirmAction::Pin
+        | ConfirmAction::Unpin
+        | ConfirmAction::BulkPin(_)
+        | ConfirmAction::BulkUnpin(_)
+        | ConfirmAction::RemoveVersion { .. } => vec![],
+        ConfirmAction::Cleanup | ConfirmAction::CleanupPruneAll => vec![
+            format!(
+                "Removing: /Users/demo/Library/Caches/Homebrew/downloads/{}--0.9.0.bottle.tar.gz... (1.2MB)",
+                pkg
+            ),
+            "==> This operation has freed approximately 1.2MB of disk space.".to_string(),
+        ],
+        ConfirmAction::Update => vec![
+            "==> Updating Homebrew...".to_string(),
+            "Updated 2 taps (homebrew/core and homebrew/cask).".to_string(),
+            "==> New Formulae".to_string(),
+            "demo-lint".to_string(),
+            "==> Updated Formulae".to_string(),
+            "Updated 12 formulae.".to_string(),
+            "==> Updated Casks".to_string(),
+            "Updated 3 casks.".to_string(),
+        ],
+        ConfirmAction::Autoremove => vec![
+            "==> Autoremoving 1 unneeded formula:".to_string(),
+            "demo-dep".to_string(),
+            "Uninstalling /opt/homebrew/Cellar/demo-dep/1.0.0... (8 files, 120KB)".to_string(),
+        ],
+        ConfirmAction::GistLogs => vec![
+            format!("==> Uploading the build logs of {}", pkg),
+            "https://gist.github.com/demo/0f3c8a1e".to_string(),
+        ],
+        ConfirmAction::PurgeCache { .. } => vec![pkg.to_string()],
+        ConfirmAction::WithOptions { action, .. } => script(action, pkg),
+        ConfirmAction::InstallBrew | ConfirmAction::AnalyticsOn | ConfirmAction::AnalyticsOff => {
+            vec![]
+        }
+    }
+}
+
+/// Simulate `action` on `name`, streaming brew-like output as operation events (without
+/// `OpStart`/`OpEnd`). Returns the exit code.
+pub fn simulate_operation(
+    state: &Mutex<DemoState>,
+    action: &ConfirmAction,
+    name: &str,
+    tx: &mpsc::Sender<AppEvent>,
+) -> Option<i32> {
+    let targets = match action.base() {
+        ConfirmAction::BulkUpgrade(pkgs)
+        | ConfirmAction::BulkInstall(pkgs)
+        | ConfirmAction::BulkUninstall(pkgs)
+        | ConfirmAction::BulkPin(pkgs)
+        | ConfirmAction::BulkUnpin(pkgs) => pkgs.clone(),
+        ConfirmAction::Cleanup | ConfirmAction::CleanupPruneAll => state
+            .lock()
+            .map(|s| s.installed.iter().map(|f| f.name.clone()).collect())
+            .unwrap_or_default(),
+        ConfirmAction::PurgeCache { older_than_days } => {
+            let now = disk::now_secs();
+            state
+                .lock()
+                .map(|s| {
+                    s.download_cache()
+                        .purgeable(*older_than_days, now)
+                        .map(|e| e.path.display().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+        _ => vec![name.to_string()],
+    };
+    for pkg in &targets {
+        let known = state.lock().map(|s| s.info(pkg).is_some()).unwrap_or(false);
+        if action.acts_on_package() && !known {
+            let _ = tx.send(AppEvent::OpErr(format!(
+                "Error: No available formula with the name \"{}\".",
+                pkg
+            )));
+            return Some(1);
+        }
+        for line in script(action, pkg) {
+            thread::sleep(LINE_DELAY);
+            let _ = tx.send(AppEvent::OpLog(line));
+        }
+        if let Ok(mut s) = state.lock() {
+            s.apply(action, pkg);
+        }
+    }
+    Some(0)
+}
@@ -0,0 +1,199 @@
+// Disk space: sizes of what Homebrew keeps on disk, measured without following symlinks: the
+// packages in the Cellar, and the download cache (`brew --cache`) with the `find` command that
+// purges it. The purge runs as
+// an ordinary operation so every removed path is streamed into the log, and it selects files
+// by the same age rule as `DownloadCache::purgeable`, so the amount shown before confirming is
+// the amount removed.
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Space taken by one installed package: its directory in the Cellar, all versions included.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PackageUsage {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Installed packages by size.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DiskUsage {
+    pub cellar: PathBuf,
+    /// Largest first.
+    pub packages: Vec<PackageUsage>,
+}
+
+impl DiskUsage {
+    /// Bytes used by all packages together.
+    pub fn total(&self) -> u64 {
+        self.packages.iter().map(|p| p.size).sum()
+    }
+
+    /// Size by package name.
+    pub fn sizes(&self) -> HashMap<String, u64> {
+        self.packages
+            .iter()
+            .map(|p| (p.name.clone(), p.size))
+            .collect()
+    }
+}
+
+/// Measure every package directory in `cellar`, largest first.
+pub fn cellar_usage(cellar: &Path) -> io::Result<DiskUsage> {
+    let mut packages: Vec<PackageUsage> = fs::read_dir(cellar)?
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| PackageUsage {
+            name: e.file_name().to_string_lossy().into_owned(),
+            size: dir_size(&e.path()),
+        })
+        .collect();
+    packages.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    Ok(DiskUsage {
+        cellar: cellar.to_path_buf(),
+        packages,
+    })
+}
+
+/// A file (or symlink) somewhere below the download cache.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CacheEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Last modification, in seconds since the Unix epoch.
+    pub modified: u64,
+}
+
+/// The contents of Homebrew's download cache.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DownloadCache {
+    pub dir: PathBuf,
+    /// Largest first.
+    pub entries: Vec<CacheEntry>,
+}
+
+impl DownloadCache {
+    /// Bytes used by everything in the cache.
+    pub fn total(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+
+    /// What a purge removes: everything for `None`, otherwise the entries last modified more
+    /// than `older_than_days` days before `now` (seconds since the Unix epoch).
+    pub fn purgeable(
+        &self,
+        older_than_days: Option<u32>,
+        now: u64,
+    ) -> impl Iterator<Item = &CacheEntry> {
+        let cutoff = older_than_days.map(|d| now.saturating_sub(u64::from(d) * DAY_SECS));
+        self.entries
+            .iter()
+            .filter(move |e| cutoff.is_none_or(|c| e.modified < c))
+    }
+
+    /// Bytes and number of entries a purge with `older_than_days` would remove.
+    pub fn reclaimable(&self, older_than_days: Option<u32>, now: u64) -> (u64, usize) {
+        self.purgeable(older_than_days, now)
+            .fold((0, 0), |(bytes, n), e| (bytes + e.size, n + 1))
+    }
+}
+
+/// List every file and symlink below `dir`, largest first. Entries that vanish or cannot be
+/// read while walking are skipped.
+pub fn scan_cache(dir: &Path) -> io::Result<DownloadCache> {
+    let mut entries = vec![];
+    walk(dir, &mut |path, meta| {
+        entries.push(CacheEntry {
+            path: path.to_path_buf(),
+            size: meta.len(),
+            modified: modified_secs(meta),
+        })
+    })?;
+    entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    Ok(DownloadCache {
+        dir: dir.to_path_buf(),
+        entries,
+    })
+}
+
+/// Bytes used by `path` and, for a directory, everything below it. Symlinks count with their
+/// own size and are not followed; unreadable parts count as nothing.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    let mut total = 0;
+    let _ = walk(path, &mut |_, meta| total += meta.len());
+    total
+}
+
+fn walk(dir: &Path, visit: &mut dyn FnMut(&Path, &fs::Metadata)) -> io::Result<()> {
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            let _ = walk(&path, visit);
+        } else {
+            visit(&path, &meta);
+        }
+    }
+    Ok(())
+}
+
+/// The current time in seconds since the Unix epoch, as `purgeable` expects it.
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn modified_secs(meta: &fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Arguments to `find` that print and delete what `DownloadCache::purgeable` selects in `dir`.
+/// Directories are left in place.
+pub fn purge_args(dir: &str, older_than_days: Option<u32>) -> Vec<String> {
+    let mut args: Vec<String> = [dir, "(", "-type", "f", "-o", "-type", "l", ")"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    if let Some(days) = older_than_days {
+        args.push("-mmin".into());
+        args.push(format!("+{}", u64::from(days) * 24 * 60));
+    }
+    args.push("-print".into());
+    args.push("-delete".into());
+    args
+}
+
+/// A size the way brew prints it: "512B", "3.1KB", "42MB", "1.2GB".
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1 << 30, "GB"), (1 << 20, "MB"), (1 << 10, "KB")];
+    for (scale, unit) in UNITS {
+        if bytes >= scale {
+            let value = (bytes as f64 / scale as f64 * 10.0).round() / 10.0;
+            return if value.fract() == 0.0 {
+                format!("{}{}", value as u64, unit)
+            } else {
+                format!("{:.1}{}", value, unit)
+            };
+        }
+    }
+    format!("{}B", bytes)
+}
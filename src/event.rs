@@ -0,0 +1,536 @@
+// Types shared by the UI, `--porcelain` and library users: the actions an operation can
+// perform and the events background work reports while it runs.
+use crate::brew::{
+    BrewEnvironment, CleanupPreview, DependencyPreview, DoctorItem, FormulaCommit, FormulaInfo,
+    InstalledCask, Livecheck, Service,
+};
+use crate::build_logs::BuildLogs;
+use crate::disk::{self, DiskUsage, DownloadCache};
+use crate::github::RepoStats;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Shell command that runs the official Homebrew installer.
+pub const HOMEBREW_INSTALL_SCRIPT: &str =
+    "/bin/bash -c \"$(curl -fsSL https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh)\"";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmAction {
+    Install,
+    Uninstall,
+    Upgrade,
+    Postinstall,
+    Link,
+    LinkOverwrite,
+    Unlink,
+    BulkUpgrade(Vec<String>),
+    /// Install several packages at once, e.g. those removed since a snapshot.
+    BulkInstall(Vec<String>),
+    /// Uninstall, pin or unpin every package marked in the Installed list at once.
+    BulkUninstall(Vec<String>),
+    BulkPin(Vec<String>),
+    BulkUnpin(Vec<String>),
+    /// Make another installed version of the formula the active one: unlink the current keg,
+    /// point `opt/<name>` at the chosen version and link again (brew resolves the keg through
+    /// `opt`). brew has had no command for this since `brew switch` was removed. Runs `sh`.
+    SwitchVersion {
+        version: String,
+    },
+    /// Delete one installed version of the formula from the Cellar, the way `brew cleanup`
+    /// removes old kegs. Runs `sh`.
+    RemoveVersion {
+        version: String,
+    },
+    /// Install an older version that has no versioned formula: `brew extract` copies the
+    /// formula as it was at that version into the local `EXTRACT_TAP` (created on first use) and
+    /// `<tap>/<name>@<version>` is installed from there. Runs `sh`.
+    ExtractInstall {
+        version: String,
+    },
+    /// `brew pin`/`brew unpin`: keep a formula at its installed version, or release it.
+    Pin,
+    Unpin,
+    InstallBrew,
+    /// `brew cleanup`; the name is not used.
+    Cleanup,
+    /// `brew cleanup --prune=all`, which also removes downloads of current versions.
+    CleanupPruneAll,
+    /// `brew analytics on`/`off`; the name is not used.
+    AnalyticsOn,
+    AnalyticsOff,
+    /// `brew update`, fetching the newest Homebrew and formula definitions; the name is not
+    /// used.
+    Update,
+    /// `brew autoremove`, uninstalling dependencies nothing needs any more; the name is not
+    /// used.
+    Autoremove,
+    /// `brew gist-logs`, uploading a formula's build logs and the system configuration as a
+    /// GitHub gist for an upstream bug report.
+    GistLogs,
+    /// Delete files from the download cache, all of them or those older than a number of
+    /// days; the name is the cache directory. Runs `find` rather than brew.
+    PurgeCache {
+        older_than_days: Option<u32>,
+    },
+    /// Another action with the flags picked for it in the options overlay (`o` in the confirm
+    /// dialog), e.g. `brew install --force jq`, and environment variables set for it alone
+    /// (`e`), e.g. `HOMEBREW_MAKE_JOBS=8`.
+    WithOptions {
+        action: Box<ConfirmAction>,
+        #[serde(default)]
+        flags: Vec<String>,
+        #[serde(default)]
+        env: Vec<(String, String)>,
+    },
+}
+
+const INSTALL_FLAGS: [(&str, &str); 4] = [
+    (
+        "--force",
+        "install even when it is installed already, overwriting files",
+    ),
+    ("--verbose", "print every step of the download and build"),
+    ("--debug", "print brew's debugging output as well"),
+    (
+        "--ignore-dependencies",
+        "skip the dependencies, which can leave it broken",
+    ),
+];
+
+const UNINSTALL_FLAGS: [(&str, &str); 4] = [
+    (
+        "--force",
+        "delete every installed version, not only the current one",
+    ),
+    ("--verbose", "print what is removed"),
+    ("--debug", "print brew's debugging output as well"),
+    (
+        "--ignore-dependencies",
+        "uninstall even if other installed formulae depend on it",
+    ),
+];
+
+/// What every other brew command takes. A bulk upgrade gives them only to the packages whose
+/// last upgrade failed (`split_retries`).
+const OUTPUT_FLAGS: [(&str, &str); 2] = [
+    ("--verbose", "print every step brew takes"),
+    ("--debug", "print brew's debugging output as well"),
+];
+
+impl ConfirmAction {
+    /// Arguments passed to `brew` for this action (empty for `InstallBrew`, which runs the
+    /// installer script instead, `PurgeCache`, which runs `find`, and the version actions,
+    /// which run `shell_script`).
+    pub fn brew_args(&self, name: &str) -> Vec<String> {
+        let (verb, args): (&str, Vec<String>) = match self {
+            ConfirmAction::Uninstall => ("uninstall", vec![name.to_string()]),
+            ConfirmAction::Upgrade => ("upgrade", vec![name.to_string()]),
+            ConfirmAction::Install => ("install", vec![name.to_string()]),
+            ConfirmAction::Postinstall => ("postinstall", vec![name.to_string()]),
+            ConfirmAction::Link => ("link", vec![name.to_string()]),
+            ConfirmAction::LinkOverwrite => ("link", vec!["--overwrite".into(), name.to_string()]),
+            ConfirmAction::Unlink => ("unlink", vec![name.to_string()]),
+            ConfirmAction::BulkUpgrade(pkgs) => ("upgrade", pkgs.clone()),
+            ConfirmAction::BulkInstall(pkgs) => ("install", pkgs.clone()),
+            ConfirmAction::BulkUninstall(pkgs) => ("uninstall", pkgs.clone()),
+            ConfirmAction::BulkPin(pkgs) => ("pin", pkgs.clone()),
+            ConfirmAction::BulkUnpin(pkgs) => ("unpin", pkgs.clone()),
+            ConfirmAction::Pin => ("pin", vec![name.to_string()]),
+            ConfirmAction::Unpin => ("unpin", vec![name.to_string()]),
+            ConfirmAction::Cleanup => ("cleanup", vec![]),
+            ConfirmAction::CleanupPruneAll => ("cleanup", vec!["--prune=all".into()]),
+            ConfirmAction::AnalyticsOn => ("analytics", vec!["on".into()]),
+            ConfirmAction::AnalyticsOff => ("analytics", vec!["off".into()]),
+            ConfirmAction::Update => ("update", vec![]),
+            ConfirmAction::Autoremove => ("autoremove", vec![]),
+            ConfirmAction::GistLogs => ("gist-logs", vec![name.to_string()]),
+            ConfirmAction::WithOptions { action, flags, .. } => {
+                let mut v = action.brew_args(name);
+                if !v.is_empty() {
+                    v.splice(1..1, flags.iter().cloned());
+                }
+                return v;
+            }
+            ConfirmAction::InstallBrew
+            | ConfirmAction::PurgeCache { .. }
+            | ConfirmAction::SwitchVersion { .. }
+            | ConfirmAction::RemoveVersion { .. }
+            | ConfirmAction::ExtractInstall { .. } => return vec![],
+        };
+        let mut v = vec![verb.to_string()];
+        v.extend(args);
+        v
+    }
+
+    /// What the action does, as shown in dialog titles ("Install", "Link --overwrite", ...).
+    pub fn title(&self) -> &'static str {
+        match self {
+            ConfirmAction::Install => "Install",
+            ConfirmAction::Uninstall => "Uninstall",
+            ConfirmAction::Upgrade => "Upgrade",
+            ConfirmAction::Postinstall => "Postinstall",
+            ConfirmAction::Link => "Link",
+            ConfirmAction::LinkOverwrite => "Link --overwrite",
+            ConfirmAction::Unlink => "Unlink",
+            ConfirmAction::BulkUpgrade(_) => "Bulk Upgrade",
+            ConfirmAction::BulkInstall(_) => "Bulk Install",
+            ConfirmAction::BulkUninstall(_) => "Bulk Uninstall",
+            ConfirmAction::BulkPin(_) => "Bulk Pin",
+            ConfirmAction::BulkUnpin(_) => "Bulk Unpin",
+            ConfirmAction::SwitchVersion { .. } => "Switch version",
+            ConfirmAction::RemoveVersion { .. } => "Remove version",
+            ConfirmAction::ExtractInstall { .. } => "Extract and install",
+            ConfirmAction::Pin => "Pin",
+            ConfirmAction::Unpin => "Unpin",
+            ConfirmAction::InstallBrew => "Install Homebrew",
+            ConfirmAction::Cleanup => "Cleanup",
+            ConfirmAction::CleanupPruneAll => "Cleanup --prune=all",
+            ConfirmAction::AnalyticsOn => "Enable analytics",
+            ConfirmAction::AnalyticsOff => "Disable analytics",
+            ConfirmAction::Update => "Update",
+            ConfirmAction::Autoremove => "Autoremove",
+            ConfirmAction::GistLogs => "Gist logs",
+            ConfirmAction::PurgeCache { .. } => "Purge download cache",
+            ConfirmAction::WithOptions { action, .. } => action.title(),
+        }
+    }
+
+    /// The action without the flags and environment picked for it.
+    pub fn base(&self) -> &ConfirmAction {
+        match self {
+            ConfirmAction::WithOptions { action, .. } => action,
+            _ => self,
+        }
+    }
+
+    /// The flags picked for the action in the options overlay.
+    pub fn flags(&self) -> &[String] {
+        match self {
+            ConfirmAction::WithOptions { flags, .. } => flags,
+            _ => &[],
+        }
+    }
+
+    /// The environment variables set for this operation on top of the configured ones.
+    pub fn env(&self) -> &[(String, String)] {
+        match self {
+            ConfirmAction::WithOptions { env, .. } => env,
+            _ => &[],
+        }
+    }
+
+    /// The same action with `flags` instead of the ones picked before, keeping its environment.
+    pub fn with_flags(&self, flags: Vec<String>) -> ConfirmAction {
+        self.base().with_options(flags, self.env().to_vec())
+    }
+
+    /// The same action with the variables of `env` instead of those set before, keeping its
+    /// flags.
+    pub fn with_env(&self, env: Vec<(String, String)>) -> ConfirmAction {
+        self.base().with_options(self.flags().to_vec(), env)
+    }
+
+    /// The plain action when there are neither flags nor variables.
+    fn with_options(&self, flags: Vec<String>, env: Vec<(String, String)>) -> ConfirmAction {
+        if flags.is_empty() && env.is_empty() {
+            self.clone()
+        } else {
+            ConfirmAction::WithOptions {
+                action: Box::new(self.clone()),
+                flags,
+                env,
+            }
+        }
+    }
+
+    /// The flags the options overlay offers for the action, with what each does: `--verbose`
+    /// and `--debug` for anything brew runs, and more for installs and uninstalls.
+    pub fn flag_choices(&self) -> &'static [(&'static str, &'static str)] {
+        match self.base() {
+            ConfirmAction::Install | ConfirmAction::BulkInstall(_) => &INSTALL_FLAGS,
+            ConfirmAction::Uninstall | ConfirmAction::BulkUninstall(_) => &UNINSTALL_FLAGS,
+            ConfirmAction::InstallBrew
+            | ConfirmAction::PurgeCache { .. }
+            | ConfirmAction::SwitchVersion { .. }
+            | ConfirmAction::RemoveVersion { .. }
+            | ConfirmAction::ExtractInstall { .. } => &[],
+            _ => &OUTPUT_FLAGS,
+        }
+    }
+
+    /// A bulk upgrade with flags, split into the upgrade of its packages not in `retry`
+    /// without them and the upgrade of those in `retry` with them (both with its variables),
+    /// leaving out either when it has no packages: with none in `retry` the flags go to no
+    /// package. None for anything else.
+    pub fn split_retries(&self, retry: &[String]) -> Option<Vec<ConfirmAction>> {
+        let ConfirmAction::BulkUpgrade(pkgs) = self.base() else {
+            return None;
+        };
+        if self.flags().is_empty() {
+            return None;
+        }
+        let (again, rest): (Vec<String>, Vec<String>) =
+            pkgs.iter().cloned().partition(|p| retry.contains(p));
+        let env = self.env().to_vec();
+        let mut stages = vec![];
+        if !rest.is_empty() {
+            stages.push(ConfirmAction::BulkUpgrade(rest).with_options(vec![], env.clone()));
+        }
+        if !again.is_empty() {
+            stages.push(ConfirmAction::BulkUpgrade(again).with_options(self.flags().to_vec(), env));
+        }
+        Some(stages)
+    }
+
+    /// The flags of `flag_choices` whose entry in `checked` is set, in their listed order.
+    pub fn checked_flags(&self, checked: &[bool]) -> Vec<String> {
+        self.flag_choices()
+            .iter()
+            .zip(checked)
+            .filter(|(_, &c)| c)
+            .map(|((flag, _), _)| flag.to_string())
+            .collect()
+    }
+
+    /// Whether the name is a package (or a count of packages) the action changes, rather than
+    /// a placeholder for an action on Homebrew itself.
+    pub fn acts_on_package(&self) -> bool {
+        !matches!(
+            self.base(),
+            ConfirmAction::InstallBrew
+                | ConfirmAction::Cleanup
+                | ConfirmAction::CleanupPruneAll
+                | ConfirmAction::AnalyticsOn
+                | ConfirmAction::AnalyticsOff
+                | ConfirmAction::Update
+                | ConfirmAction::Autoremove
+                | ConfirmAction::PurgeCache { .. }
+        )
+    }
+
+    /// The packages a bulk action runs on, which its name only counts.
+    pub fn packages(&self) -> Option<&[String]> {
+        match self.base() {
+            ConfirmAction::BulkUpgrade(pkgs)
+            | ConfirmAction::BulkInstall(pkgs)
+            | ConfirmAction::BulkUninstall(pkgs)
+            | ConfirmAction::BulkPin(pkgs)
+            | ConfirmAction::BulkUnpin(pkgs) => Some(pkgs),
+            _ => None,
+        }
+    }
+
+    /// The `sh -c` script of the version actions, calling brew as `brew`.
+    pub fn shell_script(&self, name: &str, brew: &str) -> Option<String> {
+        let (b, n) = (shell_quote(brew), shell_quote(name));
+        match self.base() {
+            ConfirmAction::SwitchVersion { version } => Some(format!(
+                "{b} unlink {n} && ln -sfn \"$({b} --cellar)\"/{n}/{v} \"$({b} --prefix)\"/opt/{n} && {b} link {n}",
+                b = b,
+                n = n,
+                v = shell_quote(version)
+            )),
+            ConfirmAction::RemoveVersion { version } => Some(format!(
+                "rm -rf \"$({} --cellar)\"/{}/{}",
+                b,
+                n,
+                shell_quote(version)
+            )),
+            ConfirmAction::ExtractInstall { version } => Some(format!(
+                "[ -d \"$({b} --repository {t})\" ] || {b} tap-new --no-git {t}; \
+                 {b} extract --version={v} {n} {t} && {b} install {t}/{n}@{v}",
+                b = b,
+                n = n,
+                t = EXTRACT_TAP,
+                v = shell_quote(version)
+            )),
+            _ => None,
+        }
+    }
+
+    /// `brew_args` followed by the default flags from the config, which only the install,
+    /// upgrade, uninstall and link operations take: `brew update` or `brew pin` would reject a
+    /// configured `--ignore-dependencies`.
+    pub fn brew_args_with(&self, name: &str, flags: &[String]) -> Vec<String> {
+        let mut v = self.brew_args(name);
+        if self.takes_config_flags() {
+            v.extend(flags.iter().cloned());
+        }
+        v
+    }
+
+    fn takes_config_flags(&self) -> bool {
+        matches!(
+            self.base(),
+            ConfirmAction::Install
+                | ConfirmAction::Upgrade
+                | ConfirmAction::Uninstall
+                | ConfirmAction::Link
+                | ConfirmAction::LinkOverwrite
+                | ConfirmAction::Unlink
+                | ConfirmAction::BulkInstall(_)
+                | ConfirmAction::BulkUpgrade(_)
+                | ConfirmAction::BulkUninstall(_)
+        )
+    }
+
+    pub fn command_line(&self, name: &str) -> String {
+        self.command_line_with(name, &[])
+    }
+
+    /// The operation's title: its command line after the names of the variables set for it,
+    /// whose values are left out (`https_proxy=… brew install jq`) so that a proxy password
+    /// does not end up in the history, the log file, the terminal title or a notification.
+    pub fn command_line_with(&self, name: &str, flags: &[String]) -> String {
+        self.render_command_line(name, flags, false)
+    }
+
+    /// The exact command line, variables and all (`HOMEBREW_MAKE_JOBS=8 brew install jq`), as
+    /// the confirm dialog shows it and `c` copies it.
+    pub fn full_command_line_with(&self, name: &str, flags: &[String]) -> String {
+        self.render_command_line(name, flags, true)
+    }
+
+    fn render_command_line(&self, name: &str, flags: &[String], values: bool) -> String {
+        let mut parts: Vec<String> = self
+            .env()
+            .iter()
+            .map(|(k, v)| {
+                if values {
+                    format!("{}={}", k, shell_quote(v))
+                } else {
+                    format!("{}=…", k)
+                }
+            })
+            .collect();
+        let (program, args) = match self.base() {
+            ConfirmAction::InstallBrew => {
+                parts.push(HOMEBREW_INSTALL_SCRIPT.to_string());
+                return parts.join(" ");
+            }
+            ConfirmAction::PurgeCache { older_than_days } => {
+                ("find", disk::purge_args(name, *older_than_days))
+            }
+            ConfirmAction::SwitchVersion { .. }
+            | ConfirmAction::RemoveVersion { .. }
+            | ConfirmAction::ExtractInstall { .. } => {
+                let script = self.shell_script(name, "brew").unwrap_or_default();
+                ("sh", vec!["-c".to_string(), script])
+            }
+            _ => ("brew", self.brew_args_with(name, flags)),
+        };
+        parts.push(program.to_string());
+        parts.extend(args.iter().map(|a| shell_quote(a)));
+        parts.join(" ")
+    }
+}
+
+/// Parse the variables typed for one operation: `KEY=VALUE` pairs separated by spaces, e.g.
+/// `HOMEBREW_MAKE_JOBS=8 https_proxy=http://proxy:3128`. Nothing typed means none.
+pub fn parse_env(input: &str) -> Result<Vec<(String, String)>> {
+    input
+        .split_whitespace()
+        .map(|pair| {
+            let Some((key, value)) = pair.split_once('=') else {
+                bail!("`{}` is not KEY=VALUE", pair);
+            };
+            let valid = key.chars().next().is_some_and(|c| !c.is_ascii_digit())
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                bail!("`{}` is not a variable name", key);
+            }
+            Ok((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// The local tap `ExtractInstall` extracts older formula versions into.
+pub const EXTRACT_TAP: &str = "homebrew-tui/versions";
+
+/// Quote an argument for display when it contains characters a shell would interpret.
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./@+=:,".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Events sent from background work to the UI loop. `--porcelain` prints them as JSON lines
+/// (`{"event": "op_log", "data": "..."}`).
+#[derive(Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum AppEvent {
+    Status(String),
+    BrewList(Vec<FormulaInfo>),
+    BrewInfo(FormulaInfo, usize),
+    BrewInfoAvailable(FormulaInfo, usize),
+    /// Stats of a GitHub repository (`owner/name`) for Details.
+    GithubStats(String, RepoStats),
+    /// A note for the Logs pane.
+    Log(String),
+    /// Something that failed, for the Logs pane.
+    Error(String),
+    OpStart(String),
+    /// A line of stdout from the running operation.
+    OpLog(String),
+    /// A line of stderr from the running operation.
+    OpErr(String),
+    /// Exit code of the running operation (`None` when it was killed or failed to spawn).
+    OpExit(Option<i32>),
+    OpEnd(String),
+    /// Brew locks held by a process outside the TUI; an empty list means they were released.
+    LockWait(Vec<String>),
+    ShowConfirm(ConfirmAction, String, Option<usize>),
+    SearchResults(Vec<String>),
+    /// When the Available list was last known to match the index (unix seconds).
+    IndexFresh(u64),
+    /// What a name typed in the Install prompt may refer to (`brew search` of its short name).
+    InstallCandidates {
+        query: String,
+        names: Vec<String>,
+    },
+    /// Result of `brew cleanup --dry-run`, opening the Cleanup modal.
+    CleanupPreview(CleanupPreview),
+    /// What is in the download cache, opening the Download cache modal.
+    DownloadCache(DownloadCache),
+    /// Installed packages by size, opening the Disk usage modal.
+    DiskUsage(DiskUsage),
+    /// Full details of every installed formula, filling the Installed table.
+    InstalledDetails(Vec<FormulaInfo>),
+    /// Installed packages by size, for the Size column of the Installed table.
+    InstalledSizes(DiskUsage),
+    /// A formula's build logs, opening them in the log viewer.
+    BuildLogs(BuildLogs),
+    /// Recent tap commits of the named formula, opening the Formula history modal.
+    FormulaLog(String, Vec<FormulaCommit>),
+    /// Upstream versions from `brew livecheck`, opening the Livecheck modal.
+    Livecheck(Vec<Livecheck>),
+    /// The dependencies an install would pull in, for its confirm dialog.
+    Dependencies(DependencyPreview),
+    /// Closing lines after a sequence of operations (the maintenance routine), added to the
+    /// Operation modal and the Logs pane.
+    OpSummary(Vec<String>),
+    /// Findings of `brew doctor`, opening the Doctor modal.
+    Doctor(Vec<DoctorItem>),
+    /// `brew config`/`brew --env` output, opening the Environment panel.
+    Environment(BrewEnvironment),
+    OutdatedList(Vec<String>),
+    /// Names of the pinned formulae.
+    PinnedList(Vec<String>),
+    /// Installed casks, for the Casks tab.
+    CaskList(Vec<InstalledCask>),
+    /// Services of the installed formulae, for the Services tab.
+    ServiceList(Vec<Service>),
+    /// Tapped repositories, for the Taps tab.
+    TapList(Vec<String>),
+    AvailableList(Vec<String>),
+    /// Descriptions for Available entries, keyed by name (from Homebrew's API cache).
+    AvailableDescriptions(HashMap<String, String>),
+}
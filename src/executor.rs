@@ -0,0 +1,114 @@
+// Running an operation's child process. `spawn` starts a command with its stdout and stderr
+// piped and hands back a `Process` that yields the output as typed lines (stdout and stderr
+// kept apart) and finally the exit status. Every operation, brew or the Homebrew installer,
+// goes through here; `run` is the common case of forwarding the lines to the UI as events.
+use crate::event::AppEvent;
+use crate::jobs::CancelToken;
+use crate::output::{LogLine, Stream};
+use std::io;
+use std::process::{ExitStatus, Stdio};
+use std::sync::mpsc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader, Lines};
+use tokio::process::{Child, ChildStderr, ChildStdout, Command};
+
+/// A spawned command whose output is read line by line.
+pub struct Process {
+    child: Child,
+    stdout: Option<Lines<BufReader<ChildStdout>>>,
+    stderr: Option<Lines<BufReader<ChildStderr>>>,
+    terminating: bool,
+}
+
+/// Start `cmd` with piped stdout and stderr. The child is killed if the `Process` is dropped
+/// before it exits.
+pub fn spawn(mut cmd: Command) -> io::Result<Process> {
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = cmd.spawn()?;
+    let stdout = child.stdout.take().map(|o| BufReader::new(o).lines());
+    let stderr = child.stderr.take().map(|e| BufReader::new(e).lines());
+    Ok(Process {
+        child,
+        stdout,
+        stderr,
+        terminating: false,
+    })
+}
+
+impl Process {
+    /// The next line from either stream, or `None` once both are closed. When `cancel` fires
+    /// the process is asked to terminate; whatever it prints until it exits is still returned.
+    pub async fn next_line(&mut self, cancel: &CancelToken) -> Option<LogLine> {
+        while self.stdout.is_some() || self.stderr.is_some() {
+            tokio::select! {
+                line = read(&mut self.stdout), if self.stdout.is_some() => match line {
+                    Some(l) => return Some(LogLine::stdout(l)),
+                    None => self.stdout = None,
+                },
+                line = read(&mut self.stderr), if self.stderr.is_some() => match line {
+                    Some(l) => return Some(LogLine::stderr(l)),
+                    None => self.stderr = None,
+                },
+                _ = cancel.cancelled(), if !self.terminating => {
+                    self.terminating = true;
+                    terminate(&mut self.child);
+                }
+            }
+        }
+        None
+    }
+
+    /// Wait for the process to exit.
+    pub async fn wait(mut self) -> io::Result<ExitStatus> {
+        self.child.wait().await
+    }
+}
+
+/// Run `cmd`, sending stdout as `OpLog` and stderr as `OpErr` lines until both are closed,
+/// and return its exit status.
+pub async fn run(
+    cmd: Command,
+    tx: &mpsc::Sender<AppEvent>,
+    cancel: &CancelToken,
+) -> io::Result<ExitStatus> {
+    run_with(cmd, tx, cancel, |_| {}).await
+}
+
+/// `run`, also showing every line to `seen` before it is sent.
+pub async fn run_with(
+    cmd: Command,
+    tx: &mpsc::Sender<AppEvent>,
+    cancel: &CancelToken,
+    mut seen: impl FnMut(&LogLine) + Send,
+) -> io::Result<ExitStatus> {
+    let mut process = spawn(cmd)?;
+    // every line is forwarded before the operation is reported as finished
+    while let Some(line) = process.next_line(cancel).await {
+        seen(&line);
+        let _ = tx.send(match line.stream {
+            Stream::Stdout => AppEvent::OpLog(line.text),
+            Stream::Stderr => AppEvent::OpErr(line.text),
+        });
+    }
+    process.wait().await
+}
+
+async fn read<R>(lines: &mut Option<Lines<R>>) -> Option<String>
+where
+    R: AsyncBufRead + Unpin,
+{
+    lines.as_mut()?.next_line().await.ok().flatten()
+}
+
+/// Ask a running child to stop: SIGTERM on unix so brew can clean up, a hard kill elsewhere.
+fn terminate(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+        return;
+    }
+    let _ = child.start_kill();
+}
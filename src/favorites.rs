@@ -0,0 +1,62 @@
+// Starred packages (`*`), installed or not: the tools wanted on every machine. They are listed
+// at the top of the Available pane and kept in the state directory.
+use crate::paths;
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Default)]
+pub struct Favorites {
+    pub names: BTreeSet<String>,
+    path: Option<PathBuf>,
+}
+
+impl Favorites {
+    /// Favorites that are never written to disk.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load `favorites.json` (a list of names) from the state directory; a missing or
+    /// unreadable file starts with none.
+    pub fn load() -> Self {
+        Self::load_from(paths::state_dir().map(|d| d.join("favorites.json")))
+    }
+
+    pub fn load_from(path: Option<PathBuf>) -> Self {
+        let names = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { names, path }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Star `name`, or unstar it when it already was; returns whether it is starred now.
+    pub fn toggle(&mut self, name: &str) -> bool {
+        if self.names.remove(name) {
+            false
+        } else {
+            self.names.insert(name.to_string());
+            true
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let s = serde_json::to_string_pretty(&self.names)?;
+        fs::write(path, s).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
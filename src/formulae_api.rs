@@ -0,0 +1,167 @@
+// The formulae.brew.sh JSON API, for package details without running brew: a formula's
+// `/api/formula/<name>.json` has the fields of `brew info --json=v2` (description, versions,
+// dependencies, bottles) and its install counts, and casks have their own. It answers when
+// brew is missing or slow to start. The whole index (`formula.jws.json`, `cask.jws.json`) can
+// be downloaded in the layout of brew's own cache, with conditional requests so an unchanged
+// index is neither downloaded nor parsed again.
+//
+// Requests go through a `curl` process, as the GitHub stats do, rather than an HTTP client
+// linked into the crate: Homebrew needs curl itself, so it is there wherever brew is, and the
+// library does not take on an HTTP and TLS stack for one optional lookup. What that costs:
+// without curl on `PATH` the API cannot be used at all (the error says so, and `shared_info`
+// falls back to, or stays with, brew), and the HTTP status is only known from the code curl
+// writes after the body (`-w %{http_code}`), which is what `get` and `download_if_newer`
+// parse.
+use crate::brew::FormulaInfo;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+pub const API_URL: &str = "https://formulae.brew.sh/api";
+
+pub fn formula_url(name: &str) -> String {
+    format!("{}/formula/{}.json", API_URL, name)
+}
+
+pub fn cask_url(token: &str) -> String {
+    format!("{}/cask/{}.json", API_URL, token)
+}
+
+/// A formula as the API describes it.
+pub fn parse_formula(json: &str) -> Result<FormulaInfo> {
+    serde_json::from_str(json).context("unexpected formula from formulae.brew.sh")
+}
+
+/// A cask as the API describes it, in the shape of a formula: the token is its name, its
+/// `version` the stable version and its `url` the stable source.
+pub fn parse_cask(json: &str) -> Result<FormulaInfo> {
+    #[derive(Deserialize)]
+    struct Cask {
+        token: String,
+        full_token: Option<String>,
+        desc: Option<String>,
+        homepage: Option<String>,
+        version: Option<String>,
+        url: Option<String>,
+        caveats: Option<String>,
+        tap: Option<String>,
+        analytics: Option<JsonValue>,
+    }
+    let cask: Cask = serde_json::from_str(json).context("unexpected cask from formulae.brew.sh")?;
+    Ok(FormulaInfo {
+        name: cask.token,
+        full_name: cask.full_token,
+        desc: cask.desc,
+        homepage: cask.homepage,
+        versions: cask.version.map(|v| json!({ "stable": v })),
+        urls: cask.url.map(|u| json!({ "stable": { "url": u } })),
+        caveats: cask.caveats,
+        tap: cask.tap,
+        analytics: cask.analytics,
+        ..FormulaInfo::default()
+    })
+}
+
+/// The formula called `name`, else the cask. Only the official taps are in the API, so a name
+/// qualified with any other tap is not looked up.
+pub fn fetch(name: &str) -> Result<FormulaInfo> {
+    let short = match name.rsplit_once('/') {
+        Some(("homebrew/core" | "homebrew/cask", short)) => short,
+        Some(_) => bail!("{} is not in the formulae.brew.sh API", name),
+        None => name,
+    };
+    if let Some(json) = get(&formula_url(short))? {
+        return parse_formula(&json);
+    }
+    match get(&cask_url(short))? {
+        Some(json) => parse_cask(&json),
+        None => bail!("no formula or cask named {} on formulae.brew.sh", short),
+    }
+}
+
+/// Bring the index under `dir` (`<dir>/api/formula.jws.json` and `cask.jws.json`, which
+/// `api_cache::load` reads) up to date. Each file is only downloaded again when the server has
+/// a newer one than the copy there, going by the ETag saved with it (`<file>.etag`) and its
+/// modification time, which is set to the server's `Last-Modified`. Returns whether either
+/// file changed.
+pub fn download_index(dir: &Path) -> Result<bool> {
+    let api = dir.join("api");
+    fs::create_dir_all(&api).with_context(|| format!("failed to create {}", api.display()))?;
+    let mut changed = false;
+    for file in ["formula.jws.json", "cask.jws.json"] {
+        changed |= download_if_newer(&format!("{}/{}", API_URL, file), &api.join(file))?;
+    }
+    Ok(changed)
+}
+
+/// Download `url` to `path` unless the copy there is current (HTTP 304); whether it was.
+fn download_if_newer(url: &str, path: &Path) -> Result<bool> {
+    let etag = path.with_extension("json.etag");
+    let part = path.with_extension("json.part");
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sSL", "--compressed", "--max-time", "300", "-R"])
+        .args(["-w", "%{http_code}", "-o"])
+        .arg(&part)
+        .arg("--etag-save")
+        .arg(&etag);
+    // with no copy yet there is nothing to compare
+    if path.exists() {
+        cmd.arg("-z").arg(path);
+        if etag.exists() {
+            cmd.arg("--etag-compare").arg(&etag);
+        }
+    }
+    let out = cmd.arg(url).output().map_err(curl_error)?;
+    if !out.status.success() {
+        let _ = fs::remove_file(&part);
+        let err = String::from_utf8_lossy(&out.stderr);
+        bail!("{} failed: {}", url, err.trim());
+    }
+    match String::from_utf8_lossy(&out.stdout).trim() {
+        "200" => {
+            fs::rename(&part, path)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            Ok(true)
+        }
+        "304" => {
+            let _ = fs::remove_file(&part);
+            Ok(false)
+        }
+        code => {
+            let _ = fs::remove_file(&part);
+            bail!("{} answered HTTP {}", url, code)
+        }
+    }
+}
+
+/// Why curl could not be started: usually that it is not installed.
+fn curl_error(e: std::io::Error) -> anyhow::Error {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        anyhow!("formulae.brew.sh lookups need curl, which is not on PATH")
+    } else {
+        anyhow!("could not run curl: {}", e)
+    }
+}
+
+/// The body of `url`; None when it is not found.
+fn get(url: &str) -> Result<Option<String>> {
+    let out = Command::new("curl")
+        .args(["-sSL", "--max-time", "10", "-w", "\n%{http_code}"])
+        .arg(url)
+        .output()
+        .map_err(curl_error)?;
+    if !out.status.success() {
+        let err = String::from_utf8_lossy(&out.stderr);
+        bail!("{} failed: {}", url, err.trim());
+    }
+    let body = String::from_utf8_lossy(&out.stdout);
+    let (body, status) = body.rsplit_once('\n').unwrap_or(("", &body));
+    match status.trim() {
+        "200" => Ok(Some(body.to_string())),
+        "404" => Ok(None),
+        code => bail!("{} answered HTTP {}", url, code),
+    }
+}
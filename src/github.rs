@@ -0,0 +1,160 @@
+// GitHub repository stats for the Details pane: when a formula's homepage or source URL points
+// at a GitHub repository, its stars and last push come from the REST API (through `curl`, which
+// Homebrew needs anyway) as a quick signal of whether the project is still maintained.
+use crate::brew::FormulaInfo;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Days since the last push after which a project is shown as stale.
+pub const STALE_DAYS: u64 = 365;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepoStats {
+    pub stars: u64,
+    /// Date of the last push to any branch, `YYYY-MM-DD`.
+    pub pushed_at: String,
+    pub archived: bool,
+}
+
+impl RepoStats {
+    /// Whole days from the last push to `now` (seconds since the unix epoch).
+    pub fn days_since_push(&self, now: u64) -> Option<u64> {
+        let pushed = days_from_date(&self.pushed_at)?;
+        Some((now / 86_400).saturating_sub(pushed))
+    }
+
+    /// Archived, or not pushed to for `STALE_DAYS`.
+    pub fn is_stale(&self, now: u64) -> bool {
+        self.archived || self.days_since_push(now).is_some_and(|d| d >= STALE_DAYS)
+    }
+
+    /// `1.2k stars, last push 2024-05-01 (30d ago)`, plus `, archived`.
+    pub fn summary(&self, now: u64) -> String {
+        let mut out = format!(
+            "{} stars, last push {}",
+            format_count(self.stars),
+            self.pushed_at
+        );
+        match self.days_since_push(now) {
+            Some(0) => out.push_str(" (today)"),
+            Some(d) => out.push_str(&format!(" ({}d ago)", d)),
+            None => {}
+        }
+        if self.archived {
+            out.push_str(", archived");
+        }
+        out
+    }
+}
+
+/// `owner/repo` of a github.com URL (`https://github.com/jqlang/jq`, a release download or an
+/// archive of it, a `.git` clone URL).
+pub fn repo_from_url(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("git+https://"))?;
+    let rest = rest.strip_prefix("www.").unwrap_or(rest);
+    let path = rest.strip_prefix("github.com/")?;
+    let mut parts = path.split(['/', '?', '#']);
+    let owner = parts.next().filter(|o| !o.is_empty())?;
+    let repo = parts.next()?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    let valid = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    };
+    (valid(owner) && valid(repo)).then(|| format!("{}/{}", owner, repo))
+}
+
+/// The GitHub repository of `info`: its homepage, else where its stable source comes from.
+pub fn repo_of(info: &FormulaInfo) -> Option<String> {
+    info.homepage
+        .as_deref()
+        .and_then(repo_from_url)
+        .or_else(|| info.source_url().and_then(repo_from_url))
+}
+
+/// Parse the API's answer for a repository (`GET /repos/{owner}/{repo}`).
+pub fn parse(json: &str) -> Result<RepoStats> {
+    #[derive(Deserialize)]
+    struct Repo {
+        stargazers_count: u64,
+        pushed_at: Option<String>,
+        #[serde(default)]
+        archived: bool,
+    }
+    let repo: Repo = serde_json::from_str(json).context("unexpected answer from the GitHub API")?;
+    Ok(RepoStats {
+        stars: repo.stargazers_count,
+        pushed_at: repo
+            .pushed_at
+            .map(|p| p.chars().take(10).collect())
+            .unwrap_or_default(),
+        archived: repo.archived,
+    })
+}
+
+/// Ask the API about `repo`. With `token` (brew's `HOMEBREW_GITHUB_API_TOKEN`) the requests
+/// count against the user's much higher rate limit; it goes to curl on stdin, not in its
+/// arguments.
+pub fn fetch(repo: &str, token: Option<&str>) -> Result<RepoStats> {
+    let url = format!("https://api.github.com/repos/{}", repo);
+    let mut cmd = Command::new("curl");
+    cmd.args(["-fsSL", "--max-time", "10"])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .args(["-H", "@-"])
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| anyhow!("could not run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some(token) = token.filter(|t| !t.is_empty()) {
+            writeln!(stdin, "Authorization: Bearer {}", token)?;
+        }
+    }
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        let err = String::from_utf8_lossy(&out.stderr);
+        return Err(anyhow!("{} failed: {}", url, err.trim()));
+    }
+    parse(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// `950`, `1.2k`, `34k`, `1.5M`.
+pub fn format_count(n: u64) -> String {
+    let short = |n: u64, unit: u64, suffix: &str| {
+        if n < 10 * unit {
+            format!("{:.1}{}", n as f64 / unit as f64, suffix).replace(".0", "")
+        } else {
+            format!("{}{}", n / unit, suffix)
+        }
+    };
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => short(n, 1_000, "k"),
+        _ => short(n, 1_000_000, "M"),
+    }
+}
+
+/// Days since the unix epoch of a `YYYY-MM-DD` date (Howard Hinnant's `days_from_civil`).
+fn days_from_date(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    u64::try_from(era * 146_097 + doe - 719_468).ok()
+}
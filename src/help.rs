@@ -0,0 +1,439 @@
+// Contents of the Help modal. The keys of the lists come from the keymap, so the listing
+// matches the preset in use and never drifts from the bindings; the dialogs match on fixed
+// keys, which are listed as they are.
+use crate::keymap::{Action, Keymap};
+use crate::symbols::Symbols;
+
+/// A titled group of help entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HelpSection {
+    pub title: &'static str,
+    pub entries: Vec<HelpEntry>,
+}
+
+/// Keys (`j / Down`) and what they do.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HelpEntry {
+    pub keys: String,
+    pub text: String,
+}
+
+/// One line of the Help modal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HelpLine {
+    Title(&'static str),
+    Entry(HelpEntry),
+    Blank,
+}
+
+/// Actions sharing a line of the help, their keys joined with ` / ` in this order, and what
+/// they do.
+type ActionLine = (&'static [Action], &'static str);
+
+/// The actions of the lists by section.
+const LIST_ACTIONS: &[(&str, &[ActionLine])] = {
+    use Action::*;
+    &[
+        (
+            "Navigation",
+            &[
+                (&[MoveDown], "move down"),
+                (&[MoveUp], "move up"),
+                (&[PageUp, PageDown], "page up / page down"),
+                (&[Top, Bottom], "jump to the first / last entry"),
+                (
+                    &[
+                        ShowTab(0),
+                        ShowTab(1),
+                        ShowTab(2),
+                        ShowTab(3),
+                        ShowTab(4),
+                        ShowTab(5),
+                    ],
+                    "show a tab: Formulae, Casks, Outdated, Services, Taps, Logs",
+                ),
+                (&[PrevTab, NextTab], "previous / next tab"),
+                (
+                    &[SwitchFocus],
+                    "switch focus between Installed and Available (Formulae tab)",
+                ),
+                (
+                    &[Zoom],
+                    "zoom the focused list full screen; Tab moves on to Details (where up / \
+                     down pick a dependency and Enter jumps to it) and Logs",
+                ),
+                (
+                    &[Cancel],
+                    "leave the zoom, clear the marks, cancel prompts and dialogs",
+                ),
+            ],
+        ),
+        (
+            "Packages",
+            &[
+                (
+                    &[Open],
+                    "uninstall (Installed) / install (Available) the selected package; on the \
+                     Outdated tab open it in the Outdated modal",
+                ),
+                (
+                    &[Menu],
+                    "menu of the actions for the selected package (also a right click)",
+                ),
+                (
+                    &[BrewPage],
+                    "open the package's formulae.brew.sh page (analytics, bottles, dependents)",
+                ),
+                (&[InstallPrompt], "install (opens input prompt)"),
+                (&[Search], "search (opens input prompt)"),
+                (
+                    &[Filter],
+                    "focus Available and prefill search with the current filter",
+                ),
+                (&[ClearFilter], "clear the Available filter"),
+                (
+                    &[SearchNext, SearchPrev],
+                    "next / previous match in the filtered Available list (in the Logs tab: \
+                     in the log)",
+                ),
+                (
+                    &[SortInstalled, ReverseSort],
+                    "sort Installed by the next column / reverse the order",
+                ),
+                (
+                    &[CycleSort],
+                    "sort both lists by name, recently installed, size or updates first",
+                ),
+                (
+                    &[Toggle],
+                    "mark an Installed package (r, u and p then act on all marked ones) / \
+                     expand the versions (name@x.y) of an Available formula",
+                ),
+                (
+                    &[ExportBrewfile],
+                    "export the marked (else all installed) packages to ./Brewfile",
+                ),
+                (
+                    &[Uninstall],
+                    "uninstall the selected installed package (confirm)",
+                ),
+                (
+                    &[Upgrade],
+                    "upgrade the selected installed package (confirm)",
+                ),
+                (
+                    &[Postinstall],
+                    "re-run the post-install step of the selected package (confirm)",
+                ),
+                (
+                    &[Link, LinkOverwrite],
+                    "link the selected keg / link --overwrite (confirm)",
+                ),
+                (&[Unlink], "unlink the selected keg (confirm)"),
+                (&[TogglePin], "pin / unpin the selected formula (confirm)"),
+                (
+                    &[ToggleFavorite],
+                    "star / unstar the selected package (listed first in Available)",
+                ),
+                (
+                    &[EditNote],
+                    "note on the selected package, shown in Details",
+                ),
+                (
+                    &[FormulaLog],
+                    "tap history of the selected formula (brew log)",
+                ),
+                (
+                    &[Versions],
+                    "installed versions: switch the linked one, remove old ones",
+                ),
+                (
+                    &[Livecheck, LivecheckLeaves],
+                    "brew livecheck: upstream version of the selected formula / all leaves",
+                ),
+            ],
+        ),
+        (
+            "Homebrew",
+            &[
+                (&[Outdated], "open the Outdated packages modal"),
+                (
+                    &[RefreshOutdated],
+                    "refresh the outdated check (background)",
+                ),
+                (
+                    &[UpdateBrew],
+                    "brew update, then re-check outdated (confirm)",
+                ),
+                (&[History], "operation history (Enter opens a past log)"),
+                (
+                    &[Snapshot, SnapshotDiff],
+                    "snapshot the installed packages / diff against it, reinstall removed ones",
+                ),
+                (
+                    &[ReopenOperation],
+                    "re-open the running / last operation's log",
+                ),
+                (&[Cleanup], "brew cleanup (previews what would be removed)"),
+                (&[Doctor], "brew doctor findings as a checklist"),
+                (
+                    &[Environment],
+                    "Homebrew environment (brew config / brew --env)",
+                ),
+                (
+                    &[DownloadCache],
+                    "download cache size, purge all or old files",
+                ),
+                (
+                    &[DiskUsage],
+                    "disk usage: installed packages by size (r uninstalls)",
+                ),
+                (
+                    &[Maintenance],
+                    "maintenance: update, upgrade, autoremove, cleanup in a row",
+                ),
+            ],
+        ),
+        (
+            "Logs",
+            &[
+                (
+                    &[LogLevel],
+                    "show all log lines / only warnings and errors / only errors",
+                ),
+                (
+                    &[LogOperation],
+                    "show the lines of every operation / the newest one / the one before...",
+                ),
+                (&[ClearLogs], "clear the log"),
+                (
+                    &[Search, Filter],
+                    "in the Logs tab: find text in the lines shown; Enter jumps to the older \
+                     match, clear filter ends the search",
+                ),
+                (
+                    &[WrapLogs],
+                    "wrap long lines in the Logs tab / cut them off",
+                ),
+                (
+                    &[Toggle],
+                    "in the Logs tab: start a range of lines at the selection / drop it",
+                ),
+                (
+                    &[FormulaLog],
+                    "in the Logs tab: copy the range, or the selected line, to the clipboard",
+                ),
+            ],
+        ),
+        (
+            "Layout",
+            &[
+                (
+                    &[GrowPane, ShrinkPane],
+                    "grow / shrink the focused list pane",
+                ),
+                (
+                    &[ShrinkBottom, GrowBottom],
+                    "shrink / grow the bottom Logs panel",
+                ),
+                (&[CycleTheme], "cycle the color theme"),
+                (&[ReloadConfig], "reload config.toml (also on SIGHUP)"),
+                (&[Help], "this help"),
+                (&[Quit], "quit"),
+            ],
+        ),
+    ]
+};
+
+/// The dialogs' own keys.
+const DIALOG_KEYS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Outdated modal",
+        &[
+            ("Space", "toggle the package selection"),
+            ("Enter", "confirm the selected upgrades (bulk)"),
+        ],
+    ),
+    (
+        "Actions menu",
+        &[(
+            "Enter",
+            "run the highlighted action; Esc in the dialog it opens comes back here",
+        )],
+    ),
+    (
+        "Dependencies / Used by",
+        &[("Enter", "jump to the selected package in the lists")],
+    ),
+    (
+        "Cleanup modal",
+        &[
+            ("a", "toggle --prune=all (also remove current downloads)"),
+            ("y / Enter", "run the cleanup, n / Esc: close"),
+        ],
+    ),
+    (
+        "Doctor modal",
+        &[
+            ("Space", "tick the selected finding off"),
+            ("c", "copy the selected finding to the clipboard"),
+        ],
+    ),
+    (
+        "Environment panel",
+        &[("a", "turn Homebrew analytics on / off (confirm)")],
+    ),
+    (
+        "Maintenance modal",
+        &[
+            (
+                "Space",
+                "include / leave out the package in the upgrade step",
+            ),
+            ("y / Enter", "run the steps, n / Esc: cancel"),
+        ],
+    ),
+    (
+        "Download cache modal",
+        &[
+            (
+                "a / o",
+                "purge everything / files older than the shown age (confirm)",
+            ),
+            ("+ / -", "change the age in days"),
+        ],
+    ),
+    (
+        "Formula history modal",
+        &[("c", "copy the selected commit (hash and subject)")],
+    ),
+    (
+        "Versions modal",
+        &[
+            ("Enter / s", "link the selected version instead (confirm)"),
+            ("d", "delete the selected version from the Cellar (confirm)"),
+            (
+                "o",
+                "install an older version: name@x.y formulae or brew extract",
+            ),
+        ],
+    ),
+    (
+        "History modal",
+        &[
+            ("Enter", "view the operation's captured log"),
+            ("s", "save the captured log to a timestamped file"),
+            (
+                "l / g",
+                "failed install: view / upload (brew gist-logs) its build logs",
+            ),
+        ],
+    ),
+    (
+        "Confirm dialogs",
+        &[
+            ("y / Enter", "confirm the action"),
+            ("n / Esc", "cancel"),
+            ("c", "copy the command line to the clipboard"),
+            (
+                "o",
+                "pick flags such as --verbose, --debug or --force for the command",
+            ),
+            (
+                "e",
+                "set environment variables (KEY=VALUE ...) for this operation alone",
+            ),
+        ],
+    ),
+    (
+        "Operation modal",
+        &[
+            ("Home / End", "jump to the top / bottom (most recent)"),
+            (
+                "s",
+                "save the whole log to a timestamped file in the working directory",
+            ),
+            (
+                "Esc / ?",
+                "close (a running operation continues in the background)",
+            ),
+        ],
+    ),
+];
+
+/// Every section of the help: the lists' keys as `keymap` binds them (actions without keys are
+/// left out), then the dialogs'.
+pub fn sections(keymap: &Keymap, symbols: Symbols) -> Vec<HelpSection> {
+    let mut out: Vec<HelpSection> = LIST_ACTIONS
+        .iter()
+        .map(|(title, actions)| HelpSection {
+            title,
+            entries: actions
+                .iter()
+                .filter_map(|(actions, text)| {
+                    let keys: Vec<String> =
+                        actions.iter().flat_map(|&a| keymap.keys_for(a)).collect();
+                    (!keys.is_empty()).then(|| HelpEntry {
+                        keys: keys.join(" / "),
+                        text: text.to_string(),
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+    out.push(HelpSection {
+        title: "Dialogs",
+        entries: vec![
+            HelpEntry {
+                keys: format!("{} / {}", symbols.up, symbols.down),
+                text: "move, also with the preset's navigation keys".into(),
+            },
+            HelpEntry {
+                keys: "Esc".into(),
+                text: "close, back to the dialog it was opened from".into(),
+            },
+        ],
+    });
+    out.extend(DIALOG_KEYS.iter().map(|(title, keys)| {
+        HelpSection {
+            title,
+            entries: keys
+                .iter()
+                .map(|(keys, text)| HelpEntry {
+                    keys: keys.to_string(),
+                    text: text.to_string(),
+                })
+                .collect(),
+        }
+    }));
+    out
+}
+
+/// The lines of `sections` matching `query` (ignoring case): a section whose title matches is
+/// shown whole, otherwise only its matching entries under the title; sections are separated by
+/// a blank line.
+pub fn lines(sections: &[HelpSection], query: &str) -> Vec<HelpLine> {
+    let query = query.to_lowercase();
+    let matches = |s: &str| s.to_lowercase().contains(&query);
+    let mut out = vec![];
+    for section in sections {
+        let entries: Vec<&HelpEntry> = if matches(section.title) {
+            section.entries.iter().collect()
+        } else {
+            section
+                .entries
+                .iter()
+                .filter(|e| matches(&e.keys) || matches(&e.text))
+                .collect()
+        };
+        if entries.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push(HelpLine::Blank);
+        }
+        out.push(HelpLine::Title(section.title));
+        out.extend(entries.into_iter().cloned().map(HelpLine::Entry));
+    }
+    out
+}
@@ -0,0 +1,406 @@
+// Operation history: every brew/installer operation started from the TUI is recorded here
+// together with its captured output, and persisted to the state directory between runs.
+use crate::output::LogLine;
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Maximum number of operations kept (oldest are dropped first).
+const MAX_RECORDS: usize = 200;
+/// Maximum number of captured log lines kept per operation.
+const MAX_LOG_LINES: usize = 2000;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OperationRecord {
+    pub command: String,
+    /// Start time as seconds since the unix epoch.
+    pub started_at: u64,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub logs: Vec<LogLine>,
+}
+
+impl OperationRecord {
+    /// `Some(true)` for a zero exit code, `Some(false)` for any other finished operation and
+    /// `None` while the operation is still running.
+    pub fn success(&self) -> Option<bool> {
+        self.duration_ms?;
+        Some(self.exit_code == Some(0))
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_ms.map(Duration::from_millis)
+    }
+
+    /// The packages of a failed `brew install`, `upgrade`, `reinstall` or `postinstall`, read
+    /// from its command line; empty for anything else. Their build logs explain the failure.
+    pub fn failed_packages(&self) -> Vec<String> {
+        if self.success() != Some(false) {
+            return vec![];
+        }
+        match self.subcommand() {
+            Some("install" | "upgrade" | "reinstall" | "postinstall") => self.packages(),
+            _ => vec![],
+        }
+    }
+
+    /// The brew command run (`install` for `brew install wget`); `None` for anything but brew.
+    pub fn subcommand(&self) -> Option<&str> {
+        subcommand(&self.command)
+    }
+
+    /// The arguments after the brew command, leaving out flags: the packages it was run on.
+    pub fn packages(&self) -> Vec<String> {
+        packages(&self.command)
+    }
+
+    /// Whether `query` is part of the command line, ignoring case: `postgres` finds
+    /// `brew upgrade postgresql@14`.
+    pub fn mentions(&self, query: &str) -> bool {
+        self.command.to_lowercase().contains(&query.to_lowercase())
+    }
+
+    /// One line for `homebrew-tui history`: when it started (UTC), how it ended, how long it
+    /// took and the command, e.g. `2026-10-14 09:30  ok            12.3s  brew upgrade jq`.
+    pub fn summary(&self) -> String {
+        let outcome = match (self.success(), self.exit_code) {
+            (Some(true), _) => "ok".to_string(),
+            (Some(false), Some(code)) => format!("failed (exit {})", code),
+            (Some(false), None) => "failed".to_string(),
+            // running, or the app went away before it finished
+            (None, _) => "unfinished".to_string(),
+        };
+        let duration = self.duration().map(format_duration).unwrap_or_default();
+        format!(
+            "{}  {:<16} {:>8}  {}",
+            format_time(self.started_at),
+            outcome,
+            duration,
+            self.command
+        )
+    }
+}
+
+/// The brew command of the command line `command`, see `OperationRecord::subcommand`.
+pub fn subcommand(command: &str) -> Option<&str> {
+    let mut words = brew_words(command);
+    if words.next() != Some("brew") {
+        return None;
+    }
+    words.find(|w| !w.starts_with('-'))
+}
+
+/// The packages of the command line `command`, see `OperationRecord::packages`.
+pub fn packages(command: &str) -> Vec<String> {
+    if subcommand(command).is_none() {
+        return vec![];
+    }
+    brew_words(command)
+        .skip(1)
+        .filter(|w| !w.starts_with('-'))
+        .skip(1)
+        .map(String::from)
+        .collect()
+}
+
+/// The words of `command` from `brew` on, past the variables set for it (`HOMEBREW_X=…`).
+fn brew_words(command: &str) -> impl Iterator<Item = &str> {
+    command.split_whitespace().skip_while(|w| {
+        w.split_once('=').is_some_and(|(key, _)| {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+    })
+}
+
+pub struct History {
+    pub records: Vec<OperationRecord>,
+    path: Option<PathBuf>,
+    current: Option<(usize, SystemTime)>,
+}
+
+impl History {
+    /// History that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            records: vec![],
+            path: None,
+            current: None,
+        }
+    }
+
+    /// Load persisted history from the state directory. A missing or unreadable file yields
+    /// an empty history that will still be saved to the same location.
+    pub fn load() -> Self {
+        Self::load_from(paths::state_dir().map(|d| d.join("history.json")))
+    }
+
+    /// Load history from `path` (nothing for `None`, which is then never saved).
+    pub fn load_from(path: Option<PathBuf>) -> Self {
+        let records = path.as_deref().map(read_records).unwrap_or_default();
+        Self {
+            records,
+            path,
+            current: None,
+        }
+    }
+
+    /// Those of `names` whose last install, upgrade, reinstall or postinstall failed. A failed
+    /// command on several packages does not say which of them broke, so only one that ran on
+    /// the package alone counts as its failure.
+    pub fn last_failed(&self, names: &[String]) -> Vec<String> {
+        names
+            .iter()
+            .filter(|name| {
+                self.records
+                    .iter()
+                    .rev()
+                    .find(|r| {
+                        let packages = r.packages();
+                        matches!(
+                            r.subcommand(),
+                            Some("install" | "upgrade" | "reinstall" | "postinstall")
+                        ) && packages.contains(name)
+                            && (r.success() == Some(true) || packages.len() == 1)
+                    })
+                    .is_some_and(|r| r.success() == Some(false))
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn start(&mut self, command: &str) {
+        let now = SystemTime::now();
+        let started_at = now
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.records.push(OperationRecord {
+            command: command.to_string(),
+            started_at,
+            duration_ms: None,
+            exit_code: None,
+            logs: vec![],
+        });
+        if self.records.len() > MAX_RECORDS {
+            let excess = self.records.len() - MAX_RECORDS;
+            self.records.drain(0..excess);
+        }
+        self.current = Some((self.records.len() - 1, now));
+    }
+
+    pub fn append_log(&mut self, line: &LogLine) {
+        if let Some(rec) = self.current_mut() {
+            rec.logs.push(line.clone());
+            if rec.logs.len() > MAX_LOG_LINES {
+                rec.logs.drain(0..500);
+            }
+        }
+    }
+
+    pub fn set_exit_code(&mut self, code: Option<i32>) {
+        if let Some(rec) = self.current_mut() {
+            rec.exit_code = code;
+        }
+    }
+
+    /// Mark the running operation as finished and persist the history.
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some((idx, started)) = self.current.take() {
+            if let Some(rec) = self.records.get_mut(idx) {
+                let elapsed = started.elapsed().unwrap_or_default();
+                rec.duration_ms = Some(elapsed.as_millis() as u64);
+            }
+        }
+        self.save()
+    }
+
+    /// Write the history back to its file. Other processes (a second TUI, a scheduled
+    /// `homebrew-tui upgrade`) may have recorded operations since it was loaded, so the file is
+    /// read again and merged first: a record in both (same start and command) is taken from
+    /// here unless only the file's copy has finished. The merged list replaces `records`, and is
+    /// written to a temporary file that then replaces the old one, so a crash cannot leave it
+    /// truncated.
+    pub fn save(&mut self) -> Result<()> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let current = self
+            .current
+            .and_then(|(idx, started)| Some((record_key(self.records.get(idx)?), started)));
+        self.merge(read_records(&path));
+        self.current = current.and_then(|(key, started)| {
+            let idx = self.records.iter().position(|r| record_key(r) == key)?;
+            Some((idx, started))
+        });
+
+        let s = serde_json::to_string(&self.records)?;
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, s).with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| {
+            let _ = fs::remove_file(&tmp);
+            format!("failed to write {}", path.display())
+        })?;
+        Ok(())
+    }
+
+    /// Add the records of `saved` this history does not have, in the order they started,
+    /// keeping the newest `MAX_RECORDS`; see `save`.
+    fn merge(&mut self, saved: Vec<OperationRecord>) {
+        let mut merged = saved;
+        for ours in self.records.drain(..) {
+            let key = record_key(&ours);
+            match merged.iter_mut().find(|r| record_key(r) == key) {
+                Some(theirs) if ours.duration_ms.is_some() || theirs.duration_ms.is_none() => {
+                    *theirs = ours;
+                }
+                Some(_) => {}
+                None => merged.push(ours),
+            }
+        }
+        // stable, so operations started in the same second keep their order
+        merged.sort_by_key(|r| r.started_at);
+        if merged.len() > MAX_RECORDS {
+            let excess = merged.len() - MAX_RECORDS;
+            merged.drain(0..excess);
+        }
+        self.records = merged;
+    }
+
+    fn current_mut(&mut self) -> Option<&mut OperationRecord> {
+        let (idx, _) = self.current?;
+        self.records.get_mut(idx)
+    }
+}
+
+/// The records saved in `path`; none when it is missing or unreadable.
+fn read_records(path: &Path) -> Vec<OperationRecord> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// What identifies a record across processes: when it started and what it ran.
+fn record_key(rec: &OperationRecord) -> (u64, String) {
+    (rec.started_at, rec.command.clone())
+}
+
+/// Write `command` and its captured `logs` to a new file in `dir`, named after the command and
+/// `stamp` (seconds since the unix epoch, shown in UTC), e.g.
+/// `homebrew-tui-install-wget-20261014-093012.log`. Returns the path of the file.
+pub fn export_log(command: &str, logs: &[LogLine], dir: &Path, stamp: u64) -> Result<PathBuf> {
+    let words: Vec<&str> = command
+        .split_whitespace()
+        .skip_while(|w| *w == "brew")
+        .collect();
+    let mut slug: String = words
+        .join("-")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .take(40)
+        .collect();
+    slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() {
+        slug = "operation".into();
+    }
+    let path = dir.join(format!("homebrew-tui-{}-{}.log", slug, file_stamp(stamp)));
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .with_context(|| format!("creating {}", path.display()))?;
+    let mut out = format!("$ {}\n", command);
+    for line in logs {
+        out.push_str(&line.text);
+        out.push('\n');
+    }
+    file.write_all(out.as_bytes())
+        .with_context(|| format!("writing {}", path.display()))?;
+    Ok(path)
+}
+
+/// A unix timestamp as `YYYYMMDD-HHMMSS` in UTC.
+pub fn file_stamp(unix_secs: u64) -> String {
+    let (year, month, day, secs) = civil(unix_secs);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// A unix timestamp as `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_time(unix_secs: u64) -> String {
+    let (year, month, day, secs) = civil(unix_secs);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60
+    )
+}
+
+/// The UTC date of a unix timestamp and the seconds into that day.
+fn civil(unix_secs: u64) -> (i64, i64, i64, u64) {
+    let days = (unix_secs / 86_400) as i64;
+    let secs = unix_secs % 86_400;
+    // days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day, secs)
+}
+
+/// Short human-readable duration such as "850ms", "12.3s" or "4m05s".
+pub fn format_duration(d: Duration) -> String {
+    let ms = d.as_millis();
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        let secs = d.as_secs();
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// Relative age of a unix timestamp, e.g. "42s ago" or "3h ago".
+pub fn format_age(unix_secs: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let secs = now.saturating_sub(unix_secs);
+    if secs < 60 {
+        format!("{}s ago", secs)
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86_400 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / 86_400)
+    }
+}
@@ -0,0 +1,168 @@
+// User hooks: shell commands from `[hooks]` run after an operation finished, once per package
+// it was run on. The hook learns what happened from the environment:
+//
+//   HOMEBREW_TUI_HOOK       post_install, post_upgrade or on_failure
+//   HOMEBREW_TUI_PACKAGE    the package (empty when the command named none, e.g. `brew update`)
+//   HOMEBREW_TUI_STATUS     success or failure
+//   HOMEBREW_TUI_EXIT_CODE  brew's exit code (empty when it was stopped)
+//   HOMEBREW_TUI_COMMAND    the whole command line
+//
+// A hook that runs longer than `[hooks] timeout` is killed.
+use crate::config::HooksConfig;
+use crate::history::OperationRecord;
+use anyhow::{anyhow, Context, Result};
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a hook may run before it is killed, unless `timeout` under `[hooks]` says
+/// otherwise.
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(300);
+/// How long a failed hook's stderr is waited for once it exited: a process it left running in
+/// the background may keep the pipe open indefinitely.
+const STDERR_GRACE: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hook {
+    PostInstall,
+    PostUpgrade,
+    OnFailure,
+}
+
+impl Hook {
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PostInstall => "post_install",
+            Hook::PostUpgrade => "post_upgrade",
+            Hook::OnFailure => "on_failure",
+        }
+    }
+
+    fn command(self, config: &HooksConfig) -> Option<&str> {
+        match self {
+            Hook::PostInstall => config.post_install.as_deref(),
+            Hook::PostUpgrade => config.post_upgrade.as_deref(),
+            Hook::OnFailure => config.on_failure.as_deref(),
+        }
+    }
+}
+
+/// One hook to run: which, its shell command and the package it is run for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookRun {
+    pub hook: Hook,
+    pub script: String,
+    pub package: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub command: String,
+    pub timeout: Duration,
+}
+
+/// The configured hooks due after the finished operation `record`, one per package.
+pub fn due(config: &HooksConfig, record: &OperationRecord) -> Vec<HookRun> {
+    let Some(success) = record.success() else {
+        return vec![];
+    };
+    let hook = match (success, record.subcommand()) {
+        (false, _) => Hook::OnFailure,
+        (true, Some("install")) => Hook::PostInstall,
+        (true, Some("upgrade")) => Hook::PostUpgrade,
+        _ => return vec![],
+    };
+    let Some(script) = hook.command(config).filter(|s| !s.trim().is_empty()) else {
+        return vec![];
+    };
+    let mut packages = record.packages();
+    if packages.is_empty() {
+        packages.push(String::new());
+    }
+    packages
+        .into_iter()
+        .map(|package| HookRun {
+            hook,
+            script: script.to_string(),
+            package,
+            success,
+            exit_code: record.exit_code,
+            command: record.command.clone(),
+            timeout: config.timeout.map_or(HOOK_TIMEOUT, Duration::from_secs),
+        })
+        .collect()
+}
+
+impl HookRun {
+    /// Run the hook and wait for it, killing it after its timeout; stdin and stdout are not
+    /// connected, a failing hook's stderr is returned in the error.
+    pub fn run(&self) -> Result<()> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.script)
+            .env("HOMEBREW_TUI_HOOK", self.hook.name())
+            .env("HOMEBREW_TUI_PACKAGE", &self.package)
+            .env(
+                "HOMEBREW_TUI_STATUS",
+                if self.success { "success" } else { "failure" },
+            )
+            .env(
+                "HOMEBREW_TUI_EXIT_CODE",
+                self.exit_code.map(|c| c.to_string()).unwrap_or_default(),
+            )
+            .env("HOMEBREW_TUI_COMMAND", &self.command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("could not run the {} hook", self.hook.name()))?;
+        // collected as it arrives, so a failure reports what the hook wrote without waiting
+        // for the pipe to close
+        let stderr = Arc::new(Mutex::new(Vec::new()));
+        let reader = child.stderr.take().map(|mut pipe| {
+            let stderr = Arc::clone(&stderr);
+            thread::spawn(move || {
+                let mut chunk = [0u8; 4096];
+                while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+                    if let Ok(mut buf) = stderr.lock() {
+                        buf.extend_from_slice(&chunk[..n]);
+                    }
+                }
+            })
+        });
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            match child.try_wait()? {
+                Some(status) => break status,
+                None if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    // what the hook started may still hold stderr open, so it is not waited for
+                    return Err(anyhow!(
+                        "{} hook timed out after {}s and was killed",
+                        self.hook.name(),
+                        self.timeout.as_secs()
+                    ));
+                }
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        if !status.success() {
+            let grace = Instant::now() + STDERR_GRACE;
+            while reader.as_ref().is_some_and(|h| !h.is_finished()) && Instant::now() < grace {
+                thread::sleep(Duration::from_millis(10));
+            }
+            let stderr = stderr
+                .lock()
+                .map(|buf| String::from_utf8_lossy(&buf).into_owned())
+                .unwrap_or_default();
+            return Err(anyhow!(
+                "{} hook failed ({}): {}",
+                self.hook.name(),
+                status,
+                stderr.trim()
+            ));
+        }
+        Ok(())
+    }
+}
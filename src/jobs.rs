@@ -0,0 +1,198 @@
+// Background jobs on a shared tokio runtime. Work that shells out to brew is limited per lane
+// instead of getting one thread per request: queries (lists, outdated checks, search, info)
+// share `QUERY_WORKERS` slots and operations run one at a time, so repeated refreshes and fast
+// scrolling queue up instead of starting dozens of brew processes at once. Every job gets a
+// `CancelToken`; cancelled jobs that are still queued are dropped without running, and running
+// ones can check (or await) the token to stop early.
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::runtime::Runtime;
+use tokio::sync::{watch, Notify, Semaphore};
+
+/// Concurrent query jobs.
+pub const QUERY_WORKERS: usize = 3;
+
+/// The process-wide runtime background work runs on. It is never shut down, so it can be
+/// used from any thread (including from jobs themselves) without ordering concerns on exit.
+pub fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("homebrew-tui-jobs")
+            .enable_all()
+            .build()
+            .expect("failed to start the background runtime")
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    /// Package lists, outdated checks and searches.
+    Refresh,
+    /// `brew info` for the Details pane.
+    Info,
+    /// Commands that change packages; never run concurrently with each other.
+    Operation,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<CancelState>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn same(&self, other: &CancelToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+type Pending = Arc<Mutex<Vec<(JobKind, CancelToken)>>>;
+
+/// A group of jobs sharing a number of slots. Jobs take a ticket when submitted and only queue
+/// for a slot once every earlier ticket has, so they start in the order they were submitted
+/// even though their tasks may first run on any runtime thread.
+struct Lane {
+    slots: Arc<Semaphore>,
+    next_ticket: AtomicU64,
+    turn: watch::Sender<u64>,
+}
+
+impl Lane {
+    fn new(slots: usize) -> Arc<Self> {
+        Arc::new(Self {
+            slots: Arc::new(Semaphore::new(slots)),
+            next_ticket: AtomicU64::new(0),
+            turn: watch::Sender::new(0),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct JobPool {
+    queries: Arc<Lane>,
+    operations: Arc<Lane>,
+    /// Jobs queued or running, so a kind can be cancelled as a whole.
+    pending: Pending,
+}
+
+impl JobPool {
+    /// A pool running up to `query_workers` queries and one operation at a time.
+    pub fn new(query_workers: usize) -> Self {
+        Self {
+            queries: Lane::new(query_workers.max(1)),
+            operations: Lane::new(1),
+            pending: Arc::default(),
+        }
+    }
+
+    /// Queue blocking `work` (typically a brew query). The returned token cancels it; the job
+    /// sees the same token.
+    pub fn submit<F>(&self, kind: JobKind, work: F) -> CancelToken
+    where
+        F: FnOnce(&CancelToken) + Send + 'static,
+    {
+        self.submit_async(kind, move |token| async move {
+            let _ = tokio::task::spawn_blocking(move || {
+                // a panicking job must not poison anything it shares with others
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| work(&token)));
+            })
+            .await;
+        })
+    }
+
+    /// Queue an async job. It starts once its lane has a free slot, unless it is cancelled
+    /// first; after that the job itself decides how to react to the token.
+    pub fn submit_async<F, Fut>(&self, kind: JobKind, job: F) -> CancelToken
+    where
+        F: FnOnce(CancelToken) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let token = CancelToken::new();
+        if let Ok(mut p) = self.pending.lock() {
+            p.push((kind, token.clone()));
+        }
+        let lane = match kind {
+            JobKind::Operation => Arc::clone(&self.operations),
+            JobKind::Refresh | JobKind::Info => Arc::clone(&self.queries),
+        };
+        let ticket = lane.next_ticket.fetch_add(1, Ordering::SeqCst);
+        let pending = Arc::clone(&self.pending);
+        let job_token = token.clone();
+        runtime().spawn(async move {
+            let mut turn = lane.turn.subscribe();
+            let _ = turn.wait_for(|t| *t == ticket).await;
+            let permit = tokio::select! {
+                biased;
+                _ = job_token.cancelled() => None,
+                permit = Arc::clone(&lane.slots).acquire_owned() => permit.ok(),
+            };
+            lane.turn.send_modify(|t| *t += 1);
+            if permit.is_some() && !job_token.is_cancelled() {
+                job(job_token.clone()).await;
+            }
+            drop(permit);
+            if let Ok(mut p) = pending.lock() {
+                p.retain(|(_, t)| !t.same(&job_token));
+            }
+        });
+        token
+    }
+
+    /// Run `task` on the runtime outside of any lane (timers and other long-lived loops).
+    pub fn spawn<Fut>(&self, task: Fut)
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        runtime().spawn(task);
+    }
+
+    /// Cancel every queued or running job of `kind`.
+    pub fn cancel(&self, kind: JobKind) {
+        if let Ok(p) = self.pending.lock() {
+            for (k, token) in p.iter() {
+                if *k == kind {
+                    token.cancel();
+                }
+            }
+        }
+    }
+
+    /// Number of jobs of `kind` that are queued or running.
+    pub fn pending(&self, kind: JobKind) -> usize {
+        self.pending
+            .lock()
+            .map(|p| p.iter().filter(|(k, _)| *k == kind).count())
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,399 @@
+// Key bindings: raw key events are resolved to `Action`s through a `Keymap` built from one of
+// the presets. Every preset starts from the default bindings and layers its own on top, so the
+// single-letter package commands work the same everywhere.
+pub use crate::config::Preset;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    Cancel,
+    /// Show the tab at this position in the tab bar (0 is Formulae).
+    ShowTab(usize),
+    NextTab,
+    PrevTab,
+    SwitchFocus,
+    Zoom,
+    MoveDown,
+    MoveUp,
+    PageDown,
+    PageUp,
+    Top,
+    Bottom,
+    Open,
+    Uninstall,
+    Upgrade,
+    Postinstall,
+    Link,
+    LinkOverwrite,
+    Unlink,
+    TogglePin,
+    /// Star or unstar the selected package of either list.
+    ToggleFavorite,
+    /// Write or change the note on the selected package.
+    EditNote,
+    Versions,
+    SortInstalled,
+    ReverseSort,
+    /// Sort both lists by the next order: name, recently installed, size, updates first.
+    CycleSort,
+    Livecheck,
+    LivecheckLeaves,
+    /// Space: mark the selected installed package, or expand / collapse the versions of the
+    /// selected formula in Available.
+    Toggle,
+    ExportBrewfile,
+    /// The quick-actions menu of the selected package.
+    Menu,
+    /// Open the selected package's page on formulae.brew.sh.
+    BrewPage,
+    InstallPrompt,
+    Search,
+    Filter,
+    ClearFilter,
+    SearchNext,
+    SearchPrev,
+    Outdated,
+    RefreshOutdated,
+    UpdateBrew,
+    Cleanup,
+    Doctor,
+    Environment,
+    DownloadCache,
+    DiskUsage,
+    FormulaLog,
+    Maintenance,
+    History,
+    /// Save the installed packages and their versions as the snapshot.
+    Snapshot,
+    /// What changed since the snapshot, with the removed packages to reinstall.
+    SnapshotDiff,
+    ReopenOperation,
+    GrowPane,
+    ShrinkPane,
+    GrowBottom,
+    ShrinkBottom,
+    CycleTheme,
+    ReloadConfig,
+    /// Show all log lines, only warnings and errors, or only errors.
+    LogLevel,
+    /// Show the log lines of every operation or of one.
+    LogOperation,
+    ClearLogs,
+    /// Wrap long entries in the Logs tab, or cut them off.
+    WrapLogs,
+}
+
+/// A key together with the modifiers that matter for matching (Ctrl and Alt; Shift is
+/// already reflected in the character).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Chord {
+    pub fn key(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    pub fn char(c: char) -> Self {
+        Self::key(KeyCode::Char(c))
+    }
+
+    pub fn ctrl(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+
+    pub fn alt(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::ALT,
+        }
+    }
+
+    fn from_event(ev: KeyEvent) -> Self {
+        let modifiers = ev.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let code = match ev.code {
+            // some terminals report Ctrl-letters as uppercase with Shift
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            c => c,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Whether the chord can be told apart from text typed into an input prompt.
+    fn is_modified(&self) -> bool {
+        !self.modifiers.is_empty() || !matches!(self.code, KeyCode::Char(_))
+    }
+
+    /// Short label such as `j`, `C-n`, `M-<` or `PgDn` for the help screen.
+    pub fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".into(),
+            KeyCode::Esc => "Esc".into(),
+            KeyCode::Tab => "Tab".into(),
+            KeyCode::Up => "Up".into(),
+            KeyCode::Down => "Down".into(),
+            KeyCode::PageUp => "PgUp".into(),
+            KeyCode::PageDown => "PgDn".into(),
+            KeyCode::Home => "Home".into(),
+            KeyCode::End => "End".into(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            format!("C-{}", key)
+        } else if self.modifiers.contains(KeyModifiers::ALT) {
+            format!("M-{}", key)
+        } else {
+            key
+        }
+    }
+}
+
+pub struct Keymap {
+    pub preset: Preset,
+    bindings: Vec<(Vec<Chord>, Action)>,
+    pending: Vec<Chord>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(Preset::Default)
+    }
+}
+
+impl Keymap {
+    pub fn new(preset: Preset) -> Self {
+        let mut km = Self {
+            preset,
+            bindings: vec![],
+            pending: vec![],
+        };
+        km.add_defaults();
+        match preset {
+            Preset::Default => {}
+            Preset::Vim => km.add_vim(),
+            Preset::Emacs => km.add_emacs(),
+        }
+        km
+    }
+
+    fn bind(&mut self, keys: &[Chord], action: Action) {
+        self.bindings.push((keys.to_vec(), action));
+    }
+
+    fn add_defaults(&mut self) {
+        use Action::*;
+        let c = Chord::char;
+        let k = Chord::key;
+        self.bind(&[c('q')], Quit);
+        self.bind(&[c('?')], Help);
+        self.bind(&[k(KeyCode::Esc)], Cancel);
+        for (i, digit) in ('1'..='6').enumerate() {
+            self.bind(&[c(digit)], ShowTab(i));
+        }
+        self.bind(&[c(']')], NextTab);
+        self.bind(&[c('[')], PrevTab);
+        self.bind(&[k(KeyCode::Tab)], SwitchFocus);
+        self.bind(&[c('e')], Zoom);
+        self.bind(&[k(KeyCode::Down)], MoveDown);
+        self.bind(&[c('j')], MoveDown);
+        self.bind(&[k(KeyCode::Up)], MoveUp);
+        self.bind(&[c('k')], MoveUp);
+        self.bind(&[k(KeyCode::PageDown)], PageDown);
+        self.bind(&[k(KeyCode::PageUp)], PageUp);
+        self.bind(&[k(KeyCode::Home)], Top);
+        self.bind(&[k(KeyCode::End)], Bottom);
+        self.bind(&[k(KeyCode::Enter)], Open);
+        self.bind(&[c('r')], Uninstall);
+        self.bind(&[c('u')], Upgrade);
+        self.bind(&[c('P')], Postinstall);
+        self.bind(&[c('l')], Link);
+        self.bind(&[c('L')], LinkOverwrite);
+        self.bind(&[c('x')], Unlink);
+        self.bind(&[c('p')], TogglePin);
+        self.bind(&[c('*')], ToggleFavorite);
+        self.bind(&[c('A')], EditNote);
+        self.bind(&[c('V')], Versions);
+        self.bind(&[c('z')], SortInstalled);
+        self.bind(&[c('Z')], ReverseSort);
+        self.bind(&[c('a')], CycleSort);
+        self.bind(&[c(' ')], Toggle);
+        self.bind(&[c('B')], ExportBrewfile);
+        self.bind(&[c('m')], Menu);
+        self.bind(&[c('b')], BrewPage);
+        self.bind(&[c('i')], InstallPrompt);
+        self.bind(&[c('s')], Search);
+        self.bind(&[c('f')], Filter);
+        self.bind(&[c('F')], ClearFilter);
+        self.bind(&[c('o')], Outdated);
+        self.bind(&[c('R')], RefreshOutdated);
+        self.bind(&[c('U')], UpdateBrew);
+        self.bind(&[c('C')], Cleanup);
+        self.bind(&[c('D')], Doctor);
+        self.bind(&[c('E')], Environment);
+        self.bind(&[c('K')], DownloadCache);
+        self.bind(&[c('S')], DiskUsage);
+        self.bind(&[c('c')], FormulaLog);
+        self.bind(&[c('w')], Livecheck);
+        self.bind(&[c('W')], LivecheckLeaves);
+        self.bind(&[c('M')], Maintenance);
+        self.bind(&[c('H')], History);
+        self.bind(&[c('Y')], Snapshot);
+        self.bind(&[c('I')], SnapshotDiff);
+        self.bind(&[c('O')], ReopenOperation);
+        self.bind(&[c('+')], GrowPane);
+        self.bind(&[c('-')], ShrinkPane);
+        self.bind(&[c(')')], GrowBottom);
+        self.bind(&[c('(')], ShrinkBottom);
+        self.bind(&[c('T')], CycleTheme);
+        self.bind(&[c('v')], LogLevel);
+        self.bind(&[c('t')], LogOperation);
+        self.bind(&[c('X')], ClearLogs);
+        self.bind(&[Chord::ctrl('w')], WrapLogs);
+        self.bind(&[k(KeyCode::F(5))], ReloadConfig);
+        self.bind(&[Chord::ctrl('l')], ReloadConfig);
+    }
+
+    fn add_vim(&mut self) {
+        use Action::*;
+        let c = Chord::char;
+        self.bind(&[c('g'), c('g')], Top);
+        self.bind(&[c('G')], Bottom);
+        self.bind(&[c('/')], Filter);
+        self.bind(&[c('n')], SearchNext);
+        self.bind(&[c('N')], SearchPrev);
+        self.bind(&[Chord::ctrl('d')], PageDown);
+        self.bind(&[Chord::ctrl('u')], PageUp);
+        self.bind(&[Chord::ctrl('f')], PageDown);
+        self.bind(&[Chord::ctrl('b')], PageUp);
+    }
+
+    fn add_emacs(&mut self) {
+        use Action::*;
+        let ctrl = Chord::ctrl;
+        let alt = Chord::alt;
+        self.bind(&[ctrl('n')], MoveDown);
+        self.bind(&[ctrl('p')], MoveUp);
+        self.bind(&[ctrl('v')], PageDown);
+        self.bind(&[alt('v')], PageUp);
+        self.bind(&[alt('<')], Top);
+        self.bind(&[alt('>')], Bottom);
+        self.bind(&[ctrl('s')], Filter);
+        self.bind(&[ctrl('r')], SearchPrev);
+        self.bind(&[ctrl('g')], Cancel);
+        self.bind(&[ctrl('x'), ctrl('c')], Quit);
+    }
+
+    /// Feed one key event. Returns the bound action once a full sequence has been typed;
+    /// `None` while a multi-key sequence (like vim's `gg`) is still pending or when the key is
+    /// unbound. A key that breaks a pending sequence is resolved on its own.
+    pub fn resolve(&mut self, ev: KeyEvent) -> Option<Action> {
+        let chord = Chord::from_event(ev);
+        self.pending.push(chord);
+        if let Some(action) = self.lookup(&self.pending) {
+            self.pending.clear();
+            return Some(action);
+        }
+        if self.is_prefix(&self.pending) {
+            return None;
+        }
+        let restart = self.pending.len() > 1;
+        self.pending.clear();
+        if restart {
+            return self.resolve(ev);
+        }
+        None
+    }
+
+    /// Resolve a key typed into a text prompt: only modified keys (Ctrl/Alt combinations,
+    /// arrows, Esc, ...) can trigger actions, plain characters are left for the buffer.
+    pub fn resolve_input(&mut self, ev: KeyEvent) -> Option<Action> {
+        self.pending.clear();
+        let chord = Chord::from_event(ev);
+        if !chord.is_modified() {
+            return None;
+        }
+        self.lookup(&[chord])
+    }
+
+    /// True while the first keys of a multi-key sequence have been typed.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Translate a key for one of the modal dialogs, which match on plain key codes: preset
+    /// navigation keys are rewritten to the arrow/paging/Esc keys the dialogs understand, a key
+    /// that only starts a sequence is swallowed, and anything else passes through unchanged.
+    pub fn translate_modal(&mut self, ev: KeyEvent) -> KeyEvent {
+        let code = match self.resolve(ev) {
+            Some(action) => match canonical_key(action) {
+                Some(code) => code,
+                None => return ev,
+            },
+            None if self.is_pending() => KeyCode::Null,
+            None => return ev,
+        };
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// Like `translate_modal` for text prompts: only modified keys are translated.
+    pub fn translate_input(&mut self, ev: KeyEvent) -> KeyEvent {
+        match self.resolve_input(ev).and_then(canonical_key) {
+            Some(code) => KeyEvent::new(code, KeyModifiers::NONE),
+            None => ev,
+        }
+    }
+
+    /// Labels of every key sequence bound to `action`, e.g. `["Down", "j", "C-n"]`.
+    pub fn keys_for(&self, action: Action) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(keys, _)| keys.iter().map(Chord::label).collect::<Vec<_>>().join(" "))
+            .collect()
+    }
+
+    fn lookup(&self, keys: &[Chord]) -> Option<Action> {
+        // later bindings (the preset layer) take precedence over the defaults
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(k, _)| k.as_slice() == keys)
+            .map(|(_, a)| *a)
+    }
+
+    fn is_prefix(&self, keys: &[Chord]) -> bool {
+        self.bindings
+            .iter()
+            .any(|(k, _)| k.len() > keys.len() && k.starts_with(keys))
+    }
+}
+
+/// The plain key the modal dialogs use for a navigation action.
+fn canonical_key(action: Action) -> Option<KeyCode> {
+    match action {
+        Action::MoveDown => Some(KeyCode::Down),
+        Action::MoveUp => Some(KeyCode::Up),
+        Action::PageDown => Some(KeyCode::PageDown),
+        Action::PageUp => Some(KeyCode::PageUp),
+        Action::Top => Some(KeyCode::Home),
+        Action::Bottom => Some(KeyCode::End),
+        Action::Cancel => Some(KeyCode::Esc),
+        Action::Open => Some(KeyCode::Enter),
+        _ => None,
+    }
+}
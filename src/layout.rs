@@ -0,0 +1,139 @@
+// Pane sizes: the Installed | Available | Details column split and the height of the bottom
+// Logs/Status panel. Defaults come from the `[layout]` config section; runtime adjustments
+// are saved to the state directory so they survive restarts, together with the order of the
+// Installed table. Sizes set in `[layout]` still win over saved ones on the next start.
+use crate::config::LayoutConfig;
+use crate::paths;
+use crate::sort::{InstalledSort, ListSort};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MIN_COLUMN: u16 = 10;
+const MIN_BOTTOM: u16 = 3;
+const MAX_BOTTOM: u16 = 30;
+const STEP: u16 = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaneLayout {
+    /// Column widths in percent (Installed, Available, Details); always sums to 100.
+    pub columns: [u16; 3],
+    /// Height of the bottom panel in rows.
+    pub bottom_height: u16,
+    /// Column and direction the Installed table is sorted by.
+    #[serde(default)]
+    pub installed_sort: InstalledSort,
+    /// The order `a` cycles both lists through.
+    #[serde(default)]
+    pub list_sort: ListSort,
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self {
+            columns: [30, 35, 35],
+            bottom_height: 7,
+            installed_sort: InstalledSort::default(),
+            list_sort: ListSort::default(),
+        }
+    }
+}
+
+impl PaneLayout {
+    /// Layout from config values, falling back to the defaults for missing fields.
+    pub fn from_config(cfg: &LayoutConfig) -> Self {
+        let mut l = Self::default();
+        if let Some(cols) = cfg.columns {
+            l.columns = cols;
+        }
+        if let Some(h) = cfg.bottom_height {
+            l.bottom_height = h;
+        }
+        l.normalize();
+        l
+    }
+
+    /// The last saved layout if there is one, otherwise the configured layout.
+    pub fn load(cfg: &LayoutConfig) -> Self {
+        Self::load_from(Self::state_path().as_deref(), cfg)
+    }
+
+    /// The layout saved at `path`, with the sizes `cfg` sets in place of the saved ones.
+    pub fn load_from(path: Option<&Path>, cfg: &LayoutConfig) -> Self {
+        let Some(mut l) = path
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str::<PaneLayout>(&s).ok())
+        else {
+            return Self::from_config(cfg);
+        };
+        if let Some(cols) = cfg.columns {
+            l.columns = cols;
+        }
+        if let Some(h) = cfg.bottom_height {
+            l.bottom_height = h;
+        }
+        l.normalize();
+        l
+    }
+
+    pub fn save(&self) -> Result<()> {
+        match Self::state_path() {
+            Some(path) => self.save_to(&path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn state_path() -> Option<PathBuf> {
+        paths::state_dir().map(|d| d.join("layout.json"))
+    }
+
+    /// Widen column `idx` by one step, taking the space from the widest other column. Only
+    /// the Installed and Available columns take focus, so Details grows as they shrink.
+    pub fn grow(&mut self, idx: usize) {
+        let donor = (0..3)
+            .filter(|&i| i != idx)
+            .max_by_key(|&i| self.columns[i])
+            .unwrap_or(2);
+        let take = STEP.min(self.columns[donor].saturating_sub(MIN_COLUMN));
+        self.columns[donor] -= take;
+        self.columns[idx] += take;
+    }
+
+    /// Narrow column `idx` by one step, giving the space to the Details column (or to the
+    /// Available column when Details itself is shrunk).
+    pub fn shrink(&mut self, idx: usize) {
+        let receiver = if idx == 2 { 1 } else { 2 };
+        let give = STEP.min(self.columns[idx].saturating_sub(MIN_COLUMN));
+        self.columns[idx] -= give;
+        self.columns[receiver] += give;
+    }
+
+    pub fn resize_bottom(&mut self, delta: i16) {
+        let h = self.bottom_height as i16 + delta;
+        self.bottom_height = (h.max(0) as u16).clamp(MIN_BOTTOM, MAX_BOTTOM);
+    }
+
+    /// Clamp values into range and rescale the columns so they add up to 100%.
+    fn normalize(&mut self) {
+        self.bottom_height = self.bottom_height.clamp(MIN_BOTTOM, MAX_BOTTOM);
+        let total: u32 = self.columns.iter().map(|&c| c.max(1) as u32).sum();
+        let mut cols = self.columns.map(|c| (c.max(1) as u32 * 100 / total) as u16);
+        for c in cols.iter_mut() {
+            *c = (*c).max(MIN_COLUMN);
+        }
+        // put any rounding difference into the widest column
+        let sum: i32 = cols.iter().map(|&c| c as i32).sum();
+        let widest = (0..3).max_by_key(|&i| cols[i]).unwrap_or(2);
+        cols[widest] = (cols[widest] as i32 + 100 - sum).max(MIN_COLUMN as i32) as u16;
+        self.columns = cols;
+    }
+}
@@ -1,3 +1,88 @@
+//! Homebrew from Rust: the library behind the `homebrew-tui` terminal UI.
+//!
+//! Without the default `tui` feature only the parts that do not draw anything are built:
+//!
+//! - [`brew::Brew`] runs the `brew` CLI and parses its output into [`brew::FormulaInfo`]
+//!   values ([`brew::parse_formulae`] parses brew's JSON on its own); failures are
+//!   [`brew::BrewError`]s. [`parse`] has the parsers for the rest of brew's output.
+//! - [`backend::BrewBackend`] is the interface to brew, with a scriptable
+//!   [`backend::MockBackend`] for tests.
+//! - [`event::ConfirmAction`] names an operation (install, upgrade, link, ...) and
+//!   [`backend::run_operation`] runs one, reporting its progress and output as
+//!   [`event::AppEvent`]s on a channel.
+//! - [`executor`] spawns commands and streams their stdout and stderr as typed lines.
+//! - [`transcript`] records the commands a `Brew` runs and replays them without brew.
+//!
+//! ```no_run
+//! use homebrew_tui::backend::run_brew_operation;
+//! use homebrew_tui::brew::Brew;
+//! use homebrew_tui::event::{AppEvent, ConfirmAction};
+//! use std::sync::mpsc;
+//!
+//! let brew = Brew::new();
+//! println!("{} outdated", brew.outdated().unwrap().len());
+//!
+//! let (tx, rx) = mpsc::channel();
+//! let code = run_brew_operation(&brew, &ConfirmAction::Upgrade, "jq", &tx);
+//! drop(tx);
+//! for ev in rx {
+//!     if let AppEvent::OpLog(line) = ev {
+//!         println!("{}", line);
+//!     }
+//! }
+//! println!("exit code {:?}", code);
+//! ```
+pub mod api_cache;
+#[cfg(feature = "tui")]
 pub mod app;
+pub mod backend;
+pub mod bottles;
 pub mod brew;
+pub mod brewfile;
+pub mod browser;
+pub mod build_logs;
+pub mod bus;
+pub mod cache;
+pub mod clipboard;
+pub mod coalesce;
+pub mod config;
+pub mod demo;
+pub mod disk;
+pub mod event;
+pub mod executor;
+pub mod favorites;
+pub mod formulae_api;
+pub mod github;
+#[cfg(feature = "tui")]
+pub mod help;
+pub mod history;
+pub mod hooks;
+pub mod jobs;
+#[cfg(feature = "tui")]
+pub mod keymap;
+pub mod layout;
+pub mod lock;
+#[cfg(feature = "tui")]
+pub mod logging;
+pub mod matcher;
+pub mod notes;
+pub mod notify;
+pub mod output;
+pub mod parse;
+pub mod paths;
+pub mod porcelain;
+pub mod progress;
+pub mod queue;
+#[cfg(feature = "tui")]
+pub mod session;
+pub mod signals;
+pub mod snapshot;
+pub mod sort;
+pub mod symbols;
+#[cfg(feature = "tui")]
+pub mod terminal;
+#[cfg(feature = "tui")]
+pub mod theme;
+pub mod transcript;
+#[cfg(feature = "tui")]
 pub mod ui;
@@ -0,0 +1,53 @@
+// Detection of Homebrew's own process locks.
+//
+// brew takes an exclusive `flock` on files in `$(brew --prefix)/var/homebrew/locks` while it
+// updates or installs (e.g. `update`, `wget.formula.lock`). When another brew process outside
+// the TUI holds one of them, our operation would fail with "Another active Homebrew process is
+// already in progress", so operations check here first and wait for the locks to clear.
+use crate::brew::Brew;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Lock directory of the given brew installation, if its prefix can be resolved.
+pub fn lock_dir(brew: &Brew) -> Option<PathBuf> {
+    Some(brew.prefix()?.join("var").join("homebrew").join("locks"))
+}
+
+/// Names of the lock files in `dir` that are currently held by another process.
+pub fn held_locks(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut held: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|x| x == "lock").unwrap_or(false))
+        .filter(|p| is_locked(p))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .collect();
+    held.sort();
+    held
+}
+
+/// Probe a lock file with a non-blocking exclusive flock, releasing it immediately if we got it.
+#[cfg(unix)]
+fn is_locked(path: &Path) -> bool {
+    use std::os::unix::io::AsRawFd;
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let fd = file.as_raw_fd();
+    // SAFETY: fd is a valid descriptor owned by `file` for the duration of these calls.
+    let rc = unsafe { libc::flock(fd, libc::LOCK_EX | libc::LOCK_NB) };
+    if rc == 0 {
+        unsafe { libc::flock(fd, libc::LOCK_UN) };
+        false
+    } else {
+        std::io::Error::last_os_error().raw_os_error() == Some(libc::EWOULDBLOCK)
+    }
+}
+
+#[cfg(not(unix))]
+fn is_locked(_path: &Path) -> bool {
+    false
+}
@@ -0,0 +1,44 @@
+// Persistent log of what the app did: operations, brew invocations and errors are recorded
+// with `tracing` in `log` in the state directory, so a problem can still be looked into once
+// the TUI is gone. `[log] level` sets how much is kept.
+use crate::config::LogLevel;
+use crate::paths;
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// Where the log is written.
+pub fn log_path() -> Option<PathBuf> {
+    paths::state_dir().map(|d| d.join("log"))
+}
+
+/// Start appending to the log at `level`. Lines are written from a background thread until the
+/// returned guard is dropped, so it has to be kept until the program exits. `None` when the
+/// level is `off` or there is no state directory.
+pub fn init(level: LogLevel) -> Result<Option<WorkerGuard>> {
+    let filter = level.filter();
+    if filter == LevelFilter::OFF {
+        return Ok(None);
+    }
+    let Some(dir) = paths::state_dir() else {
+        return Ok(None);
+    };
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let file = RollingFileAppender::builder()
+        .rotation(Rotation::NEVER)
+        .filename_prefix("log")
+        .build(&dir)
+        .with_context(|| format!("failed to open the log in {}", dir.display()))?;
+    let (writer, guard) = tracing_appender::non_blocking(file);
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_target(false)
+        .with_max_level(filter)
+        .try_init()
+        .map_err(|e| anyhow!("failed to set up logging: {}", e))?;
+    Ok(Some(guard))
+}
@@ -1,52 +1,307 @@
-use anyhow::Result;
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
-use crossterm::execute;
-use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
-};
-use homebrew_tui::app::App;
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use homebrew_tui::app::{run_brew_operation, App, AppEvent, ConfirmAction, View};
 use homebrew_tui::brew::Brew;
-use ratatui::backend::CrosstermBackend;
-use ratatui::Terminal;
-use std::env;
-use std::io;
+use homebrew_tui::config::{BrewConfig, ConfigSource};
+use homebrew_tui::history::History;
+use homebrew_tui::output::LogLine;
+use homebrew_tui::progress::PhaseTracker;
+use homebrew_tui::transcript::Transcript;
+use homebrew_tui::{logging, signals, terminal};
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+/// Terminal UI for Homebrew. Without a subcommand the interactive TUI is started.
+#[derive(Parser)]
+#[command(name = "homebrew-tui", version, about)]
+struct Cli {
+    /// Read settings from this file instead of ~/.config/homebrew-tui/config.toml
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Start with this color theme (default, light, solarized, gruvbox, monochrome)
+    #[arg(long, global = true, value_name = "NAME")]
+    theme: Option<String>,
+    /// Path of the brew executable
+    #[arg(long, global = true, value_name = "PATH")]
+    brew_path: Option<PathBuf>,
+    /// Instead of the TUI, read JSON requests from stdin and print events as JSON lines
+    #[arg(long, conflicts_with_all = ["view", "search"])]
+    porcelain: bool,
+    /// Explore the TUI with bundled sample data instead of Homebrew; operations are simulated
+    #[arg(long, conflicts_with = "porcelain")]
+    demo: bool,
+    /// Write every brew command with its output and exit code to this transcript file
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+    /// Answer brew commands from a transcript written by --record instead of running brew
+    #[arg(long, global = true, value_name = "FILE")]
+    replay: Option<PathBuf>,
+    /// Open the TUI in this view
+    #[arg(long, value_enum, value_name = "VIEW")]
+    view: Option<ViewArg>,
+    /// Open the TUI with the Available list filtered by this text
+    #[arg(long, value_name = "QUERY")]
+    search: Option<String>,
+    #[command(subcommand)]
+    command: Option<Cmd>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ViewArg {
+    Installed,
+    Available,
+    Outdated,
+    History,
+    Casks,
+    Services,
+    Taps,
+    Logs,
+}
+
+impl From<ViewArg> for View {
+    fn from(v: ViewArg) -> Self {
+        match v {
+            ViewArg::Installed => View::Installed,
+            ViewArg::Available => View::Available,
+            ViewArg::Outdated => View::Outdated,
+            ViewArg::History => View::History,
+            ViewArg::Casks => View::Casks,
+            ViewArg::Services => View::Services,
+            ViewArg::Taps => View::Taps,
+            ViewArg::Logs => View::Logs,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Cmd {
+    /// Print installed formulae
+    List,
+    /// Print installed formulae with newer versions available
+    Outdated,
+    /// Search formulae and casks
+    Search {
+        /// Text or /regex/ to search for
+        query: String,
+    },
+    /// Upgrade formulae without the TUI, printing plain progress lines (for cron and CI)
+    Upgrade {
+        /// Upgrade every outdated formula
+        #[arg(long, conflicts_with = "packages")]
+        all: bool,
+        /// Do not ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+        /// Leave this formula alone (repeatable)
+        #[arg(long, value_name = "PKG")]
+        exclude: Vec<String>,
+        /// Formulae to upgrade
+        #[arg(required_unless_present = "all")]
+        packages: Vec<String>,
+    },
+    /// Print past operations, oldest first: start time (UTC), outcome, duration and command
+    History {
+        /// Only operations whose command line contains this, e.g. a package name
+        query: Option<String>,
+    },
+}
 
 fn main() -> Result<()> {
-    // Debug helper: if HOMEBREW_TUI_DEBUG is set, print installed formulae and exit.
-    if env::var("HOMEBREW_TUI_DEBUG").is_ok() {
-        let brew = Brew::new();
-        match brew.list_installed() {
-            Ok(list) => {
-                for f in list {
-                    println!("{}", f.name);
-                }
-                return Ok(());
-            }
-            Err(e) => {
-                eprintln!("Error listing installed formulae: {}", e);
-                return Err(e);
+    let cli = Cli::parse();
+    if cli.demo && (cli.record.is_some() || cli.replay.is_some()) {
+        bail!("--demo runs no brew commands to record or replay");
+    }
+    let source = ConfigSource {
+        path: cli.config,
+        theme: cli.theme,
+        brew_path: cli.brew_path,
+    };
+    // a broken config is reported below, by whichever mode runs
+    let level = source.load().map(|c| c.log.level).unwrap_or_default();
+    // flushes the log when dropped, so it goes before any `exit`
+    let log_guard = logging::init(level).unwrap_or_else(|e| {
+        eprintln!("homebrew-tui: not logging to a file: {:#}", e);
+        None
+    });
+
+    if let Some(cmd) = cli.command {
+        let config = source.load()?;
+        let brew = brew(&config.brew, &cli.record, &cli.replay)?;
+        let lines = match cmd {
+            Cmd::List => brew.list_installed()?.into_iter().map(|f| f.name).collect(),
+            Cmd::Outdated => brew.outdated()?,
+            Cmd::Search { query } => brew.search(&query)?,
+            Cmd::History { query } => History::load()
+                .records
+                .iter()
+                .filter(|r| query.as_ref().is_none_or(|q| r.mentions(q)))
+                .map(|r| r.summary())
+                .collect(),
+            Cmd::Upgrade {
+                all,
+                yes,
+                exclude,
+                packages,
+            } => {
+                let code = upgrade(&brew, all, yes, &exclude, packages)?;
+                drop(log_guard);
+                std::process::exit(code)
             }
+        };
+        for l in lines {
+            println!("{}", l);
         }
+        return Ok(());
+    }
+
+    if cli.porcelain {
+        let config = source.load()?;
+        let brew = brew(&config.brew, &cli.record, &cli.replay)?;
+        return homebrew_tui::porcelain::run(brew, io::BufReader::new(io::stdin()), io::stdout());
+    }
+
+    // an explicitly requested config file must be valid; the default one only warns in the UI
+    if source.path.is_some() {
+        source.load()?;
     }
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new()?;
-    let res = app.run(&mut terminal);
+    // setup terminal; it is restored on every way out, including panics and SIGTERM
+    terminal::install_panic_hook();
+    signals::install_reload_handler();
+    signals::install_termination_handler();
+    let mut tui = terminal::init()?;
 
-    // restore terminal
-    disable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, LeaveAlternateScreen, DisableMouseCapture)?;
-    terminal.show_cursor()?;
+    let mut app = if cli.demo {
+        App::demo(source)?
+    } else if cli.record.is_some() || cli.replay.is_some() {
+        let config = source.load().unwrap_or_default();
+        let brew = brew(&config.brew, &cli.record, &cli.replay)?;
+        // a replayed session changes nothing, so it keeps no history (see `persistent`)
+        App::with_backend(source, Arc::new(brew))?
+    } else {
+        App::with_config_source(source)?
+    };
+    if let Some(query) = &cli.search {
+        app.start_search(query);
+    }
+    if let Some(view) = cli.view {
+        app.open_view(view.into());
+    }
+    let res = app.run(&mut tui);
 
+    terminal::restore();
+    if let Err(e) = &res {
+        tracing::error!("application error: {:#}", e);
+    }
+    drop(log_guard);
+    if let Some(sig) = signals::termination_request() {
+        signals::reraise(sig);
+    }
     if let Err(e) = res {
         eprintln!("Application error: {}", e);
         std::process::exit(1);
     }
     Ok(())
 }
+
+/// The configured brew, recording to `record` or replaying `replay` when given.
+fn brew(cfg: &BrewConfig, record: &Option<PathBuf>, replay: &Option<PathBuf>) -> Result<Brew> {
+    let mut brew = Brew::from_config(cfg);
+    if let Some(path) = record {
+        brew.transcript = Some(Arc::new(Transcript::record(path)?));
+    } else if let Some(path) = replay {
+        brew.transcript = Some(Arc::new(Transcript::replay(path)?));
+    }
+    Ok(brew)
+}
+
+/// `homebrew-tui upgrade`: runs the upgrade through the same operation runner as the TUI and
+/// prints its output plus progress lines. Pinned and excluded formulae are skipped, and the run
+/// goes into the operation history like one from the TUI. Returns the process exit code.
+fn upgrade(
+    brew: &Brew,
+    all: bool,
+    yes: bool,
+    exclude: &[String],
+    packages: Vec<String>,
+) -> Result<i32> {
+    let mut targets = if all { brew.outdated()? } else { packages };
+    let pinned = brew.pinned().unwrap_or_default();
+    targets.retain(|p| {
+        let reason = if pinned.contains(p) {
+            "pinned"
+        } else if exclude.contains(p) {
+            "excluded"
+        } else {
+            return true;
+        };
+        println!("skipping {} ({})", p, reason);
+        false
+    });
+    if targets.is_empty() {
+        println!("nothing to upgrade");
+        return Ok(0);
+    }
+    println!("upgrading {}: {}", targets.len(), targets.join(" "));
+    if !yes {
+        if !io::stdin().is_terminal() {
+            bail!("refusing to upgrade without --yes when stdin is not a terminal");
+        }
+        print!("proceed? [y/N] ");
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("aborted");
+            return Ok(1);
+        }
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let name = format!("{} packages", targets.len());
+    let action = ConfirmAction::BulkUpgrade(targets);
+    let worker_brew = brew.clone();
+    let worker = thread::spawn(move || run_brew_operation(&worker_brew, &action, &name, &tx));
+    let mut progress = PhaseTracker::new();
+    let mut history = if brew.is_replay() {
+        History::in_memory()
+    } else {
+        History::load()
+    };
+    // the channel closes once the operation (and its output readers) have finished
+    for ev in rx {
+        match ev {
+            AppEvent::OpStart(title) => {
+                println!("$ {}", title);
+                history.start(&title);
+            }
+            AppEvent::OpLog(line) => {
+                println!("{}", line);
+                history.append_log(&LogLine::stdout(line.clone()));
+                if let Some(pct) = progress.observe(&line) {
+                    println!("[{:>3}%] {}", pct, progress.phase().label());
+                }
+            }
+            AppEvent::OpErr(line) => {
+                eprintln!("{}", line);
+                history.append_log(&LogLine::stderr(line));
+            }
+            AppEvent::OpExit(code) => history.set_exit_code(code),
+            AppEvent::OpEnd(_) => {
+                if let Err(e) = history.finish() {
+                    eprintln!("failed to save history: {:#}", e);
+                }
+            }
+            AppEvent::LockWait(locks) if !locks.is_empty() => eprintln!(
+                "waiting for other brew process to finish (locks: {})",
+                locks.join(", ")
+            ),
+            AppEvent::Status(s) | AppEvent::Log(s) | AppEvent::Error(s) => eprintln!("{}", s),
+            _ => {}
+        }
+    }
+    let code = worker.join().ok().flatten();
+    Ok(code.unwrap_or(1))
+}
@@ -0,0 +1,66 @@
+// Substring matching over the Available list. The index is built once per loaded list: names
+// are lowercased up front and every three-byte window points at the names containing it, so a
+// query only has to check the names sharing all of its trigrams instead of the whole list.
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default)]
+pub struct NameIndex {
+    lowered: Vec<String>,
+    trigrams: HashMap<[u8; 3], Vec<u32>>,
+}
+
+impl NameIndex {
+    pub fn new(names: &[String]) -> Self {
+        let lowered: Vec<String> = names.iter().map(|n| n.to_lowercase()).collect();
+        let mut trigrams: HashMap<[u8; 3], Vec<u32>> = HashMap::new();
+        for (i, name) in lowered.iter().enumerate() {
+            for t in name.as_bytes().windows(3) {
+                let postings = trigrams.entry([t[0], t[1], t[2]]).or_default();
+                // names are visited in order, so each list stays sorted and duplicate-free
+                if postings.last() != Some(&(i as u32)) {
+                    postings.push(i as u32);
+                }
+            }
+        }
+        Self { lowered, trigrams }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lowered.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lowered.is_empty()
+    }
+
+    /// Indices of the names containing `query` (case-insensitive), in list order.
+    pub fn matches(&self, query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        if query.len() < 3 {
+            return self.scan(&query, 0..self.lowered.len());
+        }
+        let mut lists: Vec<&Vec<u32>> = Vec::new();
+        for t in query.as_bytes().windows(3) {
+            match self.trigrams.get(&[t[0], t[1], t[2]]) {
+                Some(list) => lists.push(list),
+                None => return vec![],
+            }
+        }
+        lists.sort_by_key(|l| l.len());
+        let mut candidates: Vec<u32> = lists[0].clone();
+        for list in &lists[1..] {
+            candidates.retain(|i| list.binary_search(i).is_ok());
+            if candidates.is_empty() {
+                return vec![];
+            }
+        }
+        // sharing every trigram does not mean they are adjacent: confirm the substring
+        self.scan(&query, candidates.into_iter().map(|i| i as usize))
+    }
+
+    fn scan(&self, query: &str, candidates: impl Iterator<Item = usize>) -> Vec<usize> {
+        candidates
+            .filter(|&i| self.lowered[i].contains(query))
+            .collect()
+    }
+}
@@ -0,0 +1,63 @@
+// Notes on packages (`A`): free text such as "installed for project X" or "pinned because 2.x
+// breaks the config", kept in the state directory and shown in Details.
+use crate::paths;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Default)]
+pub struct Notes {
+    /// By package name.
+    pub notes: BTreeMap<String, String>,
+    path: Option<PathBuf>,
+}
+
+impl Notes {
+    /// Notes that are never written to disk.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load `notes.json` (an object from package names to notes) from the state directory; a
+    /// missing or unreadable file starts with none.
+    pub fn load() -> Self {
+        Self::load_from(paths::state_dir().map(|d| d.join("notes.json")))
+    }
+
+    pub fn load_from(path: Option<PathBuf>) -> Self {
+        let notes = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { notes, path }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.notes.get(name).map(String::as_str)
+    }
+
+    /// Replace the note on `name`; a blank one removes it.
+    pub fn set(&mut self, name: &str, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.notes.remove(name);
+        } else {
+            self.notes.insert(name.to_string(), note.to_string());
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let s = serde_json::to_string_pretty(&self.notes)?;
+        fs::write(path, s).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
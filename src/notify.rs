@@ -0,0 +1,62 @@
+// Desktop notifications through the platform's notifier: `terminal-notifier` or `osascript` on
+// macOS, `notify-send` elsewhere.
+use anyhow::{anyhow, Result};
+use std::process::{Command, Stdio};
+
+/// The commands to try in order, each with its arguments for `title` and `body`.
+fn commands(title: &str, body: &str) -> Vec<(&'static str, Vec<String>)> {
+    if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification \"{}\" with title \"{}\"",
+            escape(body),
+            escape(title)
+        );
+        vec![
+            (
+                "terminal-notifier",
+                vec![
+                    "-title".into(),
+                    title.into(),
+                    "-message".into(),
+                    body.into(),
+                ],
+            ),
+            ("osascript", vec!["-e".into(), script]),
+        ]
+    } else {
+        vec![("notify-send", vec![title.into(), body.into()])]
+    }
+}
+
+/// `s` inside an AppleScript string literal.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn send(title: &str, body: &str) -> Result<()> {
+    let mut tried = vec![];
+    for (tool, args) in commands(title, body) {
+        let status = Command::new(tool)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        match status {
+            Ok(s) if s.success() => return Ok(()),
+            Ok(s) => tried.push(format!("{} failed ({})", tool, s)),
+            Err(e) => tried.push(format!("could not run {}: {}", tool, e)),
+        }
+    }
+    Err(anyhow!(tried.join(", ")))
+}
+
+/// What the notification for a finished operation says: `command`, and whether it succeeded
+/// by `exit_code` (`None` when it was stopped or never started).
+pub fn finished_message(command: &str, exit_code: Option<i32>) -> String {
+    match exit_code {
+        Some(0) => format!("{} succeeded", command),
+        Some(code) => format!("{} failed (exit code {})", command, code),
+        None => format!("{} did not finish", command),
+    }
+}
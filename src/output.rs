@@ -0,0 +1,222 @@
+// Lines of command output tagged with the stream they were read from, and the entries of the
+// app's log: those lines plus the app's own notes and errors, each with a time, a level and
+// where it came from.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Deref, Range};
+use std::time::SystemTime;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stream {
+    #[default]
+    Stdout,
+    Stderr,
+}
+
+/// How serious a log entry is, for filtering the Logs pane.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Info => "info",
+            Level::Warning => "warnings",
+            Level::Error => "errors",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "LogLineRepr")]
+pub struct LogLine {
+    pub text: String,
+    #[serde(default)]
+    pub stream: Stream,
+}
+
+/// Accepts both the tagged form and plain strings written by older versions of the history file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LogLineRepr {
+    Plain(String),
+    Tagged {
+        text: String,
+        #[serde(default)]
+        stream: Stream,
+    },
+}
+
+impl From<LogLineRepr> for LogLine {
+    fn from(r: LogLineRepr) -> Self {
+        match r {
+            LogLineRepr::Plain(text) => LogLine::stdout(text),
+            LogLineRepr::Tagged { text, stream } => LogLine { text, stream },
+        }
+    }
+}
+
+impl LogLine {
+    pub fn stdout(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            stream: Stream::Stdout,
+        }
+    }
+
+    pub fn stderr(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            stream: Stream::Stderr,
+        }
+    }
+
+    pub fn is_stderr(&self) -> bool {
+        self.stream == Stream::Stderr
+    }
+
+    /// Errors are the lines brew starts with `Error`; the rest of stderr are warnings.
+    pub fn level(&self) -> Level {
+        let text = self.text.trim_start();
+        if text.starts_with("Error") {
+            Level::Error
+        } else if self.is_stderr() || text.starts_with("Warning") {
+            Level::Warning
+        } else {
+            Level::Info
+        }
+    }
+}
+
+impl Deref for LogLine {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl From<String> for LogLine {
+    fn from(text: String) -> Self {
+        LogLine::stdout(text)
+    }
+}
+
+impl From<&str> for LogLine {
+    fn from(text: &str) -> Self {
+        LogLine::stdout(text)
+    }
+}
+
+/// What wrote a log entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// Standard output of a brew or installer command.
+    Stdout,
+    /// Its standard error.
+    Stderr,
+    /// The app itself: status notes and the failures it reports.
+    App,
+}
+
+/// An entry of the app's log (the Logs pane, tab and zoom).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    pub time: SystemTime,
+    pub level: Level,
+    pub source: Source,
+    pub message: String,
+    /// The operation it belongs to: its output, and the app's notes about it.
+    pub op: Option<String>,
+}
+
+impl LogEntry {
+    fn app(level: Level, message: impl Into<String>) -> Self {
+        Self {
+            time: SystemTime::now(),
+            level,
+            source: Source::App,
+            message: message.into(),
+            op: None,
+        }
+    }
+
+    /// A status note of the app's.
+    pub fn note(message: impl Into<String>) -> Self {
+        Self::app(Level::Info, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::app(Level::Warning, message)
+    }
+
+    /// Something the app tried that failed.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::app(Level::Error, message)
+    }
+
+    /// A line of command output, at the level brew's wording gives it.
+    pub fn output(line: LogLine) -> Self {
+        Self {
+            time: SystemTime::now(),
+            level: line.level(),
+            source: match line.stream {
+                Stream::Stdout => Source::Stdout,
+                Stream::Stderr => Source::Stderr,
+            },
+            message: line.text,
+            op: None,
+        }
+    }
+
+    /// The entry filed under operation `op`.
+    pub fn in_op(self, op: Option<String>) -> Self {
+        Self { op, ..self }
+    }
+}
+
+impl Deref for LogEntry {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Byte ranges of the occurrences of `query` in `text`, ignoring ASCII case; none for an empty
+/// query.
+pub fn match_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    let mut out = vec![];
+    if query.is_empty() {
+        return out;
+    }
+    let mut start = 0;
+    while start + query.len() <= text.len() {
+        let hit = text
+            .get(start..start + query.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(query));
+        if hit {
+            out.push(start..start + query.len());
+            start += query.len();
+        } else {
+            start += text[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    out
+}
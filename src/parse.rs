@@ -0,0 +1,286 @@
+// Parsers for what brew prints: the text of `brew outdated`, `brew search`, `brew cleanup
+// --dry-run`, `brew doctor`, ... and the JSON of `brew services` and `brew livecheck`, turned into
+// the types in `brew` (formula JSON goes straight into `FormulaInfo`, see `brew::parse_formulae`).
+// Differences between brew versions are handled here, in one place; `tests/parse.rs` runs the
+// parsers on output in the formats of several versions (`tests/fixtures/brew-output/<version>/`),
+// so a change in brew's format breaks a test before it breaks a pane.
+use crate::brew::{
+    CleanupPreview, DoctorItem, FormulaCommit, InstalledCask, Livecheck, Service, Severity,
+    UpdateSummary,
+};
+use serde::Deserialize;
+
+/// Names from `brew outdated`, which follows each with its versions in the verbose format
+/// (`awscli (2.30.7) < 2.31.2`, `wget (1.21.3) < 1.21.4 [pinned at 1.21.3]`). Section headers
+/// (`==> Formulae`) are skipped.
+pub fn parse_outdated(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|l| !l.starts_with("==> "))
+        .filter_map(|l| l.split_whitespace().next())
+        .map(String::from)
+        .collect()
+}
+
+/// One name per line, as `brew tap` and `brew list --pinned` print them.
+pub fn parse_names(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// Names from `brew search`. Newer brews list formulae and casks under `==> Formulae` and
+/// `==> Casks` headers, and on a terminal mark installed packages with `✔` (`(installed)` with
+/// `HOMEBREW_NO_EMOJI`) and print several names per line; only the names are kept.
+pub fn parse_search(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("==> "))
+        .flat_map(str::split_whitespace)
+        .filter(|w| !matches!(*w, "✔" | "(installed)"))
+        .map(String::from)
+        .collect()
+}
+
+/// Parse `brew services list --json`: the services of installed formulae. Before Homebrew 3.5
+/// the launchd plist was reported as `plist`; no output means no services.
+pub fn parse_services(json: &str) -> serde_json::Result<Vec<Service>> {
+    if json.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    serde_json::from_str(json)
+}
+
+/// Parse the output of `brew cleanup --dry-run`: one `Would remove: <path> (<size>)` line per
+/// file, `Would remove (empty directory): <path>` (or `(broken link)`) for what the prune step
+/// takes, and a closing `This operation would free approximately <size> of disk space.`
+pub fn parse_cleanup_dry_run(output: &str) -> CleanupPreview {
+    let mut preview = CleanupPreview::default();
+    for line in output.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("Would remove: ") {
+            let entry = match rest.rsplit_once(" (") {
+                Some((path, size)) if size.ends_with(')') => (
+                    path.to_string(),
+                    Some(size.trim_end_matches(')').to_string()),
+                ),
+                _ => (rest.to_string(), None),
+            };
+            preview.files.push(entry);
+        } else if let Some((_, path)) = line
+            .strip_prefix("Would remove (")
+            .and_then(|rest| rest.split_once("): "))
+        {
+            preview.files.push((path.to_string(), None));
+        } else if let Some(rest) = line.split("would free approximately ").nth(1) {
+            let size = rest.trim_end_matches(" of disk space.");
+            preview.total = Some(size.to_string());
+        }
+    }
+    preview
+}
+
+/// Split `brew doctor` output into its findings. Each starts with a `Warning:` or `Error:` line
+/// and runs until the next one; the note brew prints before the first finding is dropped.
+pub fn parse_doctor(output: &str) -> Vec<DoctorItem> {
+    let mut items: Vec<DoctorItem> = vec![];
+    for line in output.lines() {
+        let start = [
+            ("Warning: ", Severity::Warning),
+            ("Error: ", Severity::Error),
+        ]
+        .into_iter()
+        .find_map(|(prefix, severity)| line.strip_prefix(prefix).map(|t| (severity, t)));
+        match (start, items.last_mut()) {
+            (Some((severity, title)), _) => items.push(DoctorItem {
+                severity,
+                title: title.trim().to_string(),
+                details: vec![],
+            }),
+            (None, Some(item)) => item.details.push(line.trim_end().to_string()),
+            (None, None) => {}
+        }
+    }
+    for item in &mut items {
+        while item.details.last().is_some_and(|l| l.trim().is_empty()) {
+            item.details.pop();
+        }
+    }
+    items
+}
+
+/// Parse `brew list --cask --versions`: one `name version...` line per cask; the newest
+/// (last) version is kept.
+pub fn parse_cask_versions(output: &str) -> Vec<InstalledCask> {
+    output
+        .lines()
+        .filter_map(|l| {
+            let mut words = l.split_whitespace();
+            let name = words.next()?.to_string();
+            let version = words.last().unwrap_or_default().to_string();
+            Some(InstalledCask { name, version })
+        })
+        .collect()
+}
+
+/// Parse `brew livecheck --json`: an array with a `version` object per checked formula and a
+/// `status` with `messages` for those that were skipped or failed.
+pub fn parse_livecheck(json: &str) -> serde_json::Result<Vec<Livecheck>> {
+    #[derive(Deserialize)]
+    struct RawVersion {
+        current: Option<String>,
+        latest: Option<String>,
+        #[serde(default)]
+        outdated: bool,
+        #[serde(default)]
+        newer_than_upstream: bool,
+    }
+    #[derive(Deserialize)]
+    struct Raw {
+        #[serde(alias = "cask")]
+        formula: String,
+        version: Option<RawVersion>,
+        status: Option<String>,
+        #[serde(default)]
+        messages: Vec<String>,
+    }
+    let raw: Vec<Raw> = serde_json::from_str(json)?;
+    Ok(raw
+        .into_iter()
+        .map(|r| {
+            let version = r.version.unwrap_or(RawVersion {
+                current: None,
+                latest: None,
+                outdated: false,
+                newer_than_upstream: false,
+            });
+            Livecheck {
+                formula: r.formula,
+                current: version.current,
+                latest: version.latest,
+                outdated: version.outdated,
+                newer_than_upstream: version.newer_than_upstream,
+                status: r.status,
+                messages: r.messages,
+            }
+        })
+        .collect())
+}
+
+/// Split `git log` output in its default format, as `brew log` prints it, into commits:
+/// `commit <hash>`, `Author:` and `Date:` headers followed by the indented message.
+pub fn parse_log(output: &str) -> Vec<FormulaCommit> {
+    let mut commits: Vec<FormulaCommit> = vec![];
+    for line in output.lines() {
+        if let Some(hash) = line.strip_prefix("commit ") {
+            commits.push(FormulaCommit {
+                hash: hash
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(commit) = commits.last_mut() else {
+            continue;
+        };
+        if let Some(author) = line.strip_prefix("Author:") {
+            commit.author = author.trim().to_string();
+        } else if let Some(date) = line.strip_prefix("Date:") {
+            commit.date = date.trim().to_string();
+        } else if commit.subject.is_empty() && line.starts_with("    ") {
+            commit.subject = line.trim().to_string();
+        }
+    }
+    commits
+}
+
+/// Parse `key: value` lines as printed by `brew config` and `brew --env`. Lines without a
+/// separator are skipped.
+pub fn parse_key_values(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|l| {
+            let (k, v) = l.split_once(':')?;
+            Some((k.trim().to_string(), v.trim().to_string()))
+        })
+        .filter(|(k, _)| !k.is_empty())
+        .collect()
+}
+
+/// Read the state from `brew analytics state` ("InfluxDB analytics are enabled.", "Analytics
+/// are disabled.", ...).
+pub fn parse_analytics_state(output: &str) -> Option<bool> {
+    let text = output.to_ascii_lowercase();
+    if text.contains("disabled") {
+        Some(false)
+    } else if text.contains("enabled") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+enum UpdateSection {
+    NewFormulae,
+    UpdatedFormulae,
+    RenamedFormulae,
+    DeletedFormulae,
+    Casks,
+}
+
+/// Count the changes listed in `brew update` output. Each `==> New Formulae`, `==> Updated
+/// Formulae`, ... section lists names (one or several per line, `old -> new` for renames); brew
+/// prints "Updated 47 formulae." in place of a long list. Other sections, such as the outdated
+/// formulae brew mentions at the end, are not counted.
+pub fn parse_update(output: &str) -> UpdateSummary {
+    let mut summary = UpdateSummary::default();
+    let mut section = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix("==> ") {
+            section = match header {
+                "New Formulae" => Some(UpdateSection::NewFormulae),
+                "Updated Formulae" => Some(UpdateSection::UpdatedFormulae),
+                "Renamed Formulae" => Some(UpdateSection::RenamedFormulae),
+                "Deleted Formulae" => Some(UpdateSection::DeletedFormulae),
+                "New Casks" | "Updated Casks" | "Renamed Casks" | "Deleted Casks" => {
+                    Some(UpdateSection::Casks)
+                }
+                _ => None,
+            };
+            continue;
+        }
+        let Some(current) = section else {
+            continue;
+        };
+        if line.is_empty() {
+            continue;
+        }
+        let total = line
+            .strip_prefix("Updated ")
+            .and_then(|rest| rest.split(' ').next())
+            .and_then(|n| n.parse::<usize>().ok());
+        let slot = counter(&mut summary, current);
+        match total {
+            Some(n) => *slot += n,
+            None if line.contains(" -> ") => *slot += 1,
+            None => *slot += line.split_whitespace().count(),
+        }
+    }
+    summary
+}
+
+/// The count a section of `brew update` output adds to.
+fn counter(summary: &mut UpdateSummary, section: UpdateSection) -> &mut usize {
+    match section {
+        UpdateSection::NewFormulae => &mut summary.new_formulae,
+        UpdateSection::UpdatedFormulae => &mut summary.updated_formulae,
+        UpdateSection::RenamedFormulae => &mut summary.renamed_formulae,
+        UpdateSection::DeletedFormulae => &mut summary.deleted_formulae,
+        UpdateSection::Casks => &mut summary.casks,
+    }
+}
@@ -0,0 +1,36 @@
+// Locations for files homebrew-tui persists between runs.
+use std::env;
+use std::path::PathBuf;
+
+/// Directory for state that should survive restarts (history, session data).
+/// Follows `$XDG_STATE_HOME`, falling back to `~/.local/state/homebrew-tui`.
+pub fn state_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_STATE_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("homebrew-tui"));
+    }
+    home_dir().map(|h| h.join(".local").join("state").join("homebrew-tui"))
+}
+
+/// Directory holding `config.toml`. Follows `$XDG_CONFIG_HOME`, falling back to
+/// `~/.config/homebrew-tui`.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("homebrew-tui"));
+    }
+    home_dir().map(|h| h.join(".config").join("homebrew-tui"))
+}
+
+/// Directory for data that can be rebuilt at any time (package metadata). Follows
+/// `$XDG_CACHE_HOME`, falling back to `~/.cache/homebrew-tui`.
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CACHE_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("homebrew-tui"));
+    }
+    home_dir().map(|h| h.join(".cache").join("homebrew-tui"))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
@@ -0,0 +1,254 @@
+// `--porcelain`: a line-oriented JSON interface for wrappers and editor integrations. Requests
+// are read from stdin, one JSON object per line (`{"command": "install", "name": "wget"}`), and
+// every resulting `AppEvent` is written to stdout as one JSON line. Queries run concurrently on
+// a small worker pool; operations run one at a time in the order they were requested. The
+// process exits once stdin is closed and all queued work has finished.
+use crate::backend::run_operation;
+use crate::brew::Brew;
+use crate::event::{AppEvent, ConfirmAction};
+use crate::jobs::{self, JobKind, JobPool};
+use anyhow::Result;
+use serde::Deserialize;
+use std::io::{BufRead, Write};
+use std::sync::mpsc;
+use std::thread;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Request {
+    /// Installed formulae (`brew_list` event).
+    List,
+    /// All available formulae (`available_list` event).
+    Available,
+    /// Outdated formulae (`outdated_list` event).
+    Outdated,
+    /// Pinned formulae (`pinned_list` event).
+    Pinned,
+    /// Installed casks (`cask_list` event).
+    Casks,
+    /// Services of the installed formulae (`service_list` event).
+    Services,
+    /// Tapped repositories (`tap_list` event).
+    Taps,
+    Info {
+        name: String,
+    },
+    Search {
+        query: String,
+    },
+    Install {
+        name: String,
+    },
+    Uninstall {
+        name: String,
+    },
+    Upgrade {
+        names: Vec<String>,
+    },
+    Postinstall {
+        name: String,
+    },
+    Link {
+        name: String,
+        #[serde(default)]
+        overwrite: bool,
+    },
+    Unlink {
+        name: String,
+    },
+    Pin {
+        name: String,
+    },
+    Unpin {
+        name: String,
+    },
+    /// Link another installed version of a formula.
+    SwitchVersion {
+        name: String,
+        version: String,
+    },
+    /// `brew extract` a version into the local tap and install it.
+    ExtractInstall {
+        name: String,
+        version: String,
+    },
+    /// Delete an installed version that is not linked.
+    RemoveVersion {
+        name: String,
+        version: String,
+    },
+    /// `brew config` and `brew --env` (`environment` event).
+    Environment,
+    /// Findings of `brew doctor` (`doctor` event).
+    Doctor,
+    /// The files in the download cache (`download_cache` event).
+    DownloadCache,
+    /// Installed packages by size (`disk_usage` event).
+    DiskUsage,
+    /// The build logs of the most recently built of `names` (`build_logs` event).
+    BuildLogs {
+        names: Vec<String>,
+    },
+    /// Recent tap commits of a formula (`formula_log` event).
+    FormulaLog {
+        name: String,
+    },
+    /// Upstream versions of `names`, every installed leaf when omitted (`livecheck` event).
+    Livecheck {
+        #[serde(default)]
+        names: Vec<String>,
+    },
+    /// `brew gist-logs`.
+    GistLogs {
+        name: String,
+    },
+    /// What `brew cleanup` would remove (`cleanup_preview` event).
+    CleanupPreview,
+    Cleanup {
+        #[serde(default)]
+        prune_all: bool,
+    },
+    /// `brew update`.
+    Update,
+}
+
+impl Request {
+    /// The operation this request runs, with the name used for its title.
+    pub fn operation(&self) -> Option<(ConfirmAction, String)> {
+        let op = match self {
+            Request::Install { name } => (ConfirmAction::Install, name.clone()),
+            Request::Uninstall { name } => (ConfirmAction::Uninstall, name.clone()),
+            Request::Upgrade { names } => (
+                ConfirmAction::BulkUpgrade(names.clone()),
+                format!("{} packages", names.len()),
+            ),
+            Request::Postinstall { name } => (ConfirmAction::Postinstall, name.clone()),
+            Request::Link { name, overwrite } => {
+                let action = if *overwrite {
+                    ConfirmAction::LinkOverwrite
+                } else {
+                    ConfirmAction::Link
+                };
+                (action, name.clone())
+            }
+            Request::Unlink { name } => (ConfirmAction::Unlink, name.clone()),
+            Request::Pin { name } => (ConfirmAction::Pin, name.clone()),
+            Request::Unpin { name } => (ConfirmAction::Unpin, name.clone()),
+            Request::SwitchVersion { name, version } => (
+                ConfirmAction::SwitchVersion {
+                    version: version.clone(),
+                },
+                name.clone(),
+            ),
+            Request::ExtractInstall { name, version } => (
+                ConfirmAction::ExtractInstall {
+                    version: version.clone(),
+                },
+                name.clone(),
+            ),
+            Request::RemoveVersion { name, version } => (
+                ConfirmAction::RemoveVersion {
+                    version: version.clone(),
+                },
+                name.clone(),
+            ),
+            Request::Cleanup { prune_all } => {
+                let action = if *prune_all {
+                    ConfirmAction::CleanupPruneAll
+                } else {
+                    ConfirmAction::Cleanup
+                };
+                (action, "cleanup".into())
+            }
+            Request::Update => (ConfirmAction::Update, "Homebrew".into()),
+            Request::GistLogs { name } => (ConfirmAction::GistLogs, name.clone()),
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+/// One JSON line for `ev`.
+pub fn event_json(ev: &AppEvent) -> String {
+    serde_json::to_string(ev).unwrap_or_else(|e| {
+        serde_json::json!({ "event": "log", "data": format!("failed to encode event: {}", e) })
+            .to_string()
+    })
+}
+
+/// Serve requests from `input` until it is closed, writing events to `out`.
+pub fn run<R, W>(brew: Brew, input: R, mut out: W) -> Result<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+{
+    let (tx, rx) = mpsc::channel();
+    // operations are queued on the pool's operation lane so two brew processes never fight
+    // over the same locks
+    let jobs = JobPool::new(jobs::QUERY_WORKERS);
+
+    let req_events = tx;
+    thread::spawn(move || {
+        for line in input.lines().map_while(std::result::Result::ok) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Request>(&line) {
+                Ok(req) => match req.operation() {
+                    Some((action, name)) => {
+                        let brew = brew.clone();
+                        let tx = req_events.clone();
+                        jobs.submit_async(JobKind::Operation, move |token| async move {
+                            run_operation(&brew, &action, &name, &tx, &token).await;
+                        });
+                    }
+                    None => submit_query(&jobs, &brew, req, &req_events),
+                },
+                Err(e) => {
+                    let _ = req_events.send(AppEvent::Log(format!("invalid request: {}", e)));
+                }
+            }
+        }
+    });
+
+    // ends when the request reader and every queued job have dropped their senders
+    for ev in rx {
+        writeln!(out, "{}", event_json(&ev))?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+fn submit_query(jobs: &JobPool, brew: &Brew, req: Request, tx: &mpsc::Sender<AppEvent>) {
+    let mut brew = brew.clone();
+    let tx = tx.clone();
+    let kind = match req {
+        Request::Info { .. } => JobKind::Info,
+        _ => JobKind::Refresh,
+    };
+    jobs.submit(kind, move |_| {
+        let ev = match req {
+            Request::List => brew.list_installed().map(AppEvent::BrewList),
+            Request::Available => brew.all_available().map(AppEvent::AvailableList),
+            Request::Outdated => brew.outdated().map(AppEvent::OutdatedList),
+            Request::Pinned => brew.pinned().map(AppEvent::PinnedList),
+            Request::Casks => brew.casks().map(AppEvent::CaskList),
+            Request::Services => brew.services().map(AppEvent::ServiceList),
+            Request::Taps => brew.taps().map(AppEvent::TapList),
+            Request::Info { name } => brew.info(&name).map(|i| AppEvent::BrewInfo(i, 0)),
+            Request::Search { query } => brew.search(&query).map(AppEvent::SearchResults),
+            Request::CleanupPreview => brew.cleanup_preview().map(AppEvent::CleanupPreview),
+            Request::Doctor => brew.doctor().map(AppEvent::Doctor),
+            Request::DownloadCache => brew.download_cache().map(AppEvent::DownloadCache),
+            Request::DiskUsage => brew.disk_usage().map(AppEvent::DiskUsage),
+            Request::BuildLogs { names } => brew.build_logs(&names).map(AppEvent::BuildLogs),
+            Request::FormulaLog { name } => brew
+                .formula_log(&name)
+                .map(|commits| AppEvent::FormulaLog(name, commits)),
+            Request::Livecheck { names } => brew.livecheck(&names).map(AppEvent::Livecheck),
+            Request::Environment => brew.environment().map(AppEvent::Environment),
+            _ => return,
+        };
+        let _ = tx.send(ev.unwrap_or_else(|e| AppEvent::Log(format!("request failed: {}", e))));
+    });
+}
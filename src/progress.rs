@@ -0,0 +1,152 @@
+// Progress estimation for streamed brew output.
+//
+// brew rarely prints percentages when its output is piped, but it does print phase markers
+// (`==> Downloading`, `==> Pouring`, `==> Installing`, `🍺 ... summary`). Each phase owns a
+// slice of the gauge; markers move the gauge into their slice and repeated markers creep
+// forward inside it. Literal percentages (curl progress, the Homebrew installer) are mapped
+// into the current phase, or used as-is when no phase marker has been seen.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Unknown,
+    Fetching,
+    Installing,
+    Finishing,
+}
+
+impl Phase {
+    /// Gauge range `[start, end]` owned by the phase.
+    fn range(self) -> (u16, u16) {
+        match self {
+            Phase::Unknown => (0, 100),
+            Phase::Fetching => (0, 40),
+            Phase::Installing => (40, 90),
+            Phase::Finishing => (90, 100),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Unknown => "",
+            Phase::Fetching => "downloading",
+            Phase::Installing => "installing",
+            Phase::Finishing => "finishing",
+        }
+    }
+
+    fn from_line(line: &str) -> Option<Phase> {
+        let t = line.trim_start();
+        if t.starts_with('🍺') {
+            return Some(Phase::Finishing);
+        }
+        let marker = t.strip_prefix("==>")?.trim_start();
+        const FETCH: [&str; 3] = ["Fetching", "Downloading", "Tapping"];
+        const INSTALL: [&str; 6] = [
+            "Installing",
+            "Pouring",
+            "Upgrading",
+            "Reinstalling",
+            "Uninstalling",
+            "Postinstalling",
+        ];
+        const FINISH: [&str; 3] = ["Caveats", "Summary", "Running `brew cleanup"];
+        if FETCH.iter().any(|m| marker.starts_with(m)) {
+            Some(Phase::Fetching)
+        } else if INSTALL.iter().any(|m| marker.starts_with(m)) {
+            Some(Phase::Installing)
+        } else if FINISH.iter().any(|m| marker.starts_with(m)) {
+            Some(Phase::Finishing)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PhaseTracker {
+    phase: Phase,
+    percent: Option<u16>,
+}
+
+impl Default for PhaseTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhaseTracker {
+    pub fn new() -> Self {
+        Self {
+            phase: Phase::Unknown,
+            percent: None,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn percent(&self) -> Option<u16> {
+        self.percent
+    }
+
+    /// Feed one output line; returns the new gauge value when the line changed it.
+    pub fn observe(&mut self, line: &str) -> Option<u16> {
+        let before = self.percent;
+        if let Some(phase) = Phase::from_line(line) {
+            let (start, end) = phase.range();
+            let cur = self.percent.unwrap_or(0);
+            if phase > self.phase {
+                self.phase = phase;
+                self.percent = Some(cur.max(start));
+            } else if phase == self.phase {
+                // another marker of the same phase: creep a third of the way to its end
+                self.percent = Some(cur + end.saturating_sub(cur) / 3);
+            }
+            if line.trim_start().starts_with('🍺') {
+                self.percent = Some(self.percent.unwrap_or(0).max(95));
+            }
+        } else if let Some(pct) = parse_percent(line) {
+            if self.phase == Phase::Unknown {
+                // no phase information: follow the raw value
+                self.percent = Some(pct);
+            } else {
+                let (start, end) = self.phase.range();
+                let mapped = start + (end - start) * pct / 100;
+                self.percent = Some(self.percent.unwrap_or(0).max(mapped));
+            }
+        }
+        if self.percent != before {
+            self.percent
+        } else {
+            None
+        }
+    }
+}
+
+/// Try to extract a percentage value from a free-form log line.
+/// Scans for patterns like "42%" or "42.3%" and returns the integer part clamped to 0..=100.
+pub fn parse_percent(s: &str) -> Option<u16> {
+    // find the first '%' and then scan backwards for contiguous digits
+    let pos = s.find('%')?;
+    let before = &s.as_bytes()[..pos];
+    let digits_start = |end: usize| {
+        before[..end]
+            .iter()
+            .rposition(|c| !c.is_ascii_digit())
+            .map(|p| p + 1)
+            .unwrap_or(0)
+    };
+    let mut end = pos;
+    let mut start = digits_start(end);
+    // skip a fractional part such as the ".3" in "42.3%"
+    if start > 0 && start < end && before[start - 1] == b'.' {
+        end = start - 1;
+        start = digits_start(end);
+    }
+    if start == end {
+        return None;
+    }
+    let v: u16 = s[start..end].parse().ok()?;
+    Some(v.min(100))
+}
@@ -0,0 +1,76 @@
+// Operations the TUI has confirmed but not finished: queued behind another one on the operation
+// lane, or running. They are kept in the state directory from confirmation until they end, so
+// when the app exits or crashes with work left the next launch can offer to resume it.
+use crate::event::ConfirmAction;
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueuedOperation {
+    pub id: u64,
+    pub action: ConfirmAction,
+    pub name: String,
+    /// The command line, as Confirm showed it.
+    pub command: String,
+}
+
+#[derive(Default)]
+pub struct OperationQueue {
+    /// In the order they were confirmed.
+    pub entries: Vec<QueuedOperation>,
+    path: Option<PathBuf>,
+}
+
+impl OperationQueue {
+    /// A queue that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load `queue.json` from the state directory: what an earlier session left unfinished.
+    /// A missing or unreadable file is an empty queue.
+    pub fn load() -> Self {
+        Self::load_from(paths::state_dir().map(|d| d.join("queue.json")))
+    }
+
+    pub fn load_from(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { entries, path }
+    }
+
+    /// Add an operation at the end; returns the id that removes it again.
+    pub fn push(&mut self, action: ConfirmAction, name: &str, command: String) -> u64 {
+        let id = self.entries.iter().map(|e| e.id + 1).max().unwrap_or(0);
+        self.entries.push(QueuedOperation {
+            id,
+            action,
+            name: name.to_string(),
+            command,
+        });
+        id
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        self.entries.retain(|e| e.id != id);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let s = serde_json::to_string_pretty(&self.entries)?;
+        fs::write(path, s).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
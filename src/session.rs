@@ -0,0 +1,61 @@
+// Where the UI was left: the focused pane, the package selected in each list, the Available
+// filter and how far each list was scrolled. Saved to the state directory on quit so the next
+// launch opens at the same place; the sort orders are kept with the pane layout.
+use crate::app::Focus;
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub focus: Focus,
+    /// The package selected in the Installed table.
+    pub installed: Option<String>,
+    /// The package selected in the Available list.
+    pub available: Option<String>,
+    /// The Available filter (a search or `/regex/`).
+    pub filter: String,
+    /// The first visible row of each list.
+    pub installed_offset: usize,
+    pub available_offset: usize,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Session {
+    /// A session that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load `session.json` from the state directory. A missing or unreadable file starts from
+    /// the defaults, which are still saved to the same location.
+    pub fn load() -> Self {
+        Self::load_from(paths::state_dir().map(|d| d.join("session.json")))
+    }
+
+    pub fn load_from(path: Option<PathBuf>) -> Self {
+        let session: Self = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { path, ..session }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let s = serde_json::to_string_pretty(self)?;
+        fs::write(path, s).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
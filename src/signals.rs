@@ -0,0 +1,69 @@
+// Process signals the TUI reacts to. Handlers only set a flag; the main loop polls it.
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+static HANGUP: AtomicBool = AtomicBool::new(false);
+/// The termination signal received, 0 when none.
+static TERMINATE: AtomicI32 = AtomicI32::new(0);
+
+#[cfg(unix)]
+extern "C" fn on_hangup(_: libc::c_int) {
+    HANGUP.store(true, Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+extern "C" fn on_terminate(sig: libc::c_int) {
+    TERMINATE.store(sig, Ordering::SeqCst);
+}
+
+/// Install a SIGHUP handler that requests a config reload instead of terminating.
+#[cfg(unix)]
+pub fn install_reload_handler() {
+    let handler: extern "C" fn(libc::c_int) = on_hangup;
+    unsafe {
+        libc::signal(libc::SIGHUP, handler as libc::sighandler_t);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install_reload_handler() {}
+
+/// True once per received SIGHUP.
+pub fn take_reload_request() -> bool {
+    HANGUP.swap(false, Ordering::SeqCst)
+}
+
+/// Catch SIGTERM, SIGINT and SIGQUIT so the main loop can restore the terminal before the
+/// process exits (see `reraise`).
+#[cfg(unix)]
+pub fn install_termination_handler() {
+    let handler: extern "C" fn(libc::c_int) = on_terminate;
+    for sig in [libc::SIGTERM, libc::SIGINT, libc::SIGQUIT] {
+        unsafe {
+            libc::signal(sig, handler as libc::sighandler_t);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install_termination_handler() {}
+
+/// The termination signal received so far, if any.
+pub fn termination_request() -> Option<i32> {
+    match TERMINATE.load(Ordering::SeqCst) {
+        0 => None,
+        sig => Some(sig),
+    }
+}
+
+/// Deliver `sig` again with its default action, so the process ends the way it would have
+/// without our handler (and the parent sees the right exit status).
+#[cfg(unix)]
+pub fn reraise(sig: i32) {
+    unsafe {
+        libc::signal(sig, libc::SIG_DFL);
+        libc::raise(sig);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn reraise(_sig: i32) {}
@@ -0,0 +1,117 @@
+// Snapshots of the installed packages (`Y`): every name with its version, kept in the state
+// directory. The diff against the current Installed list (`I`) shows what was removed, added or
+// changed version since, and can reinstall what was removed.
+use crate::brew::FormulaInfo;
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Seconds since the unix epoch.
+    pub taken_at: u64,
+    /// Installed version by formula name.
+    pub packages: BTreeMap<String, String>,
+}
+
+/// What changed between a snapshot and the installed packages, each list by name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// In the snapshot but no longer installed, with the version they had.
+    pub removed: Vec<(String, String)>,
+    /// Installed since, with their version.
+    pub added: Vec<(String, String)>,
+    /// Installed in another version: the snapshot's and the current one.
+    pub changed: Vec<(String, String, String)>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Snapshot {
+    /// The packages in `installed` as of `taken_at`.
+    pub fn of(installed: &[FormulaInfo], taken_at: u64) -> Self {
+        let packages = installed
+            .iter()
+            .map(|f| {
+                let version = f.installed_version().unwrap_or_default();
+                (f.name.clone(), version.to_string())
+            })
+            .collect();
+        Self { taken_at, packages }
+    }
+
+    pub fn diff(&self, installed: &[FormulaInfo]) -> SnapshotDiff {
+        let now = Self::of(installed, 0).packages;
+        let mut diff = SnapshotDiff::default();
+        for (name, version) in &self.packages {
+            match now.get(name) {
+                None => diff.removed.push((name.clone(), version.clone())),
+                Some(current) if current != version => {
+                    diff.changed
+                        .push((name.clone(), version.clone(), current.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, version) in now {
+            if !self.packages.contains_key(&name) {
+                diff.added.push((name, version));
+            }
+        }
+        diff
+    }
+}
+
+/// The last snapshot taken, if any, and where it is saved.
+#[derive(Default)]
+pub struct Snapshots {
+    pub latest: Option<Snapshot>,
+    path: Option<PathBuf>,
+}
+
+impl Snapshots {
+    /// Snapshots that are never written to disk.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load `snapshot.json` from the state directory; a missing or unreadable file means no
+    /// snapshot was taken yet.
+    pub fn load() -> Self {
+        Self::load_from(paths::state_dir().map(|d| d.join("snapshot.json")))
+    }
+
+    pub fn load_from(path: Option<PathBuf>) -> Self {
+        let latest = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok());
+        Self { latest, path }
+    }
+
+    /// Replace the snapshot with `snapshot` and save it.
+    pub fn take(&mut self, snapshot: Snapshot) -> Result<()> {
+        self.latest = Some(snapshot);
+        self.save()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let (Some(path), Some(snapshot)) = (&self.path, &self.latest) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let s = serde_json::to_string_pretty(snapshot)?;
+        fs::write(path, s).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+// Order of the Installed table: the column it is sorted by and the direction; and the sort
+// both lists cycle through with `a`. The choices are saved with the pane layout so they
+// survive restarts.
+use crate::brew::{self, FormulaInfo};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortColumn {
+    #[default]
+    Name,
+    Version,
+    Size,
+    /// When the formula was installed.
+    Installed,
+    Tap,
+    Pinned,
+    Outdated,
+}
+
+impl SortColumn {
+    /// Every column, in the order the table shows them.
+    pub const ALL: [SortColumn; 7] = [
+        SortColumn::Name,
+        SortColumn::Version,
+        SortColumn::Size,
+        SortColumn::Installed,
+        SortColumn::Tap,
+        SortColumn::Pinned,
+        SortColumn::Outdated,
+    ];
+
+    /// Header of the column.
+    pub fn title(self) -> &'static str {
+        match self {
+            SortColumn::Name => "Name",
+            SortColumn::Version => "Version",
+            SortColumn::Size => "Size",
+            SortColumn::Installed => "Added",
+            SortColumn::Tap => "Tap",
+            SortColumn::Pinned => "Pin",
+            SortColumn::Outdated => "Out",
+        }
+    }
+
+    /// The column after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&c| c == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledSort {
+    pub column: SortColumn,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// What the columns show besides the formula's own fields.
+pub struct SortContext<'a> {
+    /// Bytes in the Cellar by formula name.
+    pub sizes: &'a HashMap<String, u64>,
+    pub pinned: &'a [String],
+    pub outdated: &'a [String],
+}
+
+impl InstalledSort {
+    /// Ascending order of the column: smallest version or size first, pinned or outdated
+    /// formulae first. Missing values come first; ties are broken by name.
+    pub fn compare(&self, a: &FormulaInfo, b: &FormulaInfo, ctx: &SortContext) -> Ordering {
+        let flagged = |list: &[String], f: &FormulaInfo| list.contains(&f.name);
+        let by = match self.column {
+            SortColumn::Name => a.name.cmp(&b.name),
+            SortColumn::Version => match (a.installed_version(), b.installed_version()) {
+                (Some(x), Some(y)) => brew::compare_versions(x, y),
+                (x, y) => x.is_some().cmp(&y.is_some()),
+            },
+            SortColumn::Size => ctx.sizes.get(&a.name).cmp(&ctx.sizes.get(&b.name)),
+            SortColumn::Installed => a.installed_time().cmp(&b.installed_time()),
+            SortColumn::Tap => a.tap_name().cmp(&b.tap_name()),
+            SortColumn::Pinned => flagged(ctx.pinned, b).cmp(&flagged(ctx.pinned, a)),
+            SortColumn::Outdated => flagged(ctx.outdated, b).cmp(&flagged(ctx.outdated, a)),
+        };
+        let by = if self.descending { by.reverse() } else { by };
+        by.then_with(|| a.name.cmp(&b.name))
+    }
+}
+
+/// The orders `a` cycles both lists through.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListSort {
+    #[default]
+    Name,
+    RecentlyInstalled,
+    Size,
+    /// Packages with a newer version upstream first.
+    Updates,
+}
+
+impl ListSort {
+    pub const ALL: [ListSort; 4] = [
+        ListSort::Name,
+        ListSort::RecentlyInstalled,
+        ListSort::Size,
+        ListSort::Updates,
+    ];
+
+    /// How pane titles name the order.
+    pub fn title(self) -> &'static str {
+        match self {
+            ListSort::Name => "name",
+            ListSort::RecentlyInstalled => "recently installed",
+            ListSort::Size => "size",
+            ListSort::Updates => "updates first",
+        }
+    }
+
+    /// The order after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&s| s == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// The Installed table's column and direction for this order.
+    pub fn installed(self) -> InstalledSort {
+        let (column, descending) = match self {
+            ListSort::Name => (SortColumn::Name, false),
+            ListSort::RecentlyInstalled => (SortColumn::Installed, true),
+            ListSort::Size => (SortColumn::Size, true),
+            ListSort::Updates => (SortColumn::Outdated, false),
+        };
+        InstalledSort { column, descending }
+    }
+
+    /// Reorder `rows`, the Available list's visible rows (indices into `names`), beyond name
+    /// order: installed packages come first as the Installed table would order them, the
+    /// others keep their order. The versions listed below a versioned formula (`members`) stay
+    /// with their head row.
+    pub fn sort_available(
+        self,
+        rows: &mut Vec<usize>,
+        members: &HashSet<usize>,
+        names: &[String],
+        installed: &[FormulaInfo],
+        ctx: &SortContext,
+    ) {
+        if self == ListSort::Name {
+            return;
+        }
+        let by_name: HashMap<&str, &FormulaInfo> =
+            installed.iter().map(|f| (f.name.as_str(), f)).collect();
+        let sort = self.installed();
+        let mut blocks = row_blocks(rows, members);
+        let formula = |block: &[usize]| {
+            names
+                .get(block[0])
+                .and_then(|n| by_name.get(n.as_str()).copied())
+        };
+        blocks.sort_by(|a, b| match (formula(a), formula(b)) {
+            (Some(x), Some(y)) => sort.compare(x, y, ctx),
+            (x, y) => y.is_some().cmp(&x.is_some()),
+        });
+        *rows = blocks.concat();
+    }
+}
+
+/// Move the starred packages of the Available list's `rows` to the top, each order kept. A
+/// versioned formula moves with its listed versions when any of them is starred.
+pub fn favorites_first(
+    rows: &mut Vec<usize>,
+    members: &HashSet<usize>,
+    names: &[String],
+    starred: impl Fn(&str) -> bool,
+) {
+    let mut blocks = row_blocks(rows, members);
+    blocks.sort_by_key(|block| {
+        !block
+            .iter()
+            .any(|&i| names.get(i).is_some_and(|n| starred(n)))
+    });
+    *rows = blocks.concat();
+}
+
+/// `rows` split into a block per head row, with the versions listed below it.
+fn row_blocks(rows: &[usize], members: &HashSet<usize>) -> Vec<Vec<usize>> {
+    let mut blocks: Vec<Vec<usize>> = vec![];
+    for &row in rows {
+        match blocks.last_mut() {
+            Some(block) if members.contains(&row) => block.push(row),
+            _ => blocks.push(vec![row]),
+        }
+    }
+    blocks
+}
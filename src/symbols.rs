@@ -0,0 +1,83 @@
+// Glyphs used by the UI. `ascii = true` in the config swaps the Unicode set for plain ASCII
+// so terminals and fonts without good symbol coverage still render cleanly.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Symbols {
+    /// Animation frames for the loading/operation spinner.
+    pub spinner: &'static [&'static str],
+    pub success: &'static str,
+    pub failure: &'static str,
+    pub running: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    /// Between an installed version and the one an upgrade brings.
+    pub arrow: &'static str,
+    /// One cell of a size bar.
+    pub bar: &'static str,
+    /// Marks pinned formulae.
+    pub pin: &'static str,
+    /// Marks starred packages in Available.
+    pub favorite: &'static str,
+    /// Marks a formula whose versions are collapsed / listed below it.
+    pub collapsed: &'static str,
+    pub expanded: &'static str,
+    /// The thumb of a scrollbar, drawn over a pane's right border.
+    pub scroll_thumb: &'static str,
+    /// Fills the placeholder rows of a pane that is still loading.
+    pub placeholder: &'static str,
+}
+
+pub const UNICODE: Symbols = Symbols {
+    spinner: &["◐", "◓", "◑", "◒"],
+    success: "✓",
+    failure: "✗",
+    running: "…",
+    up: "↑",
+    down: "↓",
+    arrow: "→",
+    bar: "█",
+    pin: "⚑",
+    favorite: "★",
+    collapsed: "▸",
+    expanded: "▾",
+    scroll_thumb: "┃",
+    placeholder: "░",
+};
+
+pub const ASCII: Symbols = Symbols {
+    spinner: &["|", "/", "-", "\\"],
+    success: "+",
+    failure: "x",
+    running: "~",
+    up: "Up",
+    down: "Down",
+    arrow: "->",
+    bar: "#",
+    pin: "!",
+    favorite: "*",
+    collapsed: "+",
+    expanded: "-",
+    scroll_thumb: "#",
+    placeholder: ".",
+};
+
+impl Default for Symbols {
+    fn default() -> Self {
+        UNICODE
+    }
+}
+
+impl Symbols {
+    pub fn new(ascii: bool) -> Self {
+        if ascii {
+            ASCII
+        } else {
+            UNICODE
+        }
+    }
+
+    /// Spinner frame for the given animation tick.
+    pub fn spinner_frame(&self, tick: usize) -> &'static str {
+        self.spinner[tick % self.spinner.len()]
+    }
+}
@@ -0,0 +1,141 @@
+// Entering and leaving the TUI's terminal state (raw mode, alternate screen, mouse capture and
+// focus reporting), and the window title showing what the app is doing. The title the user had
+// is pushed on the terminal's title stack (xterm's `CSI 22 t`) before the first change and
+// popped again by `restore`; tmux has no such stack, so the pane title is read beforehand and
+// written back.
+// `restore` is also run from the panic hook, so a crash leaves the user's shell usable, and
+// around job-control suspension (Ctrl-Z).
+use anyhow::Result;
+use crossterm::cursor::Show;
+use crossterm::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, KeyCode,
+    KeyEvent, KeyModifiers,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::io::{self, Stdout, Write};
+use std::panic;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Whether the title was changed since the last `restore`.
+static TITLE_SET: AtomicBool = AtomicBool::new(false);
+/// The tmux pane title from before the first change.
+static TMUX_TITLE: Mutex<Option<String>> = Mutex::new(None);
+
+pub type Tui = Terminal<CrosstermBackend<Stdout>>;
+
+/// Switch to raw mode and the alternate screen and return the terminal to draw on.
+pub fn init() -> Result<Tui> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
+    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+}
+
+/// Undo `init`. Every step is attempted even when an earlier one fails, since this also runs
+/// while the process is going down.
+pub fn restore() {
+    restore_title();
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableFocusChange,
+        Show
+    );
+}
+
+/// Show `title` as the terminal's window title (and the tmux pane title).
+pub fn set_title(title: &str) -> Result<()> {
+    let mut stdout = io::stdout();
+    if !TITLE_SET.swap(true, Ordering::SeqCst) {
+        if let Ok(mut saved) = TMUX_TITLE.lock() {
+            *saved = tmux_pane_title();
+        }
+        stdout.write_all(b"\x1b[22;0t")?;
+    }
+    execute!(stdout, SetTitle(title))?;
+    Ok(())
+}
+
+/// Put back the title from before `set_title`.
+fn restore_title() {
+    if !TITLE_SET.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let mut stdout = io::stdout();
+    let saved = TMUX_TITLE.lock().ok().and_then(|mut t| t.take());
+    if let Some(title) = saved {
+        let _ = execute!(stdout, SetTitle(title));
+    }
+    let _ = stdout.write_all(b"\x1b[23;0t").and_then(|_| stdout.flush());
+}
+
+/// The title of the pane when running inside tmux.
+fn tmux_pane_title() -> Option<String> {
+    std::env::var_os("TMUX")?;
+    let out = Command::new("tmux")
+        .args(["display-message", "-p", "#{pane_title}"])
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim_end().to_string())
+}
+
+/// Restore the terminal before a panic on the main thread is reported, so the message is
+/// readable and the shell is left in a sane state. Panics in background jobs are caught there
+/// and must not tear down the running UI.
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if thread::current().name() == Some("main") {
+            restore();
+        }
+        previous(info);
+    }));
+}
+
+/// Ctrl-Z, which raw mode delivers as a key instead of stopping the process.
+pub fn is_suspend_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Give the terminal back to the shell and stop the process like Ctrl-Z would outside raw
+/// mode. When the shell resumes it (`fg`), raw mode and the alternate screen are set up again
+/// and the next frame is drawn from scratch.
+#[cfg(unix)]
+pub fn suspend(tui: &mut Tui) -> Result<()> {
+    restore();
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+    // execution continues here once the process receives SIGCONT
+    enable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
+    tui.clear()?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn suspend(_tui: &mut Tui) -> Result<()> {
+    Ok(())
+}
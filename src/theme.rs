@@ -0,0 +1,240 @@
+// Color themes. All styles used by `ui.rs` come from the active `Theme` so switching themes
+// (config `theme = "..."` or the `T` key) restyles the whole UI.
+pub use crate::config::Background;
+use ratatui::style::{Color, Modifier, Style};
+use std::env;
+use std::time::Duration;
+
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Selected row in the Installed pane and in modal lists.
+    pub selection: Style,
+    /// Selected row in the Available pane.
+    pub selection_alt: Style,
+    /// Commands and other emphasized values.
+    pub accent: Style,
+    pub gauge: Style,
+    pub success: Style,
+    pub warning: Style,
+    pub error: Style,
+}
+
+/// Names of the built-in themes in cycling order.
+pub const THEMES: [&str; 5] = ["default", "light", "solarized", "gruvbox", "monochrome"];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::default_theme()
+    }
+}
+
+impl Theme {
+    /// Look up a built-in theme by name (case-insensitive).
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "default" | "dark" => Some(Self::default_theme()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            "gruvbox" => Some(Self::gruvbox()),
+            "monochrome" | "mono" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// The built-in theme following this one in `THEMES`.
+    pub fn next(&self) -> Self {
+        let pos = THEMES.iter().position(|n| *n == self.name).unwrap_or(0);
+        Self::by_name(THEMES[(pos + 1) % THEMES.len()]).unwrap_or_default()
+    }
+
+    pub fn default_theme() -> Self {
+        Self {
+            name: "default",
+            selection: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            selection_alt: Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+            accent: Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+            gauge: Style::default().fg(Color::Green).bg(Color::Black),
+            success: Style::default().fg(Color::Green),
+            warning: Style::default().fg(Color::Yellow),
+            error: Style::default().fg(Color::Red),
+        }
+    }
+
+    /// Palette for light terminal backgrounds, where the default yellow/cyan are hard to read.
+    pub fn light() -> Self {
+        let blue = Color::Rgb(0x00, 0x5f, 0xaf);
+        let green = Color::Rgb(0x00, 0x87, 0x00);
+        Self {
+            name: "light",
+            selection: Style::default().fg(blue).add_modifier(Modifier::BOLD),
+            selection_alt: Style::default()
+                .fg(Color::Rgb(0x87, 0x00, 0x87))
+                .add_modifier(Modifier::BOLD),
+            accent: Style::default().fg(blue).add_modifier(Modifier::BOLD),
+            gauge: Style::default().fg(green).bg(Color::Rgb(0xe4, 0xe4, 0xe4)),
+            success: Style::default().fg(green),
+            warning: Style::default().fg(Color::Rgb(0xaf, 0x5f, 0x00)),
+            error: Style::default().fg(Color::Rgb(0xd7, 0x00, 0x00)),
+        }
+    }
+
+    /// Default theme for a background: `light` on light terminals, `default` otherwise.
+    pub fn for_background(bg: Background) -> Self {
+        match resolve_background(bg) {
+            Background::Light => Self::light(),
+            _ => Self::default_theme(),
+        }
+    }
+
+    pub fn solarized() -> Self {
+        let yellow = Color::Rgb(0xb5, 0x89, 0x00);
+        let cyan = Color::Rgb(0x2a, 0xa1, 0x98);
+        let green = Color::Rgb(0x85, 0x99, 0x00);
+        let blue = Color::Rgb(0x26, 0x8b, 0xd2);
+        Self {
+            name: "solarized",
+            selection: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
+            selection_alt: Style::default().fg(cyan).add_modifier(Modifier::BOLD),
+            accent: Style::default().fg(blue).add_modifier(Modifier::BOLD),
+            gauge: Style::default().fg(green).bg(Color::Rgb(0x07, 0x36, 0x42)),
+            success: Style::default().fg(green),
+            warning: Style::default().fg(Color::Rgb(0xcb, 0x4b, 0x16)),
+            error: Style::default().fg(Color::Rgb(0xdc, 0x32, 0x2f)),
+        }
+    }
+
+    pub fn gruvbox() -> Self {
+        let yellow = Color::Rgb(0xfa, 0xbd, 0x2f);
+        let aqua = Color::Rgb(0x8e, 0xc0, 0x7c);
+        let green = Color::Rgb(0xb8, 0xbb, 0x26);
+        Self {
+            name: "gruvbox",
+            selection: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
+            selection_alt: Style::default().fg(aqua).add_modifier(Modifier::BOLD),
+            accent: Style::default()
+                .fg(Color::Rgb(0x83, 0xa5, 0x98))
+                .add_modifier(Modifier::BOLD),
+            gauge: Style::default().fg(green).bg(Color::Rgb(0x3c, 0x38, 0x36)),
+            success: Style::default().fg(green),
+            warning: Style::default().fg(Color::Rgb(0xfe, 0x80, 0x19)),
+            error: Style::default().fg(Color::Rgb(0xfb, 0x49, 0x34)),
+        }
+    }
+
+    /// No colors at all, only text attributes; readable on any background.
+    pub fn monochrome() -> Self {
+        Self {
+            name: "monochrome",
+            selection: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            selection_alt: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            accent: Style::default().add_modifier(Modifier::BOLD),
+            gauge: Style::default().add_modifier(Modifier::REVERSED),
+            success: Style::default(),
+            warning: Style::default().add_modifier(Modifier::BOLD),
+            error: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        }
+    }
+}
+
+/// Turn `Auto` into `Light` or `Dark`: `$COLORFGBG` first, then an OSC 11 query to the
+/// terminal, defaulting to dark when neither answers.
+pub fn resolve_background(bg: Background) -> Background {
+    if bg != Background::Auto {
+        return bg;
+    }
+    env::var("COLORFGBG")
+        .ok()
+        .and_then(|v| background_from_colorfgbg(&v))
+        .or_else(query_osc11)
+        .unwrap_or(Background::Dark)
+}
+
+/// `COLORFGBG` is "fg;bg" (sometimes "fg;default;bg") using ANSI color numbers.
+pub fn background_from_colorfgbg(v: &str) -> Option<Background> {
+    let bg: u8 = v.rsplit(';').next()?.trim().parse().ok()?;
+    Some(match bg {
+        7 | 9..=15 => Background::Light,
+        _ => Background::Dark,
+    })
+}
+
+/// Parse an OSC 11 reply such as `ESC ] 11 ; rgb:ffff/ffff/ffff BEL`.
+pub fn background_from_osc11(reply: &str) -> Option<Background> {
+    let rgb = reply.split("rgb:").nth(1)?;
+    let rgb: String = rgb
+        .chars()
+        .take_while(|c| c.is_ascii_hexdigit() || *c == '/')
+        .collect();
+    let channels: Vec<f64> = rgb
+        .split('/')
+        .map(|c| {
+            let max = (16f64).powi(c.len() as i32) - 1.0;
+            u32::from_str_radix(c, 16).ok().map(|v| v as f64 / max)
+        })
+        .collect::<Option<Vec<f64>>>()?;
+    if channels.len() != 3 {
+        return None;
+    }
+    let luma = 0.299 * channels[0] + 0.587 * channels[1] + 0.114 * channels[2];
+    Some(if luma > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+/// Ask the terminal for its background color. Only attempted when stdin and stdout are a tty
+/// (raw mode must already be enabled so the reply is not echoed); gives up after 100ms.
+#[cfg(unix)]
+fn query_osc11() -> Option<Background> {
+    use std::io::Write;
+    // SAFETY: isatty/poll/read only inspect the standard descriptors.
+    unsafe {
+        if libc::isatty(0) != 1 || libc::isatty(1) != 1 {
+            return None;
+        }
+    }
+    let mut out = std::io::stdout();
+    out.write_all(b"\x1b]11;?\x07").ok()?;
+    out.flush().ok()?;
+    let mut reply = Vec::new();
+    let deadline = std::time::Instant::now() + Duration::from_millis(100);
+    loop {
+        let left = deadline.saturating_duration_since(std::time::Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        let mut pfd = libc::pollfd {
+            fd: 0,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut pfd, 1, left.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+        let mut buf = [0u8; 64];
+        let n = unsafe { libc::read(0, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+        if n <= 0 {
+            break;
+        }
+        reply.extend_from_slice(&buf[..n as usize]);
+        // reply is terminated by BEL or ST (ESC \)
+        if reply.ends_with(b"\x07") || reply.ends_with(b"\x1b\\") {
+            break;
+        }
+    }
+    background_from_osc11(&String::from_utf8_lossy(&reply))
+}
+
+#[cfg(not(unix))]
+fn query_osc11() -> Option<Background> {
+    None
+}
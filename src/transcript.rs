@@ -0,0 +1,158 @@
+// Transcripts of the brew commands a session ran, for reproducible bug reports and offline
+// development. Recording (`--record FILE`) appends every query and operation `Brew` runs to the
+// file as one JSON line with its output and exit code; replaying (`--replay FILE`) answers the
+// same commands from the file instead of running brew.
+use crate::backend::ScriptLine;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// One command and what it printed. Queries keep their stdout and stderr whole, operations
+/// line by line in the order the lines came.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Exchange {
+    /// `brew outdated --formula`; operations by the command line the Operation modal shows
+    /// (with the configured flags).
+    pub command: String,
+    /// An operation rather than a query.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub operation: bool,
+    pub output: Vec<ScriptLine>,
+    /// `None` when it was killed by a signal.
+    pub exit_code: Option<i32>,
+}
+
+impl Exchange {
+    /// A finished query.
+    pub fn query(command: &str, stdout: &[u8], stderr: &[u8], exit_code: Option<i32>) -> Self {
+        let mut output = vec![];
+        if !stdout.is_empty() {
+            output.push(ScriptLine::Stdout(
+                String::from_utf8_lossy(stdout).into_owned(),
+            ));
+        }
+        if !stderr.is_empty() {
+            output.push(ScriptLine::Stderr(
+                String::from_utf8_lossy(stderr).into_owned(),
+            ));
+        }
+        Self {
+            command: command.to_string(),
+            operation: false,
+            output,
+            exit_code,
+        }
+    }
+
+    /// Everything a query printed to stdout.
+    pub fn stdout(&self) -> String {
+        self.joined(|l| match l {
+            ScriptLine::Stdout(s) => Some(s),
+            ScriptLine::Stderr(_) => None,
+        })
+    }
+
+    /// Everything a query printed to stderr.
+    pub fn stderr(&self) -> String {
+        self.joined(|l| match l {
+            ScriptLine::Stderr(s) => Some(s),
+            ScriptLine::Stdout(_) => None,
+        })
+    }
+
+    fn joined(&self, pick: impl Fn(&ScriptLine) -> Option<&String>) -> String {
+        self.output
+            .iter()
+            .filter_map(pick)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Where the commands of a session go to or come from.
+pub enum Transcript {
+    /// Every command is appended to the file once it finished.
+    Record(Mutex<File>),
+    /// Commands are answered from these exchanges, by kind and command line.
+    Replay(Mutex<HashMap<(bool, String), VecDeque<Exchange>>>),
+}
+
+impl Transcript {
+    /// Record to `path`, replacing an older transcript there.
+    pub fn record(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        Ok(Self::Record(Mutex::new(file)))
+    }
+
+    /// Replay the transcript in `path`.
+    pub fn replay(path: &Path) -> Result<Self> {
+        Ok(Self::from_exchanges(load(path)?))
+    }
+
+    /// Replay `exchanges`.
+    pub fn from_exchanges(exchanges: Vec<Exchange>) -> Self {
+        let mut by_command: HashMap<(bool, String), VecDeque<Exchange>> = HashMap::new();
+        for e in exchanges {
+            by_command
+                .entry((e.operation, e.command.clone()))
+                .or_default()
+                .push_back(e);
+        }
+        Self::Replay(Mutex::new(by_command))
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self, Self::Replay(_))
+    }
+
+    /// Append `exchange` when recording. A transcript that cannot be written is only logged;
+    /// the command ran either way.
+    pub fn save(&self, exchange: &Exchange) {
+        let Self::Record(file) = self else {
+            return;
+        };
+        let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+        let written = serde_json::to_string(exchange)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| Ok(writeln!(file, "{}", line)?));
+        if let Err(e) = written {
+            tracing::warn!("could not record {}: {:#}", exchange.command, e);
+        }
+    }
+
+    /// The recorded answer to the next run of `command`. Runs of the same command get the
+    /// exchanges in the order they were recorded, and the last one again once those run out.
+    /// `None` when recording or when `command` was never recorded.
+    pub fn next(&self, operation: bool, command: &str) -> Option<Exchange> {
+        let Self::Replay(by_command) = self else {
+            return None;
+        };
+        let mut by_command = by_command.lock().unwrap_or_else(|e| e.into_inner());
+        let runs = by_command.get_mut(&(operation, command.to_string()))?;
+        if runs.len() > 1 {
+            runs.pop_front()
+        } else {
+            runs.front().cloned()
+        }
+    }
+}
+
+/// Parse a transcript: one `Exchange` per line, blank lines ignored.
+pub fn parse(text: &str) -> Result<Vec<Exchange>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| serde_json::from_str(l).with_context(|| format!("line {}", i + 1)))
+        .collect()
+}
+
+/// Read and parse the transcript in `path`.
+pub fn load(path: &Path) -> Result<Vec<Exchange>> {
+    let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    parse(&text).with_context(|| format!("parsing {}", path.display()))
+}
@@ -1,388 +1,273 @@
 // UI drawing for homebrew-tui
-use crate::app::{App, Mode};
+use crate::app::{App, Focus, MenuItem, Mode, Tab, Zoom};
+use crate::brew::FormulaInfo;
+use crate::disk;
+use crate::help::{self, HelpLine};
+use crate::history::{format_age, format_duration, format_time};
+use crate::output::{match_ranges, Level, LogEntry, Source};
+use crate::sort::{ListSort, SortColumn};
+use crate::theme::Theme;
 use anyhow::Result;
-use ratatui::backend::CrosstermBackend;
+use ratatui::backend::Backend;
+use ratatui::buffer::Buffer;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Span, Spans};
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
-use ratatui::Terminal;
-use std::io::Stdout;
-use unicode_width::UnicodeWidthStr;
+use ratatui::widgets::{
+    Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+    Tabs, Widget, Wrap,
+};
+use ratatui::{Frame, Terminal};
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> Result<()> {
+/// First visible row of a list pane showing `rows` rows of `len` entries with `selected`
+/// highlighted. Like ratatui's own scrolling, the previous `offset` is kept until the selection
+/// would leave the window, so the view only moves when it has to.
+pub fn scroll_offset(selected: usize, offset: usize, rows: usize, len: usize) -> usize {
+    if rows == 0 || len == 0 {
+        return 0;
+    }
+    let selected = selected.min(len - 1);
+    let mut offset = offset.min(len.saturating_sub(rows));
+    if selected < offset {
+        offset = selected;
+    } else if selected >= offset + rows {
+        offset = selected + 1 - rows;
+    }
+    offset
+}
+
+/// `scroll_offset` for entries `heights` rows tall (wrapped lines): the first visible entry,
+/// moved only as far as needed to show all of `selected`.
+pub fn wrapped_offset(selected: usize, offset: usize, rows: usize, heights: &[usize]) -> usize {
+    let Some(last) = heights.len().checked_sub(1) else {
+        return 0;
+    };
+    let selected = selected.min(last);
+    let mut offset = offset.min(selected);
+    while offset < selected && heights[offset..=selected].iter().sum::<usize>() > rows {
+        offset += 1;
+    }
+    offset
+}
+
+/// Byte ranges of the pieces of `text` at most `width` columns wide, for wrapping it; always at
+/// least one piece.
+pub fn wrap_ranges(text: &str, width: usize) -> Vec<Range<usize>> {
+    let mut out = vec![];
+    let (mut start, mut cols) = (0, 0);
+    for (i, c) in text.char_indices() {
+        let w = UnicodeWidthChar::width(c).unwrap_or(0);
+        if cols + w > width.max(1) && i > start {
+            out.push(start..i);
+            start = i;
+            cols = 0;
+        }
+        cols += w;
+    }
+    out.push(start..text.len());
+    out
+}
+
+/// Position and length of the scrollbar thumb on a track `track` cells tall, for a view of
+/// `rows` rows starting at `offset` into `len` entries; `None` when everything fits. The thumb
+/// is at least one cell and reaches the end of the track once the last entry is visible.
+pub fn scrollbar_thumb(
+    offset: usize,
+    rows: usize,
+    len: usize,
+    track: usize,
+) -> Option<(usize, usize)> {
+    if track == 0 || rows == 0 || len <= rows {
+        return None;
+    }
+    let size = (track * rows / len).clamp(1, track);
+    let offset = offset.min(len - rows);
+    let start = (offset * (track - size) + (len - rows) / 2) / (len - rows);
+    Some((start, size))
+}
+
+/// Placeholder rows `width` cells wide at most, shown in a pane until its list has loaded: a
+/// name and a shorter second word drawn with `glyph`, of varying lengths so they read as rows.
+pub fn placeholder_lines(rows: usize, width: usize, glyph: &str) -> Vec<String> {
+    (0..rows)
+        .map(|i| {
+            let name = 6 + i * 7 % 9;
+            let rest = 3 + i * 5 % 6;
+            let mut line = glyph.repeat(name.min(width));
+            if name + 1 + rest <= width {
+                line.push(' ');
+                line.push_str(&glyph.repeat(rest));
+            }
+            line
+        })
+        .collect()
+}
+
+/// A pane whose list is still loading: `note` (what is loading and for how long) above
+/// placeholder rows.
+fn draw_placeholder<B: Backend>(
+    f: &mut Frame<B>,
+    app: &App,
+    area: Rect,
+    title: String,
+    note: String,
+) {
+    let rows = area.height.saturating_sub(3) as usize;
+    let width = area.width.saturating_sub(2) as usize;
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let mut lines = vec![Spans::from(Span::styled(note, app.theme.accent))];
+    lines.extend(
+        placeholder_lines(rows, width, app.symbols.placeholder)
+            .into_iter()
+            .map(|l| Spans::from(Span::styled(l, dim))),
+    );
+    let pane = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(pane, area);
+}
+
+/// The progress note of a loading list: "Loading installed packages... 2.4s".
+fn loading_note(app: &App, what: &str) -> String {
+    format!(
+        "Loading {}... {}",
+        what,
+        format_duration(app.loading_since.elapsed())
+    )
+}
+
+/// Draw the scrollbar of a bordered pane over its right border.
+fn draw_scrollbar<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    (offset, rows, len): (usize, usize, usize),
+    glyph: &'static str,
+    style: Style,
+) {
+    let track = area.height.saturating_sub(2) as usize;
+    let Some((start, size)) = scrollbar_thumb(offset, rows, len, track) else {
+        return;
+    };
+    let thumb = Rect {
+        x: area.right().saturating_sub(1),
+        y: area.y + 1 + start as u16,
+        width: 1,
+        height: size as u16,
+    };
+    let lines: Vec<Spans> = (0..size)
+        .map(|_| Spans::from(Span::styled(glyph, style)))
+        .collect();
+    f.render_widget(Paragraph::new(lines), thumb);
+}
+
+/// Log lines the Operation modal shows on a screen of `size` (its area minus the borders).
+pub fn operation_rows(size: Rect) -> usize {
+    centered_rect(60, 40, size).height.saturating_sub(2) as usize
+}
+
+/// The count shown after a tab's title in the tab bar: the outdated packages (as of the last
+/// periodic check) on the Outdated tab, once there are any.
+pub fn tab_badge(app: &App, tab: Tab) -> Option<usize> {
+    match tab {
+        Tab::Outdated => Some(app.outdated_items.len()).filter(|&n| n > 0),
+        _ => None,
+    }
+}
+
+/// The Version cell of an installed formula: the version in use, followed by the one an upgrade
+/// brings (`1.7.1 → 1.8.0`) when the formula is outdated and its details are loaded.
+pub fn version_text(f: &FormulaInfo, outdated: bool, arrow: &str) -> String {
+    let installed = f.installed_version().unwrap_or("");
+    match f.stable_version() {
+        Some(stable) if outdated && stable != installed => {
+            format!("{} {} {}", installed, arrow, stable)
+        }
+        _ => installed.to_string(),
+    }
+}
+
+/// Where the Help modal is drawn on a screen of `size`.
+fn help_area(size: Rect) -> Rect {
+    centered_rect(70, 80, size)
+}
+
+/// Draw a frame of `app`. Any backend will do, so tests can render into ratatui's
+/// `TestBackend` and compare the buffer.
+pub fn draw_ui<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    // the Help modal scrolls no further than its last page shows
+    if let Mode::Help { query, .. } = &app.mode {
+        let len = help::lines(&help::sections(&app.keymap, app.symbols), query).len();
+        let rows = help_area(terminal.size()?).height.saturating_sub(2) as usize;
+        if let Mode::Help { scroll, .. } = &mut app.mode {
+            *scroll = (*scroll).min(len.saturating_sub(rows));
+        }
+    }
     terminal.draw(|f| {
+        let theme = &app.theme;
         let size = f.size();
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(6), Constraint::Length(7)].as_ref())
-            .split(size);
-
-        // three-column layout: Installed | Available | Details
-        let main_chunks = Layout::default()
-            .direction(Direction::Horizontal)
             .constraints(
                 [
-                    Constraint::Percentage(30),
-                    Constraint::Percentage(35),
-                    Constraint::Percentage(35),
+                    Constraint::Length(1),
+                    Constraint::Min(6),
+                    Constraint::Length(app.layout.bottom_height),
                 ]
                 .as_ref(),
             )
-            .split(chunks[0]);
-
-        // list
-        let items: Vec<ListItem> = app
-            .items
-            .iter()
-            .map(|i| ListItem::new(Spans::from(vec![Span::raw(i.name.clone())])))
-            .collect();
-        let mut state = ListState::default();
-        if !app.items.is_empty() {
-            state.select(Some(app.selected));
-        }
-        // spinner frames
-        let spinner_frames = ["◐", "◓", "◑", "◒", "◐", "◓", "◑", "◒"];
-        let spin = spinner_frames[app.spinner_idx % spinner_frames.len()];
+            .split(size);
 
-        let installed_title = if app.focus == crate::app::Focus::Installed {
-            if app.loading_installed {
-                format!("Installed (focused) {}", spin)
-            } else {
-                "Installed (focused)".to_string()
-            }
+        let symbols = app.symbols;
+        let spin = if app.config.reduced_motion {
+            symbols.running
         } else {
-            if app.loading_installed {
-                format!("Installed {}", spin)
-            } else {
-                "Installed".to_string()
-            }
+            symbols.spinner_frame(app.spinner_idx)
         };
-        let list = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(installed_title),
-            )
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            );
-        f.render_stateful_widget(list, main_chunks[0], &mut state);
-
-        // available list (middle column)
-        // build a quick set of installed names for marking
-        let installed_names: std::collections::HashSet<String> =
-            app.items.iter().map(|f| f.name.clone()).collect();
-
-        // Render only filtered available items (show index mapping)
-        let avail_items: Vec<ListItem> = app
-            .available_filtered
+
+        let tabs = Tab::ALL
             .iter()
-            .filter_map(|&_idx| app.available_items.get(_idx).map(|name| (_idx, name)))
-            .map(|(_idx, name)| {
-                if installed_names.contains(name) {
-                    ListItem::new(Spans::from(vec![Span::raw(format!(
-                        "{} (Installed)",
-                        name
-                    ))]))
-                } else {
-                    ListItem::new(Spans::from(vec![Span::raw(name.clone())]))
+            .enumerate()
+            .map(|(i, &t)| {
+                let mut title = vec![Span::raw(format!("{} {}", i + 1, t.title()))];
+                if let Some(n) = tab_badge(app, t) {
+                    title.push(Span::styled(format!(" ({})", n), theme.warning));
                 }
+                Spans::from(title)
             })
             .collect();
-        let mut avail_state = ListState::default();
-        // find the position in the filtered list that corresponds to available_selected
-        let filtered_sel = app
-            .available_filtered
-            .iter()
-            .position(|&idx| idx == app.available_selected);
-        if let Some(pos) = filtered_sel {
-            avail_state.select(Some(pos));
+        let tab_bar = Tabs::new(tabs)
+            .select(app.tab.index())
+            .highlight_style(theme.selection);
+        f.render_widget(tab_bar, chunks[0]);
+        if app.zoom.is_some() {
+            let hint = Paragraph::new(Span::styled("Zoomed  Tab: next pane  e/Esc: back", theme.accent))
+                .alignment(Alignment::Right);
+            f.render_widget(hint, chunks[0]);
         }
-        let available_title = if app.focus == crate::app::Focus::Available {
-            if app.loading_available {
-                format!(
-                    "Available ({}) (focused) {}",
-                    app.available_items.len(),
-                    spin
-                )
-            } else {
-                format!("Available ({}) (focused)", app.available_items.len())
-            }
-        } else {
-            if app.loading_available {
-                format!("Available ({}) {}", app.available_items.len(), spin)
-            } else {
-                format!("Available ({})", app.available_items.len())
-            }
-        };
-        let available_list = List::new(avail_items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(available_title),
-            )
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            );
-        f.render_stateful_widget(available_list, main_chunks[1], &mut avail_state);
-
-        // details (right column) — show full details for the currently-focused selection
-        let detail = match app.focus {
-            crate::app::Focus::Installed => {
-                if let Some(sel) = app.items.get(app.selected) {
-                    let mut lines = vec![];
-                    lines.push(Spans::from(Span::raw(format!("{}", sel.name))));
-                    if let Some(fn_) = &sel.full_name {
-                        lines.push(Spans::from(Span::raw(format!("full: {}", fn_))));
-                    }
-                    if let Some(desc) = &sel.desc {
-                        lines.push(Spans::from(Span::raw(""))); // spacer
-                        lines.push(Spans::from(Span::raw(desc.clone())));
-                    }
-                    if let Some(h) = &sel.homepage {
-                        lines.push(Spans::from(Span::raw(format!("homepage: {}", h))));
-                    }
-                    if let Some(l) = &sel.license {
-                        lines.push(Spans::from(Span::raw(format!("license: {}", l))));
-                    }
-                    if !sel.dependencies.is_empty() {
-                        lines.push(Spans::from(Span::raw("")));
-                        lines.push(Spans::from(Span::raw("dependencies:")));
-                        for d in sel.dependencies.iter() {
-                            lines.push(Spans::from(Span::raw(format!("  - {}", d))));
-                        }
-                    }
-                    if !sel.installed.is_empty() {
-                        lines.push(Spans::from(Span::raw("")));
-                        lines.push(Spans::from(Span::raw("installed:")));
-                        for inst in sel.installed.iter() {
-                            lines.push(Spans::from(Span::raw(format!("  - {}", inst.version))));
-                        }
-                    }
-                    if let Some(c) = &sel.caveats {
-                        if !c.trim().is_empty() {
-                            lines.push(Spans::from(Span::raw("")));
-                            lines.push(Spans::from(Span::raw("caveats:")));
-                            for l in c.lines() {
-                                lines.push(Spans::from(Span::raw(format!("  {}", l))));
-                            }
-                        }
-                    }
-
-                    Paragraph::new(lines)
-                        .block(Block::default().borders(Borders::ALL).title("Details"))
-                        .wrap(Wrap { trim: false })
-                } else {
-                    Paragraph::new("No package selected")
-                        .block(Block::default().borders(Borders::ALL).title("Details"))
-                }
-            }
-            crate::app::Focus::Available => {
-                if let Some(details) = &app.available_details {
-                    let mut lines = vec![];
-                    lines.push(Spans::from(Span::raw(format!("{}", details.name))));
-                    if let Some(fn_) = &details.full_name {
-                        lines.push(Spans::from(Span::raw(format!("full: {}", fn_))));
-                    }
-                    if let Some(desc) = &details.desc {
-                        lines.push(Spans::from(Span::raw("")));
-                        lines.push(Spans::from(Span::raw(desc.clone())));
-                    }
-                    if let Some(h) = &details.homepage {
-                        lines.push(Spans::from(Span::raw(format!("homepage: {}", h))));
-                    }
-                    if let Some(l) = &details.license {
-                        lines.push(Spans::from(Span::raw(format!("license: {}", l))));
-                    }
-                    if !details.dependencies.is_empty() {
-                        lines.push(Spans::from(Span::raw("")));
-                        lines.push(Spans::from(Span::raw("dependencies:")));
-                        for d in details.dependencies.iter() {
-                            lines.push(Spans::from(Span::raw(format!("  - {}", d))));
-                        }
-                    }
-                    if !details.installed.is_empty() {
-                        lines.push(Spans::from(Span::raw("")));
-                        lines.push(Spans::from(Span::raw("installed:")));
-                        for inst in details.installed.iter() {
-                            lines.push(Spans::from(Span::raw(format!("  - {}", inst.version))));
-                        }
-                    }
-                    if let Some(c) = &details.caveats {
-                        if !c.trim().is_empty() {
-                            lines.push(Spans::from(Span::raw("")));
-                            lines.push(Spans::from(Span::raw("caveats:")));
-                            for l in c.lines() {
-                                lines.push(Spans::from(Span::raw(format!("  {}", l))));
-                            }
-                        }
-                    }
 
-                    Paragraph::new(lines)
-                        .block(Block::default().borders(Borders::ALL).title("Details"))
-                        .wrap(Wrap { trim: false })
-                } else if let Some(name) = app.available_items.get(app.available_selected) {
-                    Paragraph::new(format!("{}\n\n{}", name, "(no details loaded)"))
-                        .block(Block::default().borders(Borders::ALL).title("Details"))
+        // a zoomed pane takes the main area and the bottom row
+        let full = Rect {
+            height: chunks[1].height + chunks[2].height,
+            ..chunks[1]
+        };
+        let scroll = app.zoom_scroll;
+        match app.zoom {
+            None => {
+                if app.tab == Tab::Formulae {
+                    draw_formulae(f, app, chunks[1], spin);
                 } else {
-                    Paragraph::new("No package selected")
-                        .block(Block::default().borders(Borders::ALL).title("Details"))
+                    draw_tab(f, app, chunks[1], spin);
                 }
+                draw_bottom(f, app, chunks[2], spin);
             }
-        };
-        f.render_widget(detail, main_chunks[2]);
-
-        // bottom: logs and status
-        let bottom_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
-            .split(chunks[1]);
-
-        // logs
-        let logs: Vec<ListItem> = app
-            .logs
-            .iter()
-            .rev()
-            .take(100)
-            .map(|l| ListItem::new(Span::raw(l.clone())))
-            .collect();
-        let logs_block = List::new(logs).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Logs (recent)"),
-        );
-        f.render_widget(logs_block, bottom_chunks[0]);
-
-        // status
-        // Operation progress (gauge when percent available) above status
-        let right_bottom = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
-            .split(bottom_chunks[1]);
-
-        if let Some(pct) = app.operation_percent {
-            // render a Gauge with animated label
-            use ratatui::widgets::Gauge;
-            let ratio = (pct as f64) / 100.0;
-            let label = if app.operating {
-                format!(
-                    "{}% {}",
-                    pct,
-                    spinner_frames[app.spinner_idx % spinner_frames.len()]
-                )
-            } else {
-                format!("{}%", pct)
-            };
-            let gauge = Gauge::default()
-                .block(Block::default().borders(Borders::ALL).title("Op Progress"))
-                .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
-                .label(label)
-                .ratio(ratio);
-            f.render_widget(gauge, right_bottom[0]);
-        } else {
-            let mut op_progress = app
-                .operation_status
-                .clone()
-                .unwrap_or_else(|| "".to_string());
-            if app.operating {
-                op_progress = format!(
-                    "{} {}",
-                    spinner_frames[app.spinner_idx % spinner_frames.len()],
-                    op_progress
-                );
-            }
-            let op_paragraph = Paragraph::new(Spans::from(vec![Span::raw(op_progress)]))
-                .block(Block::default().borders(Borders::ALL).title("Op Progress"))
-                .alignment(Alignment::Left);
-            f.render_widget(op_paragraph, right_bottom[0]);
-        }
-
-        // Status: render multi-line status so fields wrap and are readable
-        let mut status_lines: Vec<Spans> = vec![];
-        status_lines.push(Spans::from(Span::raw(format!(
-            "Installed: {}  Available: {}",
-            app.items.len(),
-            app.available_items.len()
-        ))));
-        let focus_str = if app.focus == crate::app::Focus::Installed {
-            "Installed"
-        } else {
-            "Available"
-        };
-        status_lines.push(Spans::from(Span::raw(format!("Focus: {}", focus_str))));
-
-        // show if there are updates/upgrades available for installed packages
-        let updates_count = app.outdated_items.len();
-        if updates_count > 0 {
-            // show a short preview (first 5 names) to avoid overflowing the status pane
-            let preview: Vec<String> = app.outdated_items.iter().take(5).cloned().collect();
-            let preview_str = if preview.is_empty() {
-                "".to_string()
-            } else if preview.len() == 5 {
-                format!("{}...", preview.join(", "))
-            } else {
-                preview.join(", ")
-            };
-            status_lines.push(Spans::from(Span::styled(
-                format!("Updates available: {} {}", updates_count, preview_str),
-                Style::default().fg(Color::Yellow),
-            )));
-        } else {
-            status_lines.push(Spans::from(Span::raw("Updates available: 0")));
-        }
-
-        // mode and logs
-        let mode_str = match &app.mode {
-            Mode::Normal => "Normal".to_string(),
-            Mode::Help => "Help".to_string(),
-            Mode::Input { action, .. } => match action {
-                crate::app::InputAction::Install => "Input(Install)".to_string(),
-                crate::app::InputAction::Search => "Input(Search)".to_string(),
-            },
-            Mode::Confirm { action, name, .. } => match action {
-                crate::app::ConfirmAction::Install => format!("Confirm Install {}", name),
-                crate::app::ConfirmAction::Uninstall => format!("Confirm Uninstall {}", name),
-                crate::app::ConfirmAction::Upgrade => format!("Confirm Upgrade {}", name),
-                crate::app::ConfirmAction::BulkUpgrade(_) => {
-                    format!("Confirm Bulk Upgrade {}", name)
-                }
-                crate::app::ConfirmAction::InstallBrew => format!("Confirm Install Homebrew"),
-            },
-            Mode::SearchResults { results, selected } => {
-                format!("SearchResults {} results (sel {})", results.len(), selected)
-            }
-            Mode::Outdated {
-                packages, cursor, ..
-            } => {
-                format!("Outdated {} packages (sel {})", packages.len(), cursor)
-            }
-            Mode::Operation { title, logs, .. } => {
-                format!("Operation: {} ({} lines)", title, logs.len())
-            }
-        };
-        status_lines.push(Spans::from(Span::raw(format!(
-            "Mode: {}  Logs: {}",
-            mode_str,
-            app.logs.len()
-        ))));
-
-        if let Some(ts) = app.last_refreshed {
-            if let Ok(dur) = ts.elapsed() {
-                let secs = dur.as_secs();
-                status_lines.push(Spans::from(Span::raw(format!("refreshed {}s ago", secs))));
-            }
+            Some(Zoom::List) if app.tab != Tab::Formulae => draw_tab(f, app, full, spin),
+            Some(Zoom::List) if app.focus == Focus::Installed => draw_installed(f, app, full, spin),
+            Some(Zoom::List) => draw_available(f, app, full, spin),
+            Some(Zoom::Details) => draw_details(f, app, full, scroll.min(u16::MAX as usize) as u16),
+            Some(Zoom::Logs) => draw_logs(f, app, full, Some(scroll)),
         }
-
-        let status = Paragraph::new(status_lines)
-            .block(Block::default().borders(Borders::ALL).title("Status"))
-            .wrap(Wrap { trim: true });
-
-        f.render_widget(status, right_bottom[1]);
+        let theme = &app.theme;
 
         // Overlays: input prompt or confirmation modal
         match &app.mode {
@@ -404,7 +289,7 @@ pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App)
 
                 // desired height: one row per result up to a max (keep some room)
                 let desired_rows = (results.len())
-                    .min((max_rows.saturating_sub(6)) as usize)
+                    .min(max_rows.saturating_sub(6))
                     .max(1);
                 let desired_h = (desired_rows as u16) + 4; // padding for title and borders
                 let clamped_h = desired_h.min((max_rows.saturating_sub(4)) as u16).max(4);
@@ -452,12 +337,11 @@ pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App)
                 let list = List::new(visible)
                     .block(Block::default().borders(Borders::ALL).title(title))
                     .highlight_style(
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
+                        theme.selection,
                     );
                 f.render_widget(Clear, area);
                 f.render_stateful_widget(list, area, &mut state);
+                draw_scrollbar(f, area, (start, body_height, total), symbols.scroll_thumb, theme.accent);
 
                 // if not all items are visible, show a small footer indicator
                 if end < total {
@@ -468,59 +352,74 @@ pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App)
                         height: 1,
                     };
                     let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
-                        "Showing {}..{} of {} (↑/↓ to move)",
+                        "Showing {}..{} of {} ({}/{} to move)",
                         start + 1,
                         end,
-                        total
+                        total,
+                        symbols.up,
+                        symbols.down
                     ))]))
                     .alignment(Alignment::Center);
                     f.render_widget(footer, footer_area);
                 }
             }
-            Mode::Help => {
-                let area = centered_rect(70, 60, size);
-                let title = "Help";
-                let help_text = vec![
-                    Spans::from(Span::raw("Keys:")),
-                    Spans::from(Span::raw("  Navigation:")),
-                    Spans::from(Span::raw("    j / Down    - move down")),
-                    Spans::from(Span::raw("    k / Up      - move up")),
-                    Spans::from(Span::raw("    Tab         - switch focus between Installed/Available")),
-                    Spans::from(Span::raw("")),
-                    Spans::from(Span::raw("  Actions:")),
-                    Spans::from(Span::raw("    Enter       - open details / confirm action when applicable")),
-                    Spans::from(Span::raw("    i           - install (opens input prompt)")),
-                    Spans::from(Span::raw("    s           - search (opens input prompt)")),
-                    Spans::from(Span::raw("    f           - focus Available and prefill search with current filter")),
-                    Spans::from(Span::raw("    F           - clear Available filter")),
-                    Spans::from(Span::raw("    r           - uninstall selected installed package (confirm)")),
-                    Spans::from(Span::raw("    u           - upgrade selected installed package (confirm)")),
-                    Spans::from(Span::raw("    o           - open Outdated packages modal")),
-                    Spans::from(Span::raw("    R           - refresh outdated check (background)")),
-                    Spans::from(Span::raw("    q           - quit")),
-                    Spans::from(Span::raw("")),
-                    Spans::from(Span::raw("  Outdated modal:")),
-                    Spans::from(Span::raw("    ↑ / ↓ / j / k - move, Space: toggle package selection")),
-                    Spans::from(Span::raw("    Enter         - confirm selected upgrades (bulk)")),
-                    Spans::from(Span::raw("    Esc           - close Outdated modal")),
-                    Spans::from(Span::raw("")),
-                    Spans::from(Span::raw("  Confirm dialogs:")),
-                    Spans::from(Span::raw("    y / Enter     - confirm the action")),
-                    Spans::from(Span::raw("    n / Esc       - cancel")),
-                    Spans::from(Span::raw("")),
-                    Spans::from(Span::raw("  Operation modal (logs):")),
-                    Spans::from(Span::raw("    ↑ / ↓ / j / k - scroll lines")),
-                    Spans::from(Span::raw("    PgUp / PgDn   - page up / page down")),
-                    Spans::from(Span::raw("    Home / End    - jump to top / bottom (most recent)")),
-                    Spans::from(Span::raw("    Esc / ?       - close Operation modal")),
-                    Spans::from(Span::raw("")),
-                    Spans::from(Span::raw("Press ? or Esc to close")),
-                ];
+            Mode::Help {
+                scroll,
+                query,
+                searching,
+            } => {
+                let area = help_area(size);
+                let lines = help::lines(&help::sections(&app.keymap, symbols), query);
+                let keys_width = lines
+                    .iter()
+                    .filter_map(|l| match l {
+                        HelpLine::Entry(e) if e.keys.width() <= 16 => Some(e.keys.width()),
+                        _ => None,
+                    })
+                    .max()
+                    .unwrap_or(0);
+                let mut help_text: Vec<Spans> = lines
+                    .iter()
+                    .map(|l| match l {
+                        HelpLine::Title(t) => Spans::from(Span::styled(t.to_string(), theme.accent)),
+                        HelpLine::Entry(e) => {
+                            let pad = keys_width.saturating_sub(e.keys.width());
+                            Spans::from(Span::raw(format!("  {}{}  {}", e.keys, " ".repeat(pad), e.text)))
+                        }
+                        HelpLine::Blank => Spans::from(Span::raw("")),
+                    })
+                    .collect();
+                if help_text.is_empty() {
+                    help_text.push(Spans::from(Span::raw(format!("No keys match \"{}\"", query))));
+                }
+                let title = if *searching {
+                    format!("Help: /{}_", query)
+                } else if query.is_empty() {
+                    format!("Help (keymap preset: {})", app.keymap.preset.name())
+                } else {
+                    format!("Help: /{}", query)
+                };
                 let paragraph = Paragraph::new(help_text)
                     .block(Block::default().borders(Borders::ALL).title(title))
-                    .alignment(Alignment::Left);
+                    .alignment(Alignment::Left)
+                    .scroll((*scroll as u16, 0));
                 f.render_widget(Clear, area);
                 f.render_widget(paragraph, area);
+                let rows = area.height.saturating_sub(2) as usize;
+                draw_scrollbar(f, area, (*scroll, rows, lines.len()), symbols.scroll_thumb, theme.accent);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let hint = if *searching {
+                    "type to search  Enter: done  Esc: clear".to_string()
+                } else {
+                    format!("{}/{}: scroll  /: search  Esc: close", symbols.up, symbols.down)
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(hint)])).alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
             }
             Mode::Outdated {
                 packages,
@@ -532,6 +431,13 @@ pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App)
                 let title = format!("Outdated packages ({} updates)", packages.len());
                 let mut items: Vec<ListItem> = vec![];
                 for (i, p) in packages.iter().enumerate() {
+                    if app.is_pinned(p) {
+                        items.push(ListItem::new(Spans::from(vec![Span::styled(
+                            format!("[-] {} {} pinned", p, symbols.pin),
+                            theme.warning,
+                        )])));
+                        continue;
+                    }
                     let mark = if checked.get(i).copied().unwrap_or(false) {
                         "[x]"
                     } else {
@@ -549,12 +455,14 @@ pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App)
                 let list = List::new(items)
                     .block(Block::default().borders(Borders::ALL).title(title))
                     .highlight_style(
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
+                        theme.selection,
                     );
                 f.render_widget(Clear, area);
                 f.render_stateful_widget(list, area, &mut state);
+                // a fresh ListState scrolls just far enough to show the cursor
+                let rows = area.height.saturating_sub(2) as usize;
+                let window = (scroll_offset(*cursor, 0, rows, packages.len()), rows, packages.len());
+                draw_scrollbar(f, area, window, symbols.scroll_thumb, theme.accent);
                 // footer instructions
                 let footer_area = Rect {
                     x: area.x,
@@ -572,95 +480,297 @@ pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App)
             Mode::Confirm { action, name, idx } => {
                 // use same size as SearchResults for visual consistency
                 let area = centered_rect(60, 40, size);
-                let title = match action {
-                    crate::app::ConfirmAction::Uninstall => "Confirm Uninstall",
-                    crate::app::ConfirmAction::Upgrade => "Confirm Upgrade",
-                    crate::app::ConfirmAction::Install => "Confirm Install",
-                    crate::app::ConfirmAction::BulkUpgrade(_) => "Confirm Bulk Upgrade",
-                    crate::app::ConfirmAction::InstallBrew => "Confirm Install Homebrew",
-                };
+                let title = format!("Confirm {}", action.title());
 
                 // If idx provided, try to render richer details
-                let paragraph = if let Some(i) = idx {
-                    // prefer installed details if index matches items
-                    if let Some(pkg) = app.items.get(*i) {
-                        // build detail lines similar to Details pane
-                        let mut lines: Vec<Spans> = vec![];
-                        lines.push(Spans::from(Span::raw(format!("{}", pkg.name))));
-                        if let Some(fn_) = &pkg.full_name {
-                            lines.push(Spans::from(Span::raw(format!("full: {}", fn_))));
-                        }
-                        if let Some(desc) = &pkg.desc {
-                            lines.push(Spans::from(Span::raw("")));
-                            lines.push(Spans::from(Span::raw(desc.clone())));
-                        }
+                let mut lines: Vec<Spans> = vec![];
+                let pkg = idx
+                    .and_then(|i| app.items.get(i))
+                    .filter(|p| &p.name == name);
+                let available = app
+                    .available_details
+                    .as_ref()
+                    .filter(|d| idx.is_some() && &d.name == name);
+                if let Some(pkg) = pkg {
+                    // build detail lines similar to Details pane
+                    lines.push(Spans::from(Span::raw(pkg.name.clone())));
+                    if let Some(fn_) = &pkg.full_name {
+                        lines.push(Spans::from(Span::raw(format!("full: {}", fn_))));
+                    }
+                    if let Some(desc) = &pkg.desc {
                         lines.push(Spans::from(Span::raw("")));
-                        lines.push(Spans::from(Span::raw(format!(
-                            "{} '{}' ? (y/N)",
-                            title, name
-                        ))));
-                        Paragraph::new(lines)
-                            .block(Block::default().borders(Borders::ALL).title(title))
-                            .wrap(Wrap { trim: false })
-                    } else if let Some(details) = &app.available_details {
-                        // available details (if loaded)
-                        let mut lines: Vec<Spans> = vec![];
-                        lines.push(Spans::from(Span::raw(format!("{}", details.name))));
-                        if let Some(desc) = &details.desc {
+                        lines.push(Spans::from(Span::raw(desc.clone())));
+                    }
+                    lines.push(Spans::from(Span::raw("")));
+                } else if let Some(details) = available {
+                    // available details (if loaded)
+                    lines.push(Spans::from(Span::raw(details.name.clone())));
+                    if let Some(desc) = &details.desc {
+                        lines.push(Spans::from(Span::raw("")));
+                        lines.push(Spans::from(Span::raw(desc.clone())));
+                    }
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                lines.push(Spans::from(Span::raw(format!("{} '{}' ? (y/N)", title, name))));
+                lines.push(Spans::from(Span::raw("")));
+                if let crate::app::ConfirmAction::Install = action.base() {
+                    match app.dependencies.get(name) {
+                        Some(Some(preview)) => {
+                            let style = if preview.is_large() { theme.warning } else { Style::default() };
+                            lines.push(Spans::from(Span::styled(preview.summary(), style)));
                             lines.push(Spans::from(Span::raw("")));
-                            lines.push(Spans::from(Span::raw(desc.clone())));
                         }
-                        lines.push(Spans::from(Span::raw("")));
-                        lines.push(Spans::from(Span::raw(format!(
-                            "{} '{}' ? (y/N)",
-                            title, name
-                        ))));
-                        Paragraph::new(lines)
-                            .block(Block::default().borders(Borders::ALL).title(title))
-                            .wrap(Wrap { trim: false })
-                    } else {
-                        // Build a small set of lines for the generic confirm dialog. If this is the
-                        // InstallBrew action, add a short explanatory help text warning about
-                        // possible sudo prompts and network access.
-                        let mut lines: Vec<Spans> = vec![
-                            Spans::from(Span::raw(format!("{} '{}' ? (y/N)", title, name))),
-                            Spans::from(Span::raw("")),
-                        ];
-                        if let crate::app::ConfirmAction::InstallBrew = action {
-                            lines.push(Spans::from(Span::raw(
-                                "This will run the official Homebrew installer script from https://brew.sh.",
-                            )));
-                            lines.push(Spans::from(Span::raw(
-                                "The installer may prompt for sudo and other interactive input and requires network access.",
-                            )));
-                            lines.push(Spans::from(Span::raw(
-                                "If you prefer, cancel and run the installer manually in a terminal.",
-                            )));
+                        Some(None) => {
+                            lines.push(Spans::from(Span::raw("Looking up its dependencies...")));
                             lines.push(Spans::from(Span::raw("")));
                         }
-                        lines.push(Spans::from(Span::raw("Press Y to confirm, N or Esc to cancel.")));
-                        Paragraph::new(lines)
-                            .block(Block::default().borders(Borders::ALL).title(title))
-                            .wrap(Wrap { trim: false })
+                        None => {}
+                    }
+                }
+                // If this is the InstallBrew action, add a short explanatory help text warning
+                // about possible sudo prompts and network access.
+                if let crate::app::ConfirmAction::InstallBrew = action.base() {
+                    lines.push(Spans::from(Span::raw(
+                        "This will run the official Homebrew installer script from https://brew.sh.",
+                    )));
+                    lines.push(Spans::from(Span::raw(
+                        "The installer may prompt for sudo and other interactive input and requires network access.",
+                    )));
+                    lines.push(Spans::from(Span::raw(
+                        "If you prefer, cancel and run the installer manually in a terminal.",
+                    )));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                if let (
+                    crate::app::ConfirmAction::PurgeCache { older_than_days },
+                    Some(cache),
+                ) = (action.base(), &app.download_cache)
+                {
+                    let (bytes, files) = cache.reclaimable(*older_than_days, disk::now_secs());
+                    lines.push(Spans::from(Span::styled(
+                        format!(
+                            "Reclaims {} ({} bytes) by deleting {} files.",
+                            disk::format_size(bytes),
+                            bytes,
+                            files
+                        ),
+                        theme.warning,
+                    )));
+                    lines.push(Spans::from(Span::raw(
+                        "brew downloads them again when they are needed.",
+                    )));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                if let crate::app::ConfirmAction::Upgrade = action.base() {
+                    if app.is_pinned(name) {
+                        lines.push(Spans::from(Span::styled(
+                            format!("{} is pinned: brew upgrade leaves it at its installed version.", name),
+                            theme.warning,
+                        )));
+                        lines.push(Spans::from(Span::raw("Unpin it first (p in the Installed list) to upgrade it.")));
+                        lines.push(Spans::from(Span::raw("")));
+                    }
+                }
+                if let crate::app::ConfirmAction::Pin = action.base() {
+                    lines.push(Spans::from(Span::raw(
+                        "brew upgrade leaves a pinned formula at its installed version until it is unpinned.",
+                    )));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                if let crate::app::ConfirmAction::SwitchVersion { version } = action.base() {
+                    lines.push(Spans::from(Span::raw(format!(
+                        "Unlinks {}, points its opt link at the {} keg and links that one.",
+                        name, version
+                    ))));
+                    lines.push(Spans::from(Span::raw(
+                        "The next brew upgrade or brew cleanup may replace or delete it; pin it to keep it.",
+                    )));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                if let crate::app::ConfirmAction::RemoveVersion { version } = action.base() {
+                    lines.push(Spans::from(Span::styled(
+                        format!("Deletes the {} {} keg from the Cellar; this cannot be undone.", name, version),
+                        theme.warning,
+                    )));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                if let crate::app::ConfirmAction::ExtractInstall { version } = action.base() {
+                    lines.push(Spans::from(Span::raw(format!(
+                        "Copies the {} formula as it was at {} into the local tap {} (created if missing)",
+                        name,
+                        version,
+                        crate::event::EXTRACT_TAP
+                    ))));
+                    lines.push(Spans::from(Span::raw(format!(
+                        "and installs it as {}@{}, usually built from source.",
+                        name, version
+                    ))));
+                    lines.push(Spans::from(Span::styled(
+                        "brew extract reads the tap's git history: run `brew tap homebrew/core --force` first with API installs.",
+                        theme.warning,
+                    )));
+                    lines.push(Spans::from(Span::raw(
+                        "If its links clash with the current version, unlink that one (x) and link the new one (l).",
+                    )));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                if let crate::app::ConfirmAction::GistLogs = action.base() {
+                    lines.push(Spans::from(Span::raw(
+                        "Uploads the build logs, `brew config` and `brew doctor` output as a GitHub gist and prints its URL.",
+                    )));
+                    lines.push(Spans::from(Span::raw(
+                        "Needs HOMEBREW_GITHUB_API_TOKEN with the gist scope; the gist is public, so check the logs first (l in History).",
+                    )));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                if let crate::app::ConfirmAction::Update = action.base() {
+                    lines.push(Spans::from(Span::raw(
+                        "Fetches the newest Homebrew and formula definitions; installed packages are not changed.",
+                    )));
+                    lines.push(Spans::from(Span::raw(
+                        "The outdated list is re-checked when it finishes.",
+                    )));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                if let crate::app::ConfirmAction::AnalyticsOn | crate::app::ConfirmAction::AnalyticsOff =
+                    action.base()
+                {
+                    for l in [
+                        "Homebrew records anonymous, aggregated events: installs of formulae and casks",
+                        "(name and options), build errors, and the Homebrew, macOS and CPU versions they",
+                        "happened on. No user identifier is sent. The maintainers use the numbers to decide",
+                        "what to support. Details: https://docs.brew.sh/Analytics",
+                    ] {
+                        lines.push(Spans::from(Span::raw(l)));
                     }
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                // the exact command lines that will run
+                let stages = app.operation_stages(action, name);
+                if matches!(action.base(), crate::app::ConfirmAction::BulkUpgrade(_))
+                    && !action.flags().is_empty()
+                {
+                    let packages = stages
+                        .iter()
+                        .find(|(stage, _)| !stage.flags().is_empty())
+                        .map_or("none".to_string(), |(retry, _)| {
+                            retry.packages().unwrap_or_default().join(", ")
+                        });
+                    lines.push(Spans::from(Span::raw(format!(
+                        "{} only for what failed to upgrade last time: {}",
+                        action.flags().join(" "),
+                        packages
+                    ))));
+                    lines.push(Spans::from(Span::raw("")));
+                }
+                lines.push(Spans::from(Span::raw(if stages.len() > 1 { "commands:" } else { "command:" })));
+                for (stage, stage_name) in &stages {
+                    lines.push(Spans::from(Span::styled(
+                        format!("  $ {}", stage.full_command_line_with(stage_name, app.brew.flags())),
+                        theme.accent,
+                    )));
+                }
+                lines.push(Spans::from(Span::raw("")));
+                lines.push(Spans::from(Span::raw(if action.flag_choices().is_empty() {
+                    "Press Y to confirm, N or Esc to cancel, C to copy the command, E to set variables."
                 } else {
-                    Paragraph::new(format!("{} '{}' ? (y/N)", title, name))
-                        .block(Block::default().borders(Borders::ALL).title(title))
-                };
+                    "Press Y to confirm, N or Esc to cancel, C to copy the command, O for flags, E to set variables."
+                })));
+                let paragraph = Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .wrap(Wrap { trim: false });
 
                 f.render_widget(Clear, area); // clear underlying
                 f.render_widget(paragraph, area);
             }
+            Mode::Flags {
+                action,
+                name,
+                cursor,
+                checked,
+            } => {
+                let area = centered_rect(60, 40, size);
+                let choices = action.flag_choices();
+                let picked = action.with_flags(action.checked_flags(checked));
+                let title = format!("Flags: {}", picked.full_command_line_with(name, app.brew.flags()));
+                let items: Vec<ListItem> = choices
+                    .iter()
+                    .zip(checked.iter())
+                    .map(|((flag, what), &c)| {
+                        let mark = if c { "[x]" } else { "[ ]" };
+                        ListItem::new(Spans::from(vec![
+                            Span::raw(format!("{} {:<22} ", mark, flag)),
+                            Span::styled(*what, theme.accent),
+                        ]))
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*cursor));
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(
+                    "Space: toggle  Enter: use these flags  Esc: keep the old ones",
+                )]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::InstallCandidates {
+                query,
+                names,
+                selected,
+            } => {
+                let area = centered_rect(60, 40, size);
+                let title = format!("Install '{}': which one?", query);
+                let width = names.iter().map(|n| UnicodeWidthStr::width(n.as_str())).max().unwrap_or(0);
+                let items: Vec<ListItem> = names
+                    .iter()
+                    .map(|n| {
+                        let tap = crate::brew::tap_of(n).unwrap_or("official formula or cask");
+                        ListItem::new(Spans::from(vec![
+                            Span::raw(format!("{:<width$}  ", n, width = width)),
+                            Span::styled(tap.to_string(), theme.accent),
+                        ]))
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(
+                    "Enter: install the selected one  Esc: cancel",
+                )]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
             Mode::Operation {
                 title,
                 logs,
                 scroll,
+                live,
             } => {
                 let area = centered_rect(60, 40, size);
                 let block = Block::default().borders(Borders::ALL).title(title.as_str());
                 // logs are chronological (oldest first). `scroll` is number of lines scrolled up from bottom.
                 let total = logs.len();
-                let height = (area.height.saturating_sub(2)) as usize; // leave space for borders
+                let height = operation_rows(size);
                                                                        // determine window: show newest lines up to height, offset by scroll
                 let start_idx = if total > height + *scroll {
                     total - height - *scroll
@@ -670,15 +780,21 @@ pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App)
                 let end_idx = start_idx + height.min(total.saturating_sub(start_idx));
                 let text: Vec<Spans> = logs[start_idx..end_idx]
                     .iter()
-                    .map(|l| Spans::from(Span::raw(l.clone())))
+                    .map(|l| Spans::from(Span::styled(l.text.clone(), log_line_style(theme, l))))
                     .collect();
                 let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: false });
                 f.render_widget(Clear, area);
                 f.render_widget(paragraph, area);
+                draw_scrollbar(f, area, (start_idx, height, total), symbols.scroll_thumb, theme.accent);
                 // footer with simple position info
+                let close_hint = if *live && app.operating {
+                    "Esc: run in background"
+                } else {
+                    "Esc: close"
+                };
                 let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
-                    "lines {}/{} (↑/↓ scroll, PgUp/PgDn, Home/End)",
-                    end_idx, total
+                    "lines {}/{} ({}/{} scroll, PgUp/PgDn, Home/End, s: save, {})",
+                    end_idx, total, symbols.up, symbols.down, close_hint
                 ))]))
                 .alignment(Alignment::Right);
                 let footer_area = Rect {
@@ -689,27 +805,1827 @@ pub fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App)
                 };
                 f.render_widget(footer, footer_area);
             }
-            Mode::Input { action, buffer } => {
-                // render a small, single-line input (like a password/short text field)
-                let area = centered_rect(40, 10, size);
-                let title = match action {
-                    crate::app::InputAction::Install => "Install package",
-                    crate::app::InputAction::Search => "Search packages",
-                };
-                let text = buffer.clone();
-                // display the buffer inline
-                let paragraph = Paragraph::new(text)
-                    .block(Block::default().borders(Borders::ALL).title(title))
-                    .alignment(Alignment::Center);
-                f.render_widget(Clear, area);
-                f.render_widget(paragraph, area);
+            Mode::History { selected } => {
+                let area = centered_rect(70, 60, size);
+                let title = format!("Operation history ({})", app.history.records.len());
+                let items: Vec<ListItem> = app
+                    .history
+                    .records
+                    .iter()
+                    .rev()
+                    .map(|rec| {
+                        let (mark, color) = match rec.success() {
+                            Some(true) => (symbols.success, theme.success),
+                            Some(false) => (symbols.failure, theme.error),
+                            None => (symbols.running, theme.warning),
+                        };
+                        let exit = match (rec.success(), rec.exit_code) {
+                            (Some(false), Some(code)) => format!(" (exit {})", code),
+                            (Some(false), None) => " (no exit status)".to_string(),
+                            _ => String::new(),
+                        };
+                        let duration = rec
+                            .duration()
+                            .map(format_duration)
+                            .unwrap_or_else(|| "running".to_string());
+                        ListItem::new(Spans::from(vec![
+                            Span::styled(format!("{} ", mark), color),
+                            Span::raw(format!(
+                                "{}  {:<8} {:>8}  {}{}",
+                                format_time(rec.started_at),
+                                format_age(rec.started_at),
+                                duration,
+                                rec.command,
+                                exit
+                            )),
+                        ]))
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                if !items.is_empty() {
+                    state.select(Some(*selected));
+                }
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(
+                        theme.selection,
+                    );
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(
+                    "Enter: view captured log  s: save  l: build logs  g: gist-logs  Esc: close",
+                )]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Resume {
+                operations,
+                cursor,
+                checked,
+            } => {
+                let area = centered_rect(60, 50, size);
+                let title = format!(
+                    "Resume {} unfinished operations from the last session",
+                    operations.len()
+                );
+                let items: Vec<ListItem> = operations
+                    .iter()
+                    .zip(checked.iter())
+                    .map(|(op, &c)| {
+                        let mark = if c { "[x]" } else { "[ ]" };
+                        ListItem::new(format!("{} {}", mark, op.command))
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*cursor));
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(
+                    "Space: toggle  Enter: run the checked ones  Esc: drop them all",
+                )]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::SnapshotDiff {
+                taken_at,
+                diff,
+                cursor,
+                checked,
+            } => {
+                let area = centered_rect(60, 60, size);
+                let title = format!("Since the snapshot of {} ({})", format_time(*taken_at), format_age(*taken_at));
+                let mut items = vec![];
+                if diff.is_empty() {
+                    items.push(ListItem::new("Nothing changed"));
+                }
+                if !diff.removed.is_empty() {
+                    items.push(ListItem::new(Span::styled(format!("Removed ({})", diff.removed.len()), theme.accent)));
+                    for ((name, version), &c) in diff.removed.iter().zip(checked.iter()) {
+                        let mark = if c { "[x]" } else { "[ ]" };
+                        items.push(ListItem::new(format!("{} {} {}", mark, name, version)));
+                    }
+                }
+                if !diff.added.is_empty() {
+                    items.push(ListItem::new(Span::styled(format!("Added ({})", diff.added.len()), theme.accent)));
+                    for (name, version) in &diff.added {
+                        items.push(ListItem::new(format!("    {} {}", name, version)));
+                    }
+                }
+                if !diff.changed.is_empty() {
+                    items.push(ListItem::new(Span::styled(format!("Changed ({})", diff.changed.len()), theme.accent)));
+                    for (name, old, new) in &diff.changed {
+                        items.push(ListItem::new(format!("    {} {} -> {}", name, old, new)));
+                    }
+                }
+                let mut state = ListState::default();
+                // the removed packages come right after their heading
+                if !diff.removed.is_empty() {
+                    state.select(Some(*cursor + 1));
+                }
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(
+                    "Space: toggle  Enter: reinstall the checked removed ones  Esc: close",
+                )]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Cleanup {
+                preview,
+                prune_all,
+                scroll,
+            } => {
+                let area = centered_rect(70, 60, size);
+                let title = match &preview.total {
+                    Some(total) => format!("Cleanup: {} in {} files", total, preview.files.len()),
+                    None => format!("Cleanup: {} files", preview.files.len()),
+                };
+                let rows = (area.height as usize).saturating_sub(4);
+                let mut lines: Vec<Spans> = preview
+                    .files
+                    .iter()
+                    .skip(*scroll)
+                    .take(rows)
+                    .map(|(path, size)| match size {
+                        Some(size) => Spans::from(vec![
+                            Span::raw(path.clone()),
+                            Span::styled(format!("  {}", size), theme.accent),
+                        ]),
+                        None => Spans::from(Span::raw(path.clone())),
+                    })
+                    .collect();
+                if preview.files.is_empty() {
+                    lines.push(Spans::from(Span::raw("Nothing to clean up.")));
+                }
+                lines.push(Spans::from(Span::raw("")));
+                let mark = if *prune_all { "[x]" } else { "[ ]" };
+                lines.push(Spans::from(Span::raw(format!(
+                    "{} --prune=all: also remove downloads of current versions",
+                    mark
+                ))));
+                let action = if *prune_all {
+                    crate::app::ConfirmAction::CleanupPruneAll
+                } else {
+                    crate::app::ConfirmAction::Cleanup
+                };
+                lines.push(Spans::from(Span::styled(
+                    format!("  $ {}", action.command_line_with("", app.brew.flags())),
+                    theme.accent,
+                )));
+                let paragraph = Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title(title));
+                f.render_widget(Clear, area);
+                f.render_widget(paragraph, area);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: scroll  a: --prune=all  Enter: run cleanup  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Doctor {
+                items,
+                selected,
+                checked,
+            } => {
+                let area = centered_rect(70, 60, size);
+                let done = checked.iter().filter(|&&c| c).count();
+                let title = format!("brew doctor: {} findings ({} done)", items.len(), done);
+                let list_items: Vec<ListItem> = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        let mark = if checked.get(i).copied().unwrap_or(false) {
+                            "[x]"
+                        } else {
+                            "[ ]"
+                        };
+                        let style = match item.severity {
+                            crate::brew::Severity::Error => theme.error,
+                            crate::brew::Severity::Warning => theme.warning,
+                        };
+                        let mut lines = vec![Spans::from(vec![
+                            Span::raw(format!("{} ", mark)),
+                            Span::styled(item.title.clone(), style),
+                        ])];
+                        // the selected finding is expanded with brew's explanation
+                        if i == *selected {
+                            lines.extend(
+                                item.details
+                                    .iter()
+                                    .map(|l| Spans::from(Span::raw(format!("    {}", l)))),
+                            );
+                        }
+                        ListItem::new(lines)
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                if !items.is_empty() {
+                    state.select(Some(*selected));
+                }
+                let list = List::new(list_items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(
+                    "Space: tick off  c: copy  Esc: close",
+                )]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Environment { env, scroll } => {
+                let area = centered_rect(70, 70, size);
+                let key_width = env
+                    .config
+                    .iter()
+                    .chain(env.env.iter())
+                    .map(|(k, _)| k.width())
+                    .max()
+                    .unwrap_or(0)
+                    .min(32);
+                let row = |k: &str, v: &str| {
+                    Spans::from(vec![
+                        Span::styled(format!("  {:<w$}  ", k, w = key_width), theme.accent),
+                        Span::raw(v.to_string()),
+                    ])
+                };
+                let mut lines: Vec<Spans> = vec![];
+                for (k, v) in env.summary() {
+                    lines.push(row(k, &v));
+                }
+                lines.push(Spans::from(Span::raw("")));
+                lines.push(Spans::from(Span::styled("brew config", theme.warning)));
+                lines.extend(env.config.iter().map(|(k, v)| row(k, v)));
+                if !env.env.is_empty() {
+                    lines.push(Spans::from(Span::raw("")));
+                    lines.push(Spans::from(Span::styled("brew --env", theme.warning)));
+                    lines.extend(env.env.iter().map(|(k, v)| row(k, v)));
+                }
+                let paragraph = Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title("Homebrew environment"))
+                    .scroll((*scroll as u16, 0));
+                f.render_widget(Clear, area);
+                f.render_widget(paragraph, area);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: scroll  a: turn analytics on/off  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Maintenance {
+                steps,
+                packages,
+                checked,
+                cursor,
+            } => {
+                let area = centered_rect(70, 60, size);
+                let selected: Vec<String> = packages
+                    .iter()
+                    .zip(checked.iter())
+                    .filter(|(_, &c)| c)
+                    .map(|(p, _)| p.clone())
+                    .collect();
+                let mut lines: Vec<Spans> = vec![Spans::from(Span::raw(
+                    "Runs these steps one after another, stopping at the first failure:",
+                ))];
+                for (i, step) in steps.iter().enumerate() {
+                    let text = match step.operation(&selected) {
+                        Some((action, name)) => {
+                            format!("  {}. {}", i + 1, action.command_line_with(&name, app.brew.flags()))
+                        }
+                        None => format!("  {}. brew upgrade (skipped: no packages selected)", i + 1),
+                    };
+                    lines.push(Spans::from(Span::styled(text, theme.accent)));
+                }
+                if steps.contains(&crate::config::MaintenanceStep::Upgrade) {
+                    lines.push(Spans::from(Span::raw("")));
+                    if packages.is_empty() {
+                        lines.push(Spans::from(Span::raw("No outdated packages to upgrade.")));
+                    } else {
+                        lines.push(Spans::from(Span::raw(format!(
+                            "Upgrade ({} of {} selected):",
+                            selected.len(),
+                            packages.len()
+                        ))));
+                    }
+                    let rows = (area.height as usize).saturating_sub(lines.len() + 4);
+                    let first = cursor.saturating_sub(rows.saturating_sub(1));
+                    for (i, pkg) in packages.iter().enumerate().skip(first).take(rows) {
+                        let pinned = app.is_pinned(pkg);
+                        let mark = if pinned {
+                            "[-]"
+                        } else if checked.get(i).copied().unwrap_or(false) {
+                            "[x]"
+                        } else {
+                            "[ ]"
+                        };
+                        let text = if pinned {
+                            format!("  {} {} {} pinned", mark, pkg, symbols.pin)
+                        } else {
+                            format!("  {} {}", mark, pkg)
+                        };
+                        lines.push(if i == *cursor {
+                            Spans::from(Span::styled(text, theme.selection))
+                        } else {
+                            Spans::from(Span::raw(text))
+                        });
+                    }
+                }
+                let paragraph = Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title("Maintenance"));
+                f.render_widget(Clear, area);
+                f.render_widget(paragraph, area);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: move  Space: toggle upgrade  Enter: run  Esc: cancel",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::DiskUsage { usage, selected } => {
+                let area = centered_rect(60, 70, size);
+                let total = usage.total();
+                let title = format!(
+                    "Disk usage: {} in {} packages",
+                    disk::format_size(total),
+                    usage.packages.len()
+                );
+                const BAR_WIDTH: u64 = 20;
+                let largest = usage.packages.first().map_or(0, |p| p.size).max(1);
+                let list_items: Vec<ListItem> = usage
+                    .packages
+                    .iter()
+                    .map(|p| {
+                        // bars are relative to the largest package, percentages to the total
+                        let cells = (p.size * BAR_WIDTH).div_ceil(largest) as usize;
+                        let percent = p.size * 100 / total.max(1);
+                        ListItem::new(Spans::from(vec![
+                            Span::styled(format!("{:>8} ", disk::format_size(p.size)), theme.accent),
+                            Span::raw(format!("{:>3}% ", percent)),
+                            Span::styled(
+                                format!("{:<w$} ", symbols.bar.repeat(cells), w = BAR_WIDTH as usize),
+                                theme.warning,
+                            ),
+                            Span::raw(p.name.clone()),
+                        ]))
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+                let list = List::new(list_items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: move  r: uninstall  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::FormulaLog {
+                name,
+                commits,
+                installed,
+                selected,
+            } => {
+                let area = centered_rect(70, 60, size);
+                // commits above the newest one that mentions the installed version are new to it
+                let bumps = |c: &crate::brew::FormulaCommit| {
+                    installed.as_deref().is_some_and(|v| c.bumps_to(name, v))
+                };
+                let newer = commits.iter().position(bumps);
+                let title = match (installed, newer) {
+                    (Some(v), Some(n)) => format!(
+                        "Tap history: {} (installed {}; {} newer commits)",
+                        name, v, n
+                    ),
+                    (Some(v), None) => format!("Tap history: {} (installed {})", name, v),
+                    (None, _) => format!("Tap history: {}", name),
+                };
+                let list_items: Vec<ListItem> = commits
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let (mark, style) = if bumps(c) {
+                            ("installed ", theme.accent)
+                        } else if newer.is_some_and(|n| i < n) {
+                            ("new       ", theme.warning)
+                        } else {
+                            ("          ", Style::default())
+                        };
+                        ListItem::new(Spans::from(vec![
+                            Span::raw(format!("{:<12} ", c.day())),
+                            Span::styled(format!("{:.7} ", c.hash), theme.accent),
+                            Span::styled(mark, style),
+                            Span::styled(c.subject.clone(), style),
+                        ]))
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+                let list = List::new(list_items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: move  c: copy commit  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Versions {
+                name,
+                versions,
+                linked,
+                selected,
+            } => {
+                let area = centered_rect(50, 40, size);
+                let list_items: Vec<ListItem> = versions
+                    .iter()
+                    .map(|v| {
+                        if Some(v) == linked.as_ref() {
+                            ListItem::new(Spans::from(vec![
+                                Span::styled(v.clone(), theme.accent),
+                                Span::styled("  (linked)", theme.accent),
+                            ]))
+                        } else {
+                            ListItem::new(Spans::from(Span::raw(v.clone())))
+                        }
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+                let list = List::new(list_items)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(format!("Installed versions: {}", name)),
+                    )
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: move  Enter: switch  d: remove  o: older  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::OlderVersions {
+                name,
+                versioned,
+                selected,
+            } => {
+                let area = centered_rect(50, 40, size);
+                let mut list_items: Vec<ListItem> = versioned
+                    .iter()
+                    .map(|v| {
+                        let installed = app.items.iter().any(|f| &f.name == v);
+                        let mark = if installed { "  (installed)" } else { "" };
+                        ListItem::new(Spans::from(vec![
+                            Span::raw(v.clone()),
+                            Span::styled(mark, theme.accent),
+                        ]))
+                    })
+                    .collect();
+                list_items.push(ListItem::new(Spans::from(Span::styled(
+                    "Another version (brew extract)...",
+                    theme.warning,
+                ))));
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+                let list = List::new(list_items)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(format!("Older versions: {}", name)),
+                    )
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: move  Enter: install  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Menu {
+                name,
+                items,
+                selected,
+            } => {
+                let area = centered_rect(40, 50, size);
+                let rows: Vec<(String, String)> = items
+                    .iter()
+                    .map(|item| {
+                        let keys = match item {
+                            MenuItem::Run(action) => app.keymap.keys_for(*action).join(" / "),
+                            _ => String::new(),
+                        };
+                        (app.menu_label(item, name), keys)
+                    })
+                    .collect();
+                let width = rows.iter().map(|(l, _)| l.chars().count()).max().unwrap_or(0);
+                let list_items: Vec<ListItem> = rows
+                    .into_iter()
+                    .map(|(label, keys)| {
+                        ListItem::new(Spans::from(vec![
+                            Span::raw(format!("{:<w$}  ", label, w = width)),
+                            Span::styled(keys, theme.accent),
+                        ]))
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+                let list = List::new(list_items)
+                    .block(Block::default().borders(Borders::ALL).title(format!("Actions: {}", name)))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: move  Enter: run  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Related {
+                name,
+                dependents,
+                packages,
+                selected,
+            } => {
+                let area = centered_rect(50, 50, size);
+                let list_items: Vec<ListItem> = packages
+                    .iter()
+                    .map(|p| {
+                        let installed = app.items.iter().any(|f| &f.name == p);
+                        let mark = if installed && !*dependents { "  (installed)" } else { "" };
+                        ListItem::new(Spans::from(vec![
+                            Span::raw(p.clone()),
+                            Span::styled(mark, theme.accent),
+                        ]))
+                    })
+                    .collect();
+                let title = if *dependents {
+                    format!("Installed formulae using {}", name)
+                } else {
+                    format!("Dependencies of {}", name)
+                };
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+                let list = List::new(list_items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: move  Enter: jump to it  Esc: back",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Livecheck { results, selected } => {
+                let area = centered_rect(70, 60, size);
+                let lagging = results.iter().filter(|r| r.lags()).count();
+                let title = format!(
+                    "Livecheck: {} formulae, {} behind upstream",
+                    results.len(),
+                    lagging
+                );
+                let width = results.iter().map(|r| r.formula.len()).max().unwrap_or(0);
+                let list_items: Vec<ListItem> = results
+                    .iter()
+                    .map(|r| {
+                        let name = Span::raw(format!("{:<w$}  ", r.formula, w = width));
+                        if let Some(status) = &r.status {
+                            let reason = r.messages.first().map(String::as_str).unwrap_or("");
+                            return ListItem::new(Spans::from(vec![
+                                name,
+                                Span::styled(format!("{}: {}", status, reason), theme.error),
+                            ]));
+                        }
+                        let (mark, style) = if r.lags() {
+                            ("brew lags upstream", theme.warning)
+                        } else if r.newer_than_upstream {
+                            ("newer than upstream", theme.accent)
+                        } else {
+                            ("up to date", Style::default())
+                        };
+                        ListItem::new(Spans::from(vec![
+                            name,
+                            Span::raw(format!(
+                                "brew {:<12} upstream {:<12} ",
+                                r.current.as_deref().unwrap_or("?"),
+                                r.latest.as_deref().unwrap_or("?")
+                            )),
+                            Span::styled(mark, style),
+                        ]))
+                    })
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+                let list = List::new(list_items)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .highlight_style(theme.selection);
+                f.render_widget(Clear, area);
+                f.render_stateful_widget(list, area, &mut state);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: move  c: copy  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::DownloadCache {
+                older_than_days,
+                scroll,
+            } => {
+                let Some(cache) = &app.download_cache else {
+                    return;
+                };
+                let area = centered_rect(70, 60, size);
+                let now = disk::now_secs();
+                let title = format!(
+                    "Download cache: {} in {} files",
+                    disk::format_size(cache.total()),
+                    cache.entries.len()
+                );
+                let (old_bytes, old_files) = cache.reclaimable(Some(*older_than_days), now);
+                let mut lines = vec![
+                    Spans::from(Span::styled(cache.dir.display().to_string(), theme.accent)),
+                    Spans::from(Span::raw(format!(
+                        "Older than {} days: {} in {} files",
+                        older_than_days,
+                        disk::format_size(old_bytes),
+                        old_files
+                    ))),
+                    Spans::from(Span::raw("")),
+                ];
+                let rows = (area.height as usize).saturating_sub(4 + lines.len());
+                lines.extend(cache.entries.iter().skip(*scroll).take(rows).map(|e| {
+                    let path = e.path.strip_prefix(&cache.dir).unwrap_or(&e.path);
+                    Spans::from(vec![
+                        Span::styled(format!("{:>8}  ", disk::format_size(e.size)), theme.accent),
+                        Span::raw(format!("{:>9}  ", format_age(e.modified))),
+                        Span::raw(path.display().to_string()),
+                    ])
+                }));
+                if cache.entries.is_empty() {
+                    lines.push(Spans::from(Span::raw("The download cache is empty.")));
+                }
+                let paragraph = Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title(title));
+                f.render_widget(Clear, area);
+                f.render_widget(paragraph, area);
+                let footer_area = Rect {
+                    x: area.x,
+                    y: area.y + area.height - 1,
+                    width: area.width,
+                    height: 1,
+                };
+                let footer = Paragraph::new(Spans::from(vec![Span::raw(format!(
+                    "{}/{}: scroll  a: purge all  o: purge older  +/-: days  Esc: close",
+                    symbols.up, symbols.down
+                ))]))
+                .alignment(Alignment::Center);
+                f.render_widget(footer, footer_area);
+            }
+            Mode::Input { action, buffer } => {
+                // render a small, single-line input (like a password/short text field)
+                let area = centered_rect(40, 10, size);
+                let title = match action {
+                    crate::app::InputAction::Install => "Install package".to_string(),
+                    crate::app::InputAction::Search => "Search packages".to_string(),
+                    crate::app::InputAction::LogSearch => "Search the log".to_string(),
+                    crate::app::InputAction::ExtractVersion { name } => {
+                        format!("Version of {} to extract", name)
+                    }
+                    crate::app::InputAction::Note { name } => format!("Note on {}", name),
+                    crate::app::InputAction::OperationEnv => {
+                        "Environment for this operation (KEY=VALUE ...)".to_string()
+                    }
+                };
+                let text = buffer.clone();
+                // display the buffer inline
+                let paragraph = Paragraph::new(text)
+                    .block(Block::default().borders(Borders::ALL).title(title))
+                    .alignment(Alignment::Center);
+                f.render_widget(Clear, area);
+                f.render_widget(paragraph, area);
             }
             _ => {}
         }
+        if let Some((text, _)) = &app.toast {
+            draw_toast(f, app, text, size);
+        }
+        if app.flash_until.is_some() {
+            f.render_widget(Flash, size);
+        }
     })?;
     Ok(())
 }
 
+/// Where a toast showing `text` goes: the top-right corner of `size`, as wide as the text
+/// allows.
+pub fn toast_rect(size: Rect, text: &str) -> Rect {
+    let width = (text.chars().count() as u16 + 4).min(size.width);
+    Rect {
+        x: size.right().saturating_sub(width),
+        y: size.y,
+        width,
+        height: 3.min(size.height),
+    }
+}
+
+/// Inverts everything drawn below it, for `[notify] bell = "flash"`.
+struct Flash;
+
+impl Widget for Flash {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, Style::default().add_modifier(Modifier::REVERSED));
+    }
+}
+
+fn draw_toast<B: Backend>(f: &mut Frame<B>, app: &App, text: &str, size: Rect) {
+    let area = toast_rect(size, text);
+    let paragraph = Paragraph::new(Span::styled(text.to_string(), app.theme.success))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// The Logs and Status panes below the main area.
+fn draw_bottom<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect, spin: &str) {
+    let theme = &app.theme;
+
+    // bottom: logs and status
+    let bottom_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
+        .split(area);
+
+    draw_logs(f, app, bottom_chunks[0], None);
+
+    // status
+    // Operation progress (gauge when percent available) above status
+    let right_bottom = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+        .split(bottom_chunks[1]);
+
+    // while an operation runs in the background its command doubles as the pane title
+    let op_title = match &app.current_op {
+        Some(t) => format!("Op: {} (O: log)", t),
+        None => "Op Progress".to_string(),
+    };
+    if let Some(pct) = app.operation_percent {
+        // render a Gauge with animated label
+        use ratatui::widgets::Gauge;
+        let ratio = (pct as f64) / 100.0;
+        let label = if app.operating {
+            format!("{}% {}", pct, spin)
+        } else {
+            format!("{}%", pct)
+        };
+        let gauge = Gauge::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(op_title.clone()),
+            )
+            .gauge_style(theme.gauge)
+            .label(label)
+            .ratio(ratio);
+        f.render_widget(gauge, right_bottom[0]);
+    } else if !app.waiting_locks.is_empty() {
+        let waiting = Paragraph::new(Spans::from(vec![Span::styled(
+            format!(
+                "{} waiting for other brew process ({})",
+                spin,
+                app.waiting_locks.join(", ")
+            ),
+            theme.warning,
+        )]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(op_title.clone()),
+        )
+        .alignment(Alignment::Left);
+        f.render_widget(waiting, right_bottom[0]);
+    } else {
+        let mut op_progress = app.operation_status.clone().unwrap_or_default();
+        if app.operating {
+            op_progress = format!("{} {}", spin, op_progress);
+        }
+        let op_paragraph = Paragraph::new(Spans::from(vec![Span::raw(op_progress)]))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(op_title.clone()),
+            )
+            .alignment(Alignment::Left);
+        f.render_widget(op_paragraph, right_bottom[0]);
+    }
+
+    // Status: render multi-line status so fields wrap and are readable
+    let mut status_lines: Vec<Spans> = vec![];
+    status_lines.push(Spans::from(Span::raw(format!(
+        "Installed: {}  Available: {}",
+        app.items.len(),
+        app.available_items.len()
+    ))));
+    let focus_str = if app.focus == crate::app::Focus::Installed {
+        "Installed"
+    } else {
+        "Available"
+    };
+    status_lines.push(Spans::from(Span::raw(format!("Focus: {}", focus_str))));
+
+    // show if there are updates/upgrades available for installed packages
+    let updates_count = app.outdated_items.len();
+    if updates_count > 0 {
+        // show a short preview (first 5 names) to avoid overflowing the status pane
+        let preview: Vec<String> = app.outdated_items.iter().take(5).cloned().collect();
+        let preview_str = if preview.is_empty() {
+            "".to_string()
+        } else if preview.len() == 5 {
+            format!("{}...", preview.join(", "))
+        } else {
+            preview.join(", ")
+        };
+        status_lines.push(Spans::from(Span::styled(
+            format!("Updates available: {} {}", updates_count, preview_str),
+            theme.warning,
+        )));
+    } else {
+        status_lines.push(Spans::from(Span::raw("Updates available: 0")));
+    }
+
+    // mode and logs
+    let mode_str = match &app.mode {
+        Mode::Normal => "Normal".to_string(),
+        Mode::Help { .. } => "Help".to_string(),
+        Mode::Input { action, .. } => match action {
+            crate::app::InputAction::Install => "Input(Install)".to_string(),
+            crate::app::InputAction::Search => "Input(Search)".to_string(),
+            crate::app::InputAction::LogSearch => "Input(LogSearch)".to_string(),
+            crate::app::InputAction::ExtractVersion { .. } => "Input(ExtractVersion)".to_string(),
+            crate::app::InputAction::Note { .. } => "Input(Note)".to_string(),
+            crate::app::InputAction::OperationEnv => "Input(OperationEnv)".to_string(),
+        },
+        Mode::Confirm { action, name, .. } if action.acts_on_package() => {
+            format!("Confirm {} {}", action.title(), name)
+        }
+        Mode::Confirm { action, .. } => format!("Confirm {}", action.title()),
+        Mode::Flags { action, .. } => format!("Flags {}", action.title()),
+        Mode::SearchResults { results, selected } => {
+            format!("SearchResults {} results (sel {})", results.len(), selected)
+        }
+        Mode::InstallCandidates {
+            query,
+            names,
+            selected,
+        } => format!(
+            "InstallCandidates {} {} (sel {})",
+            query,
+            names.len(),
+            selected
+        ),
+        Mode::Outdated {
+            packages, cursor, ..
+        } => {
+            format!("Outdated {} packages (sel {})", packages.len(), cursor)
+        }
+        Mode::Operation { title, logs, .. } => {
+            format!("Operation: {} ({} lines)", title, logs.len())
+        }
+        Mode::History { .. } => format!("History ({} operations)", app.history.records.len()),
+        Mode::Resume { operations, .. } => format!("Resume ({} operations)", operations.len()),
+        Mode::SnapshotDiff { .. } => "Snapshot diff".into(),
+        Mode::Cleanup { preview, .. } => format!("Cleanup ({} files)", preview.files.len()),
+        Mode::Doctor { items, .. } => format!("Doctor ({} findings)", items.len()),
+        Mode::Environment { .. } => "Environment".to_string(),
+        Mode::DownloadCache { .. } => "Download cache".to_string(),
+        Mode::Maintenance { steps, .. } => format!("Maintenance ({} steps)", steps.len()),
+        Mode::DiskUsage { usage, .. } => format!("Disk usage ({} packages)", usage.packages.len()),
+        Mode::FormulaLog { name, commits, .. } => {
+            format!("Formula history {} ({} commits)", name, commits.len())
+        }
+        Mode::Livecheck { results, .. } => format!("Livecheck ({})", results.len()),
+        Mode::Versions { name, versions, .. } => {
+            format!("Versions {} ({} installed)", name, versions.len())
+        }
+        Mode::OlderVersions {
+            name, versioned, ..
+        } => {
+            format!("OlderVersions {} ({} versioned)", name, versioned.len())
+        }
+        Mode::Menu { name, .. } => format!("Menu {}", name),
+        Mode::Related { name, packages, .. } => {
+            format!("Related {} ({} packages)", name, packages.len())
+        }
+    };
+    status_lines.push(Spans::from(Span::raw(format!(
+        "Mode: {}  Logs: {}",
+        mode_str,
+        app.logs.len()
+    ))));
+
+    if let Some(ts) = app.last_refreshed {
+        if let Ok(dur) = ts.elapsed() {
+            let secs = dur.as_secs();
+            status_lines.push(Spans::from(Span::raw(format!("refreshed {}s ago", secs))));
+        }
+    }
+    if let Some(at) = app.index_fresh_at {
+        status_lines.push(Spans::from(Span::raw(format!(
+            "data fresh as of {} UTC ({})",
+            format_time(at),
+            format_age(at)
+        ))));
+    }
+
+    let status = Paragraph::new(status_lines)
+        .block(Block::default().borders(Borders::ALL).title("Status"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(status, right_bottom[1]);
+}
+
+/// Log lines, newest first: the most recent 100, or with `zoomed` every retained line from
+/// the given one on.
+fn draw_logs<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect, zoomed: Option<usize>) {
+    let theme = &app.theme;
+    let lines = app.visible_logs();
+    let filter = app.log_filter_label();
+    let (skip, take, title) = match zoomed {
+        Some(skip) => (skip, lines.len(), format!("Logs ({})", lines.len())),
+        None => (0, 100, "Logs (recent)".to_string()),
+    };
+    let title = if filter.is_empty() {
+        title
+    } else {
+        format!("{} [{}]", title, filter)
+    };
+    let logs: Vec<ListItem> = lines
+        .iter()
+        .rev()
+        .skip(skip)
+        .take(take)
+        .map(|l| ListItem::new(Span::styled(l.message.clone(), log_entry_style(theme, l))))
+        .collect();
+    let logs_block = List::new(logs).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(logs_block, area);
+    if zoomed.is_some() {
+        let rows = area.height.saturating_sub(2) as usize;
+        let thumb = app.symbols.scroll_thumb;
+        draw_scrollbar(f, area, (skip, rows, lines.len()), thumb, theme.accent);
+    }
+}
+
+/// The Formulae tab: Installed | Available | Details.
+fn draw_formulae<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect, spin: &str) {
+    // three-column layout: Installed | Available | Details
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(app.layout.columns[0]),
+                Constraint::Percentage(app.layout.columns[1]),
+                Constraint::Percentage(app.layout.columns[2]),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+    draw_installed(f, app, main_chunks[0], spin);
+    draw_available(f, app, main_chunks[1], spin);
+    draw_details(f, app, main_chunks[2], 0);
+}
+
+/// The Installed table.
+fn draw_installed<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect, spin: &str) {
+    let theme = &app.theme;
+    let symbols = app.symbols;
+    // table: only the rows that fit under the header are built (see `scroll_offset`)
+    let rows = area.height.saturating_sub(3) as usize;
+    app.installed_offset = scroll_offset(app.selected, app.installed_offset, rows, app.items.len());
+    let sort = app.layout.installed_sort;
+    let arrow = if sort.descending {
+        symbols.down
+    } else {
+        symbols.up
+    };
+    let columns = installed_columns(app, area.width.saturating_sub(2));
+    let header = Row::new(columns.iter().map(|(c, _)| {
+        if *c == sort.column {
+            Cell::from(format!("{}{}", c.title(), arrow))
+        } else {
+            Cell::from(c.title())
+        }
+    }))
+    .style(theme.accent);
+    // the names load first; versions and taps follow with `brew info`
+    let pending = || {
+        Cell::from(Span::styled(
+            app.symbols.placeholder.repeat(5),
+            Style::default().add_modifier(Modifier::DIM),
+        ))
+    };
+    let table_rows: Vec<Row> = app
+        .items
+        .iter()
+        .skip(app.installed_offset)
+        .take(rows)
+        .map(|i| {
+            Row::new(columns.iter().map(|(c, _)| {
+                match c {
+                    // while packages are marked every row shows its checkbox
+                    SortColumn::Name if app.marked.is_empty() => Cell::from(i.name.clone()),
+                    SortColumn::Name => {
+                        let mark = if app.marked.contains(&i.name) {
+                            "[x]"
+                        } else {
+                            "[ ]"
+                        };
+                        Cell::from(format!("{} {}", mark, i.name))
+                    }
+                    SortColumn::Version => match i.installed_version() {
+                        None if app.loading_details => pending(),
+                        _ => Cell::from(version_text(
+                            i,
+                            app.outdated_items.contains(&i.name),
+                            symbols.arrow,
+                        )),
+                    },
+                    SortColumn::Size => Cell::from(
+                        app.installed_sizes
+                            .get(&i.name)
+                            .map(|&b| disk::format_size(b))
+                            .unwrap_or_default(),
+                    ),
+                    SortColumn::Installed => match i.installed_time() {
+                        None if app.loading_details => pending(),
+                        t => Cell::from(t.map(format_age).unwrap_or_default()),
+                    },
+                    SortColumn::Tap => match i.tap_name() {
+                        None if app.loading_details => pending(),
+                        t => Cell::from(short_tap(t.unwrap_or("")).to_string()),
+                    },
+                    SortColumn::Pinned if app.is_pinned(&i.name) => {
+                        Cell::from(Span::styled(symbols.pin, theme.warning))
+                    }
+                    SortColumn::Outdated if app.outdated_items.contains(&i.name) => {
+                        Cell::from(Span::styled(symbols.up, theme.warning))
+                    }
+                    SortColumn::Pinned | SortColumn::Outdated => Cell::from(""),
+                }
+            }))
+            .style(if app.outdated_items.contains(&i.name) {
+                theme.warning
+            } else {
+                Style::default()
+            })
+        })
+        .collect();
+    let mut state = TableState::default();
+    if !app.items.is_empty() {
+        state.select(Some(app.selected - app.installed_offset));
+    }
+
+    let focused = if app.focus == crate::app::Focus::Installed {
+        " (focused)"
+    } else {
+        ""
+    };
+    let sorted = if sort == Default::default() {
+        String::new()
+    } else {
+        format!(" by {}{}", sort.column.title(), arrow)
+    };
+    let marked = if app.marked.is_empty() {
+        String::new()
+    } else {
+        format!(", {} marked", app.marked.len())
+    };
+    let installed_title = if app.loading_installed {
+        format!("Installed{}{}{} {}", focused, sorted, marked, spin)
+    } else {
+        format!("Installed{}{}{}", focused, sorted, marked)
+    };
+    if app.loading_installed && app.items.is_empty() {
+        let note = loading_note(app, "installed packages");
+        draw_placeholder(f, app, area, installed_title, note);
+        return;
+    }
+    let widths: Vec<Constraint> = columns.iter().map(|(_, w)| *w).collect();
+    let table = Table::new(table_rows)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(installed_title),
+        )
+        .widths(&widths)
+        .column_spacing(1)
+        .highlight_style(theme.selection);
+    f.render_stateful_widget(table, area, &mut state);
+    // the scrollbar runs alongside the rows, below the header
+    let body = Rect {
+        y: area.y + 1,
+        height: area.height.saturating_sub(1),
+        ..area
+    };
+    let window = (app.installed_offset, rows, app.items.len());
+    draw_scrollbar(f, body, window, symbols.scroll_thumb, theme.accent);
+}
+
+/// The Available list.
+fn draw_available<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect, spin: &str) {
+    let theme = &app.theme;
+    let symbols = app.symbols;
+    // available list (middle column)
+    // build a quick set of installed names for marking
+    let installed_names: std::collections::HashSet<&str> =
+        app.items.iter().map(|f| f.name.as_str()).collect();
+
+    // find the position in the filtered list that corresponds to available_selected
+    let filtered_sel = app
+        .available_filtered
+        .iter()
+        .position(|&idx| idx == app.available_selected);
+    let rows = area.height.saturating_sub(2) as usize;
+    app.available_offset = scroll_offset(
+        filtered_sel.unwrap_or(0),
+        app.available_offset,
+        rows,
+        app.available_filtered.len(),
+    );
+
+    // Render only the visible slice of the filtered available items; heads of versioned
+    // formulae carry an expand marker and their listed versions are indented below them
+    let listed_versions: std::collections::HashSet<usize> = app
+        .available_groups
+        .iter()
+        .filter(|(head, _)| {
+            let base = crate::brew::formula_base(&app.available_items[**head]);
+            app.available_expanded.contains(base)
+        })
+        .flat_map(|(_, members)| members.iter().copied())
+        .collect();
+    let avail_items: Vec<ListItem> = app
+        .available_filtered
+        .iter()
+        .skip(app.available_offset)
+        .take(rows)
+        .filter_map(|&idx| app.available_items.get(idx).map(|name| (idx, name)))
+        .map(|(idx, name)| {
+            let expanded = app
+                .available_expanded
+                .contains(crate::brew::formula_base(name));
+            let mut text = match app.available_groups.get(&idx) {
+                Some(_) if expanded => format!("{} {}", symbols.expanded, name),
+                Some(members) => {
+                    format!("{} {} (+{})", symbols.collapsed, name, members.len())
+                }
+                None if listed_versions.contains(&idx) => format!("    {}", name),
+                None => name.clone(),
+            };
+            if installed_names.contains(name.as_str()) {
+                text.push_str(" (Installed)");
+            }
+            if app.favorites.contains(name) {
+                text = format!("{} {}", symbols.favorite, text);
+            }
+            ListItem::new(Spans::from(vec![Span::raw(text)]))
+        })
+        .collect();
+    let mut avail_state = ListState::default();
+    if let Some(pos) = filtered_sel {
+        avail_state.select(Some(pos - app.available_offset));
+    }
+    let focused = if app.focus == crate::app::Focus::Available {
+        " (focused)"
+    } else {
+        ""
+    };
+    let sorted = match app.layout.list_sort {
+        ListSort::Name => String::new(),
+        sort => format!(" by {}", sort.title()),
+    };
+    let available_title = if app.loading_available {
+        format!(
+            "Available ({}){}{} {}",
+            app.available_items.len(),
+            focused,
+            sorted,
+            spin
+        )
+    } else {
+        format!(
+            "Available ({}){}{}",
+            app.available_items.len(),
+            focused,
+            sorted
+        )
+    };
+    if app.loading_available && app.available_items.is_empty() {
+        let note = loading_note(app, "available packages");
+        draw_placeholder(f, app, area, available_title, note);
+        return;
+    }
+    let available_list = List::new(avail_items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(available_title),
+        )
+        .highlight_style(theme.selection_alt);
+    f.render_stateful_widget(available_list, area, &mut avail_state);
+    let window = (app.available_offset, rows, app.available_filtered.len());
+    draw_scrollbar(f, area, window, symbols.scroll_thumb, theme.accent);
+}
+
+/// The first lines of a package's details: name, description, homepage, license, install counts
+/// and the stats of its GitHub repository once they are known (in the warning color when it
+/// looks abandoned).
+fn detail_header(app: &App, f: &FormulaInfo) -> Vec<Spans<'static>> {
+    let mut lines = vec![Spans::from(Span::raw(f.name.clone()))];
+    if let Some(full) = &f.full_name {
+        lines.push(Spans::from(Span::raw(format!("full: {}", full))));
+    }
+    if let Some(desc) = &f.desc {
+        lines.push(Spans::from(Span::raw(""))); // spacer
+        lines.push(Spans::from(Span::raw(desc.clone())));
+    }
+    if let Some(h) = &f.homepage {
+        lines.push(Spans::from(Span::raw(format!("homepage: {}", h))));
+    }
+    if let Some(l) = &f.license {
+        lines.push(Spans::from(Span::raw(format!("license: {}", l))));
+    }
+    if let Some([month, quarter, year]) = f.install_counts() {
+        let count = crate::github::format_count;
+        lines.push(Spans::from(Span::raw(format!(
+            "installs: {} (30d), {} (90d), {} (365d)",
+            count(month),
+            count(quarter),
+            count(year)
+        ))));
+    }
+    let stats = crate::github::repo_of(f).and_then(|r| app.github_stats.get(&r).cloned()?);
+    if let Some(stats) = stats {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let style = if stats.is_stale(now) {
+            app.theme.warning
+        } else {
+            Style::default()
+        };
+        lines.push(Spans::from(Span::styled(
+            format!("github: {}", stats.summary(now)),
+            style,
+        )));
+    }
+    if let Some(note) = app.notes.get(&f.name) {
+        lines.push(Spans::from(Span::styled(
+            format!("note: {}", note),
+            app.theme.accent,
+        )));
+    }
+    lines
+}
+
+/// Line of the details of `f` (before wrapping) showing its dependency `idx`, for scrolling
+/// the zoomed Details pane to it.
+pub fn dependency_row(app: &App, f: &FormulaInfo, idx: usize) -> usize {
+    // the header, a blank line and "dependencies:"
+    detail_header(app, f).len() + 2 + idx
+}
+
+/// The dependencies of `f`, each marked installed (success) or missing (error); in the zoomed
+/// Details pane the one Enter jumps to is highlighted.
+fn dependency_lines(app: &App, f: &FormulaInfo) -> Vec<Spans<'static>> {
+    if f.dependencies.is_empty() {
+        return vec![];
+    }
+    let theme = &app.theme;
+    let symbols = app.symbols;
+    let cursor = (app.zoom == Some(Zoom::Details)).then_some(app.details_dep);
+    let mut lines = vec![
+        Spans::from(Span::raw("")),
+        Spans::from(Span::raw("dependencies:")),
+    ];
+    for (i, d) in f.dependencies.iter().enumerate() {
+        let (mark, style, note) = if app.items.iter().any(|i| &i.name == d) {
+            (symbols.success, theme.success, "")
+        } else {
+            (symbols.failure, theme.error, " (missing)")
+        };
+        let style = if cursor == Some(i) {
+            theme.selection
+        } else {
+            style
+        };
+        lines.push(Spans::from(Span::styled(
+            format!("  {} {}{}", mark, d, note),
+            style,
+        )));
+    }
+    lines
+}
+
+/// Details of the selected package in the focused list, from line `scroll` on.
+fn draw_details<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect, scroll: u16) {
+    let theme = &app.theme;
+    // details (right column) — show full details for the currently-focused selection
+    let detail = match app.focus {
+        crate::app::Focus::Installed => {
+            if let Some(sel) = app.items.get(app.selected) {
+                let mut lines = detail_header(app, sel);
+                lines.extend(dependency_lines(app, sel));
+                if !sel.installed.is_empty() {
+                    lines.push(Spans::from(Span::raw("")));
+                    lines.push(Spans::from(Span::raw("installed:")));
+                    for inst in sel.installed.iter() {
+                        lines.push(installed_version_line(theme, sel, &inst.version));
+                    }
+                    lines.push(linked_status_line(theme, sel));
+                    if sel.installed.len() > 1 {
+                        lines.push(Spans::from(Span::styled(
+                            format!(
+                                "{} versions installed; V switches or removes them",
+                                sel.installed.len()
+                            ),
+                            theme.warning,
+                        )));
+                    }
+                    if app.is_pinned(&sel.name) {
+                        lines.push(Spans::from(Span::styled(
+                            "pinned: yes (upgrades skip it; p unpins)",
+                            theme.warning,
+                        )));
+                    }
+                }
+                if let Some(c) = &sel.caveats {
+                    if !c.trim().is_empty() {
+                        lines.push(Spans::from(Span::raw("")));
+                        lines.push(Spans::from(Span::raw("caveats:")));
+                        for l in c.lines() {
+                            lines.push(Spans::from(Span::raw(format!("  {}", l))));
+                        }
+                    }
+                }
+
+                Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title("Details"))
+                    .wrap(Wrap { trim: false })
+            } else {
+                Paragraph::new("No package selected")
+                    .block(Block::default().borders(Borders::ALL).title("Details"))
+            }
+        }
+        crate::app::Focus::Available => {
+            let selected_name = app.available_items.get(app.available_selected);
+            // details still loading for a new selection show the fallback, not the old row
+            let details = app
+                .available_details
+                .as_ref()
+                .filter(|d| Some(&d.name) == selected_name);
+            if let Some(details) = details {
+                let mut lines = detail_header(app, details);
+                lines.extend(dependency_lines(app, details));
+                if !details.installed.is_empty() {
+                    lines.push(Spans::from(Span::raw("")));
+                    lines.push(Spans::from(Span::raw("installed:")));
+                    for inst in details.installed.iter() {
+                        lines.push(installed_version_line(theme, details, &inst.version));
+                    }
+                    lines.push(linked_status_line(theme, details));
+                }
+                if let Some(c) = &details.caveats {
+                    if !c.trim().is_empty() {
+                        lines.push(Spans::from(Span::raw("")));
+                        lines.push(Spans::from(Span::raw("caveats:")));
+                        for l in c.lines() {
+                            lines.push(Spans::from(Span::raw(format!("  {}", l))));
+                        }
+                    }
+                }
+
+                Paragraph::new(lines)
+                    .block(Block::default().borders(Borders::ALL).title("Details"))
+                    .wrap(Wrap { trim: false })
+            } else if let Some(name) = selected_name {
+                let desc = app
+                    .available_desc
+                    .get(name)
+                    .map(String::as_str)
+                    .unwrap_or("(no details loaded)");
+                let note = app
+                    .notes
+                    .get(name)
+                    .map(|n| format!("\n\nnote: {}", n))
+                    .unwrap_or_default();
+                Paragraph::new(format!("{}\n\n{}{}", name, desc, note))
+                    .block(Block::default().borders(Borders::ALL).title("Details"))
+            } else {
+                Paragraph::new("No package selected")
+                    .block(Block::default().borders(Borders::ALL).title("Details"))
+            }
+        }
+    };
+    f.render_widget(detail.scroll((scroll, 0)), area);
+}
+
+/// A tab other than Formulae: one list filling the area.
+fn draw_tab<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect, spin: &str) {
+    let theme = &app.theme;
+    let symbols = app.symbols;
+    let rows = area.height.saturating_sub(2) as usize;
+    let len = app.tab_len();
+    // wrapped Logs lines are cut to the inside of the borders
+    let wrap =
+        (app.tab == Tab::Logs && app.log_wrap).then(|| area.width.saturating_sub(2) as usize);
+    app.tab_offset = match wrap {
+        Some(width) => {
+            let heights: Vec<usize> = app
+                .visible_logs()
+                .iter()
+                .map(|l| wrap_ranges(&l.message, width).len())
+                .collect();
+            wrapped_offset(app.tab_selected, app.tab_offset, rows, &heights)
+        }
+        None => scroll_offset(app.tab_selected, app.tab_offset, rows, len),
+    };
+    let width = |names: &mut dyn Iterator<Item = &String>| {
+        names
+            .map(|n| UnicodeWidthStr::width(n.as_str()))
+            .max()
+            .unwrap_or(0)
+    };
+    let (items, empty): (Vec<ListItem>, &str) = match app.tab {
+        Tab::Formulae => (vec![], ""),
+        Tab::Casks => {
+            let w = width(&mut app.casks.iter().map(|c| &c.name));
+            let items = app
+                .casks
+                .iter()
+                .map(|c| ListItem::new(format!("{:<w$}  {}", c.name, c.version, w = w)))
+                .collect();
+            (items, "No casks installed")
+        }
+        Tab::Outdated => {
+            let w = width(&mut app.outdated_items.iter());
+            let items = app
+                .outdated_items
+                .iter()
+                .map(|name| {
+                    let version = app
+                        .items
+                        .iter()
+                        .find(|f| &f.name == name)
+                        .and_then(|f| f.installed_version())
+                        .unwrap_or("");
+                    let text = format!("{:<w$}  {}", name, version, w = w);
+                    if app.is_pinned(name) {
+                        ListItem::new(Spans::from(vec![
+                            Span::raw(text),
+                            Span::styled(format!(" {} pinned", symbols.pin), theme.warning),
+                        ]))
+                    } else {
+                        ListItem::new(text)
+                    }
+                })
+                .collect();
+            (items, "Everything is up to date")
+        }
+        Tab::Services => {
+            let w = width(&mut app.services.iter().map(|s| &s.name));
+            let items = app
+                .services
+                .iter()
+                .map(|s| {
+                    let style = match s.status.as_str() {
+                        "started" | "scheduled" => theme.success,
+                        "error" => theme.error,
+                        _ => Style::default(),
+                    };
+                    let mut spans = vec![
+                        Span::raw(format!("{:<w$}  ", s.name, w = w)),
+                        Span::styled(format!("{:<9}", s.status), style),
+                    ];
+                    if let Some(user) = s.user.as_deref().filter(|u| !u.is_empty()) {
+                        spans.push(Span::raw(format!(" {}", user)));
+                    }
+                    ListItem::new(Spans::from(spans))
+                })
+                .collect();
+            (items, "No formula with a service is installed")
+        }
+        Tab::Taps => {
+            let items = app.taps.iter().map(|t| ListItem::new(t.as_str())).collect();
+            (items, "No taps")
+        }
+        Tab::Logs => {
+            let found = theme.accent.add_modifier(Modifier::REVERSED);
+            // the lines of a range being picked for copying
+            let range = app.log_anchor.map(|_| app.log_range());
+            let items = app
+                .visible_logs()
+                .into_iter()
+                .enumerate()
+                .map(|(i, l)| {
+                    let mut style = log_entry_style(theme, l);
+                    if range.as_ref().is_some_and(|r| r.contains(&i)) {
+                        style = style.patch(theme.selection_alt);
+                    }
+                    log_tab_item(l, &app.log_search, wrap, style, found)
+                })
+                .collect();
+            (items, "No log lines (yet, or matching the filter)")
+        }
+    };
+    let mut filter = match app.tab {
+        Tab::Logs if !app.log_filter_label().is_empty() => format!(" [{}]", app.log_filter_label()),
+        _ => String::new(),
+    };
+    if app.tab == Tab::Logs && !app.log_search.is_empty() {
+        filter.push_str(&format!(
+            " /{} ({} found)",
+            app.log_search,
+            app.log_matches().len()
+        ));
+    }
+    if app.tab == Tab::Logs && app.log_anchor.is_some() {
+        filter.push_str(&format!(
+            " {} lines picked, c copies",
+            app.log_range().count()
+        ));
+    }
+    let title = if app.loading_tab == Some(app.tab) {
+        format!("{} ({}) {}", app.tab.title(), len, spin)
+    } else {
+        format!("{} ({}){}", app.tab.title(), len, filter)
+    };
+    let block = Block::default().borders(Borders::ALL).title(title);
+    if items.is_empty() {
+        let text = if app.loading_tab == Some(app.tab) {
+            "Loading..."
+        } else {
+            empty
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+    let visible: Vec<ListItem> = items.into_iter().skip(app.tab_offset).take(rows).collect();
+    let mut state = ListState::default();
+    state.select(Some(app.tab_selected.min(len - 1) - app.tab_offset));
+    let list = List::new(visible)
+        .block(block)
+        .highlight_style(theme.selection);
+    f.render_stateful_widget(list, area, &mut state);
+    let window = (app.tab_offset, rows, len);
+    draw_scrollbar(f, area, window, symbols.scroll_thumb, theme.accent);
+}
+
+/// stderr output is highlighted so warnings and errors stand out inside long install logs.
+fn log_line_style(theme: &Theme, line: &crate::output::LogLine) -> Style {
+    match line.level() {
+        Level::Info => Style::default(),
+        Level::Warning => theme.warning,
+        Level::Error => theme.error,
+    }
+}
+
+/// A line of the Logs tab: `entry` in `style` with the occurrences of `query` in `found`, split
+/// into rows `wrap` columns wide when wrapping.
+fn log_tab_item(
+    entry: &LogEntry,
+    query: &str,
+    wrap: Option<usize>,
+    style: Style,
+    found: Style,
+) -> ListItem<'static> {
+    let text = entry.message.as_str();
+    let matches = match_ranges(text, query);
+    let pieces = match wrap {
+        Some(width) => wrap_ranges(text, width),
+        None => std::iter::once(0..text.len()).collect(),
+    };
+    let rows: Vec<Spans> = pieces
+        .into_iter()
+        .map(|piece| {
+            let mut spans = vec![];
+            let mut at = piece.start;
+            for m in matches
+                .iter()
+                .filter(|m| m.start < piece.end && m.end > piece.start)
+            {
+                let (start, end) = (m.start.max(piece.start), m.end.min(piece.end));
+                if start > at {
+                    spans.push(Span::styled(text[at..start].to_string(), style));
+                }
+                spans.push(Span::styled(text[start..end].to_string(), found));
+                at = end;
+            }
+            if at < piece.end || spans.is_empty() {
+                spans.push(Span::styled(text[at..piece.end].to_string(), style));
+            }
+            Spans::from(spans)
+        })
+        .collect();
+    ListItem::new(rows)
+}
+
+/// Command output as in the Operation modal; the app's notes are dimmed so the output stands
+/// out, and its own errors are bold.
+pub fn log_entry_style(theme: &Theme, entry: &LogEntry) -> Style {
+    match (entry.source, entry.level) {
+        (Source::App, Level::Info) => Style::default().add_modifier(Modifier::DIM),
+        (Source::App, Level::Error) => theme.error.add_modifier(Modifier::BOLD),
+        (_, Level::Info) => Style::default(),
+        (_, Level::Warning) => theme.warning,
+        (_, Level::Error) => theme.error,
+    }
+}
+
+/// "linked: <version>" for linked kegs, otherwise why the keg is not linked.
+fn linked_status_line(theme: &Theme, info: &crate::brew::FormulaInfo) -> Spans<'static> {
+    match &info.linked_keg {
+        Some(v) => Spans::from(Span::raw(format!("linked: {}", v))),
+        None if info.keg_only => Spans::from(Span::styled("linked: no (keg-only)", theme.warning)),
+        None => Spans::from(Span::styled("linked: no (run l to link)", theme.error)),
+    }
+}
+
+/// The Installed table's columns that fit in `width` cells, with their widths. Name takes
+/// what is left; the others are added while they fit: version and the pin and outdated marks
+/// first, then size and tap. Widths leave room for the sort arrow in the header.
+fn installed_columns(app: &App, width: u16) -> Vec<(SortColumn, Constraint)> {
+    const NAME_MIN: u16 = 12;
+    let widest = |values: &mut dyn Iterator<Item = &str>, min: usize, max: usize| {
+        values.map(|v| v.width()).max().unwrap_or(0).clamp(min, max) as u16
+    };
+    let versions: Vec<String> = app
+        .items
+        .iter()
+        .map(|f| version_text(f, app.outdated_items.contains(&f.name), app.symbols.arrow))
+        .collect();
+    let version = widest(&mut versions.iter().map(String::as_str), 8, 24);
+    let tap = widest(
+        &mut app.items.iter().filter_map(|f| f.tap_name()).map(short_tap),
+        3,
+        16,
+    );
+    let wanted = [
+        (SortColumn::Version, version),
+        (SortColumn::Pinned, 4),
+        (SortColumn::Outdated, 4),
+        (SortColumn::Size, 7),
+        (SortColumn::Tap, tap),
+        (SortColumn::Installed, 9),
+    ];
+    let mut used = NAME_MIN;
+    let mut shown = vec![SortColumn::Name];
+    for (column, w) in wanted {
+        if used + 1 + w <= width {
+            used += 1 + w;
+            shown.push(column);
+        }
+    }
+    SortColumn::ALL
+        .iter()
+        .filter(|c| shown.contains(c))
+        .map(|&c| match wanted.iter().find(|(column, _)| *column == c) {
+            Some(&(_, w)) => (c, Constraint::Length(w)),
+            None => (c, Constraint::Min(NAME_MIN)),
+        })
+        .collect()
+}
+
+/// A tap without the `homebrew/` prefix of the official ones: `core`, `user/repo`.
+fn short_tap(tap: &str) -> &str {
+    tap.strip_prefix("homebrew/").unwrap_or(tap)
+}
+
+/// One installed version in Details; the linked one is marked.
+fn installed_version_line(
+    theme: &Theme,
+    info: &crate::brew::FormulaInfo,
+    version: &str,
+) -> Spans<'static> {
+    if info.linked_keg.as_deref() == Some(version) {
+        Spans::from(Span::styled(
+            format!("  - {} (linked)", version),
+            theme.accent,
+        ))
+    } else {
+        Spans::from(Span::raw(format!("  - {}", version)))
+    }
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
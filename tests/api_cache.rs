@@ -0,0 +1,93 @@
+use homebrew_tui::api_cache::{self, ApiEntry};
+use homebrew_tui::brew::Brew;
+use std::fs;
+use std::path::PathBuf;
+
+fn write_cache(tag: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-api-{}-{}", tag, std::process::id()));
+    let api = dir.join("api");
+    fs::create_dir_all(&api).unwrap();
+    let formulae =
+        r#"[{"name":"wget","desc":"Internet file retriever"},{"name":"jq","desc":null}]"#;
+    let casks = r#"[{"token":"firefox","desc":"Web browser"}]"#;
+    for (file, payload) in [("formula.jws.json", formulae), ("cask.jws.json", casks)] {
+        let jws = serde_json::json!({ "payload": payload, "signatures": [] });
+        fs::write(api.join(file), jws.to_string()).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn reads_formulae_and_casks_from_the_api_cache() {
+    let dir = write_cache("load");
+    let entries = api_cache::load(&dir).unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            ApiEntry {
+                name: "firefox".into(),
+                desc: Some("Web browser".into()),
+                cask: true,
+            },
+            ApiEntry {
+                name: "jq".into(),
+                desc: None,
+                cask: false,
+            },
+            ApiEntry {
+                name: "wget".into(),
+                desc: Some("Internet file retriever".into()),
+                cask: false,
+            },
+        ]
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn missing_formula_cache_is_an_error() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-api-none-{}", std::process::id()));
+    assert!(api_cache::load(&dir).is_err());
+}
+
+#[test]
+fn brew_prefers_the_api_cache_over_search() {
+    let dir = write_cache("brew");
+    // a brew that cannot run proves the list did not come from `brew search`
+    let brew = Brew {
+        program: PathBuf::from("/nonexistent/brew"),
+        env: vec![("HOMEBREW_CACHE".into(), dir.display().to_string())],
+        ..Brew::new()
+    };
+    assert_eq!(
+        brew.all_available().unwrap(),
+        vec!["firefox".to_string(), "jq".into(), "wget".into()]
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn cache_dir_honours_homebrew_cache() {
+    let env = vec![("HOMEBREW_CACHE".to_string(), "/tmp/brew-cache".to_string())];
+    assert_eq!(
+        api_cache::cache_dir(&env),
+        Some(PathBuf::from("/tmp/brew-cache"))
+    );
+}
+
+#[test]
+fn brews_index_is_refreshed_by_its_modification_time() {
+    let dir = write_cache("refresh");
+    let brew = Brew {
+        program: PathBuf::from("/nonexistent/brew"),
+        env: vec![("HOMEBREW_CACHE".into(), dir.display().to_string())],
+        ..Brew::new()
+    };
+    let modified = api_cache::modified(&dir).unwrap();
+    assert!(brew.refresh_index(None).unwrap());
+    assert!(brew.refresh_index(Some(modified - 1)).unwrap());
+    // brew has not updated it since the list was read
+    assert!(!brew.refresh_index(Some(modified)).unwrap());
+    assert_eq!(api_cache::modified(&dir.join("missing")), None);
+    let _ = fs::remove_dir_all(&dir);
+}
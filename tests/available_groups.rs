@@ -0,0 +1,82 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{press, pump};
+use crossterm::event::KeyCode;
+use homebrew_tui::api_cache::ApiEntry;
+use homebrew_tui::app::{App, Focus};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::brew;
+use homebrew_tui::config::ConfigSource;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const NAMES: [&str; 7] = [
+    "node",
+    "node@18",
+    "node@20",
+    "python@3.11",
+    "python@3.12",
+    "python@3.9",
+    "wget",
+];
+
+fn names() -> Vec<String> {
+    NAMES.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn versions_collapse_into_one_row_per_formula() {
+    let names = names();
+    let all: Vec<usize> = (0..names.len()).collect();
+    let (rows, groups) = brew::group_versioned(&names, &all, &HashSet::new());
+    // node heads its versions; python has no unversioned formula, so 3.12 does
+    assert_eq!(rows, vec![0, 4, 6]);
+    assert_eq!(groups[&0], vec![2, 1]);
+    assert_eq!(groups[&4], vec![3, 5]);
+
+    let expanded = HashSet::from(["python".to_string()]);
+    let (rows, _) = brew::group_versioned(&names, &all, &expanded);
+    assert_eq!(rows, vec![0, 4, 3, 5, 6]);
+
+    // a single match is not grouped
+    let (rows, groups) = brew::group_versioned(&names, &[3], &HashSet::new());
+    assert_eq!(rows, vec![3]);
+    assert!(groups.is_empty());
+}
+
+fn selected_name(app: &App) -> &str {
+    &app.available_items[app.available_selected]
+}
+
+#[test]
+fn space_expands_and_collapses_the_selected_formula() {
+    let backend = Arc::new(MockBackend {
+        available: NAMES
+            .iter()
+            .map(|n| ApiEntry {
+                name: n.to_string(),
+                desc: None,
+                cask: false,
+            })
+            .collect(),
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+    pump(&mut app, |a| a.available_filtered.len() == 3);
+    app.focus = Focus::Available;
+
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(selected_name(&app), "python@3.12");
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.available_filtered.len(), 5);
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(selected_name(&app), "python@3.9");
+
+    // collapsing from a version goes back to the head row
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.available_filtered.len(), 3);
+    assert_eq!(selected_name(&app), "python@3.12");
+}
@@ -0,0 +1,81 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::api_cache::ApiEntry;
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::brew::FormulaInfo;
+use std::sync::Arc;
+#[cfg(feature = "tui")]
+use {
+    common::pump,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    homebrew_tui::app::{App, ConfirmAction, Mode},
+    homebrew_tui::config::ConfigSource,
+};
+
+fn mock() -> Arc<MockBackend> {
+    Arc::new(MockBackend {
+        installed: vec![FormulaInfo {
+            name: "jq".into(),
+            ..Default::default()
+        }],
+        available: ["jq", "wget", "wgetpaste"]
+            .iter()
+            .map(|n| ApiEntry {
+                name: n.to_string(),
+                desc: None,
+                cask: false,
+            })
+            .collect(),
+        outdated: vec!["jq".into()],
+        output: vec!["==> Pouring wget".into()],
+        ..MockBackend::new()
+    })
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn app_loads_its_lists_from_the_injected_backend() {
+    let backend = mock();
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+    pump(&mut app, |a| {
+        !a.items.is_empty() && a.available_items.len() == 3 && !a.outdated_items.is_empty()
+    });
+    assert_eq!(app.items[0].name, "jq");
+    assert_eq!(app.outdated_items, vec!["jq".to_string()]);
+    assert!(backend.executed().is_empty());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn confirmed_operations_run_on_the_backend() {
+    let backend = mock();
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+    app.mode = Mode::Confirm {
+        action: ConfirmAction::Install,
+        name: "wget".into(),
+        idx: None,
+    };
+    app.handle_key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+    pump(&mut app, |a| {
+        a.history.records.len() == 1 && !a.history.records[0].logs.is_empty() && !a.operating
+    });
+    assert_eq!(backend.executed(), vec!["brew install wget".to_string()]);
+    assert!(app.history.records[0]
+        .logs
+        .iter()
+        .any(|l| l.text == "==> Pouring wget"));
+}
+
+#[test]
+fn mock_search_and_info_use_its_data() {
+    use homebrew_tui::backend::BrewBackend;
+
+    let backend = mock();
+    assert_eq!(
+        backend.search("WGET").unwrap(),
+        vec!["wget".to_string(), "wgetpaste".to_string()]
+    );
+    assert_eq!(backend.info("wget").unwrap().name, "wget");
+    assert!(backend.info("nope").is_err());
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "tui")]
+
+use homebrew_tui::app::{App, AppEvent, Mode};
+use homebrew_tui::history::History;
+
+#[test]
+fn operation_keeps_running_behind_other_modals() {
+    let mut app = App::new().expect("app init");
+    app.history = History::in_memory();
+    app.mode = Mode::Help {
+        scroll: 0,
+        query: String::new(),
+        searching: false,
+    };
+    app.handle_event(AppEvent::OpStart("brew install wget".into()));
+    assert!(matches!(app.mode, Mode::Help { .. }));
+    assert_eq!(app.current_op.as_deref(), Some("brew install wget"));
+
+    app.handle_event(AppEvent::OpLog("==> Pouring wget".into()));
+    assert_eq!(app.history.records[0].logs.len(), 1);
+
+    app.handle_event(AppEvent::OpEnd("brew install wget".into()));
+    assert!(app.current_op.is_none());
+    assert!(!app.operating);
+}
+
+#[test]
+fn past_logs_are_not_appended_to() {
+    let mut app = App::new().expect("app init");
+    app.history = History::in_memory();
+    app.mode = Mode::Operation {
+        title: "History: brew upgrade jq".into(),
+        logs: vec!["old".into()],
+        scroll: 0,
+        live: false,
+    };
+    app.handle_event(AppEvent::OpStart("brew install wget".into()));
+    app.handle_event(AppEvent::OpLog("new".into()));
+    match &app.mode {
+        Mode::Operation { logs, live, .. } => {
+            // a finished log gives way to the live operation
+            assert!(*live);
+            assert_eq!(logs.len(), 1);
+            assert_eq!(&*logs[0], "new");
+        }
+        _ => panic!("expected Operation mode"),
+    }
+}
@@ -0,0 +1,149 @@
+#![cfg(unix)]
+
+use homebrew_tui::backend::run_brew_operation;
+use homebrew_tui::brew::{describe_error, Brew, BrewError};
+use homebrew_tui::event::{AppEvent, ConfirmAction};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+fn fake_brew(name: &str, body: &str) -> Brew {
+    let dir =
+        std::env::temp_dir().join(format!("homebrew-tui-err-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("brew");
+    fs::write(&script, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    Brew {
+        program: script,
+        ..Brew::new()
+    }
+}
+
+fn brew_error(e: &anyhow::Error) -> &BrewError {
+    e.downcast_ref::<BrewError>()
+        .expect("brew failures carry a BrewError")
+}
+
+#[test]
+fn missing_brew_is_not_installed() {
+    let brew = Brew {
+        program: PathBuf::from("/nonexistent/homebrew-tui/brew"),
+        ..Brew::new()
+    };
+    let e = brew.list_installed().unwrap_err();
+    assert!(matches!(brew_error(&e), BrewError::NotInstalled { .. }));
+    assert!(describe_error("loading installed failed", &e).contains("install Homebrew"));
+}
+
+#[test]
+fn failing_commands_report_code_and_stderr() {
+    let brew = fake_brew("fail", "echo 'Error: boom' >&2\nexit 2");
+    let e = brew.search("jq").unwrap_err();
+    match brew_error(&e) {
+        BrewError::CommandFailed { code, stderr, .. } => {
+            assert_eq!(*code, Some(2));
+            assert_eq!(stderr.trim(), "Error: boom");
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(
+        e.to_string(),
+        "brew search jq failed with exit code 2: Error: boom"
+    );
+}
+
+#[test]
+fn unparseable_output_is_a_parse_error() {
+    let mut brew = fake_brew("parse", "echo 'not json'");
+    let e = brew.info("jq").unwrap_err();
+    assert!(matches!(brew_error(&e), BrewError::ParseError { .. }));
+}
+
+#[test]
+fn slow_queries_time_out() {
+    let brew = Brew {
+        query_timeout: Duration::from_millis(200),
+        ..fake_brew("slow", "exec sleep 5")
+    };
+    let started = Instant::now();
+    let e = brew.outdated().unwrap_err();
+    assert!(started.elapsed() < Duration::from_secs(3));
+    assert!(matches!(brew_error(&e), BrewError::Timeout { .. }));
+}
+
+#[test]
+fn health_check_finds_version_and_prefix() {
+    let prefix = std::env::temp_dir();
+    let brew = fake_brew(
+        "health",
+        &format!(
+            "case \"$1\" in\n--version) echo 'Homebrew 4.3.0'; echo 'Homebrew/homebrew-core (git revision 1234)' ;;\n--prefix) echo '{}' ;;\nesac",
+            prefix.display()
+        ),
+    );
+    let health = brew.health_check().unwrap();
+    assert_eq!(health.version, "Homebrew 4.3.0");
+    assert_eq!(health.prefix, prefix);
+
+    let impostor = fake_brew("impostor", "echo 'brew 1.0 (the coffee tool)'");
+    let e = impostor.health_check().unwrap_err();
+    assert!(matches!(brew_error(&e), BrewError::ParseError { .. }));
+
+    let no_prefix = fake_brew(
+        "no-prefix",
+        "case \"$1\" in\n--version) echo 'Homebrew 4.3.0' ;;\n--prefix) echo /nonexistent/homebrew ;;\nesac",
+    );
+    let e = no_prefix.health_check().unwrap_err();
+    assert!(e
+        .to_string()
+        .contains("/nonexistent/homebrew, which is not a directory"));
+}
+
+#[test]
+fn a_hanging_brew_fails_the_health_check_with_a_hint() {
+    let brew = Brew {
+        query_timeout: Duration::from_millis(200),
+        ..fake_brew("hang", "exec sleep 5")
+    };
+    let started = Instant::now();
+    let e = brew.health_check().unwrap_err();
+    assert!(started.elapsed() < Duration::from_secs(3));
+    assert!(matches!(brew_error(&e), BrewError::Timeout { .. }));
+    assert!(describe_error("brew health check failed", &e).contains("`query_timeout`"));
+    // it is still there, so no installer is offered
+    assert!(homebrew_tui::backend::BrewBackend::is_available(&brew));
+}
+
+#[test]
+fn operations_are_killed_after_the_operation_timeout() {
+    // only the operation hangs; `brew --prefix` (lock lookup) returns at once
+    let brew = Brew {
+        operation_timeout: Some(Duration::from_secs(1)),
+        ..fake_brew(
+            "op-timeout",
+            "[ \"$1\" = install ] || exit 1\necho started\nexec sleep 30",
+        )
+    };
+    let (tx, rx) = mpsc::channel();
+    let started = Instant::now();
+    let code = run_brew_operation(&brew, &ConfirmAction::Install, "jq", &tx);
+    drop(tx);
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(code, None);
+    let errors: Vec<String> = rx
+        .into_iter()
+        .filter_map(|ev| match ev {
+            AppEvent::OpErr(l) => Some(l),
+            _ => None,
+        })
+        .collect();
+    assert!(
+        errors[0]
+            .starts_with("failed to run brew install jq: brew install jq did not finish within 1s"),
+        "{:?}",
+        errors
+    );
+}
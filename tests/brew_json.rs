@@ -11,3 +11,29 @@ fn parse_brew_list_json() {
     let parsed: serde_json::Value = serde_json::from_str(&s).unwrap();
     assert!(parsed.get("formulae").is_some());
 }
+
+#[test]
+fn parse_formulae_reads_brew_info_v2() {
+    use homebrew_tui::brew::parse_formulae;
+
+    let sample = json!({
+        "formulae": [{
+            "name": "jq",
+            "full_name": "jq",
+            "desc": "Lightweight and flexible command-line JSON processor",
+            "dependencies": ["oniguruma"],
+            "installed": [{ "version": "1.7.1", "used_options": [] }],
+            "linked_keg": "1.7.1",
+            "keg_only": false
+        }],
+        "casks": []
+    });
+    let formulae = parse_formulae(&sample.to_string()).unwrap();
+    assert_eq!(formulae.len(), 1);
+    assert_eq!(formulae[0].name, "jq");
+    assert_eq!(formulae[0].dependencies, vec!["oniguruma".to_string()]);
+    assert_eq!(formulae[0].installed[0].version, "1.7.1");
+    assert_eq!(formulae[0].linked_keg.as_deref(), Some("1.7.1"));
+
+    assert!(parse_formulae("[]").is_err());
+}
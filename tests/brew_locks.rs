@@ -0,0 +1,21 @@
+use homebrew_tui::lock::held_locks;
+use std::fs::{self, File};
+use std::os::unix::io::AsRawFd;
+
+#[test]
+fn reports_only_held_locks() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-locks-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    File::create(dir.join("update.lock")).unwrap();
+    File::create(dir.join("wget.formula.lock")).unwrap();
+    assert!(held_locks(&dir).is_empty());
+
+    let held = File::open(dir.join("update.lock")).unwrap();
+    let rc = unsafe { libc::flock(held.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    assert_eq!(rc, 0);
+    assert_eq!(held_locks(&dir), vec!["update".to_string()]);
+
+    drop(held);
+    assert!(held_locks(&dir).is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
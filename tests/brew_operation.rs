@@ -0,0 +1,132 @@
+#![cfg(unix)]
+
+use homebrew_tui::backend::{run_brew_operation, run_operation};
+use homebrew_tui::brew::Brew;
+use homebrew_tui::event::{AppEvent, ConfirmAction};
+use homebrew_tui::jobs::{self, CancelToken};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn streams_output_and_exit_code_from_brew() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-op-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("brew");
+    fs::write(
+        &script,
+        "#!/bin/sh\necho \"==> Upgrading $*\"\necho oops >&2\nexit 3\n",
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let brew = Brew {
+        program: script,
+        flags: vec!["--verbose".into()],
+        ..Brew::new()
+    };
+    let (tx, rx) = mpsc::channel();
+    let action = ConfirmAction::BulkUpgrade(vec!["jq".into(), "git".into()]);
+    let code = run_brew_operation(&brew, &action, "2 packages", &tx);
+    drop(tx);
+    assert_eq!(code, Some(3));
+
+    let events: Vec<AppEvent> = rx.into_iter().collect();
+    assert!(matches!(&events[0], AppEvent::OpStart(t) if t == "brew upgrade jq git --verbose"));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, AppEvent::OpLog(l) if l == "==> Upgrading upgrade jq git --verbose")));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, AppEvent::OpErr(l) if l == "oops")));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, AppEvent::OpExit(Some(3)))));
+    assert!(matches!(events.last(), Some(AppEvent::OpEnd(_))));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn cancelling_terminates_the_running_brew() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-cancel-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("brew");
+    // only the operation itself hangs; `brew --prefix` (lock lookup) returns at once
+    fs::write(
+        &script,
+        "#!/bin/sh\n[ \"$1\" = install ] || exit 1\necho started\nexec sleep 30\n",
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let brew = Brew {
+        program: script,
+        ..Brew::new()
+    };
+    let (tx, rx) = mpsc::channel();
+    let token = CancelToken::new();
+    let op_token = token.clone();
+    let started = Instant::now();
+    let worker = thread::spawn(move || {
+        jobs::runtime().block_on(run_operation(
+            &brew,
+            &ConfirmAction::Install,
+            "jq",
+            &tx,
+            &op_token,
+        ))
+    });
+    let first_line = rx
+        .iter()
+        .find_map(|ev| match ev {
+            AppEvent::OpLog(l) => Some(l),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(first_line, "started");
+    token.cancel();
+
+    // killed by a signal: no exit code
+    assert_eq!(worker.join().unwrap(), None);
+    assert!(started.elapsed() < Duration::from_secs(10));
+    let rest: Vec<AppEvent> = rx.into_iter().collect();
+    assert!(rest
+        .iter()
+        .any(|e| matches!(e, AppEvent::Log(l) if l.ends_with("cancelled"))));
+    assert!(matches!(rest.last(), Some(AppEvent::OpEnd(_))));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn variables_set_for_an_operation_reach_brew() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-env-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("brew");
+    fs::write(
+        &script,
+        "#!/bin/sh\n[ \"$1\" = install ] || exit 0\necho \"jobs=$HOMEBREW_MAKE_JOBS auto=$HOMEBREW_NO_AUTO_UPDATE\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let brew = Brew {
+        program: script,
+        env: vec![("HOMEBREW_NO_AUTO_UPDATE".into(), "1".into())],
+        ..Brew::new()
+    };
+    let (tx, rx) = mpsc::channel();
+    let action = ConfirmAction::Install.with_env(vec![("HOMEBREW_MAKE_JOBS".into(), "8".into())]);
+    assert_eq!(run_brew_operation(&brew, &action, "jq", &tx), Some(0));
+    drop(tx);
+
+    let events: Vec<AppEvent> = rx.into_iter().collect();
+    assert!(
+        matches!(&events[0], AppEvent::OpStart(t) if t == "HOMEBREW_MAKE_JOBS=… brew install jq")
+    );
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, AppEvent::OpLog(l) if l == "jobs=8 auto=1")));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,43 @@
+#![cfg(unix)]
+
+use homebrew_tui::brew::find_brew;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+#[test]
+fn finds_brew_on_path_then_in_known_prefixes() {
+    let root = std::env::temp_dir().join(format!("homebrew-tui-path-{}", std::process::id()));
+    let on_path = root.join("bin");
+    let prefix = root.join("opt").join("homebrew").join("bin");
+    fs::create_dir_all(&on_path).unwrap();
+    fs::create_dir_all(&prefix).unwrap();
+    let fallback = prefix.join("brew");
+    fs::write(&fallback, "#!/bin/sh\n").unwrap();
+    fs::set_permissions(&fallback, fs::Permissions::from_mode(0o755)).unwrap();
+
+    // nothing on PATH: the first existing fallback wins
+    let path_var = std::env::join_paths([&on_path]).unwrap();
+    let missing = root.join("missing").join("brew");
+    assert_eq!(
+        find_brew(Some(&path_var), &[missing.clone(), fallback.clone()]),
+        Some(fallback.clone())
+    );
+
+    // a non-executable file is ignored
+    let stub = on_path.join("brew");
+    fs::write(&stub, "").unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o644)).unwrap();
+    assert_eq!(
+        find_brew(Some(&path_var), std::slice::from_ref(&fallback)),
+        Some(fallback.clone())
+    );
+
+    // an executable on PATH takes precedence
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+    assert_eq!(
+        find_brew(Some(&path_var), std::slice::from_ref(&fallback)),
+        Some(stub)
+    );
+    assert_eq!(find_brew(None, &[missing]), None);
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,104 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::build_logs;
+use std::fs;
+use std::time::{Duration, SystemTime};
+#[cfg(feature = "tui")]
+use {
+    common::{press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::build_logs::{BuildLogFile, BuildLogs},
+    homebrew_tui::config::ConfigSource,
+    homebrew_tui::event::ConfirmAction,
+    std::sync::Arc,
+};
+
+#[test]
+fn log_files_are_read_in_the_order_brew_wrote_them() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-logs-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("wget")).unwrap();
+    fs::create_dir_all(dir.join("jq")).unwrap();
+    fs::write(dir.join("wget/02.make"), "make\nError 1\n").unwrap();
+    fs::write(dir.join("wget/01.configure"), "./configure\n").unwrap();
+    fs::write(dir.join("jq/01.configure"), "./configure\n").unwrap();
+    fs::File::open(dir.join("jq"))
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(3600))
+        .unwrap();
+
+    let logs = build_logs::read(&dir, "wget").unwrap();
+    assert_eq!(
+        logs.lines(),
+        vec![
+            "==> 01.configure",
+            "./configure",
+            "==> 02.make",
+            "make",
+            "Error 1"
+        ]
+    );
+    let names = vec!["jq".to_string(), "wget".to_string(), "curl".to_string()];
+    assert_eq!(build_logs::most_recent(&dir, &names).unwrap(), "wget");
+    assert_eq!(
+        build_logs::most_recent(&dir, &["curl".to_string()]).unwrap(),
+        "curl"
+    );
+    assert!(build_logs::read(&dir, "curl").is_err());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "tui")]
+fn app_after_failed_install() -> (App, Arc<MockBackend>) {
+    let backend = Arc::new(MockBackend {
+        build_logs: vec![BuildLogs {
+            name: "wget".into(),
+            dir: "/logs/wget".into(),
+            files: vec![BuildLogFile {
+                name: "02.make".into(),
+                lines: vec!["make: *** [all] Error 2".into()],
+            }],
+        }],
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+    app.history.start("brew install wget");
+    app.history.set_exit_code(Some(1));
+    app.history.finish().unwrap();
+    (app, backend)
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn build_logs_of_a_failed_install_open_from_history() {
+    let (mut app, _) = app_after_failed_install();
+    press(&mut app, KeyCode::Char('H'));
+    press(&mut app, KeyCode::Char('l'));
+    pump(&mut app, |a| matches!(a.mode, Mode::Operation { .. }));
+    match &app.mode {
+        Mode::Operation { title, logs, .. } => {
+            assert_eq!(title, "Build logs: wget (/logs/wget)");
+            assert_eq!(logs[1].text, "make: *** [all] Error 2");
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn build_logs_are_uploaded_after_confirming() {
+    let (mut app, backend) = app_after_failed_install();
+    press(&mut app, KeyCode::Char('H'));
+    press(&mut app, KeyCode::Char('g'));
+    pump(&mut app, |a| matches!(a.mode, Mode::Confirm { .. }));
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action: ConfirmAction::GistLogs, name, .. } if name == "wget"
+    ));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| a.history.records.len() == 2 && !a.operating);
+    assert_eq!(backend.executed(), vec!["brew gist-logs wget"]);
+}
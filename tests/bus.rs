@@ -0,0 +1,90 @@
+use homebrew_tui::bus::{BusEvent, EventBus};
+use homebrew_tui::event::{AppEvent, ConfirmAction};
+use homebrew_tui::output::Stream;
+use std::sync::mpsc;
+
+/// A compact description of what `collect` handed over, for comparisons.
+fn describe(events: Vec<BusEvent>) -> Vec<String> {
+    events
+        .into_iter()
+        .map(|ev| match ev {
+            BusEvent::Event(ev) => match *ev {
+                AppEvent::Status(s) => format!("status {}", s),
+                AppEvent::ShowConfirm(_, name, _) => format!("confirm {}", name),
+                AppEvent::OpStart(t) => format!("start {}", t),
+                AppEvent::OpEnd(t) => format!("end {}", t),
+                AppEvent::Log(l) => format!("log {}", l),
+                _ => "other".into(),
+            },
+            BusEvent::Lines(lines) => format!(
+                "lines {}",
+                lines
+                    .iter()
+                    .map(|l| match l.stream {
+                        Stream::Stderr => format!("!{}", l.text),
+                        _ => l.text.clone(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        })
+        .collect()
+}
+
+#[test]
+fn urgent_events_come_first_and_lines_are_batched_in_order() {
+    let (tx, rx) = mpsc::channel();
+    tx.send(AppEvent::OpStart("brew install wget".into()))
+        .unwrap();
+    tx.send(AppEvent::OpLog("a".into())).unwrap();
+    tx.send(AppEvent::OpErr("b".into())).unwrap();
+    tx.send(AppEvent::Status("busy".into())).unwrap();
+    tx.send(AppEvent::OpLog("c".into())).unwrap();
+    tx.send(AppEvent::Log("note".into())).unwrap();
+    tx.send(AppEvent::OpLog("d".into())).unwrap();
+    tx.send(AppEvent::ShowConfirm(
+        ConfirmAction::InstallBrew,
+        "Homebrew".into(),
+        None,
+    ))
+    .unwrap();
+    tx.send(AppEvent::OpEnd("brew install wget".into()))
+        .unwrap();
+
+    let mut bus = EventBus::new();
+    assert_eq!(
+        describe(bus.collect(&rx, 100)),
+        vec![
+            "status busy",
+            "confirm Homebrew",
+            "start brew install wget",
+            "lines a,!b,c",
+            "log note",
+            "lines d",
+            "end brew install wget",
+        ]
+    );
+    assert!(!bus.has_backlog());
+}
+
+#[test]
+fn lines_beyond_the_budget_wait_for_the_next_frame() {
+    let (tx, rx) = mpsc::channel();
+    for i in 0..5 {
+        tx.send(AppEvent::OpLog(i.to_string())).unwrap();
+    }
+    tx.send(AppEvent::OpEnd("done".into())).unwrap();
+
+    let mut bus = EventBus::new();
+    assert_eq!(describe(bus.collect(&rx, 3)), vec!["lines 0,1,2"]);
+    assert!(bus.has_backlog());
+
+    // urgent events sent meanwhile still overtake the queued lines
+    tx.send(AppEvent::Status("later".into())).unwrap();
+    assert_eq!(
+        describe(bus.collect(&rx, 3)),
+        vec!["status later", "lines 3,4", "end done"]
+    );
+    assert!(!bus.has_backlog());
+    assert!(bus.collect(&rx, 3).is_empty());
+}
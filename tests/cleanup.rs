@@ -0,0 +1,73 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::CleanupPreview;
+use homebrew_tui::parse::parse_cleanup_dry_run;
+#[cfg(feature = "tui")]
+use {
+    common::{press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    std::sync::Arc,
+};
+
+const DRY_RUN: &str = "\
+Would remove: /Users/me/Library/Caches/Homebrew/wget--1.21.3.arm64_ventura.bottle.tar.gz (1.5MB)
+Would remove: /opt/homebrew/Cellar/jq/1.6 (18 files, 1.1MB)
+Would remove: /Users/me/Library/Logs/Homebrew/jq
+==> This operation would free approximately 2.6MB of disk space.
+";
+
+#[test]
+fn dry_run_output_lists_files_and_the_total() {
+    let preview = parse_cleanup_dry_run(DRY_RUN);
+    assert_eq!(
+        preview.files,
+        vec![
+            (
+                "/Users/me/Library/Caches/Homebrew/wget--1.21.3.arm64_ventura.bottle.tar.gz"
+                    .to_string(),
+                Some("1.5MB".to_string())
+            ),
+            (
+                "/opt/homebrew/Cellar/jq/1.6".to_string(),
+                Some("18 files, 1.1MB".to_string())
+            ),
+            ("/Users/me/Library/Logs/Homebrew/jq".to_string(), None),
+        ]
+    );
+    assert_eq!(preview.total.as_deref(), Some("2.6MB"));
+    assert_eq!(parse_cleanup_dry_run(""), CleanupPreview::default());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn preview_opens_a_modal_that_runs_the_cleanup() {
+    let backend = Arc::new(MockBackend {
+        cleanup: parse_cleanup_dry_run(DRY_RUN),
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+
+    press(&mut app, KeyCode::Char('C'));
+    pump(&mut app, |a| matches!(a.mode, Mode::Cleanup { .. }));
+    assert!(backend.executed().is_empty(), "only previewed so far");
+
+    press(&mut app, KeyCode::Char('a'));
+    assert!(matches!(
+        app.mode,
+        Mode::Cleanup {
+            prune_all: true,
+            ..
+        }
+    ));
+    press(&mut app, KeyCode::Char('y'));
+    assert!(matches!(app.mode, Mode::Normal));
+    pump(&mut app, |a| a.history.records.len() == 1 && !a.operating);
+    assert_eq!(
+        backend.executed(),
+        vec!["brew cleanup --prune=all".to_string()]
+    );
+}
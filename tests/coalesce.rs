@@ -0,0 +1,128 @@
+use homebrew_tui::coalesce::Coalescer;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn concurrent_identical_requests_share_one_run() {
+    let coalescer = Arc::new(Coalescer::<String>::new());
+    let runs = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(4));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let coalescer = Arc::clone(&coalescer);
+            let runs = Arc::clone(&runs);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                coalescer.run("jq", || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(200));
+                    "jq 1.7".to_string()
+                })
+            })
+        })
+        .collect();
+    for h in handles {
+        assert_eq!(h.join().unwrap(), "jq 1.7");
+    }
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    // once finished, the next request runs again
+    assert_eq!(coalescer.run("jq", || "fresh".to_string()), "fresh");
+}
+
+#[test]
+fn different_keys_run_independently() {
+    let coalescer = Coalescer::<usize>::new();
+    assert_eq!(coalescer.run("a", || 1), 1);
+    assert_eq!(coalescer.run("b", || 2), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn concurrent_outdated_checks_start_one_brew_process() {
+    use homebrew_tui::brew::Brew;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-coalesce-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let calls = dir.join("calls");
+    let script = dir.join("brew");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\necho run >> {}\nsleep 0.3\necho 'jq (1.6) < 1.7'\n",
+            calls.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let brew = Brew {
+        program: script,
+        ..Brew::new()
+    };
+    let handles: Vec<_> = (0..3)
+        .map(|_| {
+            let brew = brew.clone();
+            thread::spawn(move || brew.outdated().unwrap())
+        })
+        .collect();
+    for h in handles {
+        assert_eq!(h.join().unwrap(), vec!["jq".to_string()]);
+    }
+    assert_eq!(fs::read_to_string(&calls).unwrap().lines().count(), 1);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn checks_after_an_operation_do_not_join_one_started_before_it() {
+    use homebrew_tui::backend::run_brew_operation;
+    use homebrew_tui::brew::Brew;
+    use homebrew_tui::event::ConfirmAction;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::mpsc;
+
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-stale-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let calls = dir.join("calls");
+    let script = dir.join("brew");
+    // jq is outdated until it has been upgraded
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\ncase \"$1\" in\n\
+             outdated) echo run >> {calls}; [ -e {done} ] || echo 'jq (1.6) < 1.7'; sleep 0.5 ;;\n\
+             upgrade) touch {done} ;;\n\
+             esac\n",
+            calls = calls.display(),
+            done = dir.join("upgraded").display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let brew = Brew {
+        program: script,
+        ..Brew::new()
+    };
+    // a periodic check starts, then the upgrade runs and ends while it is still going
+    let before = {
+        let brew = brew.clone();
+        thread::spawn(move || brew.outdated().unwrap())
+    };
+    thread::sleep(Duration::from_millis(100));
+    let (tx, _rx) = mpsc::channel();
+    let code = run_brew_operation(&brew, &ConfirmAction::Upgrade, "jq", &tx);
+    assert_eq!(code, Some(0));
+
+    assert!(brew.outdated().unwrap().is_empty());
+    assert_eq!(before.join().unwrap(), vec!["jq".to_string()]);
+    assert_eq!(fs::read_to_string(&calls).unwrap().lines().count(), 2);
+    let _ = fs::remove_dir_all(&dir);
+}
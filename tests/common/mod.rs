@@ -0,0 +1,52 @@
+//! Helpers for the tests that drive the app without a terminal, the way `run` does: keys go
+//! through `App::inject_key` and background work is picked up by `App::tick`.
+
+#![allow(dead_code)]
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use homebrew_tui::app::App;
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::config::ConfigSource;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Press `code` without modifiers; the key must not quit the app.
+pub fn press(app: &mut App, code: KeyCode) {
+    assert!(!app.inject_key(KeyEvent::new(code, KeyModifiers::NONE)));
+}
+
+/// Type `text` one key at a time.
+pub fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
+
+/// Tick the app until `done` holds, failing after five seconds.
+pub fn pump(app: &mut App, done: impl Fn(&App) -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        app.tick();
+        if done(app) {
+            return;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for the app");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// The brew output in `tests/fixtures/basic`: jq, oniguruma and wget installed; bat, fd,
+/// firefox, jq, oniguruma, ripgrep and wget available.
+pub fn fixtures() -> MockBackend {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/basic");
+    MockBackend::from_fixtures(&dir).expect("load fixtures")
+}
+
+/// An app on `backend` with the installed and available lists loaded.
+pub fn app_with(backend: impl Into<Arc<MockBackend>>) -> App {
+    let mut app = App::with_backend(ConfigSource::default(), backend.into()).expect("create app");
+    pump(&mut app, |a| !a.loading_installed && !a.loading_available);
+    app
+}
@@ -0,0 +1,222 @@
+use homebrew_tui::config::Config;
+
+#[test]
+fn parses_theme_setting() {
+    let c = Config::parse("theme = \"gruvbox\"\n").unwrap();
+    assert_eq!(c.theme.as_deref(), Some("gruvbox"));
+    assert!(Config::parse("").unwrap().theme.is_none());
+}
+
+#[test]
+fn rejects_unknown_keys() {
+    assert!(Config::parse("them = \"gruvbox\"\n").is_err());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn builtin_themes_cycle() {
+    use homebrew_tui::theme::{Theme, THEMES};
+    for name in THEMES {
+        assert_eq!(Theme::by_name(name).unwrap().name, name);
+    }
+    assert!(Theme::by_name("nope").is_none());
+    let mut t = Theme::default();
+    for _ in 0..THEMES.len() {
+        t = t.next();
+    }
+    assert_eq!(t.name, "default");
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn detects_background_brightness() {
+    use homebrew_tui::theme::{
+        background_from_colorfgbg, background_from_osc11, Background, Theme,
+    };
+    assert_eq!(background_from_colorfgbg("15;0"), Some(Background::Dark));
+    assert_eq!(background_from_colorfgbg("0;15"), Some(Background::Light));
+    assert_eq!(
+        background_from_colorfgbg("0;default;7"),
+        Some(Background::Light)
+    );
+    assert_eq!(background_from_colorfgbg("garbage"), None);
+    assert_eq!(
+        background_from_osc11("\x1b]11;rgb:ffff/ffff/ffff\x07"),
+        Some(Background::Light)
+    );
+    assert_eq!(
+        background_from_osc11("\x1b]11;rgb:1e/1e/2e\x1b\\"),
+        Some(Background::Dark)
+    );
+    assert_eq!(background_from_osc11(""), None);
+
+    let c = Config::parse("background = \"light\"\n").unwrap();
+    assert_eq!(c.background, Background::Light);
+    assert_eq!(Theme::for_background(Background::Light).name, "light");
+}
+
+#[test]
+fn ascii_mode_uses_plain_symbols() {
+    use homebrew_tui::symbols::Symbols;
+    assert!(!Config::parse("").unwrap().ascii);
+    let c = Config::parse("ascii = true\n").unwrap();
+    let s = Symbols::new(c.ascii);
+    let all = [
+        s.success,
+        s.failure,
+        s.running,
+        s.up,
+        s.down,
+        s.arrow,
+        s.bar,
+        s.pin,
+        s.collapsed,
+        s.expanded,
+        s.scroll_thumb,
+        s.placeholder,
+    ];
+    assert!(all.iter().chain(s.spinner).all(|g| g.is_ascii()));
+    assert!(!Symbols::default().success.is_ascii());
+    assert_eq!(s.spinner_frame(s.spinner.len()), s.spinner[0]);
+}
+
+#[test]
+fn parses_reduced_motion() {
+    assert!(!Config::parse("").unwrap().reduced_motion);
+    assert!(
+        Config::parse("reduced_motion = true\n")
+            .unwrap()
+            .reduced_motion
+    );
+}
+
+#[test]
+fn command_line_overrides_take_precedence() {
+    use homebrew_tui::brew::Brew;
+    use homebrew_tui::config::ConfigSource;
+    use std::path::PathBuf;
+
+    let path = std::env::temp_dir().join(format!("homebrew-tui-cli-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "theme = \"light\"\n[brew]\npath = \"/usr/local/bin/brew\"\n",
+    )
+    .unwrap();
+    let mut source = ConfigSource {
+        path: Some(path.clone()),
+        ..Default::default()
+    };
+    let c = source.load().unwrap();
+    assert_eq!(c.theme.as_deref(), Some("light"));
+    assert_eq!(
+        Brew::from_config(&c.brew).program,
+        PathBuf::from("/usr/local/bin/brew")
+    );
+
+    source.theme = Some("gruvbox".into());
+    source.brew_path = Some("/opt/homebrew/bin/brew".into());
+    let c = source.load().unwrap();
+    assert_eq!(c.theme.as_deref(), Some("gruvbox"));
+    assert_eq!(
+        c.brew.path.as_deref(),
+        Some(std::path::Path::new("/opt/homebrew/bin/brew"))
+    );
+    let _ = std::fs::remove_file(&path);
+
+    // an explicit config file has to exist
+    assert!(ConfigSource {
+        path: Some(path),
+        ..Default::default()
+    }
+    .load()
+    .is_err());
+    assert_eq!(
+        Brew::new().program.file_name(),
+        Some(std::ffi::OsStr::new("brew"))
+    );
+}
+
+#[test]
+fn parses_maintenance_steps() {
+    use homebrew_tui::config::MaintenanceStep;
+    assert_eq!(
+        Config::parse("").unwrap().maintenance.steps,
+        vec![
+            MaintenanceStep::Update,
+            MaintenanceStep::Upgrade,
+            MaintenanceStep::Autoremove,
+            MaintenanceStep::Cleanup,
+        ]
+    );
+    let c = Config::parse("[maintenance]\nsteps = [\"update\", \"cleanup\"]\n").unwrap();
+    assert_eq!(
+        c.maintenance.steps,
+        vec![MaintenanceStep::Update, MaintenanceStep::Cleanup]
+    );
+    assert!(Config::parse("[maintenance]\nsteps = [\"reboot\"]\n").is_err());
+}
+
+#[test]
+fn parses_log_level() {
+    use homebrew_tui::config::LogLevel;
+    assert_eq!(Config::parse("").unwrap().log.level, LogLevel::Info);
+    let c = Config::parse("[log]\nlevel = \"debug\"\n").unwrap();
+    assert_eq!(c.log.level, LogLevel::Debug);
+    assert!(Config::parse("[log]\nlevel = \"loud\"\n").is_err());
+}
+
+#[test]
+fn parses_notify_section() {
+    assert!(Config::parse("").unwrap().notify.desktop);
+    let c = Config::parse("[notify]\ndesktop = false\n").unwrap();
+    assert!(!c.notify.desktop);
+}
+
+#[test]
+fn parses_bell_setting() {
+    use homebrew_tui::config::Bell;
+    let c = Config::parse("").unwrap();
+    assert_eq!((c.notify.bell, c.notify.after), (Bell::Off, 10));
+    let c = Config::parse("[notify]\nbell = \"flash\"\nafter = 30\n").unwrap();
+    assert_eq!((c.notify.bell, c.notify.after), (Bell::Flash, 30));
+    assert!(Config::parse("[notify]\nbell = \"loud\"\n").is_err());
+}
+
+#[test]
+fn parses_hooks() {
+    assert!(Config::parse("").unwrap().hooks.post_install.is_none());
+    let c = Config::parse("[hooks]\npost_upgrade = \"~/bin/sync-dotfiles\"\n").unwrap();
+    assert_eq!(c.hooks.post_upgrade.as_deref(), Some("~/bin/sync-dotfiles"));
+    assert!(Config::parse("[hooks]\npre_install = \"true\"\n").is_err());
+}
+
+#[test]
+fn parses_github_section() {
+    assert!(Config::parse("").unwrap().github.stats);
+    assert!(
+        !Config::parse("[github]\nstats = false\n")
+            .unwrap()
+            .github
+            .stats
+    );
+}
+
+#[test]
+fn parses_terminal_section() {
+    assert!(Config::parse("").unwrap().terminal.title);
+    let c = Config::parse("[terminal]\ntitle = false\n").unwrap();
+    assert!(!c.terminal.title);
+}
+
+#[test]
+fn parses_brew_timeouts() {
+    use homebrew_tui::brew::{Brew, QUERY_TIMEOUT};
+    use std::time::Duration;
+    let brew = Brew::from_config(&Config::parse("").unwrap().brew);
+    assert_eq!(brew.query_timeout, QUERY_TIMEOUT);
+    assert_eq!(brew.operation_timeout, None);
+    let c = Config::parse("[brew]\nquery_timeout = 60\noperation_timeout = 3600\n").unwrap();
+    let brew = Brew::from_config(&c.brew);
+    assert_eq!(brew.query_timeout, Duration::from_secs(60));
+    assert_eq!(brew.operation_timeout, Some(Duration::from_secs(3600)));
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "tui")]
+
+use homebrew_tui::app::App;
+use homebrew_tui::history::History;
+use homebrew_tui::keymap::Preset;
+use std::fs;
+use std::sync::atomic::Ordering;
+
+#[test]
+fn reload_applies_new_settings_and_reports_errors() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-reload-{}", std::process::id()));
+    let config_dir = dir.join("homebrew-tui");
+    fs::create_dir_all(&config_dir).unwrap();
+    let path = config_dir.join("config.toml");
+    fs::write(&path, "theme = \"gruvbox\"\n").unwrap();
+    std::env::set_var("XDG_CONFIG_HOME", &dir);
+    std::env::set_var("XDG_STATE_HOME", &dir);
+
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    assert_eq!(app.theme.name, "gruvbox");
+
+    fs::write(
+        &path,
+        "theme = \"solarized\"\nkeymap = \"vim\"\nascii = true\noutdated_interval = 60\n",
+    )
+    .unwrap();
+    app.reload_config();
+    assert_eq!(app.theme.name, "solarized");
+    assert_eq!(app.keymap.preset, Preset::Vim);
+    assert_eq!(app.symbols.success, "+");
+    assert_eq!(app.outdated_interval.load(Ordering::Relaxed), 60);
+
+    fs::write(&path, "theme = [broken\n").unwrap();
+    app.reload_config();
+    assert_eq!(app.theme.name, "solarized");
+    assert!(app.logs.iter().any(|l| l.contains("config reload failed")));
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,176 @@
+use homebrew_tui::event::ConfirmAction;
+
+#[test]
+fn command_line_shows_full_brew_invocation() {
+    assert_eq!(
+        ConfirmAction::Install.command_line("wget"),
+        "brew install wget"
+    );
+    assert_eq!(
+        ConfirmAction::LinkOverwrite.command_line("python@3.12"),
+        "brew link --overwrite python@3.12"
+    );
+    assert_eq!(
+        ConfirmAction::BulkUpgrade(vec!["jq".into(), "git".into()]).command_line("2 packages"),
+        "brew upgrade jq git"
+    );
+    assert_eq!(
+        ConfirmAction::Install.command_line("weird name"),
+        "brew install 'weird name'"
+    );
+    assert!(ConfirmAction::InstallBrew
+        .command_line("Homebrew")
+        .contains("install.sh"));
+}
+
+#[test]
+fn configured_flags_and_env_reach_brew() {
+    use homebrew_tui::brew::Brew;
+    use homebrew_tui::config::Config;
+    use std::ffi::OsStr;
+
+    let cfg = Config::parse(
+        "[brew]\nflags = [\"--verbose\"]\n[brew.env]\nHOMEBREW_NO_AUTO_UPDATE = \"1\"\n",
+    )
+    .unwrap();
+    let brew = Brew::from_config(&cfg.brew);
+    assert_eq!(
+        ConfirmAction::Upgrade.command_line_with("jq", &brew.flags),
+        "brew upgrade jq --verbose"
+    );
+    assert!(ConfirmAction::InstallBrew
+        .brew_args_with("Homebrew", &brew.flags)
+        .is_empty());
+    // commands other than the package operations would reject them
+    for action in [
+        ConfirmAction::Update,
+        ConfirmAction::Cleanup,
+        ConfirmAction::Pin,
+        ConfirmAction::AnalyticsOff,
+        ConfirmAction::GistLogs,
+    ] {
+        assert!(!action
+            .brew_args_with("jq", &brew.flags)
+            .contains(&"--verbose".to_string()));
+    }
+    assert_eq!(
+        ConfirmAction::BulkUpgrade(vec!["jq".into(), "wget".into()])
+            .with_flags(vec!["--debug".into()])
+            .brew_args_with("", &brew.flags),
+        ["upgrade", "--debug", "jq", "wget", "--verbose"]
+    );
+    let cmd = brew.command();
+    let envs: Vec<_> = cmd.get_envs().collect();
+    assert_eq!(
+        envs,
+        vec![(OsStr::new("HOMEBREW_NO_AUTO_UPDATE"), Some(OsStr::new("1")))]
+    );
+}
+
+#[test]
+fn picked_flags_follow_the_verb() {
+    let install = ConfirmAction::Install.with_flags(vec!["--force".into(), "--verbose".into()]);
+    assert_eq!(install.title(), "Install");
+    assert_eq!(
+        install.command_line_with("wget", &["--debug".into()]),
+        "brew install --force --verbose wget --debug"
+    );
+    let bulk = ConfirmAction::BulkUninstall(vec!["jq".into(), "wget".into()])
+        .with_flags(vec!["--ignore-dependencies".into()]);
+    assert_eq!(bulk.packages().unwrap(), ["jq", "wget"]);
+    assert_eq!(
+        bulk.command_line("2 packages"),
+        "brew uninstall --ignore-dependencies jq wget"
+    );
+    // picking again replaces the flags; none gives the plain action back
+    let plain = install.with_flags(vec![]);
+    assert!(matches!(plain, ConfirmAction::Install));
+    assert!(plain.flags().is_empty());
+    let upgrade: Vec<_> = ConfirmAction::Upgrade
+        .flag_choices()
+        .iter()
+        .map(|(flag, _)| *flag)
+        .collect();
+    assert_eq!(upgrade, ["--verbose", "--debug"]);
+    assert!(ConfirmAction::InstallBrew.flag_choices().is_empty());
+}
+
+#[test]
+fn bulk_upgrade_flags_go_to_the_packages_that_failed() {
+    let pkgs = vec!["jq".to_string(), "oniguruma".into(), "wget".into()];
+    let bulk = ConfirmAction::BulkUpgrade(pkgs)
+        .with_flags(vec!["--verbose".into()])
+        .with_env(vec![("HOMEBREW_MAKE_JOBS".into(), "2".into())]);
+    let [rest, retry] = <[_; 2]>::try_from(bulk.split_retries(&["wget".into()]).unwrap()).unwrap();
+    assert_eq!(
+        rest.full_command_line_with("2 packages", &[]),
+        "HOMEBREW_MAKE_JOBS=2 brew upgrade jq oniguruma"
+    );
+    assert_eq!(
+        retry.full_command_line_with("wget", &[]),
+        "HOMEBREW_MAKE_JOBS=2 brew upgrade --verbose wget"
+    );
+    // none failed: the flags go to no package; all failed: to every one
+    let plain = bulk.split_retries(&[]).unwrap();
+    assert_eq!(plain.len(), 1);
+    assert_eq!(
+        plain[0].full_command_line_with("3 packages", &[]),
+        "HOMEBREW_MAKE_JOBS=2 brew upgrade jq oniguruma wget"
+    );
+    let all = bulk
+        .split_retries(&["jq".into(), "oniguruma".into(), "wget".into()])
+        .unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].flags(), ["--verbose"]);
+    // nothing to split: no flags, or not a bulk upgrade
+    assert!(bulk
+        .with_flags(vec![])
+        .split_retries(&["wget".into()])
+        .is_none());
+    assert!(ConfirmAction::Upgrade
+        .with_flags(vec!["--debug".into()])
+        .split_retries(&["wget".into()])
+        .is_none());
+}
+
+#[test]
+fn variables_for_one_operation_are_parsed_and_shown() {
+    use homebrew_tui::event::parse_env;
+
+    let env = parse_env("  HOMEBREW_MAKE_JOBS=8 https_proxy=http://proxy:3128 EMPTY= ").unwrap();
+    assert_eq!(
+        env,
+        [
+            ("HOMEBREW_MAKE_JOBS".to_string(), "8".to_string()),
+            ("https_proxy".to_string(), "http://proxy:3128".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ]
+    );
+    assert!(parse_env("").unwrap().is_empty());
+    assert!(parse_env("JOBS").is_err());
+    assert!(parse_env("1X=2").is_err());
+    assert!(parse_env("A-B=2").is_err());
+
+    let upgrade = ConfirmAction::Upgrade
+        .with_env(env[..2].to_vec())
+        .with_flags(vec!["--force".into()]);
+    assert_eq!(
+        upgrade.full_command_line_with("jq", &[]),
+        "HOMEBREW_MAKE_JOBS=8 https_proxy=http://proxy:3128 brew upgrade --force jq"
+    );
+    // the title, which is kept and shown around, leaves out what a proxy URL may carry
+    assert_eq!(
+        upgrade.command_line("jq"),
+        "HOMEBREW_MAKE_JOBS=… https_proxy=… brew upgrade --force jq"
+    );
+    // the flags and the variables are kept apart
+    assert_eq!(upgrade.with_flags(vec![]).env().len(), 2);
+    assert!(matches!(
+        upgrade.with_env(vec![]).base(),
+        ConfirmAction::Upgrade
+    ));
+    assert!(matches!(
+        ConfirmAction::Upgrade.with_env(vec![]),
+        ConfirmAction::Upgrade
+    ));
+}
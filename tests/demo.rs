@@ -0,0 +1,129 @@
+use homebrew_tui::backend::run_brew_operation;
+use homebrew_tui::brew::Brew;
+use homebrew_tui::event::{AppEvent, ConfirmAction};
+use std::sync::mpsc;
+
+#[test]
+fn demo_brew_answers_queries_from_fixtures() {
+    let mut brew = Brew::demo();
+    let installed = brew.list_installed().unwrap();
+    assert!(installed.iter().any(|f| f.name == "ripgrep"));
+    let outdated = brew.outdated().unwrap();
+    assert!(outdated.contains(&"jq".to_string()));
+    assert!(outdated
+        .iter()
+        .all(|o| installed.iter().any(|f| &f.name == o)));
+    assert!(brew.pinned().unwrap().is_empty());
+    assert!(brew.prefix().is_none());
+    assert!(brew.casks().unwrap().iter().any(|c| c.name == "iterm2"));
+    assert!(brew
+        .taps()
+        .unwrap()
+        .contains(&"homebrew/services".to_string()));
+    assert!(brew.services().unwrap().is_empty());
+
+    let info = brew.info("ripgrep").unwrap();
+    assert!(!info.installed.is_empty());
+    assert!(brew.info("no-such-formula").is_err());
+    assert!(brew.search("rip").unwrap().contains(&"ripgrep".to_string()));
+}
+
+#[test]
+fn demo_operations_are_simulated_and_update_the_lists() {
+    let brew = Brew::demo();
+    let (tx, rx) = mpsc::channel();
+    let code = run_brew_operation(&brew, &ConfirmAction::Upgrade, "jq", &tx);
+    drop(tx);
+    assert_eq!(code, Some(0));
+    assert!(!brew.outdated().unwrap().contains(&"jq".to_string()));
+
+    let events: Vec<AppEvent> = rx.into_iter().collect();
+    assert!(matches!(&events[0], AppEvent::OpStart(t) if t == "brew upgrade jq"));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, AppEvent::OpLog(l) if l.contains("Upgrading jq"))));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, AppEvent::OpExit(Some(0)))));
+    assert!(matches!(events.last(), Some(AppEvent::OpEnd(_))));
+}
+
+#[test]
+fn demo_install_of_unknown_formula_fails() {
+    let brew = Brew::demo();
+    let (tx, _rx) = mpsc::channel();
+    let code = run_brew_operation(&brew, &ConfirmAction::Install, "no-such-formula", &tx);
+    assert_eq!(code, Some(1));
+    assert!(!brew
+        .list_installed()
+        .unwrap()
+        .iter()
+        .any(|f| f.name == "no-such-formula"));
+}
+
+#[test]
+fn demo_pins_are_remembered() {
+    let brew = Brew::demo();
+    let (tx, _rx) = mpsc::channel();
+    assert_eq!(
+        run_brew_operation(&brew, &ConfirmAction::Pin, "jq", &tx),
+        Some(0)
+    );
+    assert_eq!(brew.pinned().unwrap(), vec!["jq"]);
+    run_brew_operation(&brew, &ConfirmAction::Unpin, "jq", &tx);
+    assert!(brew.pinned().unwrap().is_empty());
+}
+
+#[test]
+fn demo_switches_and_removes_versions() {
+    let mut brew = Brew::demo();
+    let (tx, _rx) = mpsc::channel();
+    let old = ConfirmAction::SwitchVersion {
+        version: "9.0.0".into(),
+    };
+    assert_eq!(run_brew_operation(&brew, &old, "fd", &tx), Some(0));
+    assert_eq!(
+        brew.info("fd").unwrap().linked_keg.as_deref(),
+        Some("9.0.0")
+    );
+    let remove = ConfirmAction::RemoveVersion {
+        version: "10.1.0".into(),
+    };
+    assert_eq!(run_brew_operation(&brew, &remove, "fd", &tx), Some(0));
+    let versions: Vec<_> = brew
+        .info("fd")
+        .unwrap()
+        .installed
+        .into_iter()
+        .map(|i| i.version)
+        .collect();
+    assert_eq!(versions, vec!["9.0.0"]);
+}
+
+#[test]
+fn demo_extract_installs_a_versioned_formula() {
+    let mut brew = Brew::demo();
+    let (tx, _rx) = mpsc::channel();
+    let extract = ConfirmAction::ExtractInstall {
+        version: "1.6".into(),
+    };
+    assert_eq!(run_brew_operation(&brew, &extract, "jq", &tx), Some(0));
+    let info = brew.info("jq@1.6").unwrap();
+    assert_eq!(
+        info.full_name.as_deref(),
+        Some("homebrew-tui/versions/jq@1.6")
+    );
+    assert_eq!(info.installed[0].version, "1.6");
+}
+
+#[test]
+fn demo_livecheck_reports_upstream_releases() {
+    let brew = Brew::demo();
+    let results = brew
+        .livecheck(&["gh".into(), "jq".into(), "nope".into()])
+        .unwrap();
+    assert!(results[0].lags());
+    assert_eq!(results[0].latest.as_deref(), Some("2.58.0"));
+    assert!(!results[1].lags());
+    assert_eq!(results[2].status.as_deref(), Some("error"));
+}
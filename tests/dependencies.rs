@@ -0,0 +1,143 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::bottles;
+use homebrew_tui::brew::{DependencyPreview, FormulaInfo};
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, fixtures, press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::Mode,
+    homebrew_tui::event::ConfirmAction,
+};
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+const INDEX: &str = r#"{
+  "schemaVersion": 2,
+  "manifests": [
+    {
+      "platform": { "architecture": "arm64", "os": "darwin", "os.version": "macOS 14" },
+      "annotations": { "sh.brew.bottle.size": "1500000" }
+    },
+    {
+      "platform": { "architecture": "arm64", "os": "darwin", "os.version": "macOS 13" },
+      "annotations": { "sh.brew.bottle.size": "1400000" }
+    },
+    {
+      "platform": { "architecture": "amd64", "os": "linux" },
+      "annotations": { "sh.brew.bottle.size": "2000000" }
+    },
+    { "platform": { "architecture": "amd64", "os": "darwin" }, "annotations": {} }
+  ]
+}"#;
+
+#[test]
+fn bottle_sizes_are_read_from_the_image_index() {
+    assert_eq!(
+        bottles::parse_size(INDEX, "macos", "aarch64").unwrap(),
+        Some(1_500_000)
+    );
+    assert_eq!(
+        bottles::parse_size(INDEX, "linux", "x86_64").unwrap(),
+        Some(2_000_000)
+    );
+    // no bottle for the platform: the largest one is a fair guess
+    assert_eq!(
+        bottles::parse_size(INDEX, "macos", "x86_64").unwrap(),
+        Some(2_000_000)
+    );
+    assert_eq!(
+        bottles::parse_size(r#"{"manifests": []}"#, "macos", "aarch64").unwrap(),
+        None
+    );
+    assert!(bottles::parse_size("Not Found", "macos", "aarch64").is_err());
+}
+
+#[test]
+fn bottle_images_follow_the_formula_name_and_version() {
+    assert_eq!(bottles::image_name("ripgrep"), "homebrew/core/ripgrep");
+    assert_eq!(
+        bottles::image_name("python@3.12"),
+        "homebrew/core/python/3.12"
+    );
+    assert_eq!(bottles::image_name("libstdc++"), "homebrew/core/libstdcxx");
+
+    let info: FormulaInfo = serde_json::from_str(
+        r#"{"name": "jq", "versions": {"stable": "1.7.1"}, "revision": 1,
+            "bottle": {"stable": {"rebuild": 2}}}"#,
+    )
+    .unwrap();
+    assert_eq!(bottles::image_tag(&info).as_deref(), Some("1.7.1_1-2"));
+    let info: FormulaInfo =
+        serde_json::from_str(r#"{"name": "wget", "versions": {"stable": "1.24.5"}}"#).unwrap();
+    assert_eq!(bottles::image_tag(&info).as_deref(), Some("1.24.5"));
+    let info: FormulaInfo = serde_json::from_str(
+        r#"{"name": "tool", "tap": "someone/tools", "versions": {"stable": "1.0"}}"#,
+    )
+    .unwrap();
+    assert_eq!(bottles::image_tag(&info), None);
+}
+
+#[test]
+fn previews_count_only_what_is_not_installed() {
+    let installed: HashSet<String> = ["oniguruma".to_string()].into();
+    let sizes: HashMap<String, u64> = [("pcre2".to_string(), 1_800_000)].into();
+    let preview = DependencyPreview::new(
+        "ripgrep",
+        names(&["oniguruma", "pcre2"]),
+        &installed,
+        &sizes,
+    );
+    assert_eq!(preview.new, ["pcre2"]);
+    assert_eq!((preview.installed, preview.download), (1, 1_800_000));
+    assert_eq!(
+        preview.summary(),
+        "Pulls in 1 new dependency, about 1.7MB to download: pcre2"
+    );
+    assert!(!preview.is_large());
+
+    let preview = DependencyPreview::new("ripgrep", names(&["pcre2", "rust"]), &installed, &sizes);
+    assert_eq!(preview.unknown_size, 1);
+    assert!(preview.summary().contains("at least 1.7MB to download"));
+    let preview = DependencyPreview::new("ripgrep", names(&["rust"]), &installed, &sizes);
+    assert!(preview.summary().contains("download size unknown"));
+
+    let preview = DependencyPreview::new("jq", names(&["oniguruma"]), &installed, &sizes);
+    assert!(preview.new.is_empty());
+    assert_eq!(
+        preview.summary(),
+        "No new dependencies (1 installed already)."
+    );
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn confirming_an_install_looks_up_its_dependencies() {
+    let mut backend = fixtures();
+    backend.dependencies = vec![("ripgrep".into(), names(&["oniguruma", "pcre2"]))];
+    backend.bottle_sizes = vec![("pcre2".into(), 200 << 20)];
+    let mut app = app_with(backend);
+
+    press(&mut app, KeyCode::Char('i'));
+    for c in "ripgrep".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action: ConfirmAction::Install, name, .. } if name == "ripgrep"
+    ));
+    assert!(app.dependencies.contains_key("ripgrep"));
+    pump(&mut app, |a| {
+        a.dependencies.get("ripgrep").is_some_and(|p| p.is_some())
+    });
+    let preview = app.dependencies["ripgrep"].as_ref().unwrap();
+    // oniguruma is installed in the fixtures
+    assert_eq!(preview.new, ["pcre2"]);
+    assert_eq!(preview.installed, 1);
+    assert!(preview.is_large());
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "tui")]
+
+use homebrew_tui::app::{App, AppEvent, Focus, DETAILS_DEBOUNCE};
+use homebrew_tui::brew::FormulaInfo;
+use homebrew_tui::history::History;
+use std::time::{Duration, Instant};
+
+fn app_with_installed(names: &[&str]) -> App {
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    app.items = names
+        .iter()
+        .map(|n| FormulaInfo {
+            name: n.to_string(),
+            ..Default::default()
+        })
+        .collect();
+    app.focus = Focus::Installed;
+    app.selected = 0;
+    app.last_selected = None;
+    app
+}
+
+#[test]
+fn details_are_fetched_only_after_the_selection_settles() {
+    let mut app = app_with_installed(&["bat", "fd", "jq"]);
+    let t0 = Instant::now();
+    app.load_details_if_settled(t0);
+    assert_eq!(app.last_selected, None);
+
+    // scrolling on before the debounce elapses restarts it for the new row
+    app.selected = 1;
+    app.load_details_if_settled(t0 + Duration::from_millis(100));
+    app.selected = 2;
+    app.load_details_if_settled(t0 + Duration::from_millis(200));
+    app.load_details_if_settled(t0 + Duration::from_millis(300));
+    assert_eq!(app.last_selected, None);
+
+    app.load_details_if_settled(t0 + Duration::from_millis(200) + DETAILS_DEBOUNCE);
+    assert_eq!(app.last_selected, Some((Focus::Installed, 2)));
+    assert!(app.pending_details.is_none());
+}
+
+#[test]
+fn details_for_rows_scrolled_past_are_ignored() {
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    app.available_items = vec!["bat".into(), "fd".into()];
+    app.available_selected = 1;
+    let info = |name: &str| FormulaInfo {
+        name: name.into(),
+        ..Default::default()
+    };
+    app.handle_event(AppEvent::BrewInfoAvailable(info("bat"), 0));
+    assert!(app.available_details.is_none());
+    app.handle_event(AppEvent::BrewInfoAvailable(info("fd"), 1));
+    assert_eq!(app.available_details.map(|d| d.name), Some("fd".into()));
+}
@@ -0,0 +1,77 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::disk::{self, PackageUsage};
+use std::fs;
+#[cfg(feature = "tui")]
+use {
+    common::{press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    homebrew_tui::disk::DiskUsage,
+    homebrew_tui::event::ConfirmAction,
+    std::sync::Arc,
+};
+
+#[test]
+fn cellar_packages_are_sorted_by_size() {
+    let cellar = std::env::temp_dir().join(format!("homebrew-tui-cellar-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cellar);
+    fs::create_dir_all(cellar.join("jq/1.7.1/bin")).unwrap();
+    fs::create_dir_all(cellar.join("wget/1.24.5/bin")).unwrap();
+    fs::create_dir_all(cellar.join("wget/1.21.4")).unwrap();
+    fs::write(cellar.join("jq/1.7.1/bin/jq"), vec![0u8; 1000]).unwrap();
+    fs::write(cellar.join("wget/1.24.5/bin/wget"), vec![0u8; 1500]).unwrap();
+    fs::write(cellar.join("wget/1.21.4/old"), vec![0u8; 700]).unwrap();
+    // stray files next to the package directories are not packages
+    fs::write(cellar.join(".keepme"), b"x").unwrap();
+
+    let usage = disk::cellar_usage(&cellar).unwrap();
+    assert_eq!(
+        usage.packages,
+        vec![
+            PackageUsage {
+                name: "wget".into(),
+                size: 2200
+            },
+            PackageUsage {
+                name: "jq".into(),
+                size: 1000
+            },
+        ]
+    );
+    assert_eq!(usage.total(), 3200);
+    let _ = fs::remove_dir_all(&cellar);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn selected_package_can_be_uninstalled_from_the_view() {
+    let package = |name: &str, size| PackageUsage {
+        name: name.into(),
+        size,
+    };
+    let backend = Arc::new(MockBackend {
+        disk_usage: DiskUsage {
+            cellar: "/opt/homebrew/Cellar".into(),
+            packages: vec![package("llvm", 1 << 30), package("jq", 1 << 20)],
+        },
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+
+    press(&mut app, KeyCode::Char('S'));
+    pump(&mut app, |a| matches!(a.mode, Mode::DiskUsage { .. }));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('r'));
+    match &app.mode {
+        Mode::Confirm {
+            action: ConfirmAction::Uninstall,
+            name,
+            ..
+        } => assert_eq!(name, "jq"),
+        _ => panic!("expected the uninstall confirmation"),
+    }
+}
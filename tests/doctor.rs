@@ -0,0 +1,116 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::Severity;
+use homebrew_tui::parse::parse_doctor;
+#[cfg(feature = "tui")]
+use {
+    common::{press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    std::sync::Arc,
+};
+
+const OUTPUT: &str = "\
+Please note that these warnings are just used to help the Homebrew maintainers
+with debugging if you file an issue. If everything you use Homebrew for is
+working fine: please don't worry or file an issue; just ignore this. Thanks!
+
+Warning: Some installed formulae are deprecated or disabled.
+You should find replacements for the following formulae:
+  python@3.8
+
+Error: Your Command Line Tools are too outdated.
+Update them from Software Update in System Settings.
+";
+
+#[test]
+fn doctor_output_is_split_into_findings() {
+    let items = parse_doctor(OUTPUT);
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].severity, Severity::Warning);
+    assert_eq!(
+        items[0].title,
+        "Some installed formulae are deprecated or disabled."
+    );
+    assert_eq!(
+        items[0].details,
+        vec![
+            "You should find replacements for the following formulae:",
+            "  python@3.8"
+        ]
+    );
+    assert_eq!(items[1].severity, Severity::Error);
+    assert_eq!(
+        items[1].text(),
+        "Error: Your Command Line Tools are too outdated.\nUpdate them from Software Update in System Settings."
+    );
+    assert!(parse_doctor("Your system is ready to brew.\n").is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn findings_count_even_though_doctor_exits_with_an_error() {
+    use homebrew_tui::brew::Brew;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-doctor-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("brew");
+    let write = |body: &str| {
+        fs::write(&script, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    let brew = Brew {
+        program: script.clone(),
+        ..Brew::new()
+    };
+
+    write("echo 'Warning: Unbrewed dylibs were found in /usr/local/lib.' >&2; exit 1");
+    let items = brew.doctor().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(
+        items[0].title,
+        "Unbrewed dylibs were found in /usr/local/lib."
+    );
+
+    write("echo 'Your system is ready to brew.'");
+    assert!(brew.doctor().unwrap().is_empty());
+
+    write("echo 'Error: Invalid usage' >&2; echo boom >&2; exit 1");
+    assert_eq!(brew.doctor().unwrap()[0].severity, Severity::Error);
+
+    write("echo boom >&2; exit 2");
+    assert!(brew.doctor().is_err());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn findings_open_a_checklist() {
+    let backend = Arc::new(MockBackend {
+        doctor: parse_doctor(OUTPUT),
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+    press(&mut app, KeyCode::Char('D'));
+    pump(&mut app, |a| matches!(a.mode, Mode::Doctor { .. }));
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Down);
+    match &app.mode {
+        Mode::Doctor {
+            selected, checked, ..
+        } => {
+            assert_eq!(*selected, 1);
+            assert_eq!(checked, &vec![false, true]);
+        }
+        _ => panic!("doctor modal closed"),
+    }
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Normal));
+}
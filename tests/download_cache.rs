@@ -0,0 +1,131 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::disk::{self, CacheEntry, DownloadCache};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+#[cfg(feature = "tui")]
+use {
+    common::{press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    homebrew_tui::event::ConfirmAction,
+    std::sync::Arc,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn entry(name: &str, size: u64, modified: u64) -> CacheEntry {
+    CacheEntry {
+        path: PathBuf::from("/cache").join(name),
+        size,
+        modified,
+    }
+}
+
+#[test]
+fn sizes_read_like_brew_prints_them() {
+    assert_eq!(disk::format_size(512), "512B");
+    assert_eq!(disk::format_size(3 * 1024 + 100), "3.1KB");
+    assert_eq!(disk::format_size(42 << 20), "42MB");
+    assert_eq!(disk::format_size((1 << 30) + (1 << 28)), "1.3GB");
+}
+
+#[test]
+fn purgeable_entries_follow_the_age_limit() {
+    let now = 100 * DAY;
+    let cache = DownloadCache {
+        dir: "/cache".into(),
+        entries: vec![
+            entry("old.tar.gz", 300, now - 40 * DAY),
+            entry("recent.tar.gz", 200, now - 2 * DAY),
+            entry("older.tar.gz", 100, now - 90 * DAY),
+        ],
+    };
+    assert_eq!(cache.total(), 600);
+    assert_eq!(cache.reclaimable(None, now), (600, 3));
+    assert_eq!(cache.reclaimable(Some(30), now), (400, 2));
+    assert_eq!(cache.reclaimable(Some(60), now), (100, 1));
+}
+
+#[cfg(unix)]
+#[test]
+fn purge_command_removes_what_the_scan_counted() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-dlcache-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("downloads")).unwrap();
+    fs::write(
+        dir.join("downloads/old--1.0.bottle.tar.gz"),
+        vec![0u8; 2048],
+    )
+    .unwrap();
+    fs::write(
+        dir.join("downloads/new--2.0.bottle.tar.gz"),
+        vec![0u8; 1024],
+    )
+    .unwrap();
+    fs::File::options()
+        .write(true)
+        .open(dir.join("downloads/old--1.0.bottle.tar.gz"))
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(45 * DAY))
+        .unwrap();
+
+    let cache = disk::scan_cache(&dir).unwrap();
+    assert_eq!(cache.total(), 3072);
+    assert_eq!(
+        cache.entries[0].path,
+        dir.join("downloads/old--1.0.bottle.tar.gz")
+    );
+    assert_eq!(cache.reclaimable(Some(30), disk::now_secs()), (2048, 1));
+
+    let status = std::process::Command::new("find")
+        .args(disk::purge_args(dir.to_str().unwrap(), Some(30)))
+        .stdout(std::process::Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let left = disk::scan_cache(&dir).unwrap();
+    assert_eq!(left.total(), 1024);
+    assert!(dir.join("downloads").is_dir(), "directories are kept");
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn old_files_are_purged_after_confirming() {
+    let now = disk::now_secs();
+    let backend = Arc::new(MockBackend {
+        download_cache: DownloadCache {
+            dir: "/cache".into(),
+            entries: vec![
+                entry("a.tar.gz", 5000, now - 40 * DAY),
+                entry("b.tar.gz", 1000, now - DAY),
+            ],
+        },
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+
+    press(&mut app, KeyCode::Char('K'));
+    pump(&mut app, |a| matches!(a.mode, Mode::DownloadCache { .. }));
+    press(&mut app, KeyCode::Char('o'));
+    assert!(matches!(
+        app.mode,
+        Mode::Confirm {
+            action: ConfirmAction::PurgeCache {
+                older_than_days: Some(30)
+            },
+            ..
+        }
+    ));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| a.history.records.len() == 1 && !a.operating);
+    assert_eq!(
+        backend.executed(),
+        vec!["find /cache '(' -type f -o -type l ')' -mmin +43200 -print -delete".to_string()]
+    );
+}
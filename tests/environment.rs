@@ -0,0 +1,117 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::BrewEnvironment;
+use homebrew_tui::parse::{parse_analytics_state, parse_key_values};
+#[cfg(feature = "tui")]
+use {
+    common::{press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    homebrew_tui::event::ConfirmAction,
+    std::sync::Arc,
+};
+
+const CONFIG: &str = "\
+HOMEBREW_VERSION: 4.2.21
+ORIGIN: https://github.com/Homebrew/brew
+Core tap origin: https://github.com/Homebrew/homebrew-core
+Core tap HEAD: 4d2b1c3
+HOMEBREW_PREFIX: /opt/homebrew
+HOMEBREW_NO_INSTALL_FROM_API: set
+Git: 2.39.3 => /Library/Developer/CommandLineTools/usr/bin/git
+macOS: 14.4-arm64
+CLT: 15.3.0.0.1.1708646388
+Xcode: N/A
+";
+
+#[test]
+fn config_lines_split_at_the_first_colon() {
+    let pairs = parse_key_values(CONFIG);
+    assert_eq!(pairs.len(), 10);
+    assert_eq!(
+        pairs[1],
+        (
+            "ORIGIN".to_string(),
+            "https://github.com/Homebrew/brew".to_string()
+        )
+    );
+    assert!(parse_key_values("no separator here\n: no key\n").is_empty());
+}
+
+#[test]
+fn summary_reports_tap_mode_and_tools() {
+    let env = BrewEnvironment {
+        config: parse_key_values(CONFIG),
+        env: vec![],
+        cellar: Some("/opt/homebrew/Cellar".into()),
+        analytics: Some(false),
+    };
+    assert!(!env.uses_api());
+    let summary = env.summary();
+    let get = |key: &str| {
+        summary
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap()
+    };
+    assert_eq!(get("Homebrew"), "4.2.21");
+    assert_eq!(get("Cellar"), "/opt/homebrew/Cellar");
+    assert_eq!(get("Formulae from"), "taps");
+    assert_eq!(get("Core tap"), "installed");
+    assert_eq!(get("Xcode"), "N/A");
+    assert_eq!(get("Curl"), "unknown");
+    assert_eq!(get("Analytics"), "disabled");
+
+    let api = BrewEnvironment {
+        config: parse_key_values("HOMEBREW_VERSION: 4.3.0\nCore tap JSON: 12 May 09:30 UTC\n"),
+        ..Default::default()
+    };
+    assert!(api.uses_api());
+    let summary = api.summary();
+    assert!(summary.contains(&("Formulae from", "JSON API".to_string())));
+    assert!(summary.contains(&("Core tap", "not installed".to_string())));
+    assert!(summary.contains(&("Cellar", "unknown".to_string())));
+}
+
+#[test]
+fn analytics_state_is_read_from_brew_analytics() {
+    assert_eq!(
+        parse_analytics_state("InfluxDB analytics are enabled.\n"),
+        Some(true)
+    );
+    assert_eq!(
+        parse_analytics_state("Analytics are disabled.\n"),
+        Some(false)
+    );
+    assert_eq!(parse_analytics_state(""), None);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn panel_turns_analytics_off_after_confirmation() {
+    let backend = Arc::new(MockBackend {
+        environment: BrewEnvironment {
+            analytics: Some(true),
+            ..Default::default()
+        },
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+    press(&mut app, KeyCode::Char('E'));
+    pump(&mut app, |a| matches!(a.mode, Mode::Environment { .. }));
+    press(&mut app, KeyCode::Char('a'));
+    assert!(matches!(
+        app.mode,
+        Mode::Confirm {
+            action: ConfirmAction::AnalyticsOff,
+            ..
+        }
+    ));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| a.history.records.len() == 1 && !a.operating);
+    assert_eq!(backend.executed(), vec!["brew analytics off".to_string()]);
+}
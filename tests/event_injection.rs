@@ -0,0 +1,111 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{fixtures, press, pump, type_text};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use homebrew_tui::app::{App, ConfirmAction, Focus, Mode};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::config::ConfigSource;
+use std::sync::Arc;
+
+fn app() -> (App, Arc<MockBackend>) {
+    let backend = Arc::new(fixtures());
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+    app.config.reduced_motion = true;
+    pump(&mut app, |a| {
+        a.items.len() == 3 && !a.available_items.is_empty() && !a.loading_details
+    });
+    (app, backend)
+}
+
+#[test]
+fn search_select_confirm_and_run_an_operation() {
+    let (mut app, backend) = app();
+    app.focus = Focus::Installed;
+
+    press(&mut app, KeyCode::Char('s'));
+    type_text(&mut app, "grep");
+    press(&mut app, KeyCode::Enter);
+    pump(&mut app, |a| matches!(a.mode, Mode::SearchResults { .. }));
+    let Mode::SearchResults { results, .. } = &app.mode else {
+        unreachable!()
+    };
+    assert_eq!(results, &["ripgrep"]);
+
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action: ConfirmAction::Install, name, .. } if name == "ripgrep"
+    ));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| {
+        !a.operating
+            && a.history
+                .records
+                .last()
+                .is_some_and(|r| r.exit_code.is_some())
+    });
+    assert_eq!(backend.executed(), ["brew install ripgrep"]);
+    assert!(matches!(app.mode, Mode::Operation { .. }));
+    assert_eq!(app.history.records[0].exit_code, Some(1));
+}
+
+#[test]
+fn verbose_bulk_upgrades_retry_only_what_failed_before() {
+    let (mut app, backend) = app();
+    app.history.start("brew upgrade wget");
+    app.history.set_exit_code(Some(1));
+    app.history.finish().unwrap();
+    app.mode = Mode::Confirm {
+        action: ConfirmAction::BulkUpgrade(vec!["jq".into(), "wget".into()])
+            .with_flags(vec!["--verbose".into()]),
+        name: "2 packages".into(),
+        idx: None,
+    };
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| {
+        a.history.records.len() == 3 && a.history.records[2].exit_code.is_some()
+    });
+    assert_eq!(
+        backend.executed(),
+        ["brew upgrade jq", "brew upgrade --verbose wget"]
+    );
+}
+
+#[test]
+fn a_failed_upgrade_of_several_packages_blames_none_of_them() {
+    let (mut app, backend) = app();
+    app.history.start("brew upgrade jq oniguruma wget");
+    app.history.set_exit_code(Some(1));
+    app.history.finish().unwrap();
+    app.mode = Mode::Confirm {
+        action: ConfirmAction::BulkUpgrade(vec!["jq".into(), "wget".into()])
+            .with_flags(vec!["--verbose".into()]),
+        name: "2 packages".into(),
+        idx: None,
+    };
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| {
+        a.history.records.len() == 2 && a.history.records[1].exit_code.is_some()
+    });
+    assert_eq!(backend.executed(), ["brew upgrade jq wget"]);
+}
+
+#[test]
+fn ticks_redraw_only_after_a_change() {
+    let (mut app, _) = app();
+    // settled: the lists are in and nothing moves
+    pump(&mut app, |a| !a.is_animating());
+    while app.tick() {}
+    assert!(!app.tick());
+    press(&mut app, KeyCode::Down);
+    assert!(app.tick());
+    assert!(!app.tick());
+}
+
+#[test]
+fn injected_quit_ends_the_app() {
+    let (mut app, _) = app();
+    assert!(app.inject_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE)));
+}
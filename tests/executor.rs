@@ -0,0 +1,70 @@
+#![cfg(unix)]
+
+use homebrew_tui::event::AppEvent;
+use homebrew_tui::executor;
+use homebrew_tui::jobs::{self, CancelToken};
+use homebrew_tui::output::Stream;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+fn sh(script: &str) -> Command {
+    let mut cmd = Command::new("/bin/sh");
+    cmd.arg("-c").arg(script);
+    cmd
+}
+
+#[test]
+fn process_yields_typed_lines_then_the_exit_status() {
+    let (lines, status) = jobs::runtime().block_on(async {
+        let mut process = executor::spawn(sh("echo one; echo two; echo oops >&2; exit 3")).unwrap();
+        let token = CancelToken::new();
+        let mut lines = vec![];
+        while let Some(line) = process.next_line(&token).await {
+            lines.push(line);
+        }
+        (lines, process.wait().await.unwrap())
+    });
+    assert_eq!(status.code(), Some(3));
+    let text = |stream: Stream| -> Vec<String> {
+        lines
+            .iter()
+            .filter(|l| l.stream == stream)
+            .map(|l| l.text.clone())
+            .collect()
+    };
+    assert_eq!(text(Stream::Stdout), vec!["one", "two"]);
+    assert_eq!(text(Stream::Stderr), vec!["oops"]);
+}
+
+#[test]
+fn run_forwards_lines_as_events_and_stops_on_cancel() {
+    let (tx, rx) = mpsc::channel();
+    let token = CancelToken::new();
+    let cancel = token.clone();
+    let started = Instant::now();
+    let status = jobs::runtime().block_on(async {
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            cancel.cancel();
+        });
+        executor::run(
+            sh("echo started; trap 'echo stopping; kill $!; exit 143' TERM; sleep 30 >/dev/null & wait"),
+            &tx,
+            &token,
+        )
+        .await
+        .unwrap()
+    });
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(!status.success());
+    drop(tx);
+    let events: Vec<String> = rx
+        .iter()
+        .filter_map(|ev| match ev {
+            AppEvent::OpLog(l) => Some(l),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(events, vec!["started", "stopping"]);
+}
@@ -0,0 +1,105 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::favorites::Favorites;
+use homebrew_tui::sort;
+use std::collections::HashSet;
+use std::fs;
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, fixtures, press},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Focus, MenuItem},
+    homebrew_tui::keymap::Action,
+};
+
+#[cfg(feature = "tui")]
+fn available_rows(app: &App) -> Vec<&str> {
+    app.available_filtered
+        .iter()
+        .map(|&i| app.available_items[i].as_str())
+        .collect()
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn starred_packages_are_listed_first_in_available() {
+    let mut app = app_with(fixtures());
+    assert_eq!(
+        available_rows(&app),
+        ["bat", "fd", "firefox", "jq", "oniguruma", "ripgrep", "wget"]
+    );
+
+    // one that is not installed yet, from Available
+    app.focus = Focus::Available;
+    app.available_selected = app
+        .available_items
+        .iter()
+        .position(|n| n == "ripgrep")
+        .unwrap();
+    press(&mut app, KeyCode::Char('*'));
+    // and an installed one, from Installed
+    app.focus = Focus::Installed;
+    app.selected = app.items.iter().position(|f| f.name == "wget").unwrap();
+    press(&mut app, KeyCode::Char('*'));
+    assert_eq!(
+        available_rows(&app),
+        ["ripgrep", "wget", "bat", "fd", "firefox", "jq", "oniguruma"]
+    );
+    // the selection stays on the package
+    assert_eq!(app.available_items[app.available_selected], "ripgrep");
+    assert!(app.logs.iter().any(|l| l.message == "Starred wget"));
+
+    // the menu offers to take the star back
+    let (_, items) = app.menu_items().unwrap();
+    let star = items
+        .iter()
+        .find(|i| **i == MenuItem::Run(Action::ToggleFavorite))
+        .unwrap();
+    assert_eq!(app.menu_label(star, "wget"), "Unstar");
+    press(&mut app, KeyCode::Char('*'));
+    assert!(!app.favorites.contains("wget"));
+    assert_eq!(available_rows(&app)[..2], ["ripgrep", "bat"]);
+
+    // starred packages still have to match the filter
+    app.start_search("fd");
+    assert_eq!(available_rows(&app), ["fd"]);
+}
+
+#[test]
+fn favorites_are_kept_in_the_state_file() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-favorites-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("favorites.json");
+
+    let mut favorites = Favorites::load_from(Some(path.clone()));
+    assert!(favorites.names.is_empty());
+    assert!(favorites.toggle("ripgrep"));
+    assert!(favorites.toggle("fd"));
+    assert!(!favorites.toggle("ripgrep"));
+    favorites.save().unwrap();
+    let loaded = Favorites::load_from(Some(path.clone()));
+    assert!(loaded.contains("fd") && !loaded.contains("ripgrep"));
+
+    // the in-memory set used by demo sessions and tests writes nothing
+    let mut memory = Favorites::in_memory();
+    memory.toggle("bat");
+    memory.save().unwrap();
+    assert_eq!(Favorites::load_from(Some(path)).names.len(), 1);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn starred_versions_move_with_their_formula() {
+    let names: Vec<String> = ["bat", "python", "python@3.11", "python@3.12", "wget"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    // python's versions are listed below it
+    let members: HashSet<usize> = [2, 3].into();
+    let mut rows = vec![0, 1, 2, 3, 4];
+    sort::favorites_first(&mut rows, &members, &names, |n| {
+        n == "python@3.12" || n == "wget"
+    });
+    assert_eq!(rows, [1, 2, 3, 4, 0]);
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{app_with, fixtures, press, pump};
+use crossterm::event::KeyCode;
+use homebrew_tui::app::{App, Focus, Mode};
+use homebrew_tui::backend::{BrewBackend, MockBackend, ScriptLine};
+use homebrew_tui::output::{Level, Source};
+use std::sync::Arc;
+
+/// An app on the fixtures with every list loaded.
+fn app(backend: &Arc<MockBackend>) -> App {
+    let mut app = app_with(backend.clone());
+    pump(&mut app, |a| {
+        !a.outdated_items.is_empty() && !a.pinned.is_empty()
+    });
+    app
+}
+
+/// Wait for the operation the app just started to finish.
+fn finish(app: &mut App) {
+    pump(app, |a| {
+        !a.operating
+            && a.history
+                .records
+                .last()
+                .is_some_and(|r| r.exit_code.is_some())
+    });
+}
+
+#[test]
+fn fixtures_parse_in_brew_formats() {
+    let backend = fixtures();
+    let names: Vec<_> = backend.installed.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["jq", "oniguruma", "wget"]);
+    assert_eq!(backend.installed[0].stable_version(), Some("1.7.1"));
+    assert!(backend
+        .available
+        .iter()
+        .any(|e| e.name == "firefox" && e.cask));
+    assert_eq!(backend.outdated, ["jq", "wget"]);
+    assert_eq!(backend.pinned, ["wget"]);
+    assert_eq!(backend.taps, ["homebrew/core", "homebrew/cask"]);
+    assert_eq!(backend.casks[0].version, "125.0.3");
+    assert_eq!(backend.doctor.len(), 1);
+    assert_eq!(backend.cleanup.files.len(), 2);
+    assert!(backend
+        .livecheck
+        .iter()
+        .any(|l| l.formula == "wget" && l.outdated));
+    assert_eq!(backend.formula_log.len(), 2);
+    assert_eq!(backend.scripts.len(), 2);
+    assert!(matches!(
+        &backend.scripts[1].output[1],
+        ScriptLine::Stderr(l) if l.starts_with("Error:")
+    ));
+    assert_eq!(backend.info("ripgrep").unwrap().name, "ripgrep");
+}
+
+#[test]
+fn upgrading_an_outdated_package_streams_its_script() {
+    let backend = Arc::new(fixtures());
+    let mut app = app(&backend);
+    assert!(!app.is_pinned("jq") && app.is_pinned("wget"));
+    app.focus = Focus::Installed;
+    app.selected = app.items.iter().position(|f| f.name == "jq").unwrap();
+
+    press(&mut app, KeyCode::Char('u'));
+    assert!(matches!(app.mode, Mode::Confirm { .. }), "{:?}", app.mode);
+    press(&mut app, KeyCode::Char('y'));
+    finish(&mut app);
+
+    assert_eq!(backend.executed(), ["brew upgrade jq"]);
+    let record = &app.history.records[0];
+    assert_eq!(record.exit_code, Some(0));
+    assert!(record
+        .logs
+        .iter()
+        .any(|l| l.text.contains("Pouring jq--1.7.1")));
+    assert!(app
+        .logs
+        .iter()
+        .any(|e| e.message.contains("/opt/homebrew/Cellar/jq/1.7.1")));
+}
+
+#[test]
+fn a_failing_install_reports_its_errors() {
+    let backend = Arc::new(fixtures());
+    let mut app = app(&backend);
+    assert!(app.select_package("ripgrep"));
+    assert_eq!(app.focus, Focus::Available);
+
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::Confirm { .. }), "{:?}", app.mode);
+    press(&mut app, KeyCode::Char('y'));
+    finish(&mut app);
+
+    assert_eq!(backend.executed(), ["brew install ripgrep"]);
+    assert_eq!(app.history.records[0].exit_code, Some(1));
+    assert_eq!(app.history.records[0].failed_packages(), ["ripgrep"]);
+    let errors: Vec<_> = app
+        .logs
+        .iter()
+        .filter(|e| e.source == Source::Stderr && e.level == Level::Error)
+        .map(|e| e.message.as_str())
+        .collect();
+    assert_eq!(
+        errors,
+        ["Error: ripgrep: Failed to download resource \"ripgrep\""]
+    );
+}
+
+#[test]
+fn operations_without_a_script_succeed_quietly() {
+    let backend = Arc::new(fixtures());
+    let mut app = app(&backend);
+    app.focus = Focus::Installed;
+    app.selected = app
+        .items
+        .iter()
+        .position(|f| f.name == "oniguruma")
+        .unwrap();
+    press(&mut app, KeyCode::Char('r'));
+    press(&mut app, KeyCode::Char('y'));
+    finish(&mut app);
+    assert_eq!(backend.executed(), ["brew uninstall oniguruma"]);
+    assert_eq!(app.history.records[0].exit_code, Some(0));
+}
@@ -0,0 +1,92 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::parse::parse_log;
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::Mode,
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::brew::{FormulaInfo, InstalledInfo},
+    std::sync::Arc,
+};
+
+const LOG: &str = "\
+commit 4f2a9c1d0e8b7a6f5e4d3c2b1a0f9e8d7c6b5a49
+Author: BrewTestBot <1589480+BrewTestBot@users.noreply.github.com>
+Date:   Mon Jul 1 08:12:44 2024 +0000
+
+    jq: update 1.7.1_1 bottle.
+
+commit 9e8d7c6b5a494f2a9c1d0e8b7a6f5e4d3c2b1a0f
+Author: Jane Doe <jane@example.com>
+Date:   Sun Jun 30 21:03:10 2024 +0200
+
+    jq 1.7.1_1
+
+    Closes #170000.
+
+    Signed-off-by: BrewTestBot <1589480+BrewTestBot@users.noreply.github.com>
+
+commit 0a1b2c3d4e5f60718293a4b5c6d7e8f901234567
+Author: John Roe <john@example.com>
+Date:   Thu Dec 14 10:00:00 2023 +0000
+
+    jq 1.7.1
+";
+
+#[test]
+fn git_log_output_splits_into_commits() {
+    let commits = parse_log(LOG);
+    assert_eq!(commits.len(), 3);
+    assert_eq!(commits[0].hash, "4f2a9c1d0e8b7a6f5e4d3c2b1a0f9e8d7c6b5a49");
+    assert_eq!(commits[0].subject, "jq: update 1.7.1_1 bottle.");
+    assert_eq!(commits[1].author, "Jane Doe <jane@example.com>");
+    assert_eq!(
+        commits[1].subject, "jq 1.7.1_1",
+        "only the first message line"
+    );
+    assert_eq!(commits[2].day(), "Dec 14 2023");
+
+    assert!(commits[0].bumps_to("jq", "1.7.1_1"));
+    assert!(commits[1].bumps_to("jq", "1.7.1_1"));
+    assert!(!commits[1].bumps_to("jq", "1.7.1"));
+    assert!(commits[2].bumps_to("jq", "1.7.1"));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn history_of_the_selected_formula_knows_the_installed_version() {
+    let backend = Arc::new(MockBackend {
+        installed: vec![FormulaInfo {
+            name: "jq".into(),
+            installed: vec![InstalledInfo {
+                version: "1.7.1".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+        formula_log: parse_log(LOG),
+        ..MockBackend::new()
+    });
+    let mut app = app_with(backend);
+
+    press(&mut app, KeyCode::Char('c'));
+    pump(&mut app, |a| matches!(a.mode, Mode::FormulaLog { .. }));
+    match &app.mode {
+        Mode::FormulaLog {
+            name,
+            commits,
+            installed,
+            ..
+        } => {
+            assert_eq!(name, "jq");
+            assert_eq!(commits.len(), 3);
+            assert_eq!(installed.as_deref(), Some("1.7.1"));
+        }
+        _ => unreachable!(),
+    }
+    press(&mut app, KeyCode::Char('q'));
+    assert!(matches!(app.mode, Mode::Normal));
+}
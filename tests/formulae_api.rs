@@ -0,0 +1,65 @@
+use homebrew_tui::brew::Brew;
+use homebrew_tui::config::{Config, Metadata};
+use homebrew_tui::formulae_api::{self, cask_url, formula_url, parse_cask, parse_formula};
+use std::fs;
+use std::path::Path;
+
+fn fixture(file: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/formulae-api")
+        .join(file);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+#[test]
+fn formulae_have_the_fields_of_brew_info() {
+    let jq = parse_formula(&fixture("jq.json")).unwrap();
+    assert_eq!(jq.name, "jq");
+    assert_eq!(
+        jq.desc.as_deref(),
+        Some("Lightweight and flexible command-line JSON processor")
+    );
+    assert_eq!(jq.stable_version(), Some("1.7.1"));
+    assert_eq!(jq.dependencies, ["oniguruma"]);
+    assert_eq!(jq.tap_name(), Some("homebrew/core"));
+    assert!(jq.installed.is_empty());
+    // the options' installs are added up
+    assert_eq!(jq.install_counts(), Some([61_056, 189_876, 801_234]));
+    assert!(parse_formula("<html>").is_err());
+}
+
+#[test]
+fn casks_are_described_like_formulae() {
+    let firefox = parse_cask(&fixture("firefox.json")).unwrap();
+    assert_eq!(firefox.name, "firefox");
+    assert_eq!(firefox.desc.as_deref(), Some("Web browser"));
+    assert_eq!(firefox.stable_version(), Some("125.0.3"));
+    assert!(firefox
+        .source_url()
+        .is_some_and(|u| u.ends_with("Firefox%20125.0.3.dmg")));
+    assert_eq!(firefox.tap_name(), Some("homebrew/cask"));
+    assert_eq!(firefox.install_counts(), Some([9_876, 30_210, 120_345]));
+}
+
+#[test]
+fn only_the_official_taps_are_asked_for() {
+    assert_eq!(
+        formula_url("python@3.12"),
+        "https://formulae.brew.sh/api/formula/python@3.12.json"
+    );
+    assert_eq!(
+        cask_url("firefox"),
+        "https://formulae.brew.sh/api/cask/firefox.json"
+    );
+    let e = formulae_api::fetch("someone/tools/tool").unwrap_err();
+    assert!(e.to_string().contains("not in the formulae.brew.sh API"));
+}
+
+#[test]
+fn the_metadata_source_is_configured_under_brew() {
+    let config = Config::parse("[brew]\nmetadata = \"api\"\n").unwrap();
+    assert_eq!(config.brew.metadata, Metadata::Api);
+    assert_eq!(Brew::from_config(&config.brew).metadata, Metadata::Api);
+    assert_eq!(Config::parse("").unwrap().brew.metadata, Metadata::Auto);
+    assert!(Config::parse("[brew]\nmetadata = \"web\"\n").is_err());
+}
@@ -0,0 +1,141 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::FormulaInfo;
+use homebrew_tui::github::{self, format_count, repo_from_url, RepoStats};
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, pump},
+    homebrew_tui::app::{App, Focus},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    homebrew_tui::event::AppEvent,
+    std::sync::Arc,
+};
+
+fn stats(stars: u64, pushed_at: &str) -> RepoStats {
+    RepoStats {
+        stars,
+        pushed_at: pushed_at.into(),
+        archived: false,
+    }
+}
+
+#[test]
+fn repositories_come_from_github_urls() {
+    let repo = |url| repo_from_url(url);
+    assert_eq!(
+        repo("https://github.com/jqlang/jq").as_deref(),
+        Some("jqlang/jq")
+    );
+    assert_eq!(
+        repo("https://github.com/BurntSushi/ripgrep/archive/refs/tags/14.1.0.tar.gz").as_deref(),
+        Some("BurntSushi/ripgrep")
+    );
+    assert_eq!(
+        repo("https://www.github.com/sharkdp/fd.git").as_deref(),
+        Some("sharkdp/fd")
+    );
+    assert_eq!(repo("https://github.com/jqlang"), None);
+    assert_eq!(repo("https://gitlab.com/jqlang/jq"), None);
+    assert_eq!(repo("https://jqlang.github.io/jq/"), None);
+
+    // the homepage wins, the stable source is the fallback
+    let mut info = FormulaInfo {
+        name: "wget".into(),
+        homepage: Some("https://www.gnu.org/software/wget/".into()),
+        ..Default::default()
+    };
+    assert_eq!(github::repo_of(&info), None);
+    info.urls = Some(serde_json::json!({
+        "stable": {"url": "https://github.com/mirror/wget/archive/v1.24.5.tar.gz"}
+    }));
+    assert_eq!(github::repo_of(&info).as_deref(), Some("mirror/wget"));
+}
+
+#[test]
+fn parses_the_api_answer() {
+    let s = github::parse(
+        r#"{"full_name":"jqlang/jq","stargazers_count":31234,
+            "pushed_at":"2024-05-01T12:34:56Z","archived":false}"#,
+    )
+    .unwrap();
+    assert_eq!(s, stats(31234, "2024-05-01"));
+    assert!(github::parse(r#"{"message":"API rate limit exceeded"}"#).is_err());
+}
+
+#[test]
+fn summaries_show_stars_and_the_age_of_the_last_push() {
+    assert_eq!(format_count(950), "950");
+    assert_eq!(format_count(1000), "1k");
+    assert_eq!(format_count(1234), "1.2k");
+    assert_eq!(format_count(34_567), "34k");
+    assert_eq!(format_count(1_500_000), "1.5M");
+
+    // 2024-05-31 00:00 UTC
+    let now = 1_717_113_600;
+    let s = stats(1234, "2024-05-01");
+    assert_eq!(s.summary(now), "1.2k stars, last push 2024-05-01 (30d ago)");
+    assert!(!s.is_stale(now));
+    assert!(stats(5, "2022-01-15").is_stale(now));
+    let archived = RepoStats {
+        archived: true,
+        ..stats(5, "2024-05-31")
+    };
+    assert_eq!(
+        archived.summary(now),
+        "5 stars, last push 2024-05-31 (today), archived"
+    );
+    assert!(archived.is_stale(now));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn details_look_up_the_repository_once() {
+    let backend = Arc::new(MockBackend {
+        installed: vec![FormulaInfo {
+            name: "jq".into(),
+            ..Default::default()
+        }],
+        github: vec![("jqlang/jq".into(), stats(31234, "2024-05-01"))],
+        ..MockBackend::new()
+    });
+    let mut app = app_with(backend);
+    app.focus = Focus::Installed;
+    let info = FormulaInfo {
+        name: "jq".into(),
+        homepage: Some("https://github.com/jqlang/jq".into()),
+        ..Default::default()
+    };
+    app.handle_event(AppEvent::BrewInfo(info.clone(), 0));
+    assert_eq!(app.github_stats.get("jqlang/jq"), Some(&None));
+    pump(&mut app, |a| {
+        a.github_stats.get("jqlang/jq").is_some_and(|s| s.is_some())
+    });
+    assert_eq!(app.github_stats["jqlang/jq"].as_ref().unwrap().stars, 31234);
+
+    // a repository the API does not know stays without stats
+    let mut other = info;
+    other.homepage = Some("https://github.com/nobody/nothing".into());
+    app.handle_event(AppEvent::BrewInfo(other, 0));
+    assert_eq!(app.github_stats.get("nobody/nothing"), Some(&None));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn lookups_can_be_turned_off() {
+    let mut app =
+        App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new())).expect("app");
+    app.config.github.stats = false;
+    app.items = vec![FormulaInfo {
+        name: "jq".into(),
+        ..Default::default()
+    }];
+    let info = FormulaInfo {
+        name: "jq".into(),
+        homepage: Some("https://github.com/jqlang/jq".into()),
+        ..Default::default()
+    };
+    app.handle_event(AppEvent::BrewInfo(info, 0));
+    assert!(app.github_stats.is_empty());
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::press;
+use crossterm::event::KeyCode;
+use homebrew_tui::app::{App, Mode};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::config::ConfigSource;
+use homebrew_tui::help::{self, HelpEntry, HelpLine};
+use homebrew_tui::keymap::{Keymap, Preset};
+use homebrew_tui::symbols::Symbols;
+use std::sync::Arc;
+
+fn entry<'a>(lines: &'a [HelpLine], text: &str) -> Option<&'a HelpEntry> {
+    lines.iter().find_map(|l| match l {
+        HelpLine::Entry(e) if e.text.starts_with(text) => Some(e),
+        _ => None,
+    })
+}
+
+#[test]
+fn key_listing_follows_the_keymap_preset() {
+    let default = help::lines(
+        &help::sections(&Keymap::new(Preset::Default), Symbols::default()),
+        "",
+    );
+    assert_eq!(entry(&default, "move down").unwrap().keys, "Down / j");
+    assert_eq!(
+        entry(&default, "jump to the first").unwrap().keys,
+        "Home / End"
+    );
+    // unbound actions are left out
+    assert!(entry(&default, "next / previous match").is_none());
+
+    let vim = help::lines(
+        &help::sections(&Keymap::new(Preset::Vim), Symbols::default()),
+        "",
+    );
+    assert_eq!(
+        entry(&vim, "jump to the first").unwrap().keys,
+        "Home / g g / End / G"
+    );
+    assert_eq!(entry(&vim, "next / previous match").unwrap().keys, "n / N");
+}
+
+#[test]
+fn search_keeps_matching_entries_and_sections() {
+    let sections = help::sections(&Keymap::default(), Symbols::default());
+    let pins = help::lines(&sections, "PIN");
+    assert_eq!(pins[0], HelpLine::Title("Packages"));
+    assert!(entry(&pins, "pin / unpin").is_some());
+    assert!(!pins.contains(&HelpLine::Title("Navigation")));
+
+    // a matching title brings its whole section
+    let doctor = help::lines(&sections, "doctor modal");
+    assert_eq!(doctor[0], HelpLine::Title("Doctor modal"));
+    assert!(entry(&doctor, "copy the selected finding").is_some());
+
+    assert!(help::lines(&sections, "no such key").is_empty());
+}
+
+#[test]
+fn slash_types_a_search_that_esc_clears_before_closing() {
+    let backend = Arc::new(MockBackend::new());
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+
+    press(&mut app, KeyCode::Char('?'));
+    press(&mut app, KeyCode::Char('/'));
+    // letters that scroll or close the modal are typed while searching
+    for c in "jq".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        &app.mode,
+        Mode::Help { query, searching: false, scroll: 0 } if query == "jq"
+    ));
+
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(&app.mode, Mode::Help { query, .. } if query.is_empty()));
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Normal));
+}
@@ -0,0 +1,197 @@
+use homebrew_tui::history::{
+    export_log, file_stamp, format_duration, format_time, History, OperationRecord,
+};
+use homebrew_tui::output::LogLine;
+use std::time::Duration;
+
+#[test]
+fn records_exit_status_and_logs() {
+    let mut h = History::in_memory();
+    h.start("brew install wget");
+    h.append_log(&"==> Pouring wget".into());
+    assert_eq!(h.records[0].success(), None);
+    h.set_exit_code(Some(1));
+    h.finish().unwrap();
+    let rec = &h.records[0];
+    assert_eq!(rec.command, "brew install wget");
+    assert_eq!(&*rec.logs[0], "==> Pouring wget");
+    assert_eq!(rec.success(), Some(false));
+    assert!(rec.duration().is_some());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn app_events_feed_history() {
+    use homebrew_tui::app::{App, AppEvent, Mode};
+    let mut app = App::new().expect("app init");
+    app.history = History::in_memory();
+    app.handle_event(AppEvent::OpStart("brew upgrade jq".into()));
+    app.handle_event(AppEvent::OpLog("done".into()));
+    app.handle_event(AppEvent::OpExit(Some(0)));
+    app.handle_event(AppEvent::OpEnd("brew upgrade jq".into()));
+    assert!(matches!(app.mode, Mode::Operation { .. }));
+    let rec = app.history.records.last().unwrap();
+    assert_eq!(rec.success(), Some(true));
+    assert_eq!(&*rec.logs[0], "done");
+}
+
+#[test]
+fn formats_durations() {
+    assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+    assert_eq!(format_duration(Duration::from_millis(12_340)), "12.3s");
+    assert_eq!(format_duration(Duration::from_secs(245)), "4m05s");
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn stderr_lines_are_tagged() {
+    use homebrew_tui::app::{App, AppEvent};
+    use homebrew_tui::output::Source;
+    let mut app = App::new().expect("app init");
+    app.history = History::in_memory();
+    app.handle_event(AppEvent::OpStart("brew install nope".into()));
+    app.handle_event(AppEvent::OpLog("==> Fetching nope".into()));
+    app.handle_event(AppEvent::OpErr("Error: No available formula".into()));
+    let rec = app.history.records.last().unwrap();
+    assert!(!rec.logs[0].is_stderr());
+    assert!(rec.logs[1].is_stderr());
+    assert_eq!(app.logs.last().unwrap().source, Source::Stderr);
+}
+
+#[test]
+fn reads_plain_string_logs_from_older_history_files() {
+    let json = r#"[{"command":"brew install jq","started_at":1,"logs":["a",{"text":"b","stream":"Stderr"}]}]"#;
+    let records: Vec<homebrew_tui::history::OperationRecord> = serde_json::from_str(json).unwrap();
+    assert_eq!(&*records[0].logs[0], "a");
+    assert!(records[0].logs[1].is_stderr());
+}
+
+#[test]
+fn failed_installs_name_their_packages() {
+    let failed = |command: &str, code| {
+        let mut h = History::in_memory();
+        h.start(command);
+        h.set_exit_code(Some(code));
+        h.finish().unwrap();
+        h.records[0].failed_packages()
+    };
+    assert_eq!(failed("brew install --verbose wget", 1), vec!["wget"]);
+    assert_eq!(failed("brew upgrade jq wget", 1), vec!["jq", "wget"]);
+    // past the variables set for the operation
+    assert_eq!(failed("https_proxy=… brew upgrade jq", 1), vec!["jq"]);
+    assert!(failed("brew install wget", 0).is_empty());
+    assert!(failed("brew cleanup", 1).is_empty());
+    assert!(failed("find /cache -print -delete", 1).is_empty());
+}
+
+#[test]
+fn the_last_attempt_at_a_package_tells_whether_it_failed() {
+    let mut h = History::in_memory();
+    for (command, code) in [
+        ("brew upgrade wget", 1),
+        ("brew upgrade jq oniguruma wget", 1),
+        ("brew upgrade jq", 0),
+        ("brew cleanup", 1),
+        ("brew install fd", 1),
+        ("brew upgrade bat fd", 0),
+    ] {
+        h.start(command);
+        h.set_exit_code(Some(code));
+        h.finish().unwrap();
+    }
+    let names = ["jq", "oniguruma", "wget", "fd", "bat"].map(String::from);
+    // the failed upgrade of three packages does not say which broke: wget failed on its own
+    // before, oniguruma never did, and fd was upgraded since
+    assert_eq!(h.last_failed(&names), ["wget"]);
+}
+
+#[test]
+fn formats_file_stamps_in_utc() {
+    assert_eq!(file_stamp(0), "19700101-000000");
+    assert_eq!(file_stamp(951_868_799), "20000229-235959");
+    assert_eq!(file_stamp(1_791_970_212), "20261014-093012");
+}
+
+#[test]
+fn exports_log_to_timestamped_file() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-export-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let logs = vec![
+        LogLine::stdout("==> Pouring wget"),
+        LogLine::stderr("Warning: wget 1.24 is already installed"),
+    ];
+    let path = export_log("brew install wget", &logs, &dir, 1_791_970_212).unwrap();
+    assert_eq!(
+        path.file_name().unwrap(),
+        "homebrew-tui-install-wget-20261014-093012.log"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "$ brew install wget\n==> Pouring wget\nWarning: wget 1.24 is already installed\n"
+    );
+    // an existing export is never overwritten
+    assert!(export_log("brew install wget", &logs, &dir, 1_791_970_212).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn summaries_tell_when_and_how_an_operation_ended() {
+    let record = |command: &str, duration_ms, exit_code| OperationRecord {
+        command: command.into(),
+        started_at: 1_791_970_212,
+        duration_ms,
+        exit_code,
+        logs: vec![],
+    };
+    assert_eq!(format_time(1_791_970_212), "2026-10-14 09:30");
+    assert_eq!(
+        record("brew upgrade postgresql@14", Some(12_300), Some(0)).summary(),
+        "2026-10-14 09:30  ok                  12.3s  brew upgrade postgresql@14"
+    );
+    assert_eq!(
+        record("brew install wget", Some(850), Some(1)).summary(),
+        "2026-10-14 09:30  failed (exit 1)     850ms  brew install wget"
+    );
+    assert_eq!(
+        record("brew install wget", None, None).summary(),
+        "2026-10-14 09:30  unfinished                 brew install wget"
+    );
+
+    let upgrade = record("brew upgrade postgresql@14", Some(1), Some(0));
+    assert!(upgrade.mentions("postgres") && upgrade.mentions("PostgreSQL"));
+    assert!(!upgrade.mentions("wget"));
+}
+
+#[test]
+fn sessions_sharing_the_file_keep_each_others_records() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-history-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("history.json");
+
+    // a TUI is open while a scheduled upgrade runs and records its own operation
+    let mut tui = History::load_from(Some(path.clone()));
+    let mut cron = History::load_from(Some(path.clone()));
+    cron.start("brew upgrade postgresql@14");
+    cron.set_exit_code(Some(0));
+    cron.finish().unwrap();
+    tui.start("brew install wget");
+    tui.set_exit_code(Some(0));
+    tui.finish().unwrap();
+
+    let commands =
+        |h: &History| -> Vec<String> { h.records.iter().map(|r| r.command.clone()).collect() };
+    let saved = History::load_from(Some(path.clone()));
+    assert_eq!(
+        commands(&saved),
+        ["brew upgrade postgresql@14", "brew install wget"]
+    );
+    // the TUI now shows the other session's record too
+    assert_eq!(commands(&tui), commands(&saved));
+
+    // saving again adds nothing, and no temporary file is left behind
+    tui.save().unwrap();
+    assert_eq!(History::load_from(Some(path)).records.len(), 2);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
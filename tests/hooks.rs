@@ -0,0 +1,149 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::config::HooksConfig;
+use homebrew_tui::history::OperationRecord;
+use homebrew_tui::hooks::{due, Hook, HOOK_TIMEOUT};
+use std::time::{Duration, Instant};
+#[cfg(feature = "tui")]
+use {
+    common::pump,
+    homebrew_tui::app::{App, AppEvent},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    std::sync::Arc,
+    std::{fs, path::PathBuf},
+};
+
+fn record(command: &str, exit_code: i32) -> OperationRecord {
+    OperationRecord {
+        command: command.into(),
+        started_at: 0,
+        duration_ms: Some(1200),
+        exit_code: Some(exit_code),
+        logs: vec![],
+    }
+}
+
+fn config() -> HooksConfig {
+    HooksConfig {
+        post_install: Some("echo installed".into()),
+        post_upgrade: Some("echo upgraded".into()),
+        on_failure: Some("echo failed".into()),
+        timeout: None,
+    }
+}
+
+#[test]
+fn hooks_are_due_per_package() {
+    let runs = due(&config(), &record("brew install --HEAD wget jq", 0));
+    let packages: Vec<(Hook, &str)> = runs.iter().map(|r| (r.hook, r.package.as_str())).collect();
+    assert_eq!(
+        packages,
+        vec![(Hook::PostInstall, "wget"), (Hook::PostInstall, "jq")]
+    );
+    assert_eq!(
+        due(&config(), &record("brew upgrade jq", 0))[0].hook,
+        Hook::PostUpgrade
+    );
+
+    // any failure, with no package for commands that name none
+    let runs = due(&config(), &record("brew update", 1));
+    assert_eq!(runs.len(), 1);
+    assert_eq!(
+        (runs[0].hook, runs[0].package.as_str()),
+        (Hook::OnFailure, "")
+    );
+
+    // nothing for other commands, unset hooks or an operation still running
+    assert!(due(&config(), &record("brew cleanup", 0)).is_empty());
+    assert!(due(&HooksConfig::default(), &record("brew install wget", 0)).is_empty());
+    let running = OperationRecord {
+        duration_ms: None,
+        ..record("brew install wget", 0)
+    };
+    assert!(due(&config(), &running).is_empty());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn hooks_run_with_the_outcome_in_the_environment() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-hooks-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let out: PathBuf = dir.join("hook.out");
+
+    let mut app = App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new()))
+        .expect("create app");
+    app.config.hooks.on_failure = Some(format!(
+        "echo \"$HOMEBREW_TUI_HOOK $HOMEBREW_TUI_PACKAGE $HOMEBREW_TUI_STATUS \
+         $HOMEBREW_TUI_EXIT_CODE $HOMEBREW_TUI_COMMAND\" >> '{}'",
+        out.display()
+    ));
+    app.handle_event(AppEvent::OpStart("brew install nope".into()));
+    app.handle_event(AppEvent::OpExit(Some(1)));
+    app.handle_event(AppEvent::OpEnd("brew install nope".into()));
+    pump(&mut app, |a| {
+        a.logs
+            .iter()
+            .any(|l| l.message == "on_failure hook finished for nope")
+    });
+    assert_eq!(
+        fs::read_to_string(&out).unwrap(),
+        "on_failure nope failure 1 brew install nope\n"
+    );
+
+    // a failing hook is reported
+    app.config.hooks.on_failure = Some("echo broken >&2; exit 3".into());
+    app.handle_event(AppEvent::OpStart("brew install nope".into()));
+    app.handle_event(AppEvent::OpExit(Some(1)));
+    app.handle_event(AppEvent::OpEnd("brew install nope".into()));
+    pump(&mut app, |a| {
+        a.logs.iter().any(|l| {
+            l.message.starts_with("on_failure hook failed") && l.message.ends_with("broken")
+        })
+    });
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn hooks_that_run_too_long_are_killed() {
+    let mut run = due(
+        &HooksConfig {
+            post_install: Some("sleep 5".into()),
+            timeout: Some(30),
+            ..config()
+        },
+        &record("brew install wget", 0),
+    )
+    .remove(0);
+    assert_eq!(run.timeout, Duration::from_secs(30));
+    assert_eq!(
+        due(&config(), &record("brew install wget", 0))[0].timeout,
+        HOOK_TIMEOUT
+    );
+
+    run.timeout = Duration::from_millis(100);
+    let started = Instant::now();
+    let e = run.run().unwrap_err();
+    assert!(started.elapsed() < Duration::from_secs(4));
+    assert!(e.to_string().contains("post_install hook timed out"));
+}
+
+#[test]
+fn a_failed_hook_is_reported_while_its_background_job_runs_on() {
+    let run = due(
+        &HooksConfig {
+            on_failure: Some("echo broken >&2; sleep 5 & exit 1".into()),
+            ..config()
+        },
+        &record("brew install nope", 1),
+    )
+    .remove(0);
+    let started = Instant::now();
+    let e = run.run().unwrap_err();
+    // `sleep` still holds stderr open, which is not waited for
+    assert!(started.elapsed() < Duration::from_secs(4));
+    assert!(e.to_string().starts_with("on_failure hook failed"));
+    assert!(e.to_string().ends_with("broken"));
+}
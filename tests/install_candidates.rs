@@ -0,0 +1,121 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::{install_candidates, tap_of};
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, fixtures, press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::api_cache::ApiEntry,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::event::ConfirmAction,
+};
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+/// An app on the fixtures whose brew also finds `tool` in two third-party taps, which (like the
+/// formulae of any tap outside the API) are not in the Available list.
+#[cfg(feature = "tui")]
+fn app() -> App {
+    let mut backend = fixtures();
+    for name in ["someone/tools/tool", "other/tap/tool", "other/tap/toolkit"] {
+        backend.available.push(ApiEntry {
+            name: name.into(),
+            desc: None,
+            cask: false,
+        });
+    }
+    let mut app = app_with(backend);
+    app.available_items.retain(|n| !n.contains('/'));
+    app
+}
+
+#[cfg(feature = "tui")]
+fn install(app: &mut App, name: &str) {
+    press(app, KeyCode::Char('i'));
+    for c in name.chars() {
+        press(app, KeyCode::Char(c));
+    }
+    press(app, KeyCode::Enter);
+}
+
+#[cfg(feature = "tui")]
+fn confirmed(app: &App) -> Option<&str> {
+    match &app.mode {
+        Mode::Confirm {
+            action: ConfirmAction::Install,
+            name,
+            ..
+        } => Some(name),
+        _ => None,
+    }
+}
+
+#[test]
+fn candidates_match_the_short_or_qualified_name() {
+    let results = names(&["tool", "someone/tools/tool", "other/tap/toolkit"]);
+    assert_eq!(
+        install_candidates("tool", &results),
+        ["tool", "someone/tools/tool"]
+    );
+    assert_eq!(
+        install_candidates("Someone/Tools/Tool", &results),
+        ["someone/tools/tool"]
+    );
+    // no exact name: every result is a guess
+    assert_eq!(install_candidates("too", &results), results);
+    assert!(install_candidates("nothing", &[]).is_empty());
+
+    assert_eq!(tap_of("someone/tools/tool"), Some("someone/tools"));
+    assert_eq!(tap_of("tool"), None);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn known_names_are_confirmed_right_away() {
+    let mut app = app();
+    install(&mut app, "ripgrep");
+    assert_eq!(confirmed(&app), Some("ripgrep"));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn a_name_in_several_taps_asks_which_one() {
+    let mut app = app();
+    install(&mut app, "tool");
+    assert!(matches!(app.mode, Mode::Normal));
+    pump(&mut app, |a| {
+        matches!(a.mode, Mode::InstallCandidates { .. })
+    });
+    let Mode::InstallCandidates { names, .. } = &app.mode else {
+        unreachable!()
+    };
+    assert_eq!(names, &["someone/tools/tool", "other/tap/tool"]);
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(confirmed(&app), Some("other/tap/tool"));
+    // the choice is still there to go back to
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(
+        app.mode,
+        Mode::InstallCandidates { selected: 1, .. }
+    ));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn a_qualified_or_unknown_name_goes_to_the_confirm_dialog() {
+    let mut app = app();
+    install(&mut app, "someone/tools/tool");
+    pump(&mut app, |a| confirmed(a).is_some());
+    assert_eq!(confirmed(&app), Some("someone/tools/tool"));
+
+    // brew can still tap what a name that nothing matches needs
+    let mut app = self::app();
+    install(&mut app, "new/tap/thing");
+    pump(&mut app, |a| confirmed(a).is_some());
+    assert_eq!(confirmed(&app), Some("new/tap/thing"));
+}
@@ -0,0 +1,172 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::{self, FormulaInfo, InstalledInfo};
+use homebrew_tui::config::LayoutConfig;
+use homebrew_tui::layout::PaneLayout;
+use homebrew_tui::sort::{InstalledSort, SortColumn, SortContext};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+#[cfg(feature = "tui")]
+use {
+    common::{press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::App,
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    homebrew_tui::disk::{DiskUsage, PackageUsage},
+    homebrew_tui::ui::version_text,
+    std::sync::Arc,
+};
+
+fn formula(name: &str, version: &str, tap: &str) -> FormulaInfo {
+    FormulaInfo {
+        name: name.into(),
+        installed: vec![InstalledInfo {
+            version: version.into(),
+            ..Default::default()
+        }],
+        tap: Some(tap.into()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn versions_compare_numerically() {
+    assert_eq!(brew::compare_versions("1.10", "1.9"), Ordering::Greater);
+    assert_eq!(
+        brew::compare_versions("1.7.1_1", "1.7.1"),
+        Ordering::Greater
+    );
+    assert_eq!(brew::compare_versions("2.0", "2.0"), Ordering::Equal);
+}
+
+#[test]
+fn columns_sort_with_name_as_tie_break() {
+    let (jq, wget, yq) = (
+        formula("jq", "1.10", "homebrew/core"),
+        formula("wget", "1.9", "homebrew/core"),
+        formula("yq", "4.44.3", "mikefarah/yq"),
+    );
+    let sizes = HashMap::from([("jq".to_string(), 10), ("wget".to_string(), 30)]);
+    let pinned = vec!["wget".to_string()];
+    let ctx = SortContext {
+        sizes: &sizes,
+        pinned: &pinned,
+        outdated: &[],
+    };
+    let sorted = |column, descending| {
+        let sort = InstalledSort { column, descending };
+        let mut list = vec![yq.clone(), wget.clone(), jq.clone()];
+        list.sort_by(|a, b| sort.compare(a, b, &ctx));
+        list.into_iter().map(|f| f.name).collect::<Vec<_>>()
+    };
+    assert_eq!(sorted(SortColumn::Name, false), vec!["jq", "wget", "yq"]);
+    assert_eq!(sorted(SortColumn::Name, true), vec!["yq", "wget", "jq"]);
+    assert_eq!(sorted(SortColumn::Version, false), vec!["wget", "jq", "yq"]);
+    assert_eq!(sorted(SortColumn::Size, true), vec!["wget", "jq", "yq"]);
+    assert_eq!(sorted(SortColumn::Tap, true), vec!["yq", "jq", "wget"]);
+    assert_eq!(sorted(SortColumn::Pinned, false), vec!["wget", "jq", "yq"]);
+}
+
+#[test]
+fn layouts_saved_before_sorting_existed_still_load() {
+    let old: PaneLayout =
+        serde_json::from_str(r#"{"columns":[30,35,35],"bottom_height":7}"#).unwrap();
+    assert_eq!(old.installed_sort, InstalledSort::default());
+    let layout = PaneLayout {
+        installed_sort: InstalledSort {
+            column: SortColumn::Size,
+            descending: true,
+        },
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&layout).unwrap();
+    assert!(json.contains(r#""installed_sort":{"column":"size","descending":true}"#));
+    assert_eq!(serde_json::from_str::<PaneLayout>(&json).unwrap(), layout);
+}
+
+#[cfg(feature = "tui")]
+fn names(app: &App) -> Vec<&str> {
+    app.items.iter().map(|f| f.name.as_str()).collect()
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn z_cycles_the_sort_column_and_keeps_the_selection() {
+    let package = |name: &str, size| PackageUsage {
+        name: name.into(),
+        size,
+    };
+    let backend = Arc::new(MockBackend {
+        installed: vec![
+            formula("wget", "1.24.5", "homebrew/core"),
+            formula("jq", "1.7.1", "homebrew/core"),
+            formula("bat", "0.24.0", "homebrew/core"),
+        ],
+        disk_usage: DiskUsage {
+            cellar: "/opt/homebrew/Cellar".into(),
+            packages: vec![package("jq", 3), package("bat", 1), package("wget", 2)],
+        },
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+    pump(&mut app, |a| {
+        a.installed_sizes.len() == 3 && !a.items[0].installed.is_empty()
+    });
+    assert_eq!(names(&app), vec!["bat", "jq", "wget"]);
+    app.selected = 1;
+
+    press(&mut app, KeyCode::Char('z'));
+    assert_eq!(app.layout.installed_sort.column, SortColumn::Version);
+    assert_eq!(names(&app), vec!["bat", "jq", "wget"]);
+    press(&mut app, KeyCode::Char('z'));
+    assert_eq!(names(&app), vec!["bat", "wget", "jq"]);
+    assert_eq!(app.items[app.selected].name, "jq");
+    press(&mut app, KeyCode::Char('Z'));
+    assert!(app.status.contains("Size (descending)"));
+    assert_eq!(names(&app), vec!["jq", "wget", "bat"]);
+    assert_eq!(app.selected, 0);
+}
+
+#[test]
+fn the_sort_is_saved_and_configured_sizes_win() {
+    let path = std::env::temp_dir().join(format!(
+        "homebrew-tui-layout-{}/layout.json",
+        std::process::id()
+    ));
+    let mut layout = PaneLayout {
+        bottom_height: 12,
+        installed_sort: InstalledSort {
+            column: SortColumn::Size,
+            descending: true,
+        },
+        ..Default::default()
+    };
+    layout.grow(0);
+    layout.save_to(&path).unwrap();
+
+    let saved = PaneLayout::load_from(Some(&path), &LayoutConfig::default());
+    assert_eq!(saved, layout);
+    let config = LayoutConfig {
+        columns: Some([50, 25, 25]),
+        bottom_height: None,
+    };
+    let saved = PaneLayout::load_from(Some(&path), &config);
+    assert_eq!(saved.columns, [50, 25, 25]);
+    assert_eq!(saved.bottom_height, 12);
+    assert_eq!(saved.installed_sort, layout.installed_sort);
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn outdated_versions_show_the_upgrade_inline() {
+    let mut jq = formula("jq", "1.7.1", "homebrew/core");
+    jq.versions = Some(serde_json::json!({ "stable": "1.8.0" }));
+    assert_eq!(version_text(&jq, true, "->"), "1.7.1 -> 1.8.0");
+    // Not (yet) reported outdated, or already at the stable version.
+    assert_eq!(version_text(&jq, false, "->"), "1.7.1");
+    jq.versions = Some(serde_json::json!({ "stable": "1.7.1" }));
+    assert_eq!(version_text(&jq, true, "->"), "1.7.1");
+}
@@ -0,0 +1,70 @@
+use homebrew_tui::jobs::{JobKind, JobPool};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn queries_never_exceed_the_worker_count() {
+    let pool = JobPool::new(2);
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (done_tx, done_rx) = mpsc::channel();
+    for _ in 0..8 {
+        let running = Arc::clone(&running);
+        let peak = Arc::clone(&peak);
+        let done = done_tx.clone();
+        pool.submit(JobKind::Refresh, move |_| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            running.fetch_sub(1, Ordering::SeqCst);
+            let _ = done.send(());
+        });
+    }
+    drop(done_tx);
+    assert_eq!(done_rx.iter().count(), 8);
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn cancelled_jobs_that_have_not_started_are_skipped() {
+    let pool = JobPool::new(1);
+    let (gate_tx, gate_rx) = mpsc::channel::<()>();
+    // keep the only worker busy until the queue has been cancelled
+    pool.submit(JobKind::Refresh, move |_| {
+        let _ = gate_rx.recv();
+    });
+    let ran = Arc::new(AtomicUsize::new(0));
+    for _ in 0..5 {
+        let ran = Arc::clone(&ran);
+        pool.submit(JobKind::Info, move |_| {
+            ran.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+    assert_eq!(pool.pending(JobKind::Info), 5);
+    pool.cancel(JobKind::Info);
+    let (done_tx, done_rx) = mpsc::channel();
+    pool.submit(JobKind::Info, move |token| {
+        let _ = done_tx.send(token.is_cancelled());
+    });
+    gate_tx.send(()).unwrap();
+    assert!(!done_rx.recv().unwrap());
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn operations_run_one_at_a_time_and_survive_panics() {
+    let pool = JobPool::new(3);
+    let (tx, rx) = mpsc::channel();
+    pool.submit(JobKind::Operation, |_| panic!("job failed"));
+    for i in 0..3 {
+        let tx = tx.clone();
+        pool.submit(JobKind::Operation, move |_| {
+            thread::sleep(Duration::from_millis(10));
+            let _ = tx.send(i);
+        });
+    }
+    drop(tx);
+    assert_eq!(rx.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "tui")]
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use homebrew_tui::config::Config;
+use homebrew_tui::keymap::{Action, Keymap, Preset};
+
+fn key(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+}
+
+fn ctrl(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+}
+
+#[test]
+fn default_preset_keeps_single_letter_commands() {
+    let mut km = Keymap::default();
+    assert_eq!(km.resolve(key('j')), Some(Action::MoveDown));
+    assert_eq!(km.resolve(key('r')), Some(Action::Uninstall));
+    assert_eq!(km.resolve(key('q')), Some(Action::Quit));
+    assert_eq!(km.resolve(key('g')), None);
+    assert_eq!(km.resolve(ctrl('n')), None);
+}
+
+#[test]
+fn vim_preset_resolves_sequences() {
+    let mut km = Keymap::new(Preset::Vim);
+    assert_eq!(km.resolve(key('g')), None);
+    assert!(km.is_pending());
+    assert_eq!(km.resolve(key('g')), Some(Action::Top));
+    assert_eq!(km.resolve(key('G')), Some(Action::Bottom));
+    assert_eq!(km.resolve(key('/')), Some(Action::Filter));
+    assert_eq!(km.resolve(key('n')), Some(Action::SearchNext));
+    assert_eq!(km.resolve(key('N')), Some(Action::SearchPrev));
+    // a key that breaks a sequence is handled on its own
+    assert_eq!(km.resolve(key('g')), None);
+    assert_eq!(km.resolve(key('j')), Some(Action::MoveDown));
+    assert!(!km.is_pending());
+    // the default bindings are still there
+    assert_eq!(km.resolve(key('u')), Some(Action::Upgrade));
+}
+
+#[test]
+fn emacs_preset_uses_control_keys() {
+    let mut km = Keymap::new(Preset::Emacs);
+    assert_eq!(km.resolve(ctrl('n')), Some(Action::MoveDown));
+    assert_eq!(km.resolve(ctrl('p')), Some(Action::MoveUp));
+    assert_eq!(km.resolve(ctrl('s')), Some(Action::Filter));
+    assert_eq!(
+        km.resolve(KeyEvent::new(KeyCode::Char('>'), KeyModifiers::ALT)),
+        Some(Action::Bottom)
+    );
+    assert_eq!(km.resolve(ctrl('x')), None);
+    assert_eq!(km.resolve(ctrl('c')), Some(Action::Quit));
+    assert!(km.keys_for(Action::MoveDown).contains(&"C-n".to_string()));
+}
+
+#[test]
+fn translates_preset_keys_for_modals() {
+    let mut km = Keymap::new(Preset::Emacs);
+    assert_eq!(km.translate_modal(ctrl('n')).code, KeyCode::Down);
+    assert_eq!(km.translate_modal(ctrl('g')).code, KeyCode::Esc);
+    // keys without a navigation meaning pass through unchanged
+    assert_eq!(km.translate_modal(key('y')).code, KeyCode::Char('y'));
+    // plain characters typed into a prompt are never translated
+    let mut vim = Keymap::new(Preset::Vim);
+    assert_eq!(vim.translate_input(key('j')).code, KeyCode::Char('j'));
+    assert_eq!(km.translate_input(ctrl('g')).code, KeyCode::Esc);
+}
+
+#[test]
+fn keymap_preset_from_config() {
+    assert_eq!(Config::parse("").unwrap().keymap, Preset::Default);
+    let c = Config::parse("keymap = \"vim\"\n").unwrap();
+    assert_eq!(c.keymap, Preset::Vim);
+    assert!(Config::parse("keymap = \"nano\"\n").is_err());
+}
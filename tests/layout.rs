@@ -0,0 +1,26 @@
+use homebrew_tui::config::Config;
+use homebrew_tui::layout::PaneLayout;
+
+#[test]
+fn config_columns_are_normalized() {
+    let c = Config::parse("[layout]\ncolumns = [1, 1, 2]\nbottom_height = 100\n").unwrap();
+    let l = PaneLayout::from_config(&c.layout);
+    assert_eq!(l.columns.iter().sum::<u16>(), 100);
+    assert_eq!(l.columns, [25, 25, 50]);
+    assert_eq!(l.bottom_height, 30);
+}
+
+#[test]
+fn grow_and_shrink_keep_total() {
+    let mut l = PaneLayout::default();
+    l.grow(0);
+    assert_eq!(l.columns[0], 35);
+    assert_eq!(l.columns.iter().sum::<u16>(), 100);
+    for _ in 0..20 {
+        l.shrink(1);
+    }
+    assert_eq!(l.columns[1], 10);
+    assert_eq!(l.columns.iter().sum::<u16>(), 100);
+    l.resize_bottom(-10);
+    assert_eq!(l.bottom_height, 3);
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{press, pump};
+use crossterm::event::KeyCode;
+use homebrew_tui::api_cache::ApiEntry;
+use homebrew_tui::app::App;
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::brew::{FormulaInfo, InstalledInfo};
+use homebrew_tui::config::ConfigSource;
+use homebrew_tui::sort::{ListSort, SortColumn};
+use std::sync::Arc;
+
+fn names(app: &App) -> Vec<&str> {
+    app.items.iter().map(|f| f.name.as_str()).collect()
+}
+
+#[test]
+fn a_sorts_both_lists_with_installed_packages_first() {
+    let installed_at = |name: &str, time| FormulaInfo {
+        name: name.into(),
+        installed: vec![InstalledInfo {
+            version: "1.0".into(),
+            time: Some(time),
+        }],
+        ..Default::default()
+    };
+    let entry = |name: &str| ApiEntry {
+        name: name.into(),
+        desc: None,
+        cask: false,
+    };
+    let backend = Arc::new(MockBackend {
+        installed: vec![installed_at("jq", 100), installed_at("wget", 300)],
+        available: vec![entry("bat"), entry("jq"), entry("wget"), entry("yq")],
+        outdated: vec!["jq".into()],
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+    pump(&mut app, |a| {
+        a.available_items.len() == 4
+            && !a.outdated_items.is_empty()
+            && a.items.iter().all(|f| f.installed_time().is_some())
+    });
+    let available = |app: &App| -> Vec<String> {
+        app.available_filtered
+            .iter()
+            .map(|&i| app.available_items[i].clone())
+            .collect()
+    };
+    assert_eq!(available(&app), vec!["bat", "jq", "wget", "yq"]);
+
+    press(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.layout.list_sort, ListSort::RecentlyInstalled);
+    assert_eq!(app.layout.installed_sort.column, SortColumn::Installed);
+    assert_eq!(names(&app), vec!["wget", "jq"]);
+    assert_eq!(available(&app), vec!["wget", "jq", "bat", "yq"]);
+
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.layout.list_sort, ListSort::Updates);
+    assert_eq!(available(&app), vec!["jq", "wget", "bat", "yq"]);
+    press(&mut app, KeyCode::Char('a'));
+    assert_eq!(available(&app), vec!["bat", "jq", "wget", "yq"]);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "tui")]
+
+use homebrew_tui::ui::{
+    placeholder_lines, scroll_offset, scrollbar_thumb, wrap_ranges, wrapped_offset,
+};
+
+#[test]
+fn window_only_moves_when_the_selection_leaves_it() {
+    // 7000 entries, 20 visible rows
+    assert_eq!(scroll_offset(0, 0, 20, 7000), 0);
+    assert_eq!(scroll_offset(19, 0, 20, 7000), 0);
+    assert_eq!(scroll_offset(20, 0, 20, 7000), 1);
+    // moving back up inside the window keeps it where it is
+    assert_eq!(scroll_offset(10, 5, 20, 7000), 5);
+    assert_eq!(scroll_offset(4, 5, 20, 7000), 4);
+    assert_eq!(scroll_offset(6999, 0, 20, 7000), 6980);
+}
+
+#[test]
+fn window_is_clamped_to_the_list() {
+    // the list shrank (e.g. a filter was typed) below the old offset
+    assert_eq!(scroll_offset(3, 500, 20, 10), 0);
+    assert_eq!(scroll_offset(40, 30, 20, 45), 25);
+    assert_eq!(scroll_offset(9, 0, 20, 0), 0);
+    assert_eq!(scroll_offset(9, 4, 0, 100), 0);
+}
+
+#[test]
+fn scrollbar_thumb_tracks_the_window() {
+    // a long list keeps a one-cell thumb that reaches both ends of the track
+    assert_eq!(scrollbar_thumb(0, 20, 7000, 20), Some((0, 1)));
+    assert_eq!(scrollbar_thumb(3490, 20, 7000, 20), Some((10, 1)));
+    assert_eq!(scrollbar_thumb(6980, 20, 7000, 20), Some((19, 1)));
+    // half the list visible: half the track
+    assert_eq!(scrollbar_thumb(10, 20, 40, 20), Some((5, 10)));
+    assert_eq!(scrollbar_thumb(20, 20, 40, 20), Some((10, 10)));
+    // nothing to scroll
+    assert_eq!(scrollbar_thumb(0, 20, 20, 20), None);
+    assert_eq!(scrollbar_thumb(0, 20, 7000, 0), None);
+}
+
+#[test]
+fn placeholder_rows_vary_and_fit_the_pane() {
+    let lines = placeholder_lines(6, 14, "░");
+    assert_eq!(lines.len(), 6);
+    assert!(lines.iter().all(|l| l.chars().count() <= 14));
+    assert!(lines.windows(2).all(|w| w[0] != w[1]));
+    // a narrow pane gets the name block only, cut to its width
+    assert!(placeholder_lines(3, 4, ".").iter().all(|l| l == "...."));
+}
+
+#[test]
+fn wrapped_entries_stay_in_view() {
+    assert_eq!(wrap_ranges("abcdefg", 3), vec![0..3, 3..6, 6..7]);
+    assert_eq!(wrap_ranges("", 3), vec![0..0]);
+    // wide characters are not split across rows
+    assert_eq!(wrap_ranges("ab✓✓", 3), vec![0..5, 5..8]);
+
+    let heights = [1, 3, 1, 2, 1];
+    assert_eq!(wrapped_offset(2, 0, 5, &heights), 0);
+    // both rows of the selected entry are shown, so the three-row one scrolls away
+    assert_eq!(wrapped_offset(3, 0, 5, &heights), 2);
+    assert_eq!(wrapped_offset(3, 0, 6, &heights), 1);
+    assert_eq!(wrapped_offset(4, 0, 5, &heights), 2);
+    assert_eq!(wrapped_offset(0, 3, 5, &heights), 0);
+    assert_eq!(wrapped_offset(9, 9, 5, &[]), 0);
+}
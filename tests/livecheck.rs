@@ -0,0 +1,69 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::parse;
+#[cfg(feature = "tui")]
+use {
+    common::pump,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::brew::Livecheck,
+    homebrew_tui::config::ConfigSource,
+    std::sync::Arc,
+};
+
+const LIVECHECK_JSON: &str = r#"[
+  {
+    "formula": "jq",
+    "version": {"current": "1.7.1", "latest": "1.7.1", "outdated": false, "newer_than_upstream": false},
+    "meta": {"livecheckable": true}
+  },
+  {
+    "formula": "gh",
+    "version": {"current": "2.55.0", "latest": "2.58.0", "outdated": true, "newer_than_upstream": false},
+    "meta": {"livecheckable": true}
+  },
+  {
+    "formula": "libfoo",
+    "status": "skipped",
+    "messages": ["Disabled"]
+  }
+]"#;
+
+#[test]
+fn livecheck_json_is_parsed() {
+    let results = parse::parse_livecheck(LIVECHECK_JSON).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(!results[0].lags());
+    assert_eq!(results[1].latest.as_deref(), Some("2.58.0"));
+    assert!(results[1].lags());
+    assert_eq!(results[2].status.as_deref(), Some("skipped"));
+    assert_eq!(results[2].messages, vec!["Disabled"]);
+    assert!(!results[2].lags());
+    assert!(parse::parse_livecheck("Error: not json").is_err());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn bulk_livecheck_lists_lagging_formulae_first() {
+    let backend = Arc::new(MockBackend {
+        livecheck: parse::parse_livecheck(LIVECHECK_JSON).unwrap(),
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+    assert!(!app.handle_key(KeyEvent::new(KeyCode::Char('W'), KeyModifiers::NONE)));
+    pump(&mut app, |a| matches!(a.mode, Mode::Livecheck { .. }));
+    let Mode::Livecheck { results, .. } = &app.mode else {
+        unreachable!()
+    };
+    let order: Vec<&str> = results
+        .iter()
+        .map(|r: &Livecheck| r.formula.as_str())
+        .collect();
+    assert_eq!(order, vec!["gh", "jq", "libfoo"]);
+    assert!(app
+        .logs
+        .iter()
+        .any(|l| l.contains("1 of 3 checked formulae lag upstream")));
+}
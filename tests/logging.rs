@@ -0,0 +1,28 @@
+#![cfg(feature = "tui")]
+
+use homebrew_tui::config::LogLevel;
+use homebrew_tui::logging;
+use std::fs;
+
+#[test]
+fn log_lines_are_appended_to_the_state_directory() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-logging-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    std::env::set_var("XDG_STATE_HOME", &dir);
+    let path = logging::log_path().unwrap();
+    assert_eq!(path, dir.join("homebrew-tui").join("log"));
+
+    let guard = logging::init(LogLevel::Info)
+        .unwrap()
+        .expect("logging enabled");
+    tracing::info!("operation completed: brew install jq");
+    tracing::debug!("running brew list --formula");
+    drop(guard);
+
+    let log = fs::read_to_string(&path).unwrap();
+    assert!(log.contains("INFO"));
+    assert!(log.contains("operation completed: brew install jq"));
+    // below the configured level
+    assert!(!log.contains("brew list"));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,241 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::output::{match_ranges, Level, LogLine};
+#[cfg(feature = "tui")]
+use {
+    common::press,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    homebrew_tui::app::{App, Tab},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    homebrew_tui::event::AppEvent,
+    homebrew_tui::output::{LogEntry, Source},
+    std::sync::Arc,
+};
+
+#[cfg(feature = "tui")]
+fn visible(app: &App) -> Vec<String> {
+    app.visible_logs()
+        .iter()
+        .map(|l| l.message.clone())
+        .collect()
+}
+
+#[cfg(feature = "tui")]
+fn run(app: &mut App, title: &str, events: Vec<AppEvent>) {
+    app.handle_event(AppEvent::OpStart(title.into()));
+    for ev in events {
+        app.handle_event(ev);
+    }
+    app.handle_event(AppEvent::OpEnd(title.into()));
+}
+
+#[test]
+fn lines_are_classified_by_level() {
+    assert_eq!(LogLine::stdout("==> Pouring wget").level(), Level::Info);
+    assert_eq!(
+        LogLine::stderr("Warning: jq 1.7 is installed").level(),
+        Level::Warning
+    );
+    assert_eq!(LogLine::stderr("Error: No such keg").level(), Level::Error);
+    assert_eq!(
+        LogLine::stdout("==> Checking what failed before").level(),
+        Level::Info
+    );
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn entries_record_their_source() {
+    let out = LogEntry::output(LogLine::stderr("Warning: jq 1.7 is installed"));
+    assert_eq!((out.source, out.level), (Source::Stderr, Level::Warning));
+    let err = LogEntry::error("brew install nope failed: exit code 1");
+    assert_eq!((err.source, err.level), (Source::App, Level::Error));
+
+    let mut app = App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new()))
+        .expect("create app");
+    app.logs.clear();
+    run(
+        &mut app,
+        "brew install wget",
+        vec![
+            AppEvent::OpLog("==> Pouring wget".into()),
+            AppEvent::Error("brew install wget failed: exit code 1".into()),
+        ],
+    );
+    let entries: Vec<(Source, Level, Option<&str>)> = app
+        .logs
+        .iter()
+        .map(|l| (l.source, l.level, l.op.as_deref()))
+        .collect();
+    let op = Some("brew install wget");
+    assert_eq!(
+        entries,
+        vec![
+            (Source::App, Level::Info, op),
+            (Source::Stdout, Level::Info, op),
+            (Source::App, Level::Error, None),
+            (Source::App, Level::Info, op),
+            // the hint pointing at the build logs of the failed install
+            (Source::App, Level::Error, op),
+        ]
+    );
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn logs_filter_by_level_and_operation_and_clear() {
+    let mut app = App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new()))
+        .expect("create app");
+    app.logs.clear();
+    app.show_tab(Tab::Logs);
+    run(
+        &mut app,
+        "brew install wget",
+        vec![
+            AppEvent::OpLog("==> Downloading wget".into()),
+            AppEvent::OpErr("Warning: wget is keg-only".into()),
+            AppEvent::OpExit(Some(0)),
+        ],
+    );
+    run(
+        &mut app,
+        "brew install nope",
+        vec![
+            AppEvent::OpErr("Error: No available formula".into()),
+            AppEvent::OpExit(Some(1)),
+        ],
+    );
+    assert_eq!(app.visible_logs().len(), 8);
+    // close the log modal the operations opened
+    press(&mut app, KeyCode::Esc);
+    let hint = "brew install nope failed: in History (H), l shows its build logs and g uploads \
+                them with brew gist-logs";
+
+    press(&mut app, KeyCode::Char('v'));
+    assert_eq!(
+        visible(&app),
+        vec![
+            "Warning: wget is keg-only",
+            "Error: No available formula",
+            hint
+        ]
+    );
+    press(&mut app, KeyCode::Char('v'));
+    assert_eq!(visible(&app), vec!["Error: No available formula", hint]);
+    assert_eq!(app.log_filter_label(), "errors");
+    press(&mut app, KeyCode::Char('v'));
+    assert_eq!(app.log_level, Level::Info);
+
+    // the newest operation first, then the one before, then every line again
+    press(&mut app, KeyCode::Char('t'));
+    assert_eq!(
+        visible(&app),
+        vec![
+            "Started: brew install nope",
+            "Error: No available formula",
+            "Finished: brew install nope",
+            hint
+        ]
+    );
+    assert_eq!(app.tab_selected, 3);
+    press(&mut app, KeyCode::Char('t'));
+    assert_eq!(app.log_op.as_deref(), Some("brew install wget"));
+    assert_eq!(app.visible_logs().len(), 4);
+    press(&mut app, KeyCode::Char('t'));
+    assert_eq!(app.log_op, None);
+
+    press(&mut app, KeyCode::Char('X'));
+    assert!(app.logs.is_empty());
+    assert_eq!(app.tab_len(), 0);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn logs_tab_searches_the_lines_shown() {
+    let mut app = App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new()))
+        .expect("create app");
+    app.logs.clear();
+    run(
+        &mut app,
+        "brew install wget",
+        vec![
+            AppEvent::OpLog("==> Downloading wget".into()),
+            AppEvent::OpLog("==> Pouring libidn2".into()),
+            AppEvent::OpLog("==> Pouring WGET".into()),
+            AppEvent::OpExit(Some(0)),
+        ],
+    );
+    press(&mut app, KeyCode::Esc);
+    app.show_tab(Tab::Logs);
+    press(&mut app, KeyCode::Char('s'));
+    for c in "wget".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    // Started, Downloading, Pouring WGET and Finished, newest first
+    assert_eq!(app.log_matches(), vec![0, 1, 3, 4]);
+    assert_eq!(app.tab_selected, 4);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.tab_selected, 3);
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.tab_selected, 4);
+
+    press(&mut app, KeyCode::Char('F'));
+    assert!(app.log_search.is_empty());
+    assert!(app.log_matches().is_empty());
+
+    press(&mut app, KeyCode::Char('w'));
+    assert!(!app.log_wrap);
+    assert!(!app.handle_key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL)));
+    assert!(app.log_wrap);
+}
+
+#[test]
+fn match_ranges_ignore_case() {
+    assert_eq!(
+        match_ranges("==> Pouring WGET wget", "wget"),
+        vec![12..16, 17..21]
+    );
+    assert_eq!(match_ranges("✓ jq", "JQ"), vec![4..6]);
+    assert!(match_ranges("anything", "").is_empty());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn logs_tab_picks_a_range_to_copy() {
+    let mut app = App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new()))
+        .expect("create app");
+    app.logs.clear();
+    run(
+        &mut app,
+        "brew install nope",
+        vec![
+            AppEvent::OpErr("Error: No available formula with the name \"nope\".".into()),
+            AppEvent::OpErr("Did you mean node?".into()),
+            AppEvent::OpExit(Some(1)),
+        ],
+    );
+    press(&mut app, KeyCode::Esc);
+    app.show_tab(Tab::Logs);
+    // above the build logs hint
+    press(&mut app, KeyCode::Up);
+    // the selected line alone
+    assert_eq!(app.log_selection_text(), "Finished: brew install nope");
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.log_range(), 1..=3);
+    assert_eq!(
+        app.log_selection_text(),
+        "Error: No available formula with the name \"nope\".\nDid you mean node?\nFinished: \
+         brew install nope"
+    );
+    // copying (or failing to, without a clipboard tool) ends the range
+    press(&mut app, KeyCode::Char('c'));
+    assert_eq!(app.log_anchor, None);
+    assert!(matches!(app.mode, homebrew_tui::app::Mode::Normal));
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{press, pump};
+use crossterm::event::KeyCode;
+use homebrew_tui::app::{App, Mode};
+use homebrew_tui::backend::MockBackend;
+use std::sync::Arc;
+
+fn summary(app: &App) -> Vec<String> {
+    let Some(start) = app
+        .logs
+        .iter()
+        .position(|l| l.message.starts_with("Maintenance finished"))
+    else {
+        return vec![];
+    };
+    app.logs[start..]
+        .iter()
+        .map(|l| l.message.clone())
+        .collect()
+}
+
+fn app_with(backend: MockBackend) -> (App, Arc<MockBackend>) {
+    let backend = Arc::new(MockBackend {
+        outdated: vec!["jq".into(), "wget".into()],
+        ..backend
+    });
+    let mut app = common::app_with(backend.clone());
+    pump(&mut app, |a| a.outdated_items.len() == 2);
+    (app, backend)
+}
+
+#[test]
+fn steps_run_in_order_with_the_selected_upgrades() {
+    let (mut app, backend) = app_with(MockBackend::new());
+
+    press(&mut app, KeyCode::Char('M'));
+    assert!(matches!(app.mode, Mode::Maintenance { .. }));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| !summary(a).is_empty());
+
+    assert_eq!(
+        backend.executed(),
+        vec![
+            "brew update",
+            "brew upgrade jq",
+            "brew autoremove",
+            "brew cleanup"
+        ]
+    );
+    let summary = summary(&app);
+    assert!(
+        summary[0].ends_with("4 of 4 steps succeeded"),
+        "{:?}",
+        summary
+    );
+    assert!(summary[2].starts_with("  ok       brew upgrade jq"));
+}
+
+#[test]
+fn a_failing_step_stops_the_routine() {
+    let (mut app, backend) = app_with(MockBackend {
+        exit_code: 1,
+        ..MockBackend::new()
+    });
+    app.config
+        .maintenance
+        .steps
+        .retain(|s| *s != homebrew_tui::config::MaintenanceStep::Upgrade);
+
+    press(&mut app, KeyCode::Char('M'));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| !summary(a).is_empty());
+
+    assert_eq!(backend.executed(), vec!["brew update"]);
+    assert_eq!(
+        &summary(&app)[1..],
+        [
+            "  failed   brew update (exit code 1)",
+            "  skipped  brew autoremove",
+            "  skipped  brew cleanup",
+        ]
+    );
+}
@@ -0,0 +1,108 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::FormulaInfo;
+use homebrew_tui::brewfile;
+use std::fs;
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::event::ConfirmAction,
+    std::sync::Arc,
+};
+
+fn formula(name: &str, tap: Option<&str>) -> FormulaInfo {
+    FormulaInfo {
+        name: name.into(),
+        tap: tap.map(String::from),
+        ..Default::default()
+    }
+}
+
+#[cfg(feature = "tui")]
+fn confirm_action(app: &App) -> Option<(ConfirmAction, String)> {
+    match &app.mode {
+        Mode::Confirm { action, name, .. } => Some((action.clone(), name.clone())),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn marked_packages_are_acted_on_together() {
+    let backend = Arc::new(MockBackend {
+        installed: vec![
+            formula("curl", None),
+            formula("jq", None),
+            formula("wget", None),
+        ],
+        pinned: vec!["jq".into()],
+        ..MockBackend::new()
+    });
+    let mut app = app_with(backend);
+    pump(&mut app, |a| a.is_pinned("jq"));
+
+    for _ in 0..3 {
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('j'));
+    }
+    assert_eq!(app.marked_names(), vec!["curl", "jq", "wget"]);
+    let all: Vec<String> = app.marked_names();
+    let unpinned = vec!["curl".to_string(), "wget".to_string()];
+
+    press(&mut app, KeyCode::Char('r'));
+    assert!(matches!(
+        confirm_action(&app),
+        Some((ConfirmAction::BulkUninstall(pkgs), n)) if pkgs == all && n == "3 packages"
+    ));
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('u'));
+    assert!(matches!(
+        confirm_action(&app),
+        Some((ConfirmAction::BulkUpgrade(pkgs), _)) if pkgs == unpinned
+    ));
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('p'));
+    assert!(matches!(
+        confirm_action(&app),
+        Some((ConfirmAction::BulkPin(pkgs), _)) if pkgs == unpinned
+    ));
+    press(&mut app, KeyCode::Esc);
+
+    // Space on a marked package unmarks it; Esc drops every mark
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.marked_names(), vec!["curl", "jq"]);
+    press(&mut app, KeyCode::Esc);
+    assert!(app.marked.is_empty());
+    press(&mut app, KeyCode::Char('r'));
+    assert!(matches!(
+        confirm_action(&app),
+        Some((ConfirmAction::Uninstall, n)) if n == "wget"
+    ));
+}
+
+#[test]
+fn brewfile_lists_third_party_taps_first() {
+    let jq = formula("jq", Some("homebrew/core"));
+    let tool = formula("tool", Some("user/tools"));
+    let wget = formula("wget", None);
+    assert_eq!(
+        brewfile::render(&[&jq, &tool, &wget]),
+        "tap \"user/tools\"\nbrew \"jq\"\nbrew \"user/tools/tool\"\nbrew \"wget\"\n"
+    );
+}
+
+#[test]
+fn an_existing_brewfile_is_not_replaced() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-brewfile-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Brewfile");
+    brewfile::write_new(&path, "brew \"jq\"\n").unwrap();
+    assert!(brewfile::write_new(&path, "brew \"wget\"\n").is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "brew \"jq\"\n");
+    let _ = fs::remove_dir_all(&dir);
+}
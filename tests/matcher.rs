@@ -0,0 +1,39 @@
+use homebrew_tui::matcher::NameIndex;
+
+fn names(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn matches_substrings_case_insensitively_in_list_order() {
+    let index = NameIndex::new(&names(&["bat", "grep", "ripgrep", "Ripgrep-All", "git"]));
+    assert_eq!(index.matches("grep"), vec![1, 2, 3]);
+    assert_eq!(index.matches("RIPG"), vec![2, 3]);
+    assert_eq!(index.matches("g"), vec![1, 2, 3, 4]);
+    assert_eq!(index.matches(""), vec![0, 1, 2, 3, 4]);
+    assert!(index.matches("zzz").is_empty());
+}
+
+#[test]
+fn shared_trigrams_alone_do_not_match() {
+    // "abcxbcd" has every trigram of "abcd" except "bcd" adjacent to "abc"
+    let index = NameIndex::new(&names(&["abcxbcd", "abcd"]));
+    assert_eq!(index.matches("abcd"), vec![1]);
+}
+
+#[test]
+fn agrees_with_a_linear_scan_on_a_large_list() {
+    let list: Vec<String> = (0..20_000)
+        .map(|i| format!("pkg-{}-lib{}", i, i % 7))
+        .collect();
+    let index = NameIndex::new(&list);
+    for q in ["lib3", "pkg-1999", "-lib", "9-l", "99", "x"] {
+        let expected: Vec<usize> = list
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.contains(q))
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(index.matches(q), expected, "query {}", q);
+    }
+}
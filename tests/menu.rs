@@ -0,0 +1,158 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{app_with, press, pump};
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use homebrew_tui::app::{App, ConfirmAction, Focus, MenuItem, Mode, Zoom};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::brew::FormulaInfo;
+use homebrew_tui::browser::brew_page;
+use homebrew_tui::keymap::Action;
+use std::sync::Arc;
+
+fn formula(name: &str, dependencies: &[&str]) -> FormulaInfo {
+    FormulaInfo {
+        name: name.into(),
+        dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        homepage: Some(format!("https://example.com/{}", name)),
+        ..Default::default()
+    }
+}
+
+fn app() -> App {
+    let backend = Arc::new(MockBackend {
+        installed: vec![
+            formula("jq", &["oniguruma", "pcre2"]),
+            formula("oniguruma", &[]),
+            formula("wget", &[]),
+        ],
+        outdated: vec!["wget".into()],
+        ..MockBackend::new()
+    });
+    let mut app = app_with(backend);
+    pump(&mut app, |a| !a.outdated_items.is_empty());
+    app
+}
+
+fn select(app: &mut App, name: &str) {
+    app.focus = Focus::Installed;
+    app.selected = app.items.iter().position(|f| f.name == name).unwrap();
+}
+
+#[test]
+fn menu_lists_what_applies_and_esc_returns_to_it() {
+    let mut app = app();
+    select(&mut app, "jq");
+    let (_, items) = app.menu_items().unwrap();
+    assert!(!items.contains(&MenuItem::Run(Action::Upgrade)));
+    assert!(items.contains(&MenuItem::Dependencies));
+    assert!(!items.contains(&MenuItem::Dependents));
+
+    select(&mut app, "wget");
+    press(&mut app, KeyCode::Char('m'));
+    let Mode::Menu { name, items, .. } = &app.mode else {
+        panic!("menu not open: {:?}", app.mode);
+    };
+    assert_eq!(name, "wget");
+    assert_eq!(items[0], MenuItem::Run(Action::Upgrade));
+    assert!(items.contains(&MenuItem::Homepage("https://example.com/wget".into())));
+    assert_eq!(app.menu_label(&items[2], "wget"), "Pin");
+
+    // Upgrade opens its confirmation on top of the menu
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action: ConfirmAction::Upgrade, name, .. } if name == "wget"
+    ));
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Menu { .. }));
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Normal));
+}
+
+#[test]
+fn brew_page_only_for_official_taps() {
+    assert_eq!(
+        brew_page("jq", Some("homebrew/core"), false).as_deref(),
+        Some("https://formulae.brew.sh/formula/jq")
+    );
+    assert_eq!(
+        brew_page("firefox", None, true).as_deref(),
+        Some("https://formulae.brew.sh/cask/firefox")
+    );
+    assert_eq!(brew_page("k9s", Some("derailed/k9s"), false), None);
+    assert_eq!(brew_page("derailed/k9s/k9s", None, false), None);
+
+    let mut app = app();
+    select(&mut app, "jq");
+    let (_, items) = app.menu_items().unwrap();
+    assert!(items.contains(&MenuItem::Run(Action::BrewPage)));
+    app.items[0].tap = Some("derailed/k9s".into());
+    let (_, items) = app.menu_items().unwrap();
+    assert!(!items.contains(&MenuItem::Run(Action::BrewPage)));
+}
+
+#[test]
+fn related_packages_jump_to_the_lists() {
+    let mut app = app();
+    select(&mut app, "oniguruma");
+    assert_eq!(app.dependents("oniguruma"), vec!["jq"]);
+    app.perform(Action::Menu);
+    let Mode::Menu { items, .. } = &app.mode else {
+        panic!("menu not open: {:?}", app.mode);
+    };
+    let used_by = items
+        .iter()
+        .position(|i| *i == MenuItem::Dependents)
+        .unwrap();
+    for _ in 0..used_by {
+        press(&mut app, KeyCode::Down);
+    }
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        &app.mode,
+        Mode::Related { dependents: true, packages, .. } if packages == &["jq".to_string()]
+    ));
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::Normal));
+    assert!(app.nav_stack.is_empty());
+    assert_eq!(app.items[app.selected].name, "jq");
+}
+
+#[test]
+fn right_click_opens_the_menu() {
+    let mut app = app();
+    app.handle_mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Right),
+        column: 3,
+        row: 5,
+        modifiers: KeyModifiers::NONE,
+    });
+    assert!(matches!(app.mode, Mode::Menu { .. }));
+}
+
+#[test]
+fn zoomed_details_walk_the_dependencies_and_jump_to_one() {
+    let mut app = app();
+    select(&mut app, "jq");
+    app.zoom = Some(Zoom::Details);
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.details_dep, 1);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.details_dep, 0);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.zoom, None);
+    assert_eq!(app.focus, Focus::Installed);
+    assert_eq!(app.items[app.selected].name, "oniguruma");
+
+    // a missing dependency cannot be jumped to, it is in neither list
+    select(&mut app, "jq");
+    app.zoom = Some(Zoom::Details);
+    app.details_dep = 0;
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.zoom, Some(Zoom::Details));
+    assert_eq!(app.items[app.selected].name, "jq");
+    assert!(app.logs.last().unwrap().message.contains("pcre2"));
+}
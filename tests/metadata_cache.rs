@@ -0,0 +1,182 @@
+use homebrew_tui::api_cache::ApiEntry;
+use homebrew_tui::brew::FormulaInfo;
+use homebrew_tui::cache::{Cached, MetadataCache, AVAILABLE_TTL, GITHUB_TTL, INFO_TTL};
+use homebrew_tui::github::RepoStats;
+use std::fs;
+use std::time::{Duration, SystemTime};
+#[cfg(feature = "tui")]
+use {
+    homebrew_tui::app::{load_available, App, AppEvent, Focus, DETAILS_DEBOUNCE},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::history::History,
+    std::sync::{mpsc, Arc, Mutex},
+    std::time::Instant,
+};
+
+fn info(name: &str, desc: &str) -> FormulaInfo {
+    FormulaInfo {
+        name: name.into(),
+        desc: Some(desc.into()),
+        ..Default::default()
+    }
+}
+
+#[cfg(feature = "tui")]
+fn entry(name: &str) -> ApiEntry {
+    ApiEntry {
+        name: name.into(),
+        desc: None,
+        cask: false,
+    }
+}
+
+#[test]
+fn cache_round_trips_through_the_file() {
+    let path = std::env::temp_dir().join(format!(
+        "homebrew-tui-cache-{}/metadata.json",
+        std::process::id()
+    ));
+    let now = SystemTime::now();
+    let mut cache = MetadataCache::load_from(Some(path.clone()));
+    assert!(cache.available.is_none());
+    cache.set_available(
+        vec![ApiEntry {
+            name: "wget".into(),
+            desc: Some("Internet file retriever".into()),
+            cask: false,
+        }],
+        now,
+    );
+    cache.set_info(info("wget", "Internet file retriever"), now);
+    let stats = RepoStats {
+        stars: 1200,
+        pushed_at: "2024-05-01".into(),
+        archived: false,
+    };
+    cache.set_github("mirror/wget", stats.clone(), now);
+    cache.save().unwrap();
+
+    let loaded = MetadataCache::load_from(Some(path.clone()));
+    let available = loaded.available.expect("available list cached");
+    assert_eq!(available.value[0].name, "wget");
+    assert!(available.is_fresh(AVAILABLE_TTL, now));
+    assert_eq!(
+        loaded.info["wget"].value.desc.as_deref(),
+        Some("Internet file retriever")
+    );
+    assert_eq!(loaded.github["mirror/wget"].value, stats);
+    assert!(loaded.github["mirror/wget"].is_fresh(GITHUB_TTL, now));
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn files_from_another_cache_version_are_ignored() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-cache-old-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("metadata.json");
+    fs::write(
+        &path,
+        r#"{"version":0,"available":{"fetched_at":0,"value":[]},"info":{}}"#,
+    )
+    .unwrap();
+    assert!(MetadataCache::load_from(Some(path)).available.is_none());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn entries_expire_after_their_ttl() {
+    let fetched = SystemTime::now() - INFO_TTL - Duration::from_secs(1);
+    let entry = Cached::new(info("jq", "JSON processor"), fetched);
+    assert!(!entry.is_fresh(INFO_TTL, SystemTime::now()));
+    assert!(entry.is_fresh(INFO_TTL, fetched + Duration::from_secs(5)));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn fresh_cached_details_are_used_without_brew() {
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    let mut cache = MetadataCache::in_memory();
+    cache.set_info(info("bat", "Clone of cat with wings"), SystemTime::now());
+    app.cache = Arc::new(Mutex::new(cache));
+    app.items = vec![FormulaInfo {
+        name: "bat".into(),
+        ..Default::default()
+    }];
+    app.focus = Focus::Installed;
+    app.selected = 0;
+    app.last_selected = None;
+    while app.rx.try_recv().is_ok() {}
+
+    let t0 = Instant::now();
+    app.load_details_if_settled(t0);
+    app.load_details_if_settled(t0 + DETAILS_DEBOUNCE);
+    let details = app
+        .rx
+        .try_iter()
+        .find_map(|ev| match ev {
+            AppEvent::BrewInfo(info, 0) => Some(info),
+            _ => None,
+        })
+        .expect("cached details sent immediately");
+    assert_eq!(details.desc.as_deref(), Some("Clone of cat with wings"));
+}
+
+#[test]
+fn operations_forget_the_changed_packages() {
+    let mut cache = MetadataCache::in_memory();
+    cache.set_info(info("jq", "JSON processor"), SystemTime::now());
+    cache.set_info(info("fd", "find alternative"), SystemTime::now());
+    cache.forget_info(&["jq".to_string()]);
+    assert!(!cache.info.contains_key("jq"));
+    assert!(cache.info.contains_key("fd"));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn an_unchanged_index_keeps_the_expired_list() {
+    let mut backend = MockBackend {
+        available: vec![entry("fd")],
+        index_unchanged: true,
+        ..Default::default()
+    };
+    let mut cache = MetadataCache::in_memory();
+    let fetched = SystemTime::now() - AVAILABLE_TTL - Duration::from_secs(60);
+    cache.set_available(vec![entry("wget")], fetched);
+    let cache = Mutex::new(cache);
+    let (tx, rx) = mpsc::channel();
+
+    load_available(&backend, &cache, &tx);
+    let events: Vec<AppEvent> = rx.try_iter().collect();
+    // only the cached list was sent, and it is current as of now
+    let lists: Vec<&Vec<String>> = events
+        .iter()
+        .filter_map(|e| match e {
+            AppEvent::AvailableList(list) => Some(list),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(lists, [&vec!["wget".to_string()]]);
+    let fresh: Vec<u64> = events
+        .iter()
+        .filter_map(|e| match e {
+            AppEvent::IndexFresh(at) => Some(*at),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fresh.len(), 2);
+    assert!(fresh[1] > fresh[0]);
+    let cached = cache.lock().unwrap().available.clone().unwrap();
+    assert!(cached.is_fresh(AVAILABLE_TTL, SystemTime::now()));
+
+    // once it changed the list is read again
+    backend.index_unchanged = false;
+    cache
+        .lock()
+        .unwrap()
+        .set_available(vec![entry("wget")], fetched);
+    load_available(&backend, &cache, &tx);
+    assert!(rx
+        .try_iter()
+        .any(|e| matches!(e, AppEvent::AvailableList(list) if list == ["fd"])));
+}
@@ -0,0 +1,232 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::press;
+use crossterm::event::KeyCode;
+use homebrew_tui::app::{App, ConfirmAction, InputAction, Mode};
+use homebrew_tui::history::History;
+use homebrew_tui::output::LogLine;
+
+fn app() -> App {
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    app
+}
+
+#[test]
+fn scrolling_an_operation_log_keeps_it_in_place() {
+    let mut app = app();
+    let logs: Vec<LogLine> = (0..2000)
+        .map(|i| LogLine::stdout(format!("line {}", i)))
+        .collect();
+    let buffer = logs.as_ptr();
+    app.mode = Mode::Operation {
+        title: "install wget".into(),
+        logs,
+        scroll: 0,
+        live: false,
+    };
+
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Up);
+    }
+    press(&mut app, KeyCode::PageUp);
+    press(&mut app, KeyCode::Down);
+    match &app.mode {
+        Mode::Operation { logs, scroll, .. } => {
+            assert_eq!(*scroll, 14);
+            assert_eq!(logs.as_ptr(), buffer);
+        }
+        _ => panic!("operation modal closed"),
+    }
+
+    press(&mut app, KeyCode::Home);
+    assert!(matches!(app.mode, Mode::Operation { scroll: 2000, .. }));
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Normal));
+}
+
+#[test]
+fn outdated_selection_hands_over_to_confirm() {
+    let mut app = app();
+    app.mode = Mode::Outdated {
+        packages: vec!["jq".into(), "fd".into(), "bat".into()],
+        cursor: 0,
+        checked: vec![false; 3],
+        scroll: 0,
+    };
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::Outdated { .. }), "nothing checked");
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    match &app.mode {
+        Mode::Outdated {
+            cursor, checked, ..
+        } => {
+            assert_eq!(*cursor, 2);
+            assert_eq!(checked, &vec![false, true, true]);
+        }
+        _ => panic!("outdated modal closed"),
+    }
+
+    press(&mut app, KeyCode::Enter);
+    match &app.mode {
+        Mode::Confirm {
+            action: ConfirmAction::BulkUpgrade(pkgs),
+            name,
+            ..
+        } => {
+            assert_eq!(pkgs, &vec!["fd".to_string(), "bat".to_string()]);
+            assert_eq!(name, "2 packages");
+        }
+        _ => panic!("expected the bulk upgrade confirmation"),
+    }
+}
+
+#[test]
+fn input_edits_the_buffer_until_enter() {
+    let mut app = app();
+    // a known name, which needs no lookup of its tap
+    app.available_items = vec!["wget".into()];
+    app.mode = Mode::Input {
+        action: InputAction::Install,
+        buffer: String::new(),
+    };
+    for c in "wgetx".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Enter);
+    match &app.mode {
+        Mode::Confirm {
+            action: ConfirmAction::Install,
+            name,
+            ..
+        } => assert_eq!(name, "wget"),
+        _ => panic!("expected the install confirmation"),
+    }
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(&app.mode, Mode::Input { buffer, .. } if buffer == "wget"));
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Normal));
+}
+
+#[test]
+fn esc_closes_help_and_normal_keys_open_modals() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('?'));
+    press(&mut app, KeyCode::Char('j'));
+    assert!(matches!(app.mode, Mode::Help { scroll: 1, .. }));
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Normal));
+
+    press(&mut app, KeyCode::Char('H'));
+    assert!(matches!(app.mode, Mode::History { selected: 0 }));
+    press(&mut app, KeyCode::Char('H'));
+    assert!(matches!(app.mode, Mode::Normal));
+}
+
+#[test]
+fn flags_picked_in_the_overlay_reach_the_confirm_dialog() {
+    let mut app = app();
+    app.mode = Mode::Confirm {
+        action: ConfirmAction::Install,
+        name: "wget".into(),
+        idx: None,
+    };
+    press(&mut app, KeyCode::Char('o'));
+    assert!(matches!(&app.mode, Mode::Flags { checked, .. } if checked == &[false; 4]));
+    // --force and --ignore-dependencies
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Enter);
+    let Mode::Confirm { action, .. } = &app.mode else {
+        panic!("expected the install confirmation, got {:?}", app.mode);
+    };
+    assert_eq!(
+        action.command_line("wget"),
+        "brew install --force --ignore-dependencies wget"
+    );
+
+    // reopening shows them checked, and Esc keeps them
+    press(&mut app, KeyCode::Char('o'));
+    assert!(
+        matches!(&app.mode, Mode::Flags { checked, .. } if checked == &[true, false, false, true])
+    );
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Esc);
+    let Mode::Confirm { action, .. } = &app.mode else {
+        panic!("expected the install confirmation, got {:?}", app.mode);
+    };
+    assert_eq!(action.flags(), ["--force", "--ignore-dependencies"]);
+
+    // actions brew does not run ignore the key
+    app.mode = Mode::Confirm {
+        action: ConfirmAction::SwitchVersion {
+            version: "1.24.5".into(),
+        },
+        name: "wget".into(),
+        idx: None,
+    };
+    press(&mut app, KeyCode::Char('o'));
+    assert!(matches!(app.mode, Mode::Confirm { .. }));
+}
+
+#[test]
+fn variables_typed_in_the_prompt_reach_the_confirm_dialog() {
+    let mut app = app();
+    app.mode = Mode::Confirm {
+        action: ConfirmAction::Upgrade,
+        name: "jq".into(),
+        idx: None,
+    };
+    press(&mut app, KeyCode::Char('e'));
+    assert!(matches!(
+        app.mode,
+        Mode::Input {
+            action: InputAction::OperationEnv,
+            ..
+        }
+    ));
+    // a mistake keeps the prompt open
+    for c in "JOBS".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::Input { .. }));
+    assert_eq!(app.status, "`JOBS` is not KEY=VALUE");
+    for c in "=8".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    let Mode::Confirm { action, .. } = &app.mode else {
+        panic!("expected the upgrade confirmation, got {:?}", app.mode);
+    };
+    assert_eq!(
+        action.full_command_line_with("jq", &[]),
+        "JOBS=8 brew upgrade jq"
+    );
+
+    // the prompt opens with them again; clearing it removes them
+    press(&mut app, KeyCode::Char('e'));
+    assert!(matches!(&app.mode, Mode::Input { buffer, .. } if buffer == "JOBS=8"));
+    for _ in 0.."JOBS=8".len() {
+        press(&mut app, KeyCode::Backspace);
+    }
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        app.mode,
+        Mode::Confirm {
+            action: ConfirmAction::Upgrade,
+            ..
+        }
+    ));
+}
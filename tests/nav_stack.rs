@@ -0,0 +1,65 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{press, pump};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use homebrew_tui::app::{App, ConfirmAction, Mode};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::config::ConfigSource;
+use homebrew_tui::disk::{DiskUsage, PackageUsage};
+use std::sync::Arc;
+
+fn esc(app: &mut App) {
+    assert!(!app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+}
+
+fn disk_usage_app() -> App {
+    let backend = Arc::new(MockBackend {
+        disk_usage: DiskUsage {
+            cellar: "/opt/homebrew/Cellar".into(),
+            packages: vec![PackageUsage {
+                name: "jq".into(),
+                size: 1 << 20,
+            }],
+        },
+        ..MockBackend::new()
+    });
+    App::with_backend(ConfigSource::default(), backend).expect("create app")
+}
+
+#[test]
+fn esc_steps_back_through_the_dialogs_it_opened() {
+    let mut app = disk_usage_app();
+    press(&mut app, KeyCode::Char('S'));
+    pump(&mut app, |a| matches!(a.mode, Mode::DiskUsage { .. }));
+    press(&mut app, KeyCode::Char('r'));
+    assert!(matches!(
+        app.mode,
+        Mode::Confirm {
+            action: ConfirmAction::Uninstall,
+            ..
+        }
+    ));
+    assert_eq!(app.nav_stack.len(), 1);
+
+    esc(&mut app);
+    assert!(matches!(app.mode, Mode::DiskUsage { .. }));
+    assert!(app.nav_stack.is_empty());
+    esc(&mut app);
+    assert!(matches!(app.mode, Mode::Normal));
+}
+
+#[test]
+fn confirming_drops_the_dialogs_below() {
+    let mut app = disk_usage_app();
+    press(&mut app, KeyCode::Char('S'));
+    pump(&mut app, |a| matches!(a.mode, Mode::DiskUsage { .. }));
+    press(&mut app, KeyCode::Char('r'));
+    press(&mut app, KeyCode::Char('y'));
+    assert!(!matches!(
+        app.mode,
+        Mode::DiskUsage { .. } | Mode::Confirm { .. }
+    ));
+    assert!(app.nav_stack.is_empty());
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{app_with, fixtures, press, pump, type_text};
+use crossterm::event::KeyCode;
+use homebrew_tui::app::{App, Focus, InputAction, Mode};
+use homebrew_tui::notes::Notes;
+use homebrew_tui::ui::draw_ui;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::fs;
+
+fn app() -> App {
+    let mut app = app_with(fixtures());
+    pump(&mut app, |a| !a.loading_details);
+    app
+}
+
+fn screen(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    draw_ui(&mut terminal, app).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer.content.iter().map(|c| c.symbol.as_str()).collect()
+}
+
+#[test]
+fn notes_are_written_in_a_prompt_and_shown_in_details() {
+    let mut app = app();
+    app.focus = Focus::Installed;
+    app.selected = app.items.iter().position(|f| f.name == "jq").unwrap();
+    press(&mut app, KeyCode::Char('A'));
+    assert!(matches!(
+        &app.mode,
+        Mode::Input { action: InputAction::Note { name }, buffer } if name == "jq" && buffer.is_empty()
+    ));
+    type_text(&mut app, "pinned because 1.7 breaks ci");
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::Normal));
+    assert_eq!(app.notes.get("jq"), Some("pinned because 1.7 breaks ci"));
+    assert!(screen(&mut app).contains("note: pinned because 1.7 breaks ci"));
+
+    // the prompt starts from the note, and a blank one removes it
+    press(&mut app, KeyCode::Char('A'));
+    let Mode::Input { buffer, .. } = &app.mode else {
+        panic!("no prompt");
+    };
+    assert_eq!(buffer, "pinned because 1.7 breaks ci");
+    for _ in 0..buffer.len() {
+        press(&mut app, KeyCode::Backspace);
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.notes.get("jq"), None);
+
+    // packages that are not installed take notes too; Esc keeps what was there
+    app.focus = Focus::Available;
+    app.available_selected = app.available_items.iter().position(|n| n == "fd").unwrap();
+    press(&mut app, KeyCode::Char('A'));
+    type_text(&mut app, "for project x");
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Char('A'));
+    type_text(&mut app, " and y");
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.notes.get("fd"), Some("for project x"));
+    assert!(screen(&mut app).contains("note: for project x"));
+}
+
+#[test]
+fn notes_are_kept_in_the_state_file() {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-notes-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("notes.json");
+
+    let mut notes = Notes::load_from(Some(path.clone()));
+    assert!(notes.notes.is_empty());
+    notes.set("postgresql@14", "  data in ~/pg  ");
+    notes.set("wget", "installed for project X");
+    notes.set("wget", " ");
+    notes.save().unwrap();
+    let loaded = Notes::load_from(Some(path));
+    assert_eq!(loaded.get("postgresql@14"), Some("data in ~/pg"));
+    assert_eq!(loaded.get("wget"), None);
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,49 @@
+use homebrew_tui::notify::finished_message;
+
+#[test]
+fn finished_message_reports_the_outcome() {
+    assert_eq!(
+        finished_message("brew upgrade jq", Some(0)),
+        "brew upgrade jq succeeded"
+    );
+    assert_eq!(
+        finished_message("brew install nope", Some(1)),
+        "brew install nope failed (exit code 1)"
+    );
+    assert_eq!(
+        finished_message("brew cleanup", None),
+        "brew cleanup did not finish"
+    );
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn long_operations_ring_the_bell_or_flash() {
+    use homebrew_tui::app::{App, AppEvent};
+    use homebrew_tui::backend::MockBackend;
+    use homebrew_tui::config::{Bell, ConfigSource};
+    use std::sync::Arc;
+
+    let mut app = App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new()))
+        .expect("create app");
+    let run = |app: &mut App| {
+        app.handle_event(AppEvent::OpStart("brew upgrade jq".into()));
+        app.handle_event(AppEvent::OpExit(Some(0)));
+        app.handle_event(AppEvent::OpEnd("brew upgrade jq".into()));
+    };
+    // off by default
+    run(&mut app);
+    assert!(!app.ring_bell && app.flash_until.is_none());
+
+    app.config.notify.bell = Bell::Flash;
+    run(&mut app);
+    // quicker than the 10 seconds it takes by default
+    assert!(app.flash_until.is_none());
+    app.config.notify.after = 0;
+    run(&mut app);
+    assert!(app.flash_until.is_some());
+
+    app.config.notify.bell = Bell::Bell;
+    run(&mut app);
+    assert!(app.ring_bell);
+}
@@ -0,0 +1,107 @@
+use homebrew_tui::parse::{parse_cleanup_dry_run, parse_outdated, parse_search, parse_services};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The brew versions with fixtures in `tests/fixtures/brew-output`; each has the output of
+/// the same packages in that version's format.
+const VERSIONS: [&str; 3] = ["2.7.0", "3.6.0", "4.3.0"];
+
+fn dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/brew-output")
+}
+
+fn fixture(version: &str, file: &str) -> String {
+    let path = dir().join(version).join(file);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+#[test]
+fn every_fixture_version_is_checked() {
+    let mut found: Vec<String> = fs::read_dir(dir())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    found.sort();
+    assert_eq!(found, VERSIONS);
+}
+
+#[test]
+fn outdated_lists_names_in_every_format() {
+    for v in VERSIONS {
+        assert_eq!(
+            parse_outdated(&fixture(v, "outdated.txt")),
+            ["jq", "wget"],
+            "brew {}",
+            v
+        );
+    }
+    assert_eq!(
+        parse_outdated("==> Formulae\njq (1.6) < 1.7.1\n\n==> Casks\nfirefox (124.0) != 125.0\n"),
+        ["jq", "firefox"]
+    );
+}
+
+#[test]
+fn search_results_drop_headers_and_markers() {
+    for v in VERSIONS {
+        assert_eq!(
+            parse_search(&fixture(v, "search.txt")),
+            ["ripgrep", "ripgrep-all", "ripme"],
+            "brew {}",
+            v
+        );
+    }
+    assert!(parse_search("").is_empty());
+}
+
+#[test]
+fn services_parse_with_plist_or_file() {
+    for v in VERSIONS {
+        let services = parse_services(&fixture(v, "services.json"))
+            .unwrap_or_else(|e| panic!("brew {}: {}", v, e));
+        let names: Vec<_> = services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["postgresql@14", "redis"], "brew {}", v);
+        let postgres = &services[0];
+        assert_eq!(postgres.status, "started", "brew {}", v);
+        assert_eq!(postgres.user.as_deref(), Some("me"), "brew {}", v);
+        assert_eq!(
+            postgres.file.as_deref(),
+            Some("/Users/me/Library/LaunchAgents/homebrew.mxcl.postgresql@14.plist"),
+            "brew {}",
+            v
+        );
+        assert_ne!(services[1].status, "started", "brew {}", v);
+    }
+    assert!(parse_services("").unwrap().is_empty());
+    assert!(parse_services("Error: unknown command").is_err());
+}
+
+#[test]
+fn cleanup_previews_count_files_sizes_and_pruned_paths() {
+    let totals = ["1.6MB", "1.6MB", "1.7MB"];
+    for (v, total) in VERSIONS.into_iter().zip(totals) {
+        let preview = parse_cleanup_dry_run(&fixture(v, "cleanup.txt"));
+        assert_eq!(preview.files.len(), 3, "brew {}", v);
+        assert_eq!(preview.total.as_deref(), Some(total), "brew {}", v);
+        assert!(
+            preview.files[0].0.ends_with("/Cellar/jq/1.6")
+                || preview.files[0].0.ends_with("/Cellar/jq/1.7"),
+            "brew {}",
+            v
+        );
+        assert!(
+            preview.files[0]
+                .1
+                .as_deref()
+                .is_some_and(|s| s.ends_with("files, 1.1MB") || s.ends_with("files, 1.2MB")),
+            "brew {}",
+            v
+        );
+    }
+    // what the prune step would take has no size
+    let preview = parse_cleanup_dry_run(&fixture("2.7.0", "cleanup.txt"));
+    assert_eq!(
+        preview.files[2],
+        ("/usr/local/share/doc/oniguruma".to_string(), None)
+    );
+}
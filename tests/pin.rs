@@ -0,0 +1,77 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{press, pump};
+use crossterm::event::KeyCode;
+use homebrew_tui::app::{App, ConfirmAction, Mode};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::brew::FormulaInfo;
+use homebrew_tui::config::ConfigSource;
+use std::sync::Arc;
+
+/// jq (pinned) and wget, both outdated.
+fn app() -> App {
+    let formula = |name: &str| FormulaInfo {
+        name: name.into(),
+        ..Default::default()
+    };
+    let backend = Arc::new(MockBackend {
+        installed: vec![formula("jq"), formula("wget")],
+        outdated: vec!["jq".into(), "wget".into()],
+        pinned: vec!["jq".into()],
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+    pump(&mut app, |a| {
+        a.is_pinned("jq") && a.outdated_items.len() == 2
+    });
+    app
+}
+
+fn confirm_action(app: &App) -> Option<(ConfirmAction, String)> {
+    match &app.mode {
+        Mode::Confirm { action, name, .. } => Some((action.clone(), name.clone())),
+        _ => None,
+    }
+}
+
+#[test]
+fn p_unpins_a_pinned_formula_and_pins_the_others() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('p'));
+    assert!(matches!(
+        confirm_action(&app),
+        Some((ConfirmAction::Unpin, n)) if n == "jq"
+    ));
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('p'));
+    assert!(matches!(
+        confirm_action(&app),
+        Some((ConfirmAction::Pin, n)) if n == "wget"
+    ));
+}
+
+#[test]
+fn pinned_packages_are_left_out_of_upgrades() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        confirm_action(&app),
+        Some((ConfirmAction::BulkUpgrade(pkgs), _)) if pkgs == vec!["wget".to_string()]
+    ));
+
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Outdated { .. }));
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('M'));
+    match &app.mode {
+        Mode::Maintenance { checked, .. } => assert_eq!(checked, &vec![false, true]),
+        _ => panic!("expected the maintenance modal"),
+    }
+}
@@ -0,0 +1,82 @@
+use homebrew_tui::event::{AppEvent, ConfirmAction};
+use homebrew_tui::porcelain::{event_json, Request};
+
+#[test]
+fn events_are_tagged_json_lines() {
+    assert_eq!(
+        event_json(&AppEvent::OpLog("==> Pouring jq".into())),
+        r#"{"event":"op_log","data":"==> Pouring jq"}"#
+    );
+    assert_eq!(
+        event_json(&AppEvent::OpExit(Some(1))),
+        r#"{"event":"op_exit","data":1}"#
+    );
+    let v: serde_json::Value =
+        serde_json::from_str(&event_json(&AppEvent::SearchResults(vec!["jq".into()]))).unwrap();
+    assert_eq!(v["event"], "search_results");
+    assert_eq!(v["data"][0], "jq");
+}
+
+#[test]
+fn parses_requests() {
+    let r: Request = serde_json::from_str(r#"{"command":"link","name":"python@3.12"}"#).unwrap();
+    assert_eq!(
+        r,
+        Request::Link {
+            name: "python@3.12".into(),
+            overwrite: false
+        }
+    );
+    assert!(matches!(
+        r.operation(),
+        Some((ConfirmAction::Link, n)) if n == "python@3.12"
+    ));
+    let r: Request = serde_json::from_str(r#"{"command":"upgrade","names":["jq","git"]}"#).unwrap();
+    assert!(matches!(r.operation(), Some((ConfirmAction::BulkUpgrade(p), _)) if p.len() == 2));
+    let r: Request = serde_json::from_str(r#"{"command":"outdated"}"#).unwrap();
+    assert!(r.operation().is_none());
+    let r: Request = serde_json::from_str(r#"{"command":"services"}"#).unwrap();
+    assert_eq!(r, Request::Services);
+    assert!(serde_json::from_str::<Request>(r#"{"command":"install"}"#).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn serves_requests_until_input_closes() {
+    use homebrew_tui::brew::Brew;
+    use std::fs;
+    use std::io::Cursor;
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-porcelain-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("brew");
+    fs::write(&script, "#!/bin/sh\necho \"ran $1\"\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    let brew = Brew {
+        program: script,
+        ..Brew::new()
+    };
+
+    let input = Cursor::new(b"{\"command\":\"install\",\"name\":\"jq\"}\nnot json\n".to_vec());
+    let mut out = vec![];
+    homebrew_tui::porcelain::run(brew, input, &mut out).unwrap();
+    let lines: Vec<serde_json::Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    let events: Vec<&str> = lines.iter().map(|v| v["event"].as_str().unwrap()).collect();
+    assert!(events.contains(&"op_start"));
+    assert!(lines
+        .iter()
+        .any(|v| v["event"] == "op_log" && v["data"] == "ran install"));
+    assert!(
+        lines
+            .iter()
+            .any(|v| v["event"] == "log"
+                && v["data"].as_str().unwrap().starts_with("invalid request"))
+    );
+    assert_eq!(events.last(), Some(&"op_end"));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,48 @@
+use homebrew_tui::progress::{parse_percent, Phase, PhaseTracker};
+
+#[test]
+fn parses_literal_percentages() {
+    assert_eq!(parse_percent("Downloading... 42%"), Some(42));
+    assert_eq!(parse_percent("######## 42.3%"), Some(42));
+    assert_eq!(parse_percent("150%"), Some(100));
+    assert_eq!(parse_percent("no percent here"), None);
+    assert_eq!(parse_percent("just a % sign"), None);
+}
+
+#[test]
+fn phases_drive_progress_without_percentages() {
+    let output = [
+        "==> Fetching dependencies for wget: openssl@3",
+        "==> Downloading https://ghcr.io/v2/homebrew/core/openssl/3/manifests/3.3.1",
+        "==> Downloading https://ghcr.io/v2/homebrew/core/wget/manifests/1.24.5",
+        "==> Installing dependencies for wget: openssl@3",
+        "==> Pouring openssl@3--3.3.1.arm64_sonoma.bottle.tar.gz",
+        "==> Installing wget",
+        "==> Pouring wget--1.24.5.arm64_sonoma.bottle.tar.gz",
+        "🍺  /opt/homebrew/Cellar/wget/1.24.5: 90 files, 4.5MB",
+        "==> Running `brew cleanup wget`...",
+    ];
+    let mut t = PhaseTracker::new();
+    let mut last = 0;
+    for line in output {
+        t.observe(line);
+        let p = t.percent().unwrap();
+        assert!(p >= last, "progress went backwards on {:?}", line);
+        last = p;
+        if line.contains("Downloading") {
+            assert_eq!(t.phase(), Phase::Fetching);
+            assert!(p < 40);
+        }
+    }
+    assert_eq!(t.phase(), Phase::Finishing);
+    assert!(last >= 95);
+}
+
+#[test]
+fn percentages_map_into_current_phase() {
+    let mut t = PhaseTracker::new();
+    t.observe("==> Downloading https://example.com/x.tar.gz");
+    assert_eq!(t.observe("######## 50.0%"), Some(20));
+    // unrelated lines do not move the gauge
+    assert_eq!(t.observe("plain output"), None);
+}
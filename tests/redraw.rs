@@ -0,0 +1,29 @@
+#![cfg(feature = "tui")]
+
+use homebrew_tui::app::App;
+use homebrew_tui::history::History;
+
+#[test]
+fn idle_app_does_not_animate() {
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    app.config.reduced_motion = false;
+    app.loading_installed = false;
+    app.loading_available = false;
+    app.operating = false;
+    app.waiting_locks.clear();
+    assert!(!app.is_animating());
+
+    app.operating = true;
+    assert!(app.is_animating());
+    app.operating = false;
+    app.waiting_locks = vec!["update".into()];
+    assert!(app.is_animating());
+    app.waiting_locks.clear();
+    app.loading_available = true;
+    assert!(app.is_animating());
+
+    // reduced motion never animates, the screen only changes on events
+    app.config.reduced_motion = true;
+    assert!(!app.is_animating());
+}
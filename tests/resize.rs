@@ -0,0 +1,43 @@
+#![cfg(feature = "tui")]
+
+use homebrew_tui::app::{App, Mode};
+use homebrew_tui::brew::FormulaInfo;
+use homebrew_tui::history::History;
+use homebrew_tui::output::LogLine;
+use homebrew_tui::ui::operation_rows;
+use ratatui::layout::Rect;
+
+#[test]
+fn resizing_clamps_selections_and_the_operation_scroll() {
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    app.items = vec![FormulaInfo {
+        name: "jq".into(),
+        ..Default::default()
+    }];
+    app.selected = 4;
+    app.available_items = vec![];
+    app.available_selected = 2;
+    app.mode = Mode::Operation {
+        title: "upgrade".into(),
+        logs: (0..100).map(|i| LogLine::stdout(i.to_string())).collect(),
+        scroll: 100,
+        live: false,
+    };
+
+    let small = Rect::new(0, 0, 80, 24);
+    app.handle_resize(small);
+    assert_eq!(app.selected, 0);
+    assert_eq!(app.available_selected, 0);
+    assert!(
+        matches!(app.mode, Mode::Operation { scroll, .. } if scroll == 100 - operation_rows(small))
+    );
+
+    // a taller screen shows more lines, so less scrolling is left
+    let tall = Rect::new(0, 0, 80, 60);
+    assert!(operation_rows(tall) > operation_rows(small));
+    app.handle_resize(tall);
+    assert!(
+        matches!(app.mode, Mode::Operation { scroll, .. } if scroll == 100 - operation_rows(tall))
+    );
+}
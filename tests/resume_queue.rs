@@ -0,0 +1,162 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::event::ConfirmAction;
+use homebrew_tui::queue::OperationQueue;
+use std::fs;
+use std::path::PathBuf;
+#[cfg(feature = "tui")]
+use {
+    common::{fixtures, press},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    std::path::Path,
+    std::sync::{Arc, Mutex},
+    std::thread,
+    std::time::{Duration, Instant},
+};
+
+fn temp_file(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("queue.json")
+}
+
+/// What an earlier session left in `path`: an upgrade of jq and an install of fd.
+#[cfg(feature = "tui")]
+fn interrupted(path: &Path) {
+    let mut queue = OperationQueue::load_from(Some(path.to_path_buf()));
+    queue.push(ConfirmAction::Upgrade, "jq", "brew upgrade jq".into());
+    queue.push(ConfirmAction::Install, "fd", "brew install fd".into());
+    queue.save().unwrap();
+}
+
+/// An app on the fixtures that uses the queue in `path`, as a launch would.
+#[cfg(feature = "tui")]
+fn app(path: &Path) -> (App, Arc<MockBackend>) {
+    let backend = Arc::new(fixtures());
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+    app.queue = Arc::new(Mutex::new(OperationQueue::load_from(Some(
+        path.to_path_buf(),
+    ))));
+    app.offer_resume();
+    (app, backend)
+}
+
+/// Tick the app until every operation it started has ended.
+#[cfg(feature = "tui")]
+fn finish_operations(app: &mut App, count: usize) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while app
+        .history
+        .records
+        .iter()
+        .filter(|r| r.duration_ms.is_some())
+        .count()
+        < count
+    {
+        assert!(
+            Instant::now() < deadline,
+            "timed out waiting for the operations"
+        );
+        app.tick();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn queue_entries_keep_their_order_and_ids() {
+    let path = temp_file("queue-file");
+    let mut queue = OperationQueue::load_from(Some(path.clone()));
+    assert!(queue.entries.is_empty());
+    let first = queue.push(ConfirmAction::Upgrade, "jq", "brew upgrade jq".into());
+    let second = queue.push(
+        ConfirmAction::BulkUpgrade(vec!["jq".into(), "wget".into()]),
+        "2 packages",
+        "brew upgrade jq wget".into(),
+    );
+    assert_ne!(first, second);
+    queue.remove(first);
+    queue.save().unwrap();
+
+    let loaded = OperationQueue::load_from(Some(path.clone()));
+    assert_eq!(loaded.entries.len(), 1);
+    assert_eq!(loaded.entries[0].command, "brew upgrade jq wget");
+    assert!(matches!(
+        &loaded.entries[0].action,
+        ConfirmAction::BulkUpgrade(p) if p == &["jq", "wget"]
+    ));
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn confirmed_operations_stay_queued_until_they_end() {
+    let path = temp_file("queue-confirm");
+    let (mut app, backend) = app(&path);
+    assert!(matches!(app.mode, Mode::Normal));
+    app.mode = Mode::Confirm {
+        action: ConfirmAction::Install,
+        name: "fd".into(),
+        idx: None,
+    };
+    press(&mut app, KeyCode::Char('y'));
+    finish_operations(&mut app, 1);
+    assert_eq!(backend.executed(), ["brew install fd"]);
+    assert!(OperationQueue::load_from(Some(path.clone()))
+        .entries
+        .is_empty());
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn unfinished_operations_are_reviewed_and_resumed() {
+    let path = temp_file("queue-resume");
+    interrupted(&path);
+    let (mut app, backend) = app(&path);
+    let Mode::Resume {
+        operations,
+        checked,
+        ..
+    } = &app.mode
+    else {
+        panic!("no review of the unfinished operations");
+    };
+    assert_eq!(operations.len(), 2);
+    assert_eq!(checked, &[true, true]);
+
+    // leave the install out
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::Normal));
+    finish_operations(&mut app, 1);
+    assert_eq!(backend.executed(), ["brew upgrade jq"]);
+    assert!(OperationQueue::load_from(Some(path.clone()))
+        .entries
+        .is_empty());
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn dropping_the_review_forgets_the_queue() {
+    let path = temp_file("queue-drop");
+    interrupted(&path);
+    let (mut app, backend) = app(&path);
+    assert!(matches!(app.mode, Mode::Resume { .. }));
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Normal));
+    assert!(OperationQueue::load_from(Some(path.clone()))
+        .entries
+        .is_empty());
+    assert!(backend.executed().is_empty());
+
+    // the next launch has nothing to offer
+    let (app, _) = self::app(&path);
+    assert!(matches!(app.mode, Mode::Normal));
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
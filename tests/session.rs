@@ -0,0 +1,95 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{fixtures, pump};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use homebrew_tui::app::{App, Focus};
+use homebrew_tui::config::ConfigSource;
+use homebrew_tui::session::Session;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn temp_file(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("session.json")
+}
+
+/// An app on the fixtures that starts from `session` (and `--search`), ticked until both lists
+/// are in.
+fn app(session: Session, search: Option<&str>) -> App {
+    let mut app =
+        App::with_backend(ConfigSource::default(), Arc::new(fixtures())).expect("create app");
+    app.restore_session(session);
+    if let Some(query) = search {
+        app.start_search(query);
+    }
+    pump(&mut app, |a| !a.loading_installed && !a.loading_available);
+    app
+}
+
+#[test]
+fn sessions_round_trip_through_the_state_file() {
+    let path = temp_file("session-file");
+    // nothing saved yet
+    let mut session = Session::load_from(Some(path.clone()));
+    assert_eq!(session.focus, Focus::Installed);
+    assert!(session.installed.is_none() && session.filter.is_empty());
+    session.focus = Focus::Available;
+    session.available = Some("ripgrep".into());
+    session.filter = "rip".into();
+    session.available_offset = 4;
+    session.save().unwrap();
+    let loaded = Session::load_from(Some(path.clone()));
+    assert_eq!(loaded.focus, Focus::Available);
+    assert_eq!(loaded.available.as_deref(), Some("ripgrep"));
+    assert_eq!(loaded.filter, "rip");
+    assert_eq!((loaded.installed_offset, loaded.available_offset), (0, 4));
+
+    // a broken file starts over
+    fs::write(&path, "{ not json").unwrap();
+    assert_eq!(
+        Session::load_from(Some(path.clone())).focus,
+        Focus::Installed
+    );
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn the_last_session_is_restored_and_saved_on_quit() {
+    let path = temp_file("session-restore");
+    let mut session = Session::load_from(Some(path.clone()));
+    session.focus = Focus::Available;
+    session.installed = Some("wget".into());
+    session.available = Some("fd".into());
+    session.filter = "fd".into();
+    let mut app = app(session, None);
+    assert_eq!(app.focus, Focus::Available);
+    assert_eq!(app.items[app.selected].name, "wget");
+    assert_eq!(app.available_filter, "fd");
+    assert_eq!(app.available_items[app.available_selected], "fd");
+
+    // move on and quit: the next launch starts there
+    app.focus = Focus::Installed;
+    app.selected = app.items.iter().position(|f| f.name == "jq").unwrap();
+    app.available_filter.clear();
+    assert!(app.inject_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE)));
+    let saved = Session::load_from(Some(path.clone()));
+    assert_eq!(saved.focus, Focus::Installed);
+    assert_eq!(saved.installed.as_deref(), Some("jq"));
+    assert_eq!(saved.available.as_deref(), Some("fd"));
+    assert_eq!(saved.filter, "");
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn a_search_on_the_command_line_wins_over_the_restored_filter() {
+    let mut session = Session::in_memory();
+    session.available = Some("fd".into());
+    session.filter = "fd".into();
+    let app = app(session, Some("ripgrep"));
+    assert_eq!(app.available_filter, "ripgrep");
+    assert_eq!(app.available_items[app.available_selected], "ripgrep");
+}
@@ -0,0 +1,13 @@
+#![cfg(unix)]
+
+use homebrew_tui::signals;
+
+#[test]
+fn termination_signals_are_recorded_instead_of_killing_the_process() {
+    assert_eq!(signals::termination_request(), None);
+    signals::install_termination_handler();
+    unsafe {
+        libc::raise(libc::SIGTERM);
+    }
+    assert_eq!(signals::termination_request(), Some(libc::SIGTERM));
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "tui")]
+
 use homebrew_tui::app::{App, AppEvent};
 
 // Smoke test: simulate installer events without running any external process.
@@ -0,0 +1,119 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::{FormulaInfo, InstalledInfo};
+use homebrew_tui::snapshot::{Snapshot, Snapshots};
+use std::fs;
+use std::path::PathBuf;
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, fixtures, press},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::Mode,
+    homebrew_tui::event::ConfirmAction,
+};
+
+fn temp_file(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("snapshot.json")
+}
+
+fn formula(name: &str, version: &str) -> FormulaInfo {
+    FormulaInfo {
+        name: name.into(),
+        installed: vec![InstalledInfo {
+            version: version.into(),
+            time: None,
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn diffs_list_removed_added_and_changed_packages() {
+    let before = [
+        formula("bat", "0.24.0"),
+        formula("jq", "1.6"),
+        formula("wget", "1.21.4"),
+    ];
+    let snapshot = Snapshot::of(&before, 1_700_000_000);
+    assert_eq!(snapshot.packages["jq"], "1.6");
+    assert!(snapshot.diff(&before).is_empty());
+
+    let now = [
+        formula("fd", "9.0.0"),
+        formula("jq", "1.7"),
+        formula("wget", "1.21.4"),
+    ];
+    let diff = snapshot.diff(&now);
+    assert_eq!(diff.removed, [("bat".to_string(), "0.24.0".to_string())]);
+    assert_eq!(diff.added, [("fd".to_string(), "9.0.0".to_string())]);
+    assert_eq!(
+        diff.changed,
+        [("jq".to_string(), "1.6".to_string(), "1.7".to_string())]
+    );
+}
+
+#[test]
+fn snapshots_are_kept_in_the_state_file() {
+    let path = temp_file("snapshot-file");
+    let mut snapshots = Snapshots::load_from(Some(path.clone()));
+    assert!(snapshots.latest.is_none());
+    snapshots
+        .take(Snapshot::of(&[formula("jq", "1.7")], 42))
+        .unwrap();
+    let loaded = Snapshots::load_from(Some(path.clone()));
+    assert_eq!(loaded.latest, snapshots.latest);
+
+    // a broken file reads as no snapshot
+    fs::write(&path, "{ not json").unwrap();
+    assert!(Snapshots::load_from(Some(path.clone())).latest.is_none());
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn removed_packages_are_reinstalled_from_the_diff() {
+    let mut app = app_with(fixtures());
+    // nothing to compare with yet
+    press(&mut app, KeyCode::Char('I'));
+    assert!(matches!(app.mode, Mode::Normal));
+
+    press(&mut app, KeyCode::Char('Y'));
+    let snapshot = app.snapshots.latest.clone().unwrap();
+    assert_eq!(snapshot.packages.len(), 3);
+    assert_eq!(snapshot.packages["wget"], "1.21.4");
+
+    // pretend ripgrep and bat were installed when it was taken, and jq was older
+    let mut snapshot = snapshot;
+    snapshot.packages.insert("bat".into(), "0.24.0".into());
+    snapshot.packages.insert("ripgrep".into(), "14.1.0".into());
+    snapshot.packages.insert("jq".into(), "1.6".into());
+    snapshot.packages.remove("oniguruma");
+    app.snapshots.latest = Some(snapshot);
+
+    press(&mut app, KeyCode::Char('I'));
+    let Mode::SnapshotDiff { diff, checked, .. } = &app.mode else {
+        panic!("expected the snapshot diff, got {:?}", app.mode);
+    };
+    let removed: Vec<_> = diff.removed.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(removed, ["bat", "ripgrep"]);
+    assert_eq!(diff.added, [("oniguruma".to_string(), "6.9.9".to_string())]);
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(checked, &[true, true]);
+
+    // leave bat out
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Enter);
+    let Mode::Confirm { action, name, .. } = &app.mode else {
+        panic!("expected a confirm, got {:?}", app.mode);
+    };
+    assert!(matches!(action, ConfirmAction::BulkInstall(names) if names == &["ripgrep"]));
+    assert_eq!(name, "ripgrep");
+    assert_eq!(action.command_line("ripgrep"), "brew install ripgrep");
+
+    // Esc returns to the diff
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::SnapshotDiff { .. }));
+}
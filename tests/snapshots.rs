@@ -0,0 +1,127 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{press, pump};
+use crossterm::event::KeyCode;
+use homebrew_tui::app::{App, AppEvent, Focus, Mode};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::config::ConfigSource;
+use homebrew_tui::ui::draw_ui;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const WIDTH: u16 = 100;
+const HEIGHT: u16 = 30;
+
+/// An app on the fixtures with every list loaded, the first installed package selected and no
+/// animation, so frames only depend on the state.
+fn app() -> App {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/basic");
+    let backend = Arc::new(MockBackend::from_fixtures(&dir).expect("load fixtures"));
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+    app.config.reduced_motion = true;
+    app.config.github.stats = false;
+    pump(&mut app, |a| {
+        a.items.len() == 3
+            && !a.available_items.is_empty()
+            && a.outdated_items.len() == 2
+            && !a.pinned.is_empty()
+            && !a.loading_installed
+            && !a.loading_available
+            && !a.loading_details
+    });
+    app.focus = Focus::Installed;
+    app.selected = 0;
+    app
+}
+
+/// The frame `app` draws, one line per row with trailing blanks cut off.
+fn render(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+    draw_ui(&mut terminal, app).unwrap();
+    let buffer = terminal.backend().buffer();
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let row: String = (0..buffer.area.width)
+            .map(|x| buffer.get(x, y).symbol.as_str())
+            .collect();
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Compare the frame with `tests/snapshots/<name>.txt`. With `UPDATE_SNAPSHOTS=1` the file is
+/// written instead, to review the change in the diff.
+fn assert_snapshot(name: &str, frame: &str) {
+    let path: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, frame).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "no snapshot {} ({}); run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display(),
+            e
+        )
+    });
+    assert!(
+        frame == expected,
+        "{} changed; run with UPDATE_SNAPSHOTS=1 and review the diff\n--- expected\n{}--- actual\n{}",
+        name,
+        expected,
+        frame
+    );
+}
+
+#[test]
+fn normal_mode() {
+    let mut app = app();
+    assert!(matches!(app.mode, Mode::Normal));
+    assert_snapshot("normal", &render(&mut app));
+}
+
+#[test]
+fn help_modal() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('?'));
+    assert!(matches!(app.mode, Mode::Help { .. }));
+    assert_snapshot("help", &render(&mut app));
+}
+
+#[test]
+fn confirm_dialog() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('u'));
+    assert!(matches!(app.mode, Mode::Confirm { .. }));
+    assert_snapshot("confirm", &render(&mut app));
+}
+
+#[test]
+fn outdated_modal() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('o'));
+    assert!(matches!(app.mode, Mode::Outdated { .. }));
+    assert_snapshot("outdated", &render(&mut app));
+}
+
+#[test]
+fn operation_modal() {
+    let mut app = app();
+    app.handle_event(AppEvent::OpStart("brew upgrade jq".into()));
+    app.handle_event(AppEvent::OpLog("==> Upgrading 1 outdated package:".into()));
+    app.handle_event(AppEvent::OpLog("jq 1.7 -> 1.7.1".into()));
+    app.handle_event(AppEvent::OpErr(
+        "Warning: jq 1.7.1 is already downloaded".into(),
+    ));
+    assert!(matches!(app.mode, Mode::Operation { .. }));
+    assert_snapshot("operation", &render(&mut app));
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "tui")]
+
+use homebrew_tui::app::{App, AppEvent, Focus, Mode, Tab, View};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::config::ConfigSource;
+use homebrew_tui::history::History;
+use std::sync::Arc;
+
+#[test]
+fn search_filter_applies_once_available_list_loads() {
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    app.start_search("grep");
+    assert!(app.focus == Focus::Available);
+    app.handle_event(AppEvent::AvailableList(vec![
+        "bat".into(),
+        "grep".into(),
+        "ripgrep".into(),
+    ]));
+    assert_eq!(app.available_filtered, vec![1, 2]);
+    assert_eq!(app.available_selected, 1);
+}
+
+#[test]
+fn outdated_view_opens_when_check_finishes() {
+    let mut app = App::new().expect("create app");
+    app.history = History::in_memory();
+    app.mode = Mode::Normal;
+    app.outdated_items.clear();
+    app.open_view(View::Outdated);
+    assert!(matches!(app.mode, Mode::Normal));
+    app.handle_event(AppEvent::OutdatedList(vec!["jq".into()]));
+    match &app.mode {
+        Mode::Outdated { packages, .. } => assert_eq!(packages, &vec!["jq".to_string()]),
+        _ => panic!("expected Outdated modal"),
+    }
+
+    app.open_view(View::History);
+    assert!(matches!(app.mode, Mode::History { .. }));
+}
+
+#[test]
+fn tab_views_open_their_tab() {
+    let backend = Arc::new(MockBackend::new());
+    let mut app = App::with_backend(ConfigSource::default(), backend).expect("create app");
+    app.open_view(View::Services);
+    assert_eq!(app.tab, Tab::Services);
+    assert_eq!(app.loading_tab, Some(Tab::Services));
+    assert!(matches!(app.mode, Mode::Normal));
+    for (view, tab) in [
+        (View::Casks, Tab::Casks),
+        (View::Taps, Tab::Taps),
+        (View::Logs, Tab::Logs),
+    ] {
+        app.open_view(view);
+        assert_eq!(app.tab, tab);
+    }
+}
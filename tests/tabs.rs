@@ -0,0 +1,131 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::{InstalledCask, Service};
+use homebrew_tui::parse;
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode, Tab},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::brew::FormulaInfo,
+    homebrew_tui::config::ConfigSource,
+    homebrew_tui::event::AppEvent,
+    homebrew_tui::ui::tab_badge,
+    std::sync::Arc,
+};
+
+#[test]
+fn cask_versions_keep_the_newest() {
+    let casks = parse::parse_cask_versions("firefox 130.0 131.0.2\niterm2 3.5.4\n\n");
+    assert_eq!(
+        casks,
+        vec![
+            InstalledCask {
+                name: "firefox".into(),
+                version: "131.0.2".into()
+            },
+            InstalledCask {
+                name: "iterm2".into(),
+                version: "3.5.4".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn services_parse_from_json() {
+    let json = r#"[{"name":"postgresql@16","status":"started","user":"me","file":"/Users/me/Library/LaunchAgents/homebrew.mxcl.postgresql@16.plist","exit_code":0},
+                   {"name":"redis","status":"none","user":null,"file":"/opt/homebrew/opt/redis/homebrew.mxcl.redis.plist","exit_code":null}]"#;
+    let services: Vec<Service> = serde_json::from_str(json).unwrap();
+    assert_eq!(services[0].status, "started");
+    assert_eq!(services[0].user.as_deref(), Some("me"));
+    assert_eq!(services[1].user, None);
+}
+
+#[cfg(feature = "tui")]
+fn app() -> App {
+    let cask = |name: &str| InstalledCask {
+        name: name.into(),
+        version: "1.0".into(),
+    };
+    let formula = |name: &str| FormulaInfo {
+        name: name.into(),
+        ..Default::default()
+    };
+    let backend = Arc::new(MockBackend {
+        installed: vec![formula("gh"), formula("jq"), formula("wget")],
+        outdated: vec!["gh".into(), "jq".into()],
+        casks: vec![cask("firefox"), cask("iterm2")],
+        taps: vec!["homebrew/services".into()],
+        ..MockBackend::new()
+    });
+    let mut app = app_with(backend);
+    pump(&mut app, |a| !a.outdated_items.is_empty());
+    app
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn number_and_bracket_keys_switch_tabs() {
+    let mut app = app();
+    assert_eq!(app.tab, Tab::Formulae);
+    press(&mut app, KeyCode::Char('2'));
+    assert_eq!(app.tab, Tab::Casks);
+    assert_eq!(app.loading_tab, Some(Tab::Casks));
+    pump(&mut app, |a| a.loading_tab.is_none());
+    assert_eq!(app.casks.len(), 2);
+
+    press(&mut app, KeyCode::Char(']'));
+    assert_eq!(app.tab, Tab::Outdated);
+    press(&mut app, KeyCode::Char('5'));
+    pump(&mut app, |a| a.loading_tab.is_none());
+    assert_eq!(app.taps, vec!["homebrew/services"]);
+    // stepping wraps around both ends
+    press(&mut app, KeyCode::Char('1'));
+    press(&mut app, KeyCode::Char('['));
+    assert_eq!(app.tab, Tab::Logs);
+    press(&mut app, KeyCode::Char(']'));
+    assert_eq!(app.tab, Tab::Formulae);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn tab_lists_have_their_own_selection() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('3'));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.tab_selected, 1);
+    assert_eq!(app.selected, 0, "the Installed selection is left alone");
+    // package commands need the Formulae tab
+    press(&mut app, KeyCode::Char('u'));
+    assert!(matches!(app.mode, Mode::Normal));
+
+    press(&mut app, KeyCode::Enter);
+    match &app.mode {
+        Mode::Outdated {
+            packages, cursor, ..
+        } => assert_eq!(packages[*cursor], "jq"),
+        _ => panic!("expected the Outdated modal"),
+    }
+    press(&mut app, KeyCode::Esc);
+
+    // the filter belongs to the Available pane
+    press(&mut app, KeyCode::Char('f'));
+    assert_eq!(app.tab, Tab::Formulae);
+    assert!(matches!(app.mode, Mode::Input { .. }));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn outdated_tab_carries_the_outdated_count() {
+    let mut app =
+        App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new())).expect("app");
+    app.handle_event(AppEvent::OutdatedList(vec![]));
+    assert_eq!(tab_badge(&app, Tab::Outdated), None);
+    app.handle_event(AppEvent::OutdatedList(vec!["jq".into(), "wget".into()]));
+    assert_eq!(tab_badge(&app, Tab::Outdated), Some(2));
+    assert_eq!(tab_badge(&app, Tab::Casks), None);
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "tui")]
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use homebrew_tui::app::{App, AppEvent};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::config::ConfigSource;
+use homebrew_tui::terminal::is_suspend_key;
+use std::sync::Arc;
+
+#[test]
+fn only_ctrl_z_suspends() {
+    assert!(is_suspend_key(&KeyEvent::new(
+        KeyCode::Char('z'),
+        KeyModifiers::CONTROL
+    )));
+    assert!(!is_suspend_key(&KeyEvent::new(
+        KeyCode::Char('z'),
+        KeyModifiers::NONE
+    )));
+    assert!(!is_suspend_key(&KeyEvent::new(
+        KeyCode::Char('x'),
+        KeyModifiers::CONTROL
+    )));
+}
+
+#[test]
+fn window_title_follows_the_running_operation() {
+    let mut app =
+        App::with_backend(ConfigSource::default(), Arc::new(MockBackend::new())).expect("app");
+    assert_eq!(app.window_title(), "homebrew-tui");
+
+    app.handle_event(AppEvent::OpStart("brew upgrade ripgrep".into()));
+    assert_eq!(app.window_title(), "homebrew-tui: upgrading ripgrep");
+    app.operation_percent = Some(42);
+    assert_eq!(app.window_title(), "homebrew-tui: upgrading ripgrep (42%)");
+    app.handle_event(AppEvent::OpEnd("brew upgrade ripgrep".into()));
+    assert_eq!(app.window_title(), "homebrew-tui");
+
+    let title = |app: &mut App, command: &str| {
+        app.handle_event(AppEvent::OpStart(command.into()));
+        let title = app.window_title();
+        app.handle_event(AppEvent::OpEnd(command.into()));
+        title
+    };
+    assert_eq!(
+        title(&mut app, "brew install --verbose wget jq"),
+        "homebrew-tui: installing wget, jq"
+    );
+    assert_eq!(
+        title(&mut app, "brew upgrade bat fd jq"),
+        "homebrew-tui: upgrading 3 packages"
+    );
+    assert_eq!(
+        title(&mut app, "brew upgrade"),
+        "homebrew-tui: upgrading all packages"
+    );
+    assert_eq!(
+        title(&mut app, "brew update"),
+        "homebrew-tui: updating Homebrew"
+    );
+    assert_eq!(
+        title(&mut app, "find /cache -type f -delete"),
+        "homebrew-tui: running find /cache -type f -delete"
+    );
+}
@@ -0,0 +1,146 @@
+#![cfg(unix)]
+
+use homebrew_tui::backend::{run_brew_operation, BrewBackend, ScriptLine};
+use homebrew_tui::brew::{Brew, BrewError};
+use homebrew_tui::event::{AppEvent, ConfirmAction};
+use homebrew_tui::transcript::{self, Exchange, Transcript};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("homebrew-tui-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Run `action` to completion and collect what it printed, as the Operation modal gets it.
+fn operation(brew: &Brew, action: ConfirmAction, name: &str) -> (Option<i32>, Vec<String>) {
+    let (tx, rx) = mpsc::channel();
+    let code = run_brew_operation(brew, &action, name, &tx);
+    drop(tx);
+    let lines = rx
+        .into_iter()
+        .filter_map(|ev| match ev {
+            AppEvent::OpLog(l) => Some(format!("out: {}", l)),
+            AppEvent::OpErr(l) => Some(format!("err: {}", l)),
+            _ => None,
+        })
+        .collect();
+    (code, lines)
+}
+
+fn query(command: &str, stdout: &str) -> Exchange {
+    Exchange::query(command, stdout.as_bytes(), b"", Some(0))
+}
+
+#[test]
+fn a_recorded_session_replays_without_brew() {
+    let dir = temp_dir("record");
+    let script = dir.join("brew");
+    fs::write(
+        &script,
+        "#!/bin/sh\ncase \"$1\" in\n\
+         outdated) echo jq; echo wget ;;\n\
+         upgrade) echo \"==> Upgrading $2\"; echo 'Warning: already downloaded' >&2; echo done ;;\n\
+         *) exit 1 ;;\nesac\n",
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = dir.join("session.jsonl");
+
+    let recording = Brew {
+        program: script,
+        transcript: Some(Arc::new(Transcript::record(&path).unwrap())),
+        ..Brew::new()
+    };
+    assert_eq!(recording.outdated().unwrap(), ["jq", "wget"]);
+    let recorded = operation(&recording, ConfirmAction::Upgrade, "jq");
+    assert_eq!(recorded.0, Some(0));
+    assert!(recording.search("rip").is_err());
+
+    let exchanges = transcript::load(&path).unwrap();
+    let outdated = exchanges
+        .iter()
+        .find(|e| e.command == "brew outdated --formula")
+        .unwrap();
+    assert_eq!(outdated.output, [ScriptLine::Stdout("jq\nwget\n".into())]);
+    let upgrade = exchanges.iter().find(|e| e.operation).unwrap();
+    assert_eq!(upgrade.command, "brew upgrade jq");
+    assert_eq!(upgrade.exit_code, Some(0));
+    assert_eq!(upgrade.output.len(), 3);
+    let search = exchanges
+        .iter()
+        .find(|e| e.command == "brew search rip")
+        .unwrap();
+    assert_eq!(search.exit_code, Some(1));
+
+    // brew is gone: everything comes from the transcript
+    let replay = Brew {
+        program: dir.join("missing"),
+        transcript: Some(Arc::new(Transcript::replay(&path).unwrap())),
+        ..Brew::new()
+    };
+    assert!(replay.is_replay() && replay.is_available() && !replay.persistent());
+    assert_eq!(replay.outdated().unwrap(), ["jq", "wget"]);
+    assert_eq!(operation(&replay, ConfirmAction::Upgrade, "jq"), recorded);
+    assert!(replay.search("rip").is_err());
+
+    // commands that were never recorded fail like a failing brew
+    let err = replay.search("fd").unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<BrewError>(),
+        Some(BrewError::CommandFailed { command, .. }) if command == "brew search fd"
+    ));
+    let (code, lines) = operation(&replay, ConfirmAction::Install, "fd");
+    assert_eq!(code, None);
+    assert!(lines[0].contains("`brew install fd` is not in the transcript"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn repeated_commands_replay_in_the_recorded_order() {
+    let t = Transcript::from_exchanges(vec![
+        query("brew outdated --formula", "jq\n"),
+        query("brew tap", "homebrew/core\n"),
+        query("brew outdated --formula", ""),
+    ]);
+    let next = |command| t.next(false, command).map(|e| e.stdout());
+    assert_eq!(next("brew outdated --formula").as_deref(), Some("jq\n"));
+    assert_eq!(next("brew outdated --formula").as_deref(), Some(""));
+    // the last answer stays
+    assert_eq!(next("brew outdated --formula").as_deref(), Some(""));
+    assert_eq!(next("brew tap").as_deref(), Some("homebrew/core\n"));
+    assert_eq!(next("brew list --pinned"), None);
+    // queries and operations are apart
+    assert_eq!(t.next(true, "brew tap"), None);
+}
+
+#[test]
+fn transcripts_are_json_lines() {
+    let exchanges = transcript::parse(
+        r#"{"command":"brew tap","output":[{"stdout":"homebrew/core\n"}],"exit_code":0}
+
+{"command":"brew upgrade jq","operation":true,"output":[{"stderr":"Error: boom"}],"exit_code":1}
+"#,
+    )
+    .unwrap();
+    assert_eq!(exchanges.len(), 2);
+    assert!(!exchanges[0].operation && exchanges[1].operation);
+    assert_eq!(exchanges[1].stderr(), "Error: boom");
+
+    let err = transcript::parse("{\"command\":\"brew tap\"}\nnot json\n").unwrap_err();
+    assert!(format!("{:#}", err).starts_with("line 2"));
+
+    // what a recording writes reads back the same
+    let dir = temp_dir("transcript");
+    let path = dir.join("t.jsonl");
+    let t = Transcript::record(&path).unwrap();
+    for e in &exchanges {
+        t.save(e);
+    }
+    assert_eq!(transcript::load(&path).unwrap(), exchanges);
+    let _ = fs::remove_dir_all(&dir);
+}
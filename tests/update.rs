@@ -0,0 +1,121 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew::UpdateSummary;
+use homebrew_tui::parse::parse_update;
+#[cfg(feature = "tui")]
+use {
+    common::{app_with, press, pump},
+    crossterm::event::KeyCode,
+    homebrew_tui::app::{App, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::config::ConfigSource,
+    std::sync::Arc,
+    std::time::Duration,
+};
+
+const UPDATE: &str = "\
+==> Updating Homebrew...
+Updated 2 taps (homebrew/core and homebrew/cask).
+==> New Formulae
+kubectx   zizmor
+==> Renamed Formulae
+exa -> eza
+==> Updated Formulae
+Updated 47 formulae.
+==> Deleted Formulae
+old-tool
+==> New Casks
+ghostty
+==> Outdated Formulae
+jq
+You have 1 outdated formula installed.
+";
+
+#[test]
+fn update_output_is_counted_per_section() {
+    let summary = parse_update(UPDATE);
+    assert_eq!(
+        summary,
+        UpdateSummary {
+            new_formulae: 2,
+            updated_formulae: 47,
+            renamed_formulae: 1,
+            deleted_formulae: 1,
+            casks: 1,
+        }
+    );
+    assert_eq!(
+        summary.message(),
+        "brew update: 51 formulae changed (2 new, 47 updated, 1 renamed, 1 deleted), 1 cask changed"
+    );
+    assert_eq!(
+        parse_update("Already up-to-date.\n").message(),
+        "brew update: already up to date"
+    );
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn update_reports_changes_and_rechecks_outdated() {
+    let backend = Arc::new(MockBackend {
+        output: UPDATE.lines().map(String::from).collect(),
+        outdated: vec!["jq".into()],
+        ..MockBackend::new()
+    });
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+    // let the startup checks settle so the outdated list below comes from the re-check
+    pump(&mut app, |a| {
+        !a.loading_installed && a.outdated_items == ["jq"]
+    });
+    while let Ok(ev) = app.rx.recv_timeout(Duration::from_millis(300)) {
+        app.handle_event(ev);
+    }
+    app.outdated_items.clear();
+
+    press(&mut app, KeyCode::Char('U'));
+    assert!(matches!(app.mode, Mode::Confirm { .. }));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| !a.outdated_items.is_empty());
+    assert_eq!(backend.executed(), vec!["brew update".to_string()]);
+    assert!(app
+        .logs
+        .iter()
+        .any(|l| l.message.starts_with("brew update: 51 formulae changed")));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn an_update_with_flags_still_reports_and_rechecks() {
+    let backend = Arc::new(MockBackend {
+        output: UPDATE.lines().map(String::from).collect(),
+        outdated: vec!["jq".into()],
+        ..MockBackend::new()
+    });
+    let mut app = app_with(backend.clone());
+    pump(&mut app, |a| a.outdated_items == ["jq"]);
+    while let Ok(ev) = app.rx.recv_timeout(Duration::from_millis(300)) {
+        app.handle_event(ev);
+    }
+    app.outdated_items.clear();
+
+    press(&mut app, KeyCode::Char('U'));
+    // tick --verbose in the flags overlay
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action, .. } if action.flags() == ["--verbose"]
+    ));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |a| !a.outdated_items.is_empty());
+    assert_eq!(
+        backend.executed(),
+        vec!["brew update --verbose".to_string()]
+    );
+    assert!(app
+        .logs
+        .iter()
+        .any(|l| l.message.starts_with("brew update: 51 formulae changed")));
+}
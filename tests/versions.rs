@@ -0,0 +1,206 @@
+#[cfg(feature = "tui")]
+mod common;
+
+use homebrew_tui::brew;
+use homebrew_tui::event::ConfirmAction;
+#[cfg(feature = "tui")]
+use {
+    common::{press, pump},
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    homebrew_tui::api_cache::ApiEntry,
+    homebrew_tui::app::{App, InputAction, Mode},
+    homebrew_tui::backend::MockBackend,
+    homebrew_tui::brew::{FormulaInfo, InstalledInfo},
+    homebrew_tui::config::ConfigSource,
+    std::sync::Arc,
+};
+
+#[test]
+fn version_actions_run_through_sh() {
+    let switch = ConfirmAction::SwitchVersion {
+        version: "9.0.0".into(),
+    };
+    assert!(switch.brew_args("fd").is_empty());
+    assert_eq!(
+        switch.shell_script("fd", "/opt/homebrew/bin/brew").unwrap(),
+        "/opt/homebrew/bin/brew unlink fd && ln -sfn \"$(/opt/homebrew/bin/brew --cellar)\"/fd/9.0.0 \
+         \"$(/opt/homebrew/bin/brew --prefix)\"/opt/fd && /opt/homebrew/bin/brew link fd"
+    );
+    let remove = ConfirmAction::RemoveVersion {
+        version: "9.0.0".into(),
+    };
+    assert_eq!(
+        remove.command_line_with("fd", &[]),
+        "sh -c 'rm -rf \"$(brew --cellar)\"/fd/9.0.0'"
+    );
+}
+
+#[test]
+fn versioned_formulae_are_listed_newest_first() {
+    let available: Vec<String> = [
+        "python",
+        "python-tk@3.12",
+        "python@3.9",
+        "python@3.12",
+        "python@3.11",
+    ]
+    .iter()
+    .map(|n| n.to_string())
+    .collect();
+    assert_eq!(
+        brew::versioned_formulae(&available, "python"),
+        vec!["python@3.12", "python@3.11", "python@3.9"]
+    );
+    assert_eq!(
+        brew::versioned_formulae(&available, "python@3.12"),
+        vec!["python@3.11", "python@3.9"]
+    );
+    assert!(brew::versioned_formulae(&available, "jq").is_empty());
+}
+
+#[test]
+fn extract_installs_from_the_local_tap() {
+    let extract = ConfirmAction::ExtractInstall {
+        version: "1.6".into(),
+    };
+    let script = extract.shell_script("jq", "brew").unwrap();
+    assert!(script.starts_with(
+        "[ -d \"$(brew --repository homebrew-tui/versions)\" ] || brew tap-new --no-git homebrew-tui/versions;"
+    ));
+    assert!(script.ends_with(
+        "brew extract --version=1.6 jq homebrew-tui/versions && brew install homebrew-tui/versions/jq@1.6"
+    ));
+}
+
+/// fd with 10.1.0 (linked) and 9.0.0 installed.
+#[cfg(feature = "tui")]
+fn app(backend: &Arc<MockBackend>) -> App {
+    let mut app = App::with_backend(ConfigSource::default(), backend.clone()).expect("create app");
+    pump(&mut app, |a| {
+        a.items.first().is_some_and(|f| f.installed.len() == 2)
+    });
+    app
+}
+
+#[cfg(feature = "tui")]
+fn backend() -> Arc<MockBackend> {
+    backend_with(&[])
+}
+
+/// fd installed as above, with `available` formulae.
+#[cfg(feature = "tui")]
+fn backend_with(available: &[&str]) -> Arc<MockBackend> {
+    let version = |v: &str| InstalledInfo {
+        version: v.into(),
+        ..Default::default()
+    };
+    Arc::new(MockBackend {
+        installed: vec![FormulaInfo {
+            name: "fd".into(),
+            installed: vec![version("10.1.0"), version("9.0.0")],
+            linked_keg: Some("10.1.0".into()),
+            ..Default::default()
+        }],
+        available: available
+            .iter()
+            .map(|n| ApiEntry {
+                name: n.to_string(),
+                desc: None,
+                cask: false,
+            })
+            .collect(),
+        ..MockBackend::new()
+    })
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn switching_links_the_selected_version() {
+    let backend = backend();
+    let mut app = app(&backend);
+    press(&mut app, KeyCode::Char('V'));
+    assert!(matches!(&app.mode, Mode::Versions { versions, .. } if versions.len() == 2));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('s'));
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action: ConfirmAction::SwitchVersion { version }, name, .. }
+            if version == "9.0.0" && name == "fd"
+    ));
+    press(&mut app, KeyCode::Char('y'));
+    pump(&mut app, |_| !backend.executed().is_empty());
+    assert!(backend.executed()[0].starts_with("sh -c 'brew unlink fd && "));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn the_linked_version_is_not_removed() {
+    let backend = backend();
+    let mut app = app(&backend);
+    press(&mut app, KeyCode::Char('V'));
+    press(&mut app, KeyCode::Char('d'));
+    assert!(matches!(app.mode, Mode::Versions { .. }));
+    assert!(app.status.contains("already the linked version"));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('d'));
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action: ConfirmAction::RemoveVersion { version }, .. } if version == "9.0.0"
+    ));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn older_versions_offer_versioned_formulae_then_extract() {
+    let backend = backend_with(&["fd", "fd@9", "fd@8"]);
+    let mut app = app(&backend);
+    pump(&mut app, |a| !a.available_items.is_empty());
+    press(&mut app, KeyCode::Char('V'));
+    press(&mut app, KeyCode::Char('o'));
+    assert!(matches!(
+        &app.mode,
+        Mode::OlderVersions { versioned, .. } if versioned == &["fd@9", "fd@8"]
+    ));
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action: ConfirmAction::Install, name, .. } if name == "fd@9"
+    ));
+
+    app.mode = Mode::Normal;
+    press(&mut app, KeyCode::Char('V'));
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(matches!(
+        &app.mode,
+        Mode::Input { action: InputAction::ExtractVersion { name }, .. } if name == "fd"
+    ));
+    for c in "7.4.0".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(matches!(
+        &app.mode,
+        Mode::Confirm { action: ConfirmAction::ExtractInstall { version }, name, .. }
+            if version == "7.4.0" && name == "fd"
+    ));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn without_versioned_formulae_the_version_is_asked_for() {
+    let backend = backend_with(&["fd", "jq"]);
+    let mut app = app(&backend);
+    pump(&mut app, |a| !a.available_items.is_empty());
+    press(&mut app, KeyCode::Char('V'));
+    press(&mut app, KeyCode::Char('o'));
+    assert!(matches!(
+        app.mode,
+        Mode::Input {
+            action: InputAction::ExtractVersion { .. },
+            ..
+        }
+    ));
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::{app_with, press};
+use crossterm::event::KeyCode;
+use homebrew_tui::app::{App, Focus, Tab, Zoom};
+use homebrew_tui::backend::MockBackend;
+use homebrew_tui::brew::FormulaInfo;
+use std::sync::Arc;
+
+fn app() -> App {
+    let formula = |name: &str| FormulaInfo {
+        name: name.into(),
+        ..Default::default()
+    };
+    let backend = Arc::new(MockBackend {
+        installed: vec![formula("gh"), formula("jq"), formula("wget")],
+        ..MockBackend::new()
+    });
+    app_with(backend)
+}
+
+#[test]
+fn tab_walks_the_zoom_through_every_pane() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('e'));
+    assert_eq!(app.zoom, Some(Zoom::List));
+    // a zoomed list still moves its selection
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.selected, 1);
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!((app.zoom, app.focus), (Some(Zoom::List), Focus::Available));
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.zoom, Some(Zoom::Details));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.zoom_scroll, 2);
+    assert_eq!(app.selected, 1, "scrolling Details leaves the lists alone");
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!((app.zoom, app.zoom_scroll), (Some(Zoom::Logs), 0));
+    press(&mut app, KeyCode::End);
+    assert_eq!(app.zoom_scroll, app.logs.len().saturating_sub(1));
+    press(&mut app, KeyCode::Tab);
+    assert_eq!((app.zoom, app.focus), (Some(Zoom::List), Focus::Installed));
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.zoom, None);
+}
+
+#[test]
+fn other_tabs_zoom_their_list_and_logs() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('e'));
+    press(&mut app, KeyCode::Char('3'));
+    assert_eq!(app.zoom, None, "switching tabs leaves the zoom");
+    press(&mut app, KeyCode::Char('e'));
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.zoom, Some(Zoom::Logs));
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.zoom, Some(Zoom::List));
+    press(&mut app, KeyCode::Char('e'));
+    assert_eq!((app.zoom, app.tab), (None, Tab::Outdated));
+}